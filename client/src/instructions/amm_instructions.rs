@@ -9,11 +9,20 @@ use solana_sdk::{
 use raydium_amm_v3::accounts as raydium_accounts;
 use raydium_amm_v3::instruction as raydium_instruction;
 use raydium_amm_v3::states::{
-    AMM_CONFIG_SEED, OPERATION_SEED, POOL_SEED, POOL_VAULT_SEED, POSITION_SEED, TICK_ARRAY_SEED,
+    AdminAction, AmmConfigOwnerProposal, AmmConfigOwnerType, PoolState, TimelockAction,
+    TimelockConfig, AMM_CONFIG_OWNER_PROPOSAL_SEED, AMM_CONFIG_SEED, OPERATION_SEED, POOL_SEED,
+    POOL_VAULT_SEED, POSITION_SEED, TICK_ARRAY_SEED, TICK_ARRAY_BITMAP_EXTENSION_SEED,
+    POOL_REWARD_EXTENSION_SEED, TIMELOCK_ACTION_SEED, TIMELOCK_CONFIG_SEED,
 };
+use std::collections::VecDeque;
 use std::rc::Rc;
 
 use super::super::{read_keypair_file, ClientConfig};
+use super::{
+    deserialize_anchor_account, get_pool_reward_extension_account_metas,
+    get_tick_array_bitmap_extension_account_metas, get_transfer_hook_account_metas,
+    TransferHookTransfer,
+};
 
 pub fn create_amm_config_instr(
     config: &ClientConfig,
@@ -53,7 +62,6 @@ pub fn create_amm_config_instr(
 pub fn update_amm_config_instr(
     config: &ClientConfig,
     amm_config: Pubkey,
-    remaining_accounts: Vec<AccountMeta>,
     param: u8,
     value: u32,
 ) -> Result<Vec<Instruction>> {
@@ -63,14 +71,233 @@ pub fn update_amm_config_instr(
     // Client.
     let client = Client::new(url, Rc::new(payer));
     let program = client.program(config.raydium_v3_program);
-    let instructions = program
+    let request = program
         .request()
         .accounts(raydium_accounts::UpdateAmmConfig {
             owner: admin.pubkey(),
             amm_config,
+            timelock_config: get_timelock_config(&program.id()),
+        });
+    let instructions = match param {
+        0 => request.args(raydium_instruction::SetTradeFeeRate {
+            trade_fee_rate: value,
+        }),
+        1 => request.args(raydium_instruction::SetProtocolFeeRate {
+            protocol_fee_rate: value,
+        }),
+        2 => request.args(raydium_instruction::SetFundFeeRate {
+            fund_fee_rate: value,
+        }),
+        _ => panic!("error input"),
+    }
+    .instructions()?;
+    Ok(instructions)
+}
+
+fn get_timelock_config(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[TIMELOCK_CONFIG_SEED.as_bytes()], program_id).0
+}
+
+fn get_timelock_action(program_id: &Pubkey, id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[TIMELOCK_ACTION_SEED.as_bytes(), &id.to_be_bytes()],
+        program_id,
+    )
+    .0
+}
+
+pub fn set_timelock_delay_instr(config: &ClientConfig, delay: u64) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.admin_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program);
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::SetTimelockDelay {
+            owner: program.payer(),
+            timelock_config: get_timelock_config(&program.id()),
+            system_program: system_program::id(),
         })
-        .accounts(remaining_accounts)
-        .args(raydium_instruction::UpdateAmmConfig { param, value })
+        .args(raydium_instruction::SetTimelockDelay { delay })
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn queue_admin_action_instr(
+    config: &ClientConfig,
+    action: AdminAction,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.admin_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program);
+    let timelock_config = get_timelock_config(&program.id());
+    let timelock_config_account: TimelockConfig = program.account(timelock_config)?;
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::QueueAdminAction {
+            owner: program.payer(),
+            timelock_config,
+            timelock_action: get_timelock_action(
+                &program.id(),
+                timelock_config_account.action_num,
+            ),
+            system_program: system_program::id(),
+        })
+        .args(raydium_instruction::QueueAdminAction { action })
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn cancel_admin_action_instr(config: &ClientConfig, id: u64) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.admin_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program);
+    let timelock_action = get_timelock_action(&program.id(), id);
+    let timelock_action_account: TimelockAction = program.account(timelock_action)?;
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::CancelAdminAction {
+            owner: program.payer(),
+            timelock_action,
+            payer: timelock_action_account.payer,
+        })
+        .args(raydium_instruction::CancelAdminAction {})
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn execute_admin_action_instr(config: &ClientConfig, id: u64) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program);
+    let timelock_action = get_timelock_action(&program.id(), id);
+    let timelock_action_account: TimelockAction = program.account(timelock_action)?;
+    let (amm_config, pool_state) = match timelock_action_account.action {
+        AdminAction::SetTradeFeeRate { amm_config, .. }
+        | AdminAction::SetProtocolFeeRate { amm_config, .. }
+        | AdminAction::SetFundFeeRate { amm_config, .. } => (Some(amm_config), None),
+        AdminAction::UpdatePoolStatus { pool_state, .. } => (None, Some(pool_state)),
+        AdminAction::SetPoolTradeFeeRate { pool_state, .. }
+        | AdminAction::SetDynamicFeeParams { pool_state, .. } => {
+            // both changes read the trade fee rate of the amm config of the pool
+            let pool_state_account =
+                deserialize_anchor_account::<PoolState>(&program.rpc().get_account(&pool_state)?)?;
+            (Some(pool_state_account.amm_config), Some(pool_state))
+        }
+        AdminAction::SetTimelockDelay { .. } => (None, None),
+    };
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::ExecuteAdminAction {
+            executor: program.payer(),
+            timelock_config: get_timelock_config(&program.id()),
+            timelock_action,
+            payer: timelock_action_account.payer,
+            amm_config,
+            pool_state,
+        })
+        .args(raydium_instruction::ExecuteAdminAction {})
+        .instructions()?;
+    Ok(instructions)
+}
+
+fn get_amm_config_owner_proposal(
+    program_id: &Pubkey,
+    amm_config: Pubkey,
+    owner_type: AmmConfigOwnerType,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            AMM_CONFIG_OWNER_PROPOSAL_SEED.as_bytes(),
+            amm_config.as_ref(),
+            &[owner_type as u8],
+        ],
+        program_id,
+    )
+    .0
+}
+
+pub fn propose_amm_config_owner_instr(
+    config: &ClientConfig,
+    amm_config: Pubkey,
+    owner_type: AmmConfigOwnerType,
+    new_owner: Pubkey,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let admin = read_keypair_file(&config.admin_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program);
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::ProposeAmmConfigOwner {
+            owner: admin.pubkey(),
+            amm_config,
+            proposal: get_amm_config_owner_proposal(&program.id(), amm_config, owner_type),
+            system_program: system_program::id(),
+        })
+        .args(raydium_instruction::ProposeAmmConfigOwner {
+            owner_type,
+            new_owner,
+        })
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn accept_amm_config_owner_instr(
+    config: &ClientConfig,
+    amm_config: Pubkey,
+    owner_type: AmmConfigOwnerType,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program);
+    let proposal = get_amm_config_owner_proposal(&program.id(), amm_config, owner_type);
+    let proposal_account: AmmConfigOwnerProposal = program.account(proposal)?;
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::AcceptAmmConfigOwner {
+            pending_owner: program.payer(),
+            amm_config,
+            proposal,
+            payer: proposal_account.payer,
+        })
+        .args(raydium_instruction::AcceptAmmConfigOwner {})
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn cancel_amm_config_owner_instr(
+    config: &ClientConfig,
+    amm_config: Pubkey,
+    owner_type: AmmConfigOwnerType,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let admin = read_keypair_file(&config.admin_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program);
+    let proposal = get_amm_config_owner_proposal(&program.id(), amm_config, owner_type);
+    let proposal_account: AmmConfigOwnerProposal = program.account(proposal)?;
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::CancelAmmConfigOwner {
+            owner: admin.pubkey(),
+            proposal,
+            payer: proposal_account.payer,
+        })
+        .args(raydium_instruction::CancelAmmConfigOwner {})
         .instructions()?;
     Ok(instructions)
 }
@@ -107,15 +334,21 @@ pub fn update_operation_account_instr(
     let program = client.program(config.raydium_v3_program);
     let (operation_account_key, __bump) =
         Pubkey::find_program_address(&[OPERATION_SEED.as_bytes()], &program.id());
-    let instructions = program
+    let request = program
         .request()
         .accounts(raydium_accounts::UpdateOperationAccount {
             owner: program.payer(),
             operation_state: operation_account_key,
             system_program: system_program::id(),
-        })
-        .args(raydium_instruction::UpdateOperationAccount { param, keys })
-        .instructions()?;
+        });
+    let instructions = match param {
+        0 => request.args(raydium_instruction::AddOperationOwners { keys }),
+        1 => request.args(raydium_instruction::RemoveOperationOwners { keys }),
+        2 => request.args(raydium_instruction::AddWhitelistMints { keys }),
+        3 => request.args(raydium_instruction::RemoveWhitelistMints { keys }),
+        _ => panic!("error input"),
+    }
+    .instructions()?;
     Ok(instructions)
 }
 
@@ -125,6 +358,8 @@ pub fn create_pool_instr(
     observation_key: Pubkey,
     token_mint_0: Pubkey,
     token_mint_1: Pubkey,
+    token_program_0: Pubkey,
+    token_program_1: Pubkey,
     sqrt_price_x64: u128,
     open_time: u64,
 ) -> Result<Vec<Instruction>> {
@@ -142,6 +377,8 @@ pub fn create_pool_instr(
         ],
         &program.id(),
     );
+    let (operation_account_key, __bump) =
+        Pubkey::find_program_address(&[OPERATION_SEED.as_bytes()], &program.id());
     let (token_vault_0, __bump) = Pubkey::find_program_address(
         &[
             POOL_VAULT_SEED.as_bytes(),
@@ -166,10 +403,12 @@ pub fn create_pool_instr(
             pool_state: pool_account_key,
             token_mint_0,
             token_mint_1,
+            operation_state: operation_account_key,
             token_vault_0,
             token_vault_1,
             observation_state: observation_key,
-            token_program: spl_token_2022::id(),
+            token_program_0,
+            token_program_1,
             system_program: system_program::id(),
             rent: sysvar::rent::id(),
         })
@@ -184,8 +423,13 @@ pub fn create_pool_instr(
 pub fn open_position_instr(
     config: &ClientConfig,
     pool_account_key: Pubkey,
+    observation_key: Pubkey,
     token_vault_0: Pubkey,
     token_vault_1: Pubkey,
+    token_mint_0: Pubkey,
+    token_mint_1: Pubkey,
+    token_program_0: Pubkey,
+    token_program_1: Pubkey,
     nft_mint_key: Pubkey,
     nft_to_owner: Pubkey,
     user_token_account_0: Pubkey,
@@ -242,6 +486,39 @@ pub fn open_position_instr(
         &[POSITION_SEED.as_bytes(), nft_mint_key.to_bytes().as_ref()],
         &program.id(),
     );
+    // the tick array bitmap extension, if created, the reward extensions of the pool and the transfer
+    // hook accounts of token_0 and token_1, if any, lead the remaining accounts
+    let mut transfer_hook_accounts = get_tick_array_bitmap_extension_account_metas(
+        &program.rpc(),
+        &program.id(),
+        &pool_account_key,
+    )?;
+    transfer_hook_accounts.extend(get_pool_reward_extension_account_metas(
+        &program.rpc(),
+        &program.id(),
+        &pool_account_key,
+        Some(&program.payer()),
+    )?);
+    transfer_hook_accounts.extend(get_transfer_hook_account_metas(
+        &program.rpc(),
+        &token_mint_0,
+        &TransferHookTransfer {
+            source: user_token_account_0,
+            destination: token_vault_0,
+            authority: program.payer(),
+            amount: amount_0_max,
+        },
+    )?);
+    transfer_hook_accounts.extend(get_transfer_hook_account_metas(
+        &program.rpc(),
+        &token_mint_1,
+        &TransferHookTransfer {
+            source: user_token_account_1,
+            destination: token_vault_1,
+            authority: program.payer(),
+            amount: amount_1_max,
+        },
+    )?);
     let instructions = program
         .request()
         .accounts(raydium_accounts::OpenPosition {
@@ -259,12 +536,18 @@ pub fn open_position_instr(
             token_account_1: user_token_account_1,
             token_vault_0,
             token_vault_1,
+            vault_0_mint: token_mint_0,
+            vault_1_mint: token_mint_1,
             rent: sysvar::rent::id(),
             system_program: system_program::id(),
             token_program: spl_token_2022::id(),
+            token_program_0,
+            token_program_1,
             associated_token_program: spl_associated_token_account::id(),
             metadata_program: mpl_token_metadata::id(),
+            observation_state: observation_key,
         })
+        .accounts(transfer_hook_accounts)
         .args(raydium_instruction::OpenPosition {
             liquidity,
             amount_0_max,
@@ -281,8 +564,13 @@ pub fn open_position_instr(
 pub fn increase_liquidity_instr(
     config: &ClientConfig,
     pool_account_key: Pubkey,
+    observation_key: Pubkey,
     token_vault_0: Pubkey,
     token_vault_1: Pubkey,
+    token_mint_0: Pubkey,
+    token_mint_1: Pubkey,
+    token_program_0: Pubkey,
+    token_program_1: Pubkey,
     nft_mint_key: Pubkey,
     user_token_account_0: Pubkey,
     user_token_account_1: Pubkey,
@@ -331,6 +619,39 @@ pub fn increase_liquidity_instr(
         &program.id(),
     );
 
+    // the tick array bitmap extension, if created, the reward extensions of the pool and the transfer
+    // hook accounts of token_0 and token_1, if any, lead the remaining accounts
+    let mut transfer_hook_accounts = get_tick_array_bitmap_extension_account_metas(
+        &program.rpc(),
+        &program.id(),
+        &pool_account_key,
+    )?;
+    transfer_hook_accounts.extend(get_pool_reward_extension_account_metas(
+        &program.rpc(),
+        &program.id(),
+        &pool_account_key,
+        Some(&program.payer()),
+    )?);
+    transfer_hook_accounts.extend(get_transfer_hook_account_metas(
+        &program.rpc(),
+        &token_mint_0,
+        &TransferHookTransfer {
+            source: user_token_account_0,
+            destination: token_vault_0,
+            authority: program.payer(),
+            amount: amount_0_max,
+        },
+    )?);
+    transfer_hook_accounts.extend(get_transfer_hook_account_metas(
+        &program.rpc(),
+        &token_mint_1,
+        &TransferHookTransfer {
+            source: user_token_account_1,
+            destination: token_vault_1,
+            authority: program.payer(),
+            amount: amount_1_max,
+        },
+    )?);
     let instructions = program
         .request()
         .accounts(raydium_accounts::IncreaseLiquidity {
@@ -345,8 +666,13 @@ pub fn increase_liquidity_instr(
             token_account_1: user_token_account_1,
             token_vault_0,
             token_vault_1,
-            token_program: spl_token_2022::id(),
+            vault_0_mint: token_mint_0,
+            vault_1_mint: token_mint_1,
+            token_program_0,
+            token_program_1,
+            observation_state: observation_key,
         })
+        .accounts(transfer_hook_accounts)
         .args(raydium_instruction::IncreaseLiquidity {
             liquidity,
             amount_0_max,
@@ -359,8 +685,13 @@ pub fn increase_liquidity_instr(
 pub fn decrease_liquidity_instr(
     config: &ClientConfig,
     pool_account_key: Pubkey,
+    observation_key: Pubkey,
     token_vault_0: Pubkey,
     token_vault_1: Pubkey,
+    token_mint_0: Pubkey,
+    token_mint_1: Pubkey,
+    token_program_0: Pubkey,
+    token_program_1: Pubkey,
     nft_mint_key: Pubkey,
     user_token_account_0: Pubkey,
     user_token_account_1: Pubkey,
@@ -409,6 +740,39 @@ pub fn decrease_liquidity_instr(
         ],
         &program.id(),
     );
+    // the tick array bitmap extension, if created, the reward extensions of the pool and the transfer
+    // hook accounts of token_0 and token_1, if any, lead the remaining accounts
+    let mut transfer_hook_accounts = get_tick_array_bitmap_extension_account_metas(
+        &program.rpc(),
+        &program.id(),
+        &pool_account_key,
+    )?;
+    transfer_hook_accounts.extend(get_pool_reward_extension_account_metas(
+        &program.rpc(),
+        &program.id(),
+        &pool_account_key,
+        Some(&program.payer()),
+    )?);
+    transfer_hook_accounts.extend(get_transfer_hook_account_metas(
+        &program.rpc(),
+        &token_mint_0,
+        &TransferHookTransfer {
+            source: token_vault_0,
+            destination: user_token_account_0,
+            authority: pool_account_key,
+            amount: amount_0_min,
+        },
+    )?);
+    transfer_hook_accounts.extend(get_transfer_hook_account_metas(
+        &program.rpc(),
+        &token_mint_1,
+        &TransferHookTransfer {
+            source: token_vault_1,
+            destination: user_token_account_1,
+            authority: pool_account_key,
+            amount: amount_1_min,
+        },
+    )?);
     let instructions = program
         .request()
         .accounts(raydium_accounts::DecreaseLiquidity {
@@ -423,8 +787,13 @@ pub fn decrease_liquidity_instr(
             tick_array_upper,
             recipient_token_account_0: user_token_account_0,
             recipient_token_account_1: user_token_account_1,
-            token_program: spl_token_2022::id(),
+            vault_0_mint: token_mint_0,
+            vault_1_mint: token_mint_1,
+            token_program_0,
+            token_program_1,
+            observation_state: observation_key,
         })
+        .accounts(transfer_hook_accounts)
         .accounts(remaining_accounts)
         .args(raydium_instruction::DecreaseLiquidity {
             liquidity,
@@ -435,133 +804,298 @@ pub fn decrease_liquidity_instr(
     Ok(instructions)
 }
 
-pub fn close_personal_position_instr(
+pub fn flash_instr(
     config: &ClientConfig,
-    nft_mint_key: Pubkey,
+    amm_config: Pubkey,
+    pool_account_key: Pubkey,
+    token_vault_0: Pubkey,
+    token_vault_1: Pubkey,
+    token_mint_0: Pubkey,
+    token_mint_1: Pubkey,
+    token_program_0: Pubkey,
+    token_program_1: Pubkey,
+    recipient_token_account_0: Pubkey,
+    recipient_token_account_1: Pubkey,
+    callback_program: Pubkey,
+    callback_accounts: Vec<AccountMeta>,
+    amount_0: u64,
+    amount_1: u64,
+    data: Vec<u8>,
 ) -> Result<Vec<Instruction>> {
     let payer = read_keypair_file(&config.payer_path)?;
     let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
     // Client.
     let client = Client::new(url, Rc::new(payer));
     let program = client.program(config.raydium_v3_program);
-    let nft_ata_token_account =
-        spl_associated_token_account::get_associated_token_address(&program.payer(), &nft_mint_key);
-    let (personal_position_key, __bump) = Pubkey::find_program_address(
-        &[POSITION_SEED.as_bytes(), nft_mint_key.to_bytes().as_ref()],
-        &program.id(),
-    );
+    // the transfer hook accounts of token_0 and token_1, if any, come before the callback accounts
+    let mut remaining_accounts = get_transfer_hook_account_metas(
+        &program.rpc(),
+        &token_mint_0,
+        &TransferHookTransfer {
+            source: token_vault_0,
+            destination: recipient_token_account_0,
+            authority: pool_account_key,
+            amount: amount_0,
+        },
+    )?;
+    remaining_accounts.extend(get_transfer_hook_account_metas(
+        &program.rpc(),
+        &token_mint_1,
+        &TransferHookTransfer {
+            source: token_vault_1,
+            destination: recipient_token_account_1,
+            authority: pool_account_key,
+            amount: amount_1,
+        },
+    )?);
+    remaining_accounts.extend(callback_accounts);
     let instructions = program
         .request()
-        .accounts(raydium_accounts::ClosePosition {
-            nft_owner: program.payer(),
-            position_nft_mint: nft_mint_key,
-            position_nft_account: nft_ata_token_account,
-            personal_position: personal_position_key,
-            system_program: system_program::id(),
-            token_program: spl_token_2022::id(),
+        .accounts(raydium_accounts::Flash {
+            borrower: program.payer(),
+            amm_config,
+            pool_state: pool_account_key,
+            token_vault_0,
+            token_vault_1,
+            recipient_token_account_0,
+            recipient_token_account_1,
+            vault_0_mint: token_mint_0,
+            vault_1_mint: token_mint_1,
+            token_program_0,
+            token_program_1,
+            callback_program,
+        })
+        .accounts(remaining_accounts)
+        .args(raydium_instruction::Flash {
+            amount_0,
+            amount_1,
+            data,
         })
-        .args(raydium_instruction::ClosePosition)
         .instructions()?;
     Ok(instructions)
 }
 
-pub fn swap_instr(
+pub fn donate_instr(
     config: &ClientConfig,
-    amm_config: Pubkey,
     pool_account_key: Pubkey,
-    input_vault: Pubkey,
-    output_vault: Pubkey,
-    observation_state: Pubkey,
-    user_input_token: Pubkey,
-    user_out_put_token: Pubkey,
-    tick_array: Pubkey,
-    remaining_accounts: Vec<AccountMeta>,
-    amount: u64,
-    other_amount_threshold: u64,
-    sqrt_price_limit_x64: Option<u128>,
-    is_base_input: bool,
+    token_vault_0: Pubkey,
+    token_vault_1: Pubkey,
+    token_mint_0: Pubkey,
+    token_mint_1: Pubkey,
+    token_program_0: Pubkey,
+    token_program_1: Pubkey,
+    user_token_account_0: Pubkey,
+    user_token_account_1: Pubkey,
+    amount_0: u64,
+    amount_1: u64,
 ) -> Result<Vec<Instruction>> {
     let payer = read_keypair_file(&config.payer_path)?;
     let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
     // Client.
     let client = Client::new(url, Rc::new(payer));
     let program = client.program(config.raydium_v3_program);
+    let mut remaining_accounts = get_transfer_hook_account_metas(
+        &program.rpc(),
+        &token_mint_0,
+        &TransferHookTransfer {
+            source: user_token_account_0,
+            destination: token_vault_0,
+            authority: program.payer(),
+            amount: amount_0,
+        },
+    )?;
+    remaining_accounts.extend(get_transfer_hook_account_metas(
+        &program.rpc(),
+        &token_mint_1,
+        &TransferHookTransfer {
+            source: user_token_account_1,
+            destination: token_vault_1,
+            authority: program.payer(),
+            amount: amount_1,
+        },
+    )?);
     let instructions = program
         .request()
-        .accounts(raydium_accounts::SwapSingle {
-            payer: program.payer(),
-            amm_config,
+        .accounts(raydium_accounts::Donate {
+            donor: program.payer(),
             pool_state: pool_account_key,
-            input_token_account: user_input_token,
-            output_token_account: user_out_put_token,
-            input_vault,
-            output_vault,
-            tick_array,
-            observation_state,
-            token_program: spl_token_2022::id(),
+            token_account_0: user_token_account_0,
+            token_account_1: user_token_account_1,
+            token_vault_0,
+            token_vault_1,
+            vault_0_mint: token_mint_0,
+            vault_1_mint: token_mint_1,
+            token_program_0,
+            token_program_1,
         })
         .accounts(remaining_accounts)
-        .args(raydium_instruction::Swap {
-            amount,
-            other_amount_threshold,
-            sqrt_price_limit_x64: sqrt_price_limit_x64.unwrap_or(0u128),
-            is_base_input,
-        })
+        .args(raydium_instruction::Donate { amount_0, amount_1 })
         .instructions()?;
     Ok(instructions)
 }
 
-pub fn initialize_reward_instr(
+pub fn collect_fees_instr(
     config: &ClientConfig,
     pool_account_key: Pubkey,
-    amm_config: Pubkey,
-    operation_account_key: Pubkey,
-    reward_token_mint: Pubkey,
-    reward_token_vault: Pubkey,
-    user_reward_token: Pubkey,
-    open_time: u64,
-    end_time: u64,
-    emissions_per_second_x64: u128,
+    token_vault_0: Pubkey,
+    token_vault_1: Pubkey,
+    token_mint_0: Pubkey,
+    token_mint_1: Pubkey,
+    token_program_0: Pubkey,
+    token_program_1: Pubkey,
+    nft_mint_key: Pubkey,
+    recipient_token_account_0: Pubkey,
+    recipient_token_account_1: Pubkey,
+    amount_0_requested: u64,
+    amount_1_requested: u64,
+    recipient: Pubkey,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    tick_array_lower_start_index: i32,
+    tick_array_upper_start_index: i32,
 ) -> Result<Vec<Instruction>> {
-    let admin = read_keypair_file(&config.admin_path)?;
+    let payer = read_keypair_file(&config.payer_path)?;
     let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
     // Client.
-    let client = Client::new(url, Rc::new(admin));
+    let client = Client::new(url, Rc::new(payer));
     let program = client.program(config.raydium_v3_program);
-
-    let instructions = program
-        .request()
-        .accounts(raydium_accounts::InitializeReward {
-            reward_funder: program.payer(),
-            funder_token_account: user_reward_token,
-            amm_config,
-            pool_state: pool_account_key,
-            operation_state: operation_account_key,
-            reward_token_mint,
-            reward_token_vault,
-            token_program: spl_token_2022::id(),
-            system_program: system_program::id(),
+    let nft_ata_token_account =
+        spl_associated_token_account::get_associated_token_address(&program.payer(), &nft_mint_key);
+    let (personal_position_key, __bump) = Pubkey::find_program_address(
+        &[POSITION_SEED.as_bytes(), nft_mint_key.to_bytes().as_ref()],
+        &program.id(),
+    );
+    let (protocol_position_key, __bump) = Pubkey::find_program_address(
+        &[
+            POSITION_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+            &tick_lower_index.to_be_bytes(),
+            &tick_upper_index.to_be_bytes(),
+        ],
+        &program.id(),
+    );
+    let (tick_array_lower, __bump) = Pubkey::find_program_address(
+        &[
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+            &tick_array_lower_start_index.to_be_bytes(),
+        ],
+        &program.id(),
+    );
+    let (tick_array_upper, __bump) = Pubkey::find_program_address(
+        &[
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+            &tick_array_upper_start_index.to_be_bytes(),
+        ],
+        &program.id(),
+    );
+    // the transfer hook accounts of token_0 and token_1, if any, are the remaining accounts
+    let mut transfer_hook_accounts = get_transfer_hook_account_metas(
+        &program.rpc(),
+        &token_mint_0,
+        &TransferHookTransfer {
+            source: token_vault_0,
+            destination: recipient_token_account_0,
+            authority: pool_account_key,
+            amount: amount_0_requested,
+        },
+    )?;
+    transfer_hook_accounts.extend(get_transfer_hook_account_metas(
+        &program.rpc(),
+        &token_mint_1,
+        &TransferHookTransfer {
+            source: token_vault_1,
+            destination: recipient_token_account_1,
+            authority: pool_account_key,
+            amount: amount_1_requested,
+        },
+    )?);
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::CollectFees {
+            nft_owner: program.payer(),
+            nft_account: nft_ata_token_account,
+            personal_position: personal_position_key,
+            pool_state: pool_account_key,
+            protocol_position: protocol_position_key,
+            tick_array_lower,
+            tick_array_upper,
+            token_vault_0,
+            token_vault_1,
+            recipient_token_account_0,
+            recipient_token_account_1,
+            vault_0_mint: token_mint_0,
+            vault_1_mint: token_mint_1,
+            token_program_0,
+            token_program_1,
+        })
+        .accounts(transfer_hook_accounts)
+        .args(raydium_instruction::CollectFees {
+            amount_0_requested,
+            amount_1_requested,
+            recipient,
+        })
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn initialize_reward_schedule_instr(
+    config: &ClientConfig,
+    pool_account_key: Pubkey,
+    amm_config: Pubkey,
+    operation_account_key: Pubkey,
+    reward_token_mint: Pubkey,
+    reward_token_program: Pubkey,
+    reward_token_vault: Pubkey,
+    user_reward_token: Pubkey,
+    open_time: u64,
+    end_time: u64,
+    emissions_per_second_x64: u128,
+    phases: Vec<raydium_amm_v3::instructions::RewardPhaseParam>,
+) -> Result<Vec<Instruction>> {
+    let admin = read_keypair_file(&config.admin_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(admin));
+    let program = client.program(config.raydium_v3_program);
+
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::InitializeReward {
+            reward_funder: program.payer(),
+            funder_token_account: user_reward_token,
+            amm_config,
+            pool_state: pool_account_key,
+            operation_state: operation_account_key,
+            reward_token_mint,
+            reward_token_vault,
+            token_program: reward_token_program,
+            system_program: system_program::id(),
             rent: sysvar::rent::id(),
         })
-        .args(raydium_instruction::InitializeReward {
+        .args(raydium_instruction::InitializeRewardSchedule {
             param: raydium_amm_v3::instructions::InitializeRewardParam {
                 open_time,
                 end_time,
                 emissions_per_second_x64,
             },
+            phases,
         })
         .instructions()?;
     Ok(instructions)
 }
 
-pub fn set_reward_params_instr(
+pub fn initialize_extension_reward_instr(
     config: &ClientConfig,
-    amm_config: Pubkey,
     pool_account_key: Pubkey,
+    amm_config: Pubkey,
+    operation_account_key: Pubkey,
+    reward_token_mint: Pubkey,
+    reward_token_program: Pubkey,
     reward_token_vault: Pubkey,
     user_reward_token: Pubkey,
-    operation_account_key: Pubkey,
-    reward_index: u8,
+    reward_extension_index: u16,
     open_time: u64,
     end_time: u64,
     emissions_per_second_x64: u128,
@@ -571,50 +1105,1124 @@ pub fn set_reward_params_instr(
     // Client.
     let client = Client::new(url, Rc::new(admin));
     let program = client.program(config.raydium_v3_program);
-
-    let remaining_accounts = vec![
-        AccountMeta::new(reward_token_vault, false),
-        AccountMeta::new(user_reward_token, false),
-        AccountMeta::new_readonly(spl_token_2022::id(), false),
-    ];
+    let (pool_reward_extension, __bump) = Pubkey::find_program_address(
+        &[
+            POOL_REWARD_EXTENSION_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+            &reward_extension_index.to_be_bytes(),
+        ],
+        &program.id(),
+    );
 
     let instructions = program
         .request()
-        .accounts(raydium_accounts::SetRewardParams {
-            authority: program.payer(),
+        .accounts(raydium_accounts::InitializeExtensionReward {
+            reward_funder: program.payer(),
+            funder_token_account: user_reward_token,
             amm_config,
             pool_state: pool_account_key,
             operation_state: operation_account_key,
+            reward_token_mint,
+            reward_token_vault,
+            pool_reward_extension,
+            token_program: reward_token_program,
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
         })
-        .accounts(remaining_accounts)
-        .args(raydium_instruction::SetRewardParams {
-            reward_index,
-            emissions_per_second_x64,
-            open_time,
-            end_time,
+        .args(raydium_instruction::InitializeExtensionReward {
+            param: raydium_amm_v3::instructions::InitializeRewardParam {
+                open_time,
+                end_time,
+                emissions_per_second_x64,
+            },
         })
         .instructions()?;
     Ok(instructions)
 }
 
-pub fn transfer_reward_owner(
+pub fn collect_rewards_instr(
     config: &ClientConfig,
     pool_account_key: Pubkey,
-    new_owner: Pubkey,
+    nft_mint_key: Pubkey,
+    remaining_accounts: Vec<AccountMeta>,
+    reward_mask: u8,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    tick_array_lower_start_index: i32,
+    tick_array_upper_start_index: i32,
 ) -> Result<Vec<Instruction>> {
-    let admin = read_keypair_file(&config.admin_path)?;
+    let payer = read_keypair_file(&config.payer_path)?;
     let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
     // Client.
-    let client = Client::new(url, Rc::new(admin));
+    let client = Client::new(url, Rc::new(payer));
     let program = client.program(config.raydium_v3_program);
+    let nft_ata_token_account =
+        spl_associated_token_account::get_associated_token_address(&program.payer(), &nft_mint_key);
+    let (personal_position_key, __bump) = Pubkey::find_program_address(
+        &[POSITION_SEED.as_bytes(), nft_mint_key.to_bytes().as_ref()],
+        &program.id(),
+    );
+    let (protocol_position_key, __bump) = Pubkey::find_program_address(
+        &[
+            POSITION_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+            &tick_lower_index.to_be_bytes(),
+            &tick_upper_index.to_be_bytes(),
+        ],
+        &program.id(),
+    );
+    let (tick_array_lower, __bump) = Pubkey::find_program_address(
+        &[
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+            &tick_array_lower_start_index.to_be_bytes(),
+        ],
+        &program.id(),
+    );
+    let (tick_array_upper, __bump) = Pubkey::find_program_address(
+        &[
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+            &tick_array_upper_start_index.to_be_bytes(),
+        ],
+        &program.id(),
+    );
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::CollectRewards {
+            nft_owner: program.payer(),
+            nft_account: nft_ata_token_account,
+            personal_position: personal_position_key,
+            pool_state: pool_account_key,
+            protocol_position: protocol_position_key,
+            tick_array_lower,
+            tick_array_upper,
+        })
+        .accounts(get_pool_reward_extension_account_metas(
+            &program.rpc(),
+            &program.id(),
+            &pool_account_key,
+            Some(&program.payer()),
+        )?)
+        .accounts(remaining_accounts)
+        .args(raydium_instruction::CollectRewards { reward_mask })
+        .instructions()?;
+    Ok(instructions)
+}
 
+pub fn compound_position_instr(
+    config: &ClientConfig,
+    pool_account_key: Pubkey,
+    observation_key: Pubkey,
+    token_vault_0: Pubkey,
+    token_vault_1: Pubkey,
+    token_mint_0: Pubkey,
+    token_mint_1: Pubkey,
+    token_program_0: Pubkey,
+    token_program_1: Pubkey,
+    nft_owner: Pubkey,
+    nft_mint_key: Pubkey,
+    recipient_token_account_0: Pubkey,
+    recipient_token_account_1: Pubkey,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    tick_array_lower_start_index: i32,
+    tick_array_upper_start_index: i32,
+    liquidity_min: u128,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program);
+    let nft_ata_token_account =
+        spl_associated_token_account::get_associated_token_address(&nft_owner, &nft_mint_key);
+    let (personal_position_key, __bump) = Pubkey::find_program_address(
+        &[POSITION_SEED.as_bytes(), nft_mint_key.to_bytes().as_ref()],
+        &program.id(),
+    );
+    let (protocol_position_key, __bump) = Pubkey::find_program_address(
+        &[
+            POSITION_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+            &tick_lower_index.to_be_bytes(),
+            &tick_upper_index.to_be_bytes(),
+        ],
+        &program.id(),
+    );
+    let (tick_array_lower, __bump) = Pubkey::find_program_address(
+        &[
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+            &tick_array_lower_start_index.to_be_bytes(),
+        ],
+        &program.id(),
+    );
+    let (tick_array_upper, __bump) = Pubkey::find_program_address(
+        &[
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+            &tick_array_upper_start_index.to_be_bytes(),
+        ],
+        &program.id(),
+    );
+    // the tick array bitmap extension, if created, the reward extensions of the pool and the transfer
+    // hook accounts of token_0 and token_1, if any, are the remaining accounts
+    let mut transfer_hook_accounts = get_tick_array_bitmap_extension_account_metas(
+        &program.rpc(),
+        &program.id(),
+        &pool_account_key,
+    )?;
+    transfer_hook_accounts.extend(get_pool_reward_extension_account_metas(
+        &program.rpc(),
+        &program.id(),
+        &pool_account_key,
+        Some(&program.payer()),
+    )?);
+    transfer_hook_accounts.extend(get_transfer_hook_account_metas(
+        &program.rpc(),
+        &token_mint_0,
+        &TransferHookTransfer {
+            source: token_vault_0,
+            destination: recipient_token_account_0,
+            authority: pool_account_key,
+            amount: 0,
+        },
+    )?);
+    transfer_hook_accounts.extend(get_transfer_hook_account_metas(
+        &program.rpc(),
+        &token_mint_1,
+        &TransferHookTransfer {
+            source: token_vault_1,
+            destination: recipient_token_account_1,
+            authority: pool_account_key,
+            amount: 0,
+        },
+    )?);
     let instructions = program
         .request()
-        .accounts(raydium_accounts::TransferRewardOwner {
+        .accounts(raydium_accounts::CompoundPosition {
             authority: program.payer(),
+            nft_account: nft_ata_token_account,
             pool_state: pool_account_key,
+            protocol_position: protocol_position_key,
+            personal_position: personal_position_key,
+            tick_array_lower,
+            tick_array_upper,
+            token_vault_0,
+            token_vault_1,
+            recipient_token_account_0,
+            recipient_token_account_1,
+            vault_0_mint: token_mint_0,
+            vault_1_mint: token_mint_1,
+            token_program_0,
+            token_program_1,
+            observation_state: observation_key,
+        })
+        .accounts(transfer_hook_accounts)
+        .args(raydium_instruction::CompoundPosition { liquidity_min })
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn set_compound_keeper_instr(
+    config: &ClientConfig,
+    nft_mint_key: Pubkey,
+    compound_keeper: Pubkey,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program);
+    let nft_ata_token_account =
+        spl_associated_token_account::get_associated_token_address(&program.payer(), &nft_mint_key);
+    let (personal_position_key, __bump) = Pubkey::find_program_address(
+        &[POSITION_SEED.as_bytes(), nft_mint_key.to_bytes().as_ref()],
+        &program.id(),
+    );
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::SetCompoundKeeper {
+            nft_owner: program.payer(),
+            nft_account: nft_ata_token_account,
+            personal_position: personal_position_key,
+        })
+        .args(raydium_instruction::SetCompoundKeeper { compound_keeper })
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn set_range_order_instr(
+    config: &ClientConfig,
+    pool_account_key: Pubkey,
+    nft_mint_key: Pubkey,
+    range_order_type: u8,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program);
+    let nft_ata_token_account =
+        spl_associated_token_account::get_associated_token_address(&program.payer(), &nft_mint_key);
+    let (personal_position_key, __bump) = Pubkey::find_program_address(
+        &[POSITION_SEED.as_bytes(), nft_mint_key.to_bytes().as_ref()],
+        &program.id(),
+    );
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::SetRangeOrder {
+            nft_owner: program.payer(),
+            nft_account: nft_ata_token_account,
+            personal_position: personal_position_key,
+            pool_state: pool_account_key,
+        })
+        .args(raydium_instruction::SetRangeOrder { range_order_type })
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn settle_range_order_instr(
+    config: &ClientConfig,
+    pool_account_key: Pubkey,
+    observation_key: Pubkey,
+    nft_mint_key: Pubkey,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    tick_array_lower_start_index: i32,
+    tick_array_upper_start_index: i32,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program);
+    let (personal_position_key, __bump) = Pubkey::find_program_address(
+        &[POSITION_SEED.as_bytes(), nft_mint_key.to_bytes().as_ref()],
+        &program.id(),
+    );
+    let (protocol_position_key, __bump) = Pubkey::find_program_address(
+        &[
+            POSITION_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+            &tick_lower_index.to_be_bytes(),
+            &tick_upper_index.to_be_bytes(),
+        ],
+        &program.id(),
+    );
+    let (tick_array_lower, __bump) = Pubkey::find_program_address(
+        &[
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+            &tick_array_lower_start_index.to_be_bytes(),
+        ],
+        &program.id(),
+    );
+    let (tick_array_upper, __bump) = Pubkey::find_program_address(
+        &[
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+            &tick_array_upper_start_index.to_be_bytes(),
+        ],
+        &program.id(),
+    );
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::SettleRangeOrder {
+            personal_position: personal_position_key,
+            pool_state: pool_account_key,
+            protocol_position: protocol_position_key,
+            tick_array_lower,
+            tick_array_upper,
+            observation_state: observation_key,
+        })
+        .accounts(get_tick_array_bitmap_extension_account_metas(
+            &program.rpc(),
+            &program.id(),
+            &pool_account_key,
+        )?)
+        .accounts(get_pool_reward_extension_account_metas(
+            &program.rpc(),
+            &program.id(),
+            &pool_account_key,
+            Some(&program.payer()),
+        )?)
+        .args(raydium_instruction::SettleRangeOrder {})
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn close_personal_position_instr(
+    config: &ClientConfig,
+    pool_account_key: Pubkey,
+    nft_mint_key: Pubkey,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program);
+    let nft_ata_token_account =
+        spl_associated_token_account::get_associated_token_address(&program.payer(), &nft_mint_key);
+    let (personal_position_key, __bump) = Pubkey::find_program_address(
+        &[POSITION_SEED.as_bytes(), nft_mint_key.to_bytes().as_ref()],
+        &program.id(),
+    );
+    let (protocol_position_key, __bump) = Pubkey::find_program_address(
+        &[
+            POSITION_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+            &tick_lower_index.to_be_bytes(),
+            &tick_upper_index.to_be_bytes(),
+        ],
+        &program.id(),
+    );
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::ClosePosition {
+            nft_owner: program.payer(),
+            position_nft_mint: nft_mint_key,
+            position_nft_account: nft_ata_token_account,
+            personal_position: personal_position_key,
+            protocol_position: protocol_position_key,
+            system_program: system_program::id(),
+            token_program: spl_token_2022::id(),
+        })
+        .args(raydium_instruction::ClosePosition)
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn swap_instr(
+    config: &ClientConfig,
+    amm_config: Pubkey,
+    pool_account_key: Pubkey,
+    input_vault: Pubkey,
+    output_vault: Pubkey,
+    input_vault_mint: Pubkey,
+    output_vault_mint: Pubkey,
+    input_token_program: Pubkey,
+    output_token_program: Pubkey,
+    observation_state: Pubkey,
+    user_input_token: Pubkey,
+    user_out_put_token: Pubkey,
+    tick_array: Pubkey,
+    remaining_accounts: Vec<AccountMeta>,
+    amount: u64,
+    other_amount_threshold: u64,
+    sqrt_price_limit_x64: Option<u128>,
+    is_base_input: bool,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program);
+    // the transfer hook accounts of the input and output mint, if any, the tick array bitmap
+    // extension, if created, and the reward extensions of the pool come before the tick arrays
+    let mut transfer_hook_accounts = get_transfer_hook_account_metas(
+        &program.rpc(),
+        &input_vault_mint,
+        &TransferHookTransfer {
+            source: user_input_token,
+            destination: input_vault,
+            authority: program.payer(),
+            amount: amount,
+        },
+    )?;
+    transfer_hook_accounts.extend(get_transfer_hook_account_metas(
+        &program.rpc(),
+        &output_vault_mint,
+        &TransferHookTransfer {
+            source: output_vault,
+            destination: user_out_put_token,
+            authority: pool_account_key,
+            amount: other_amount_threshold,
+        },
+    )?);
+    transfer_hook_accounts.extend(get_tick_array_bitmap_extension_account_metas(
+        &program.rpc(),
+        &program.id(),
+        &pool_account_key,
+    )?);
+    transfer_hook_accounts.extend(get_pool_reward_extension_account_metas(
+        &program.rpc(),
+        &program.id(),
+        &pool_account_key,
+        None,
+    )?);
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::SwapSingle {
+            payer: program.payer(),
+            amm_config,
+            pool_state: pool_account_key,
+            input_token_account: user_input_token,
+            output_token_account: user_out_put_token,
+            input_vault,
+            output_vault,
+            input_vault_mint,
+            output_vault_mint,
+            tick_array,
+            observation_state,
+            input_token_program,
+            output_token_program,
+        })
+        .accounts(transfer_hook_accounts)
+        .accounts(remaining_accounts)
+        .args(raydium_instruction::Swap {
+            amount,
+            other_amount_threshold,
+            sqrt_price_limit_x64: sqrt_price_limit_x64.unwrap_or(0u128),
+            is_base_input,
+        })
+        .instructions()?;
+    Ok(instructions)
+}
+
+/// The remaining accounts of one pool of a `swap_router_base_in` or `swap_router_base_out` route,
+/// its tick array count is the number of `tick_array_start_indexs`
+pub fn swap_router_hop_accounts(
+    config: &ClientConfig,
+    pool_account_key: Pubkey,
+    pool_state: &PoolState,
+    zero_for_one: bool,
+    input_token_account: Pubkey,
+    output_token_account: Pubkey,
+    output_token_program: Pubkey,
+    tick_array_start_indexs: VecDeque<i32>,
+) -> Result<Vec<AccountMeta>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program);
+    let (input_vault, output_vault, input_mint, output_mint) = if zero_for_one {
+        (
+            pool_state.token_vault_0,
+            pool_state.token_vault_1,
+            pool_state.token_mint_0,
+            pool_state.token_mint_1,
+        )
+    } else {
+        (
+            pool_state.token_vault_1,
+            pool_state.token_vault_0,
+            pool_state.token_mint_1,
+            pool_state.token_mint_0,
+        )
+    };
+    let mut tick_arrays = tick_array_start_indexs.into_iter().map(|index| {
+        AccountMeta::new(
+            Pubkey::find_program_address(
+                &[
+                    TICK_ARRAY_SEED.as_bytes(),
+                    pool_account_key.to_bytes().as_ref(),
+                    &index.to_be_bytes(),
+                ],
+                &program.id(),
+            )
+            .0,
+            false,
+        )
+    });
+    let mut accounts = vec![
+        AccountMeta::new_readonly(pool_state.amm_config, false),
+        AccountMeta::new(pool_account_key, false),
+        AccountMeta::new(output_token_account, false),
+        AccountMeta::new(input_vault, false),
+        AccountMeta::new(output_vault, false),
+        AccountMeta::new_readonly(output_mint, false),
+        AccountMeta::new_readonly(output_token_program, false),
+        AccountMeta::new(pool_state.observation_key, false),
+    ];
+    accounts.extend(tick_arrays.next());
+    // the amounts of the hops are only known once swapped, the hook accounts are resolved without
+    accounts.extend(get_transfer_hook_account_metas(
+        &program.rpc(),
+        &input_mint,
+        &TransferHookTransfer {
+            source: input_token_account,
+            destination: input_vault,
+            authority: program.payer(),
+            amount: 0,
+        },
+    )?);
+    accounts.extend(get_transfer_hook_account_metas(
+        &program.rpc(),
+        &output_mint,
+        &TransferHookTransfer {
+            source: output_vault,
+            destination: output_token_account,
+            authority: pool_account_key,
+            amount: 0,
+        },
+    )?);
+    accounts.extend(get_tick_array_bitmap_extension_account_metas(
+        &program.rpc(),
+        &program.id(),
+        &pool_account_key,
+    )?);
+    accounts.extend(get_pool_reward_extension_account_metas(
+        &program.rpc(),
+        &program.id(),
+        &pool_account_key,
+        None,
+    )?);
+    accounts.extend(tick_arrays);
+    Ok(accounts)
+}
+
+pub fn swap_router_base_out_instr(
+    config: &ClientConfig,
+    input_token_account: Pubkey,
+    input_token_mint: Pubkey,
+    input_token_program: Pubkey,
+    route_accounts: Vec<AccountMeta>,
+    amount_out: u64,
+    amount_in_maximum: u64,
+    tick_array_counts: Vec<u8>,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program);
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::SwapRouterBaseOut {
+            payer: program.payer(),
+            input_token_account,
+            input_token_mint,
+            input_token_program,
+        })
+        .accounts(route_accounts)
+        .args(raydium_instruction::SwapRouterBaseOut {
+            amount_out,
+            amount_in_maximum,
+            tick_array_counts,
+        })
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn initialize_reward_instr(
+    config: &ClientConfig,
+    pool_account_key: Pubkey,
+    amm_config: Pubkey,
+    operation_account_key: Pubkey,
+    reward_token_mint: Pubkey,
+    reward_token_program: Pubkey,
+    reward_token_vault: Pubkey,
+    user_reward_token: Pubkey,
+    open_time: u64,
+    end_time: u64,
+    emissions_per_second_x64: u128,
+) -> Result<Vec<Instruction>> {
+    let admin = read_keypair_file(&config.admin_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(admin));
+    let program = client.program(config.raydium_v3_program);
+
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::InitializeReward {
+            reward_funder: program.payer(),
+            funder_token_account: user_reward_token,
+            amm_config,
+            pool_state: pool_account_key,
+            operation_state: operation_account_key,
+            reward_token_mint,
+            reward_token_vault,
+            token_program: reward_token_program,
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+        })
+        .args(raydium_instruction::InitializeReward {
+            param: raydium_amm_v3::instructions::InitializeRewardParam {
+                open_time,
+                end_time,
+                emissions_per_second_x64,
+            },
+        })
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn set_reward_params_instr(
+    config: &ClientConfig,
+    amm_config: Pubkey,
+    pool_account_key: Pubkey,
+    reward_token_mint: Pubkey,
+    reward_token_program: Pubkey,
+    reward_token_vault: Pubkey,
+    user_reward_token: Pubkey,
+    operation_account_key: Pubkey,
+    reward_index: u8,
+    open_time: u64,
+    end_time: u64,
+    emissions_per_second_x64: u128,
+) -> Result<Vec<Instruction>> {
+    let admin = read_keypair_file(&config.admin_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(admin));
+    let program = client.program(config.raydium_v3_program);
+
+    let remaining_accounts = vec![
+        AccountMeta::new(reward_token_vault, false),
+        AccountMeta::new(user_reward_token, false),
+        AccountMeta::new_readonly(reward_token_program, false),
+        AccountMeta::new_readonly(reward_token_mint, false),
+    ];
+
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::SetRewardParams {
+            authority: program.payer(),
+            amm_config,
+            pool_state: pool_account_key,
+            operation_state: operation_account_key,
+        })
+        .accounts(remaining_accounts)
+        .args(raydium_instruction::SetRewardParams {
+            reward_index,
+            emissions_per_second_x64,
+            open_time,
+            end_time,
+        })
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn fund_reward_instr(
+    config: &ClientConfig,
+    pool_account_key: Pubkey,
+    reward_token_mint: Pubkey,
+    reward_token_program: Pubkey,
+    reward_token_vault: Pubkey,
+    user_reward_token: Pubkey,
+    reward_index: u8,
+    amount: u64,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program);
+
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::FundReward {
+            funder: program.payer(),
+            funder_token_account: user_reward_token,
+            pool_state: pool_account_key,
+            reward_token_vault,
+            reward_vault_mint: reward_token_mint,
+            token_program: reward_token_program,
+        })
+        .accounts(get_transfer_hook_account_metas(
+            &program.rpc(),
+            &reward_token_mint,
+            &TransferHookTransfer {
+                source: user_reward_token,
+                destination: reward_token_vault,
+                authority: program.payer(),
+                amount: amount,
+            },
+        )?)
+        .args(raydium_instruction::FundReward {
+            reward_index,
+            amount,
+        })
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn cancel_reward_instr(
+    config: &ClientConfig,
+    pool_account_key: Pubkey,
+    reward_index: u8,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program);
+
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::CancelReward {
+            authority: program.payer(),
+            pool_state: pool_account_key,
+        })
+        .args(raydium_instruction::CancelReward { reward_index })
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn transfer_reward_owner(
+    config: &ClientConfig,
+    pool_account_key: Pubkey,
+    new_owner: Pubkey,
+) -> Result<Vec<Instruction>> {
+    let admin = read_keypair_file(&config.admin_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(admin));
+    let program = client.program(config.raydium_v3_program);
+
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::TransferRewardOwner {
+            authority: program.payer(),
+            pool_state: pool_account_key,
+        })
+        .args(raydium_instruction::TransferRewardOwner { new_owner })
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn accept_reward_owner_instr(
+    config: &ClientConfig,
+    pool_account_key: Pubkey,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program);
+
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::AcceptRewardOwner {
+            pending_owner: program.payer(),
+            pool_state: pool_account_key,
+        })
+        .args(raydium_instruction::AcceptRewardOwner {})
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn cancel_reward_owner_instr(
+    config: &ClientConfig,
+    pool_account_key: Pubkey,
+) -> Result<Vec<Instruction>> {
+    let admin = read_keypair_file(&config.admin_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(admin));
+    let program = client.program(config.raydium_v3_program);
+
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::CancelRewardOwner {
+            authority: program.payer(),
+            pool_state: pool_account_key,
+        })
+        .args(raydium_instruction::CancelRewardOwner {})
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn propose_reward_authority_instr(
+    config: &ClientConfig,
+    pool_account_key: Pubkey,
+    reward_index: u8,
+    new_authority: Pubkey,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program);
+
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::ProposeRewardAuthority {
+            authority: program.payer(),
+            pool_state: pool_account_key,
+        })
+        .args(raydium_instruction::ProposeRewardAuthority {
+            reward_index,
+            new_authority,
+        })
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn accept_reward_authority_instr(
+    config: &ClientConfig,
+    pool_account_key: Pubkey,
+    reward_index: u8,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program);
+
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::AcceptRewardAuthority {
+            pending_authority: program.payer(),
+            pool_state: pool_account_key,
+        })
+        .args(raydium_instruction::AcceptRewardAuthority { reward_index })
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn cancel_reward_authority_instr(
+    config: &ClientConfig,
+    pool_account_key: Pubkey,
+    reward_index: u8,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program);
+
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::CancelRewardAuthority {
+            authority: program.payer(),
+            pool_state: pool_account_key,
+        })
+        .args(raydium_instruction::CancelRewardAuthority { reward_index })
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn update_observation_update_duration_instr(
+    config: &ClientConfig,
+    pool_account_key: Pubkey,
+    observation_update_duration: u16,
+) -> Result<Vec<Instruction>> {
+    let admin = read_keypair_file(&config.admin_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(admin));
+    let program = client.program(config.raydium_v3_program);
+
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::UpdateObservationUpdateDuration {
+            authority: program.payer(),
+            pool_state: pool_account_key,
+        })
+        .args(raydium_instruction::UpdateObservationUpdateDuration {
+            observation_update_duration,
+        })
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn increase_observation_num_instr(
+    config: &ClientConfig,
+    pool_account_key: Pubkey,
+    observation_key: Pubkey,
+    observation_num_next: u16,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program);
+
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::IncreaseObservationNum {
+            payer: program.payer(),
+            pool_state: pool_account_key,
+            observation_state: observation_key,
+            system_program: system_program::id(),
+        })
+        .args(raydium_instruction::IncreaseObservationNum {
+            observation_num_next,
+        })
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn create_tick_array_bitmap_extension_instr(
+    config: &ClientConfig,
+    pool_account_key: Pubkey,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program);
+    let (tick_array_bitmap_extension, __bump) = Pubkey::find_program_address(
+        &[
+            TICK_ARRAY_BITMAP_EXTENSION_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+        ],
+        &program.id(),
+    );
+
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::CreateTickArrayBitmapExtension {
+            payer: program.payer(),
+            pool_state: pool_account_key,
+            tick_array_bitmap_extension,
+            system_program: system_program::id(),
+        })
+        .args(raydium_instruction::CreateTickArrayBitmapExtension {})
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn close_tick_array_instr(
+    config: &ClientConfig,
+    pool_account_key: Pubkey,
+    rent_receiver: Pubkey,
+    tick_array_start_index: i32,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program);
+    let (tick_array, __bump) = Pubkey::find_program_address(
+        &[
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+            &tick_array_start_index.to_be_bytes(),
+        ],
+        &program.id(),
+    );
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::CloseTickArray {
+            rent_receiver,
+            pool_state: pool_account_key,
+            tick_array,
+        })
+        .accounts(get_tick_array_bitmap_extension_account_metas(
+            &program.rpc(),
+            &program.id(),
+            &pool_account_key,
+        )?)
+        .args(raydium_instruction::CloseTickArray {})
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn close_protocol_position_instr(
+    config: &ClientConfig,
+    pool_account_key: Pubkey,
+    rent_receiver: Pubkey,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    tick_array_lower_start_index: i32,
+    tick_array_upper_start_index: i32,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program);
+    let (protocol_position, __bump) = Pubkey::find_program_address(
+        &[
+            POSITION_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+            &tick_lower_index.to_be_bytes(),
+            &tick_upper_index.to_be_bytes(),
+        ],
+        &program.id(),
+    );
+    let (tick_array_lower, __bump) = Pubkey::find_program_address(
+        &[
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+            &tick_array_lower_start_index.to_be_bytes(),
+        ],
+        &program.id(),
+    );
+    let (tick_array_upper, __bump) = Pubkey::find_program_address(
+        &[
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+            &tick_array_upper_start_index.to_be_bytes(),
+        ],
+        &program.id(),
+    );
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::CloseProtocolPosition {
+            rent_receiver,
+            pool_state: pool_account_key,
+            protocol_position,
+            tick_array_lower,
+            tick_array_upper,
+        })
+        .args(raydium_instruction::CloseProtocolPosition {})
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn set_dynamic_fee_params_instr(
+    config: &ClientConfig,
+    pool_account_key: Pubkey,
+    amm_config_key: Pubkey,
+    param: raydium_amm_v3::instructions::DynamicFeeParam,
+) -> Result<Vec<Instruction>> {
+    let admin = read_keypair_file(&config.admin_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(admin));
+    let program = client.program(config.raydium_v3_program);
+    let (operation_account_key, __bump) =
+        Pubkey::find_program_address(&[OPERATION_SEED.as_bytes()], &program.id());
+
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::SetDynamicFeeParams {
+            authority: program.payer(),
+            pool_state: pool_account_key,
+            amm_config: amm_config_key,
+            operation_state: operation_account_key,
+            timelock_config: get_timelock_config(&program.id()),
+        })
+        .args(raydium_instruction::SetDynamicFeeParams { param })
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn set_pool_trade_fee_rate_instr(
+    config: &ClientConfig,
+    pool_account_key: Pubkey,
+    amm_config_key: Pubkey,
+    trade_fee_rate_override: u32,
+) -> Result<Vec<Instruction>> {
+    let admin = read_keypair_file(&config.admin_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(admin));
+    let program = client.program(config.raydium_v3_program);
+    let (operation_account_key, __bump) =
+        Pubkey::find_program_address(&[OPERATION_SEED.as_bytes()], &program.id());
+
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::SetPoolTradeFeeRate {
+            authority: program.payer(),
+            pool_state: pool_account_key,
+            amm_config: amm_config_key,
+            operation_state: operation_account_key,
+            timelock_config: get_timelock_config(&program.id()),
+        })
+        .args(raydium_instruction::SetPoolTradeFeeRate {
+            trade_fee_rate_override,
         })
-        .args(raydium_instruction::TransferRewardOwner { new_owner })
         .instructions()?;
     Ok(instructions)
 }
@@ -1,13 +1,21 @@
 use std::collections::VecDeque;
 
+use anchor_lang::prelude::AccountMeta;
 use anchor_lang::AccountDeserialize;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use raydium_amm_v3::util::{
+    get_mint_extensions, EXTRA_ACCOUNT_METAS_SEED, EXTRA_ACCOUNT_META_LEN,
+    TRANSFER_HOOK_EXTENSION_TYPE,
+};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
 use raydium_amm_v3::libraries::fixed_point_64;
 use raydium_amm_v3::libraries::*;
 use raydium_amm_v3::states::*;
 use solana_sdk::account::Account;
 use std::ops::DerefMut;
 use std::ops::Neg;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 pub fn deserialize_anchor_account<T: AccountDeserialize>(account: &Account) -> Result<T> {
     let mut data: &[u8] = &account.data;
@@ -16,6 +24,257 @@ pub fn deserialize_anchor_account<T: AccountDeserialize>(account: &Account) -> R
 
 pub const Q_RATIO: f64 = 1.0001;
 
+/// Get the tick array bitmap extension of the pool if it is created, the program takes it from the
+/// front of the remaining accounts of the instructions that flip or search tick arrays
+pub fn get_tick_array_bitmap_extension_account_metas(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    pool_id: &Pubkey,
+) -> Result<Vec<AccountMeta>> {
+    let (tick_array_bitmap_extension, _) = Pubkey::find_program_address(
+        &[
+            TICK_ARRAY_BITMAP_EXTENSION_SEED.as_bytes(),
+            pool_id.as_ref(),
+        ],
+        program_id,
+    );
+    if rpc_client.get_account(&tick_array_bitmap_extension).is_err() {
+        return Ok(Vec::new());
+    }
+    Ok(vec![AccountMeta::new(tick_array_bitmap_extension, false)])
+}
+
+/// Get the reward extensions of the pool in index order, the program takes all of them right after
+/// the tick array bitmap extension in the instructions that change liquidity or cross ticks.
+/// The instructions changing positions take them followed by the system program and `rent_payer`,
+/// which pays the rent of their new position entries, swaps take no rent payer.
+pub fn get_pool_reward_extension_account_metas(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    pool_id: &Pubkey,
+    rent_payer: Option<&Pubkey>,
+) -> Result<Vec<AccountMeta>> {
+    let pool_state = deserialize_anchor_account::<PoolState>(&rpc_client.get_account(pool_id)?)?;
+    if pool_state.reward_extension_num == 0 {
+        return Ok(Vec::new());
+    }
+    let mut accounts: Vec<AccountMeta> = (0..pool_state.reward_extension_num)
+        .map(|index| {
+            let (pool_reward_extension, _) = Pubkey::find_program_address(
+                &[
+                    POOL_REWARD_EXTENSION_SEED.as_bytes(),
+                    pool_id.as_ref(),
+                    &index.to_be_bytes(),
+                ],
+                program_id,
+            );
+            AccountMeta::new(pool_reward_extension, false)
+        })
+        .collect();
+    if let Some(rent_payer) = rent_payer {
+        accounts.push(AccountMeta::new_readonly(
+            solana_sdk::system_program::id(),
+            false,
+        ));
+        accounts.push(AccountMeta::new(*rent_payer, true));
+    }
+    Ok(accounts)
+}
+
+/// Load the tick array bitmap extension of the pool to quote swaps, `None` if it is not created
+pub fn load_tick_array_bitmap_extension(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    pool_id: &Pubkey,
+) -> Option<TickArrayBitmapExtension> {
+    let (tick_array_bitmap_extension, _) = Pubkey::find_program_address(
+        &[
+            TICK_ARRAY_BITMAP_EXTENSION_SEED.as_bytes(),
+            pool_id.as_ref(),
+        ],
+        program_id,
+    );
+    let account = rpc_client.get_account(&tick_array_bitmap_extension).ok()?;
+    deserialize_anchor_account::<TickArrayBitmapExtension>(&account).ok()
+}
+
+/// Discriminator of the transfer hook interface `Execute` instruction,
+/// the first 8 bytes of sha256("spl-transfer-hook-interface:execute")
+const TRANSFER_HOOK_EXECUTE_DISCRIMINATOR: [u8; 8] = [105, 37, 101, 197, 75, 251, 102, 26];
+
+/// The transfer the transfer hook accounts are resolved for, the seeds of the extra accounts can
+/// refer to its accounts and instruction data
+pub struct TransferHookTransfer {
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    pub authority: Pubkey,
+    /// The amount of the transfer, only the seeds from the instruction data depend on it
+    pub amount: u64,
+}
+
+/// Get the accounts the transfer hook of `mint` requires, in the order the program expects them:
+/// `[hook_program, extra_account_meta_list, ...extra accounts]`. Empty if the mint has no hook.
+pub fn get_transfer_hook_account_metas(
+    rpc_client: &RpcClient,
+    mint: &Pubkey,
+    transfer: &TransferHookTransfer,
+) -> Result<Vec<AccountMeta>> {
+    let mint_account = rpc_client.get_account(mint)?;
+    if mint_account.owner != spl_token_2022::id() {
+        return Ok(Vec::new());
+    }
+    let mut hook_program_id = None;
+    for (extension_type, value) in
+        get_mint_extensions(&mint_account.data).map_err(|e| anyhow!("{:?}", e))?
+    {
+        if extension_type == TRANSFER_HOOK_EXTENSION_TYPE && value.len() == 64 {
+            let program_id = Pubkey::new(&value[32..64]);
+            if program_id != Pubkey::default() {
+                hook_program_id = Some(program_id);
+            }
+        }
+    }
+    let hook_program_id = match hook_program_id {
+        Some(hook_program_id) => hook_program_id,
+        None => return Ok(Vec::new()),
+    };
+    let (extra_account_meta_list, _) = Pubkey::find_program_address(
+        &[EXTRA_ACCOUNT_METAS_SEED.as_bytes(), mint.as_ref()],
+        &hook_program_id,
+    );
+    // the accounts and data of the execute instruction the hook program receives, the seeds of the
+    // extra accounts refer to them by index, the extra accounts resolved before included
+    let mut execute_accounts = vec![
+        AccountMeta::new_readonly(transfer.source, false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new_readonly(transfer.destination, false),
+        AccountMeta::new_readonly(transfer.authority, false),
+        AccountMeta::new_readonly(extra_account_meta_list, false),
+    ];
+    let mut execute_data = TRANSFER_HOOK_EXECUTE_DISCRIMINATOR.to_vec();
+    execute_data.extend_from_slice(&transfer.amount.to_le_bytes());
+
+    let data = rpc_client.get_account_data(&extra_account_meta_list)?;
+    if data.len() >= 16 {
+        let count = u32::from_le_bytes(data[12..16].try_into().unwrap()) as usize;
+        for i in 0..count {
+            let offset = 16 + i * EXTRA_ACCOUNT_META_LEN;
+            let meta = data
+                .get(offset..offset + EXTRA_ACCOUNT_META_LEN)
+                .ok_or(anyhow!("invalid extra account meta list"))?;
+            let pubkey = match meta[0] {
+                0 => Pubkey::new(&meta[1..33]),
+                // a PDA of the hook program, or of the program at the account index of the
+                // discriminator past 128
+                discriminator => {
+                    let program_id = if discriminator == 1 {
+                        hook_program_id
+                    } else if discriminator >= 128 {
+                        execute_accounts
+                            .get(usize::from(discriminator - 128))
+                            .ok_or(anyhow!("invalid extra account meta program index"))?
+                            .pubkey
+                    } else {
+                        return Err(anyhow!("unsupported transfer hook extra account meta"));
+                    };
+                    let seeds = resolve_extra_account_meta_seeds(
+                        rpc_client,
+                        &meta[1..33],
+                        &execute_accounts,
+                        &execute_data,
+                    )?;
+                    let seeds = seeds.iter().map(Vec::as_slice).collect::<Vec<&[u8]>>();
+                    Pubkey::find_program_address(&seeds, &program_id).0
+                }
+            };
+            execute_accounts.push(if meta[34] != 0 {
+                AccountMeta::new(pubkey, meta[33] != 0)
+            } else {
+                AccountMeta::new_readonly(pubkey, meta[33] != 0)
+            });
+        }
+    }
+    let mut account_metas = vec![
+        AccountMeta::new_readonly(hook_program_id, false),
+        AccountMeta::new_readonly(extra_account_meta_list, false),
+    ];
+    account_metas.extend(execute_accounts.split_off(5));
+    Ok(account_metas)
+}
+
+/// Resolve the packed seeds of a seed derived extra account meta, each seed is a literal, a slice of
+/// the instruction data, the key of an account or a slice of the data of an account
+fn resolve_extra_account_meta_seeds(
+    rpc_client: &RpcClient,
+    address_config: &[u8],
+    execute_accounts: &[AccountMeta],
+    execute_data: &[u8],
+) -> Result<Vec<Vec<u8>>> {
+    let invalid_seed = || anyhow!("invalid transfer hook extra account meta seed");
+    let get_account = |index: u8| -> Result<Pubkey> {
+        Ok(execute_accounts
+            .get(usize::from(index))
+            .ok_or_else(invalid_seed)?
+            .pubkey)
+    };
+    let mut seeds = Vec::new();
+    let mut config = address_config;
+    while let Some((&seed_type, rest)) = config.split_first() {
+        config = rest;
+        match seed_type {
+            // the seeds end at the first uninitialized byte
+            0 => break,
+            // literal: length, bytes
+            1 => {
+                let length = usize::from(*config.first().ok_or_else(invalid_seed)?);
+                seeds.push(config.get(1..1 + length).ok_or_else(invalid_seed)?.to_vec());
+                config = &config[1 + length..];
+            }
+            // instruction data: index, length
+            2 => {
+                let (index, length) = match config {
+                    [index, length, ..] => (usize::from(*index), usize::from(*length)),
+                    _ => return Err(invalid_seed()),
+                };
+                seeds.push(
+                    execute_data
+                        .get(index..index + length)
+                        .ok_or_else(invalid_seed)?
+                        .to_vec(),
+                );
+                config = &config[2..];
+            }
+            // account key: account index
+            3 => {
+                let index = *config.first().ok_or_else(invalid_seed)?;
+                seeds.push(get_account(index)?.to_bytes().to_vec());
+                config = &config[1..];
+            }
+            // account data: account index, data index, length
+            4 => {
+                let (account_index, data_index, length) = match config {
+                    [account_index, data_index, length, ..] => (
+                        *account_index,
+                        usize::from(*data_index),
+                        usize::from(*length),
+                    ),
+                    _ => return Err(invalid_seed()),
+                };
+                let account_data = rpc_client.get_account_data(&get_account(account_index)?)?;
+                seeds.push(
+                    account_data
+                        .get(data_index..data_index + length)
+                        .ok_or_else(invalid_seed)?
+                        .to_vec(),
+                );
+                config = &config[3..];
+            }
+            _ => return Err(invalid_seed()),
+        }
+    }
+    Ok(seeds)
+}
+
 pub fn tick_to_price(tick: i32) -> f64 {
     Q_RATIO.powi(tick)
 }
@@ -96,21 +355,23 @@ pub fn get_out_put_amount_and_remaining_accounts(
     is_base_input: bool,
     pool_config: &AmmConfig,
     pool_state: &PoolState,
+    tick_array_bitmap_extension: Option<&TickArrayBitmapExtension>,
     tick_arrays: &mut VecDeque<TickArrayState>,
 ) -> Result<(u64, VecDeque<i32>), &'static str> {
     let (is_pool_current_tick_array, current_vaild_tick_array_start_index) = pool_state
-        .get_first_initialized_tick_array(zero_for_one)
+        .get_first_initialized_tick_array(tick_array_bitmap_extension, zero_for_one)
         .unwrap();
 
     let (amount_calculated, tick_array_start_index_vec) = swap_compute(
         zero_for_one,
         is_base_input,
         is_pool_current_tick_array,
-        pool_config.trade_fee_rate,
+        pool_state.get_trade_fee_rate(pool_config.trade_fee_rate),
         input_amount,
         current_vaild_tick_array_start_index,
         sqrt_price_limit_x64.unwrap_or(0),
         pool_state,
+        tick_array_bitmap_extension,
         tick_arrays,
     )?;
     println!("tick_array_start_index:{:?}", tick_array_start_index_vec);
@@ -118,6 +379,42 @@ pub fn get_out_put_amount_and_remaining_accounts(
     Ok((amount_calculated, tick_array_start_index_vec))
 }
 
+/// A pool of a swap route with the states required to quote it
+pub struct RouteHop {
+    pub amm_config: AmmConfig,
+    pub pool_state: PoolState,
+    pub tick_array_bitmap_extension: Option<TickArrayBitmapExtension>,
+    pub tick_arrays: VecDeque<TickArrayState>,
+    pub zero_for_one: bool,
+}
+
+/// Quote a `swap_router_base_out` route from the output side, the amount a pool must output is
+/// the amount the next pool takes in. Return the amount in of the route and the tick array start
+/// indexes of each pool, transfer fees of token program 2022 mints are not included.
+pub fn get_route_base_out_amount_in(
+    amount_out: u64,
+    hops: &mut [RouteHop],
+) -> Result<(u64, Vec<VecDeque<i32>>), &'static str> {
+    let mut amount = amount_out;
+    let mut tick_array_indexs = Vec::with_capacity(hops.len());
+    for hop in hops.iter_mut().rev() {
+        let (amount_in, hop_tick_array_indexs) = get_out_put_amount_and_remaining_accounts(
+            amount,
+            None,
+            hop.zero_for_one,
+            false,
+            &hop.amm_config,
+            &hop.pool_state,
+            hop.tick_array_bitmap_extension.as_ref(),
+            &mut hop.tick_arrays,
+        )?;
+        amount = amount_in;
+        tick_array_indexs.push(hop_tick_array_indexs);
+    }
+    tick_array_indexs.reverse();
+    Ok((amount, tick_array_indexs))
+}
+
 fn swap_compute(
     zero_for_one: bool,
     is_base_input: bool,
@@ -127,6 +424,7 @@ fn swap_compute(
     current_vaild_tick_array_start_index: i32,
     sqrt_price_limit_x64: u128,
     pool_state: &PoolState,
+    tick_array_bitmap_extension: Option<&TickArrayBitmapExtension>,
     tick_arrays: &mut VecDeque<TickArrayState>,
 ) -> Result<(u64, VecDeque<i32>), &'static str> {
     if amount_specified == 0 {
@@ -158,6 +456,17 @@ fn swap_compute(
     }
     let mut tick_match_current_tick_array = is_pool_current_tick_array;
 
+    // the dynamic fee of the pool grows with the price movement the same as the program does
+    let mut dynamic_fee_info = pool_state.dynamic_fee_info;
+    let dynamic_fee_enabled = dynamic_fee_info.is_enabled();
+    if dynamic_fee_enabled {
+        let block_timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        dynamic_fee_info.update_references(pool_state.tick_current, block_timestamp);
+    }
+
     let mut state = SwapState {
         amount_specified_remaining: amount_specified,
         amount_calculated: 0,
@@ -206,10 +515,12 @@ fn swap_compute(
             let current_vaild_tick_array_start_index =
                 tick_array_bit_map::next_initialized_tick_array_start_index(
                     U1024(pool_state.tick_array_bitmap),
+                    tick_array_bitmap_extension,
                     current_vaild_tick_array_start_index,
                     pool_state.tick_spacing.into(),
                     zero_for_one,
                 )
+                .map_err(|_| "tick array bitmap extension is required")?
                 .unwrap();
             tick_array_current = tick_arrays.pop_front().unwrap();
             if tick_array_current.start_tick_index != current_vaild_tick_array_start_index {
@@ -239,15 +550,17 @@ fn swap_compute(
         } else {
             step.sqrt_price_next_x64
         };
-        let swap_step = swap_math::compute_swap_step(
-            state.sqrt_price_x64,
-            target_price,
-            state.liquidity,
-            state.amount_specified_remaining,
-            fee,
-            is_base_input,
-            zero_for_one,
-        );
+        let swap_step = dynamic_fee_info
+            .compute_swap_step(
+                fee,
+                state.sqrt_price_x64,
+                target_price,
+                state.liquidity,
+                state.amount_specified_remaining,
+                is_base_input,
+                zero_for_one,
+            )
+            .unwrap();
         state.sqrt_price_x64 = swap_step.sqrt_price_next_x64;
         step.amount_in = swap_step.amount_in;
         step.amount_out = swap_step.amount_out;
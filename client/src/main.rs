@@ -38,7 +38,7 @@ use instructions::token_instructions::*;
 use instructions::utils::*;
 use raydium_amm_v3::{
     libraries::{fixed_point_64, liquidity_math, tick_array_bit_map, tick_math},
-    states::{PoolState, TickArrayState},
+    states::{AdminAction, PoolState, TickArrayBitmapExtension, TickArrayState},
 };
 
 use crate::instructions::utils;
@@ -170,10 +170,11 @@ fn load_cur_and_next_five_tick_array(
     rpc_client: &RpcClient,
     pool_config: &ClientConfig,
     pool_state: &PoolState,
+    tick_array_bitmap_extension: Option<&TickArrayBitmapExtension>,
     zero_for_one: bool,
 ) -> VecDeque<TickArrayState> {
     let (_, mut current_vaild_tick_array_start_index) = pool_state
-        .get_first_initialized_tick_array(zero_for_one)
+        .get_first_initialized_tick_array(tick_array_bitmap_extension, zero_for_one)
         .unwrap();
     let mut tick_array_keys = Vec::new();
     tick_array_keys.push(
@@ -191,10 +192,12 @@ fn load_cur_and_next_five_tick_array(
     while max_array_size != 0 {
         let next_tick_array_index = tick_array_bit_map::next_initialized_tick_array_start_index(
             raydium_amm_v3::libraries::U1024(pool_state.tick_array_bitmap),
+            tick_array_bitmap_extension,
             current_vaild_tick_array_start_index,
             pool_state.tick_spacing.into(),
             zero_for_one,
-        );
+        )
+        .unwrap();
         if next_tick_array_index.is_none() {
             break;
         }
@@ -552,25 +555,13 @@ fn main() -> Result<()> {
                 if v.len() == 4 {
                     let config_index = v[1].parse::<u16>().unwrap();
                     let param = v[2].parse::<u8>().unwrap();
-                    let mut remaing_accounts = Vec::new();
                     let mut value = 0;
                     let match_param = Some(param);
                     match match_param {
                         Some(0) => value = v[3].parse::<u32>().unwrap(),
                         Some(1) => value = v[3].parse::<u32>().unwrap(),
                         Some(2) => value = v[3].parse::<u32>().unwrap(),
-                        Some(3) => {
-                            remaing_accounts.push(AccountMeta::new_readonly(
-                                Pubkey::from_str(&v[3]).unwrap(),
-                                false,
-                            ));
-                        }
-                        Some(4) => {
-                            remaing_accounts.push(AccountMeta::new_readonly(
-                                Pubkey::from_str(&v[3]).unwrap(),
-                                false,
-                            ));
-                        }
+                        // the owners change through propose_cfg_owner
                         _ => panic!("error input"),
                     }
                     let (amm_config_key, __bump) = Pubkey::find_program_address(
@@ -583,7 +574,6 @@ fn main() -> Result<()> {
                     let update_amm_config_instr = update_amm_config_instr(
                         &pool_config.clone(),
                         amm_config_key,
-                        remaing_accounts,
                         param,
                         value,
                     )?;
@@ -602,6 +592,178 @@ fn main() -> Result<()> {
                     println!("invalid command: [set_new_cfg_owner config_index new_owner]");
                 }
             }
+            "propose_cfg_owner" | "accept_cfg_owner" | "cancel_cfg_owner" => {
+                if (v[0] == "propose_cfg_owner" && v.len() == 4) || v.len() == 3 {
+                    let config_index = v[1].parse::<u16>().unwrap();
+                    let owner_type = match v[2].as_str() {
+                        "owner" => raydium_amm_v3::states::AmmConfigOwnerType::Owner,
+                        "fund_owner" => raydium_amm_v3::states::AmmConfigOwnerType::FundOwner,
+                        _ => panic!("error input"),
+                    };
+                    let (amm_config_key, __bump) = Pubkey::find_program_address(
+                        &[
+                            raydium_amm_v3::states::AMM_CONFIG_SEED.as_bytes(),
+                            &config_index.to_be_bytes(),
+                        ],
+                        &pool_config.raydium_v3_program,
+                    );
+                    let (signers, instr) = match v[0].as_str() {
+                        "propose_cfg_owner" => (
+                            vec![&payer, &admin],
+                            propose_amm_config_owner_instr(
+                                &pool_config.clone(),
+                                amm_config_key,
+                                owner_type,
+                                Pubkey::from_str(&v[3]).unwrap(),
+                            )?,
+                        ),
+                        // the proposed owner signs as the payer
+                        "accept_cfg_owner" => (
+                            vec![&payer],
+                            accept_amm_config_owner_instr(
+                                &pool_config.clone(),
+                                amm_config_key,
+                                owner_type,
+                            )?,
+                        ),
+                        _ => (
+                            vec![&payer, &admin],
+                            cancel_amm_config_owner_instr(
+                                &pool_config.clone(),
+                                amm_config_key,
+                                owner_type,
+                            )?,
+                        ),
+                    };
+                    // send
+                    let recent_hash = rpc_client.get_latest_blockhash()?;
+                    let txn = Transaction::new_signed_with_payer(
+                        &instr,
+                        Some(&payer.pubkey()),
+                        &signers,
+                        recent_hash,
+                    );
+                    let signature = send_txn(&rpc_client, &txn, true)?;
+                    println!("{}", signature);
+                } else {
+                    println!("invalid command: [propose_cfg_owner config_index owner|fund_owner new_owner], [accept_cfg_owner|cancel_cfg_owner config_index owner|fund_owner]");
+                }
+            }
+            "set_timelock_delay" => {
+                if v.len() == 2 {
+                    let delay = v[1].parse::<u64>().unwrap();
+                    let set_timelock_delay_instr =
+                        set_timelock_delay_instr(&pool_config.clone(), delay)?;
+                    // send
+                    let signers = vec![&payer, &admin];
+                    let recent_hash = rpc_client.get_latest_blockhash()?;
+                    let txn = Transaction::new_signed_with_payer(
+                        &set_timelock_delay_instr,
+                        Some(&payer.pubkey()),
+                        &signers,
+                        recent_hash,
+                    );
+                    let signature = send_txn(&rpc_client, &txn, true)?;
+                    println!("{}", signature);
+                } else {
+                    println!("invalid command: [set_timelock_delay delay]");
+                }
+            }
+            "queue_admin_action" => {
+                if v.len() == 3 || v.len() == 4 || v.len() == 9 {
+                    let get_amm_config = |config_index: &str| {
+                        Pubkey::find_program_address(
+                            &[
+                                raydium_amm_v3::states::AMM_CONFIG_SEED.as_bytes(),
+                                &config_index.parse::<u16>().unwrap().to_be_bytes(),
+                            ],
+                            &pool_config.raydium_v3_program,
+                        )
+                        .0
+                    };
+                    let action = match (v[1].as_str(), v.len()) {
+                        ("trade_fee_rate", 4) => AdminAction::SetTradeFeeRate {
+                            amm_config: get_amm_config(&v[2]),
+                            trade_fee_rate: v[3].parse::<u32>().unwrap(),
+                        },
+                        ("protocol_fee_rate", 4) => AdminAction::SetProtocolFeeRate {
+                            amm_config: get_amm_config(&v[2]),
+                            protocol_fee_rate: v[3].parse::<u32>().unwrap(),
+                        },
+                        ("fund_fee_rate", 4) => AdminAction::SetFundFeeRate {
+                            amm_config: get_amm_config(&v[2]),
+                            fund_fee_rate: v[3].parse::<u32>().unwrap(),
+                        },
+                        ("pool_status", 4) => AdminAction::UpdatePoolStatus {
+                            pool_state: Pubkey::from_str(&v[2]).unwrap(),
+                            status: v[3].parse::<u8>().unwrap(),
+                        },
+                        ("pool_trade_fee_rate", 4) => AdminAction::SetPoolTradeFeeRate {
+                            pool_state: Pubkey::from_str(&v[2]).unwrap(),
+                            trade_fee_rate_override: v[3].parse::<u32>().unwrap(),
+                        },
+                        ("dynamic_fee_params", 9) => AdminAction::SetDynamicFeeParams {
+                            pool_state: Pubkey::from_str(&v[2]).unwrap(),
+                            param: raydium_amm_v3::instructions::DynamicFeeParam {
+                                max_fee_rate: v[3].parse::<u32>().unwrap(),
+                                dynamic_fee_control: v[4].parse::<u32>().unwrap(),
+                                max_volatility_accumulator: v[5].parse::<u32>().unwrap(),
+                                filter_period: v[6].parse::<u16>().unwrap(),
+                                decay_period: v[7].parse::<u16>().unwrap(),
+                                reduction_factor: v[8].parse::<u16>().unwrap(),
+                            },
+                        },
+                        ("timelock_delay", 3) => AdminAction::SetTimelockDelay {
+                            delay: v[2].parse::<u64>().unwrap(),
+                        },
+                        _ => panic!("error input"),
+                    };
+                    let queue_admin_action_instr =
+                        queue_admin_action_instr(&pool_config.clone(), action)?;
+                    // send
+                    let signers = vec![&payer, &admin];
+                    let recent_hash = rpc_client.get_latest_blockhash()?;
+                    let txn = Transaction::new_signed_with_payer(
+                        &queue_admin_action_instr,
+                        Some(&payer.pubkey()),
+                        &signers,
+                        recent_hash,
+                    );
+                    let signature = send_txn(&rpc_client, &txn, true)?;
+                    println!("{}", signature);
+                } else {
+                    println!("invalid command: [queue_admin_action trade_fee_rate|protocol_fee_rate|fund_fee_rate config_index rate], [queue_admin_action pool_status pool_id status], [queue_admin_action pool_trade_fee_rate pool_id rate], [queue_admin_action dynamic_fee_params pool_id max_fee_rate dynamic_fee_control max_volatility_accumulator filter_period decay_period reduction_factor], [queue_admin_action timelock_delay delay]");
+                }
+            }
+            "cancel_admin_action" | "execute_admin_action" => {
+                if v.len() == 2 {
+                    let id = v[1].parse::<u64>().unwrap();
+                    let (signers, instr) = if v[0] == "cancel_admin_action" {
+                        (
+                            vec![&payer, &admin],
+                            cancel_admin_action_instr(&pool_config.clone(), id)?,
+                        )
+                    } else {
+                        // anyone can execute once the delay passes
+                        (
+                            vec![&payer],
+                            execute_admin_action_instr(&pool_config.clone(), id)?,
+                        )
+                    };
+                    // send
+                    let recent_hash = rpc_client.get_latest_blockhash()?;
+                    let txn = Transaction::new_signed_with_payer(
+                        &instr,
+                        Some(&payer.pubkey()),
+                        &signers,
+                        recent_hash,
+                    );
+                    let signature = send_txn(&rpc_client, &txn, true)?;
+                    println!("{}", signature);
+                } else {
+                    println!("invalid command: [cancel_admin_action|execute_admin_action id]");
+                }
+            }
             "cmp_key" => {
                 if v.len() == 3 {
                     let mut token_mint_0 = Pubkey::from_str(&v[1]).unwrap();
@@ -726,6 +888,8 @@ fn main() -> Result<()> {
                         observation_account.pubkey(),
                         mint0,
                         mint1,
+                        rsps[0].as_ref().unwrap().owner,
+                        rsps[1].as_ref().unwrap().owner,
                         sqrt_price_x64,
                         open_time,
                     )?;
@@ -907,6 +1071,7 @@ fn main() -> Result<()> {
                         pool_account.amm_config,
                         operator_account_key,
                         reward_token_mint,
+                        rpc_client.get_account(&reward_token_mint)?.owner,
                         reward_token_vault,
                         user_reward_token,
                         open_time,
@@ -928,6 +1093,195 @@ fn main() -> Result<()> {
                     println!("invalid command: [init_reward open_time, end_time, emissions_per_second_x64, reward_token_mint]");
                 }
             }
+            "init_reward_schedule" => {
+                if v.len() >= 5 && v.len() % 2 == 1 {
+                    let open_time = v[1].parse::<u64>().unwrap();
+                    let end_time = v[2].parse::<u64>().unwrap();
+                    // emissions_per_second is mul 10^^decimals
+                    let emissions_per_second = v[3].parse::<f64>().unwrap();
+                    let reward_token_mint = Pubkey::from_str(&v[4]).unwrap();
+                    // each phase is a start_time followed by its emissions_per_second
+                    let phases = v[5..]
+                        .chunks(2)
+                        .map(|phase| raydium_amm_v3::instructions::RewardPhaseParam {
+                            start_time: phase[0].parse::<u64>().unwrap(),
+                            emissions_per_second_x64: (phase[1].parse::<f64>().unwrap()
+                                * fixed_point_64::Q64 as f64)
+                                as u128,
+                        })
+                        .collect::<Vec<_>>();
+
+                    let emissions_per_second_x64 =
+                        (emissions_per_second * fixed_point_64::Q64 as f64) as u128;
+
+                    let program = anchor_client.program(pool_config.raydium_v3_program);
+                    let pool_account: raydium_amm_v3::states::PoolState =
+                        program.account(pool_config.pool_id_account.unwrap())?;
+                    let operator_account_key = Pubkey::find_program_address(
+                        &[raydium_amm_v3::states::OPERATION_SEED.as_bytes()],
+                        &program.id(),
+                    )
+                        .0;
+
+                    let reward_token_vault = Pubkey::find_program_address(
+                        &[
+                            raydium_amm_v3::states::POOL_REWARD_VAULT_SEED.as_bytes(),
+                            pool_config.pool_id_account.unwrap().to_bytes().as_ref(),
+                            reward_token_mint.to_bytes().as_ref(),
+                        ],
+                        &program.id(),
+                    )
+                        .0;
+                    let user_reward_token =
+                        get_associated_token_address(&admin.pubkey(), &reward_token_mint);
+                    let create_instr = initialize_reward_schedule_instr(
+                        &pool_config.clone(),
+                        pool_config.pool_id_account.unwrap(),
+                        pool_account.amm_config,
+                        operator_account_key,
+                        reward_token_mint,
+                        rpc_client.get_account(&reward_token_mint)?.owner,
+                        reward_token_vault,
+                        user_reward_token,
+                        open_time,
+                        end_time,
+                        emissions_per_second_x64,
+                        phases,
+                    )?;
+                    // send
+                    let signers = vec![&payer, &admin];
+                    let recent_hash = rpc_client.get_latest_blockhash()?;
+                    let txn = Transaction::new_signed_with_payer(
+                        &create_instr,
+                        Some(&payer.pubkey()),
+                        &signers,
+                        recent_hash,
+                    );
+                    let signature = send_txn(&rpc_client, &txn, true)?;
+                    println!("{}", signature);
+                } else {
+                    println!("invalid command: [init_reward_schedule open_time, end_time, emissions_per_second_x64, reward_token_mint, [phase_start_time, phase_emissions_per_second]...]");
+                }
+            }
+            "init_extension_reward" => {
+                if v.len() == 5 {
+                    let open_time = v[1].parse::<u64>().unwrap();
+                    let end_time = v[2].parse::<u64>().unwrap();
+                    // emissions_per_second is mul 10^^decimals
+                    let emissions_per_second = v[3].parse::<f64>().unwrap();
+                    let reward_token_mint = Pubkey::from_str(&v[4]).unwrap();
+
+                    let emissions_per_second_x64 =
+                        (emissions_per_second * fixed_point_64::Q64 as f64) as u128;
+
+                    let program = anchor_client.program(pool_config.raydium_v3_program);
+                    let pool_account: raydium_amm_v3::states::PoolState =
+                        program.account(pool_config.pool_id_account.unwrap())?;
+                    let operator_account_key = Pubkey::find_program_address(
+                        &[raydium_amm_v3::states::OPERATION_SEED.as_bytes()],
+                        &program.id(),
+                    )
+                        .0;
+
+                    let reward_token_vault = Pubkey::find_program_address(
+                        &[
+                            raydium_amm_v3::states::POOL_REWARD_VAULT_SEED.as_bytes(),
+                            pool_config.pool_id_account.unwrap().to_bytes().as_ref(),
+                            reward_token_mint.to_bytes().as_ref(),
+                        ],
+                        &program.id(),
+                    )
+                        .0;
+                    let user_reward_token =
+                        get_associated_token_address(&admin.pubkey(), &reward_token_mint);
+                    let create_instr = initialize_extension_reward_instr(
+                        &pool_config.clone(),
+                        pool_config.pool_id_account.unwrap(),
+                        pool_account.amm_config,
+                        operator_account_key,
+                        reward_token_mint,
+                        rpc_client.get_account(&reward_token_mint)?.owner,
+                        reward_token_vault,
+                        user_reward_token,
+                        pool_account.reward_extension_num,
+                        open_time,
+                        end_time,
+                        emissions_per_second_x64,
+                    )?;
+                    // send
+                    let signers = vec![&payer, &admin];
+                    let recent_hash = rpc_client.get_latest_blockhash()?;
+                    let txn = Transaction::new_signed_with_payer(
+                        &create_instr,
+                        Some(&payer.pubkey()),
+                        &signers,
+                        recent_hash,
+                    );
+                    let signature = send_txn(&rpc_client, &txn, true)?;
+                    println!("{}", signature);
+                } else {
+                    println!("invalid command: [init_extension_reward open_time, end_time, emissions_per_second_x64, reward_token_mint]");
+                }
+            }
+            "fund_reward" => {
+                if v.len() == 3 {
+                    let index = v[1].parse::<u8>().unwrap();
+                    let amount = v[2].parse::<u64>().unwrap();
+
+                    let program = anchor_client.program(pool_config.raydium_v3_program);
+                    let pool_account: raydium_amm_v3::states::PoolState =
+                        program.account(pool_config.pool_id_account.unwrap())?;
+                    let reward_info = pool_account.reward_infos[index as usize];
+                    let user_reward_token =
+                        get_associated_token_address(&payer.pubkey(), &reward_info.token_mint);
+                    let fund_instr = fund_reward_instr(
+                        &pool_config.clone(),
+                        pool_config.pool_id_account.unwrap(),
+                        reward_info.token_mint,
+                        rpc_client.get_account(&reward_info.token_mint)?.owner,
+                        reward_info.token_vault,
+                        user_reward_token,
+                        index,
+                        amount,
+                    )?;
+                    // send
+                    let signers = vec![&payer];
+                    let recent_hash = rpc_client.get_latest_blockhash()?;
+                    let txn = Transaction::new_signed_with_payer(
+                        &fund_instr,
+                        Some(&payer.pubkey()),
+                        &signers,
+                        recent_hash,
+                    );
+                    let signature = send_txn(&rpc_client, &txn, true)?;
+                    println!("{}", signature);
+                } else {
+                    println!("invalid command: [fund_reward index, amount]");
+                }
+            }
+            "cancel_reward" => {
+                if v.len() == 2 {
+                    let index = v[1].parse::<u8>().unwrap();
+                    let cancel_instr = cancel_reward_instr(
+                        &pool_config.clone(),
+                        pool_config.pool_id_account.unwrap(),
+                        index,
+                    )?;
+                    // send
+                    let signers = vec![&payer];
+                    let recent_hash = rpc_client.get_latest_blockhash()?;
+                    let txn = Transaction::new_signed_with_payer(
+                        &cancel_instr,
+                        Some(&payer.pubkey()),
+                        &signers,
+                        recent_hash,
+                    );
+                    let signature = send_txn(&rpc_client, &txn, true)?;
+                    println!("{}", signature);
+                } else {
+                    println!("invalid command: [cancel_reward index]");
+                }
+            }
             "set_reward_params" => {
                 if v.len() == 6 {
                     let index = v[1].parse::<u8>().unwrap();
@@ -964,6 +1318,8 @@ fn main() -> Result<()> {
                         &pool_config.clone(),
                         pool_account.amm_config,
                         pool_config.pool_id_account.unwrap(),
+                        reward_token_mint,
+                        rpc_client.get_account(&reward_token_mint)?.owner,
                         reward_token_vault,
                         user_reward_token,
                         operator_account_key,
@@ -1016,13 +1372,56 @@ fn main() -> Result<()> {
                     println!("{:#?}", personal_account);
                 }
             }
-            "open_position" | "open" => {
-                if v.len() == 5 {
-                    let tick_lower_price = v[1].parse::<f64>().unwrap();
-                    let tick_upper_price = v[2].parse::<f64>().unwrap();
-                    let is_base_0 = v[3].parse::<bool>().unwrap();
-                    let imput_amount = v[4].parse::<u64>().unwrap();
-
+            "pobservation" => {
+                let program = anchor_client.program(pool_config.raydium_v3_program);
+                let pool_id = if v.len() >= 2 {
+                    Pubkey::from_str(&v[1]).unwrap()
+                } else {
+                    pool_config.pool_id_account.unwrap()
+                };
+                let count = if v.len() == 3 {
+                    v[2].parse::<usize>().unwrap()
+                } else {
+                    10
+                };
+                let pool: raydium_amm_v3::states::PoolState = program.account(pool_id)?;
+                let observation_account = rpc_client.get_account(&pool.observation_key)?;
+                let observation_state = deserialize_anchor_account::<
+                    raydium_amm_v3::states::ObservationState,
+                >(&observation_account)?;
+                // the observations beyond OBSERVATION_NUM are appended to the account
+                let extension: &[raydium_amm_v3::states::Observation] =
+                    anchor_lang::__private::bytemuck::cast_slice(
+                        &observation_account.data[raydium_amm_v3::states::ObservationState::LEN..],
+                    );
+                let observation_num = observation_state.observation_num();
+                println!(
+                    "observation_num:{}, observation_num_next:{}, observation_update_duration:{}",
+                    observation_num,
+                    observation_state.observation_num_next(),
+                    identity(pool.observation_update_duration)
+                );
+                // print the most recent observations, from the latest to the oldest
+                for i in 0..count.min(observation_num) {
+                    let index = (pool.observation_index as usize + observation_num - i) % observation_num;
+                    let observation = if index < raydium_amm_v3::states::OBSERVATION_NUM {
+                        observation_state.observations[index]
+                    } else {
+                        extension[index - raydium_amm_v3::states::OBSERVATION_NUM]
+                    };
+                    if observation.block_timestamp == 0 {
+                        break;
+                    }
+                    println!("index:{}, {:?}", index, observation);
+                }
+            }
+            "open_position" | "open" => {
+                if v.len() == 5 {
+                    let tick_lower_price = v[1].parse::<f64>().unwrap();
+                    let tick_upper_price = v[2].parse::<f64>().unwrap();
+                    let is_base_0 = v[3].parse::<bool>().unwrap();
+                    let imput_amount = v[4].parse::<u64>().unwrap();
+
                     // load pool to get observation
                     let program = anchor_client.program(pool_config.raydium_v3_program);
                     let pool: raydium_amm_v3::states::PoolState =
@@ -1131,8 +1530,13 @@ fn main() -> Result<()> {
                         let open_position_instr = open_position_instr(
                             &pool_config.clone(),
                             pool_config.pool_id_account.unwrap(),
+                            pool.observation_key,
                             pool.token_vault_0,
                             pool.token_vault_1,
+                            pool.token_mint_0,
+                            pool.token_mint_1,
+                            rpc_client.get_account(&pool.token_mint_0)?.owner,
+                            rpc_client.get_account(&pool.token_mint_1)?.owner,
                             nft_mint.pubkey(),
                             payer.pubkey(),
                             spl_associated_token_account::get_associated_token_address(
@@ -1316,8 +1720,13 @@ fn main() -> Result<()> {
                         let increase_instr = increase_liquidity_instr(
                             &pool_config.clone(),
                             pool_config.pool_id_account.unwrap(),
+                            pool.observation_key,
                             pool.token_vault_0,
                             pool.token_vault_1,
+                            pool.token_mint_0,
+                            pool.token_mint_1,
+                            rpc_client.get_account(&pool.token_mint_0)?.owner,
+                            rpc_client.get_account(&pool.token_mint_1)?.owner,
                             find_position.nft_mint,
                             spl_associated_token_account::get_associated_token_address(
                                 &payer.pubkey(),
@@ -1354,6 +1763,387 @@ fn main() -> Result<()> {
                     println!("invalid command: [increase_liquidity tick_lower_price tick_upper_price is_base_0 imput_amount]");
                 }
             }
+            "flash" => {
+                if v.len() != 4 {
+                    panic!("invalild args")
+                }
+                let amount_0 = v[1].parse::<u64>().unwrap();
+                let amount_1 = v[2].parse::<u64>().unwrap();
+                let callback_program = Pubkey::from_str(&v[3]).unwrap();
+                let program = anchor_client.program(pool_config.raydium_v3_program);
+                let pool: raydium_amm_v3::states::PoolState =
+                    program.account(pool_config.pool_id_account.unwrap())?;
+                let flash_instrs = flash_instr(
+                    &pool_config.clone(),
+                    pool.amm_config,
+                    pool_config.pool_id_account.unwrap(),
+                    pool.token_vault_0,
+                    pool.token_vault_1,
+                    pool.token_mint_0,
+                    pool.token_mint_1,
+                    rpc_client.get_account(&pool.token_mint_0)?.owner,
+                    rpc_client.get_account(&pool.token_mint_1)?.owner,
+                    spl_associated_token_account::get_associated_token_address(
+                        &payer.pubkey(),
+                        &pool.token_mint_0,
+                    ),
+                    spl_associated_token_account::get_associated_token_address(
+                        &payer.pubkey(),
+                        &pool.token_mint_1,
+                    ),
+                    callback_program,
+                    Vec::new(),
+                    amount_0,
+                    amount_1,
+                    Vec::new(),
+                )
+                .unwrap();
+                // send
+                let signers = vec![&payer];
+                let recent_hash = rpc_client.get_latest_blockhash()?;
+                let txn = Transaction::new_signed_with_payer(
+                    &flash_instrs,
+                    Some(&payer.pubkey()),
+                    &signers,
+                    recent_hash,
+                );
+                let signature = send_txn(&rpc_client, &txn, true)?;
+                println!("{}", signature);
+            }
+            "donate" => {
+                if v.len() != 3 {
+                    panic!("invalild args")
+                }
+                let amount_0 = v[1].parse::<u64>().unwrap();
+                let amount_1 = v[2].parse::<u64>().unwrap();
+                let program = anchor_client.program(pool_config.raydium_v3_program);
+                let pool: raydium_amm_v3::states::PoolState =
+                    program.account(pool_config.pool_id_account.unwrap())?;
+                let donate_instrs = donate_instr(
+                    &pool_config.clone(),
+                    pool_config.pool_id_account.unwrap(),
+                    pool.token_vault_0,
+                    pool.token_vault_1,
+                    pool.token_mint_0,
+                    pool.token_mint_1,
+                    rpc_client.get_account(&pool.token_mint_0)?.owner,
+                    rpc_client.get_account(&pool.token_mint_1)?.owner,
+                    spl_associated_token_account::get_associated_token_address(
+                        &payer.pubkey(),
+                        &pool.token_mint_0,
+                    ),
+                    spl_associated_token_account::get_associated_token_address(
+                        &payer.pubkey(),
+                        &pool.token_mint_1,
+                    ),
+                    amount_0,
+                    amount_1,
+                )
+                .unwrap();
+                // send
+                let signers = vec![&payer];
+                let recent_hash = rpc_client.get_latest_blockhash()?;
+                let txn = Transaction::new_signed_with_payer(
+                    &donate_instrs,
+                    Some(&payer.pubkey()),
+                    &signers,
+                    recent_hash,
+                );
+                let signature = send_txn(&rpc_client, &txn, true)?;
+                println!("{}", signature);
+            }
+            "collect_fees" => {
+                if v.len() != 4 {
+                    panic!("invalild args")
+                }
+                let nft_mint = Pubkey::from_str(&v[1]).unwrap();
+                let amount_0_requested = v[2].parse::<u64>().unwrap();
+                let amount_1_requested = v[3].parse::<u64>().unwrap();
+                let program = anchor_client.program(pool_config.raydium_v3_program);
+                let (personal_position_key, __bump) = Pubkey::find_program_address(
+                    &[
+                        raydium_amm_v3::states::POSITION_SEED.as_bytes(),
+                        nft_mint.to_bytes().as_ref(),
+                    ],
+                    &program.id(),
+                );
+                let position: raydium_amm_v3::states::PersonalPositionState =
+                    program.account(personal_position_key)?;
+                let pool: raydium_amm_v3::states::PoolState = program.account(position.pool_id)?;
+                let collect_fees_instrs = collect_fees_instr(
+                    &pool_config.clone(),
+                    position.pool_id,
+                    pool.token_vault_0,
+                    pool.token_vault_1,
+                    pool.token_mint_0,
+                    pool.token_mint_1,
+                    rpc_client.get_account(&pool.token_mint_0)?.owner,
+                    rpc_client.get_account(&pool.token_mint_1)?.owner,
+                    nft_mint,
+                    spl_associated_token_account::get_associated_token_address(
+                        &payer.pubkey(),
+                        &pool.token_mint_0,
+                    ),
+                    spl_associated_token_account::get_associated_token_address(
+                        &payer.pubkey(),
+                        &pool.token_mint_1,
+                    ),
+                    amount_0_requested,
+                    amount_1_requested,
+                    payer.pubkey(),
+                    position.tick_lower_index,
+                    position.tick_upper_index,
+                    raydium_amm_v3::states::TickArrayState::get_arrary_start_index(
+                        position.tick_lower_index,
+                        pool.tick_spacing.into(),
+                    ),
+                    raydium_amm_v3::states::TickArrayState::get_arrary_start_index(
+                        position.tick_upper_index,
+                        pool.tick_spacing.into(),
+                    ),
+                )
+                .unwrap();
+                // send
+                let signers = vec![&payer];
+                let recent_hash = rpc_client.get_latest_blockhash()?;
+                let txn = Transaction::new_signed_with_payer(
+                    &collect_fees_instrs,
+                    Some(&payer.pubkey()),
+                    &signers,
+                    recent_hash,
+                );
+                let signature = send_txn(&rpc_client, &txn, true)?;
+                println!("{}", signature);
+            }
+            "collect_rewards" => {
+                if v.len() != 3 {
+                    panic!("invalild args")
+                }
+                let nft_mint = Pubkey::from_str(&v[1]).unwrap();
+                let reward_mask = v[2].parse::<u8>().unwrap();
+                let program = anchor_client.program(pool_config.raydium_v3_program);
+                let (personal_position_key, __bump) = Pubkey::find_program_address(
+                    &[
+                        raydium_amm_v3::states::POSITION_SEED.as_bytes(),
+                        nft_mint.to_bytes().as_ref(),
+                    ],
+                    &program.id(),
+                );
+                let position: raydium_amm_v3::states::PersonalPositionState =
+                    program.account(personal_position_key)?;
+                let pool: raydium_amm_v3::states::PoolState = program.account(position.pool_id)?;
+                let mut remaining_accounts = Vec::new();
+                for (i, item) in pool.reward_infos.into_iter().enumerate() {
+                    if item.token_mint != Pubkey::default() && reward_mask & (1 << i) != 0 {
+                        remaining_accounts.push(AccountMeta::new(item.token_vault, false));
+                        remaining_accounts.push(AccountMeta::new(
+                            get_associated_token_address(&payer.pubkey(), &item.token_mint),
+                            false,
+                        ));
+                        remaining_accounts.push(AccountMeta::new_readonly(item.token_mint, false));
+                        remaining_accounts.push(AccountMeta::new_readonly(
+                            rpc_client.get_account(&item.token_mint)?.owner,
+                            false,
+                        ));
+                        remaining_accounts.extend(get_transfer_hook_account_metas(
+                            &rpc_client,
+                            &item.token_mint,
+                            &TransferHookTransfer {
+                                source: item.token_vault,
+                                destination: get_associated_token_address(
+                                    &payer.pubkey(),
+                                    &item.token_mint,
+                                ),
+                                authority: position.pool_id,
+                                amount: position.reward_infos[i].reward_amount_owed,
+                            },
+                        )?);
+                    }
+                }
+                let collect_rewards_instrs = collect_rewards_instr(
+                    &pool_config.clone(),
+                    position.pool_id,
+                    nft_mint,
+                    remaining_accounts,
+                    reward_mask,
+                    position.tick_lower_index,
+                    position.tick_upper_index,
+                    raydium_amm_v3::states::TickArrayState::get_arrary_start_index(
+                        position.tick_lower_index,
+                        pool.tick_spacing.into(),
+                    ),
+                    raydium_amm_v3::states::TickArrayState::get_arrary_start_index(
+                        position.tick_upper_index,
+                        pool.tick_spacing.into(),
+                    ),
+                )
+                .unwrap();
+                // send
+                let signers = vec![&payer];
+                let recent_hash = rpc_client.get_latest_blockhash()?;
+                let txn = Transaction::new_signed_with_payer(
+                    &collect_rewards_instrs,
+                    Some(&payer.pubkey()),
+                    &signers,
+                    recent_hash,
+                );
+                let signature = send_txn(&rpc_client, &txn, true)?;
+                println!("{}", signature);
+            }
+            "compound_position" => {
+                if v.len() != 4 {
+                    panic!("invalild args")
+                }
+                let nft_mint = Pubkey::from_str(&v[1]).unwrap();
+                let nft_owner = Pubkey::from_str(&v[2]).unwrap();
+                let liquidity_min = v[3].parse::<u128>().unwrap();
+                let program = anchor_client.program(pool_config.raydium_v3_program);
+                let (personal_position_key, __bump) = Pubkey::find_program_address(
+                    &[
+                        raydium_amm_v3::states::POSITION_SEED.as_bytes(),
+                        nft_mint.to_bytes().as_ref(),
+                    ],
+                    &program.id(),
+                );
+                let position: raydium_amm_v3::states::PersonalPositionState =
+                    program.account(personal_position_key)?;
+                let pool: raydium_amm_v3::states::PoolState = program.account(position.pool_id)?;
+                let compound_instrs = compound_position_instr(
+                    &pool_config.clone(),
+                    position.pool_id,
+                    pool.observation_key,
+                    pool.token_vault_0,
+                    pool.token_vault_1,
+                    pool.token_mint_0,
+                    pool.token_mint_1,
+                    rpc_client.get_account(&pool.token_mint_0)?.owner,
+                    rpc_client.get_account(&pool.token_mint_1)?.owner,
+                    nft_owner,
+                    nft_mint,
+                    spl_associated_token_account::get_associated_token_address(
+                        &nft_owner,
+                        &pool.token_mint_0,
+                    ),
+                    spl_associated_token_account::get_associated_token_address(
+                        &nft_owner,
+                        &pool.token_mint_1,
+                    ),
+                    position.tick_lower_index,
+                    position.tick_upper_index,
+                    raydium_amm_v3::states::TickArrayState::get_arrary_start_index(
+                        position.tick_lower_index,
+                        pool.tick_spacing.into(),
+                    ),
+                    raydium_amm_v3::states::TickArrayState::get_arrary_start_index(
+                        position.tick_upper_index,
+                        pool.tick_spacing.into(),
+                    ),
+                    liquidity_min,
+                )
+                .unwrap();
+                // send
+                let signers = vec![&payer];
+                let recent_hash = rpc_client.get_latest_blockhash()?;
+                let txn = Transaction::new_signed_with_payer(
+                    &compound_instrs,
+                    Some(&payer.pubkey()),
+                    &signers,
+                    recent_hash,
+                );
+                let signature = send_txn(&rpc_client, &txn, true)?;
+                println!("{}", signature);
+            }
+            "set_compound_keeper" => {
+                if v.len() != 3 {
+                    panic!("invalild args")
+                }
+                let nft_mint = Pubkey::from_str(&v[1]).unwrap();
+                let compound_keeper = Pubkey::from_str(&v[2]).unwrap();
+                let set_keeper_instrs =
+                    set_compound_keeper_instr(&pool_config.clone(), nft_mint, compound_keeper)
+                        .unwrap();
+                // send
+                let signers = vec![&payer];
+                let recent_hash = rpc_client.get_latest_blockhash()?;
+                let txn = Transaction::new_signed_with_payer(
+                    &set_keeper_instrs,
+                    Some(&payer.pubkey()),
+                    &signers,
+                    recent_hash,
+                );
+                let signature = send_txn(&rpc_client, &txn, true)?;
+                println!("{}", signature);
+            }
+            "set_range_order" => {
+                if v.len() != 3 {
+                    panic!("invalild args")
+                }
+                let nft_mint = Pubkey::from_str(&v[1]).unwrap();
+                let range_order_type = v[2].parse::<u8>().unwrap();
+                let set_range_order_instrs = set_range_order_instr(
+                    &pool_config.clone(),
+                    pool_config.pool_id_account.unwrap(),
+                    nft_mint,
+                    range_order_type,
+                )
+                .unwrap();
+                // send
+                let signers = vec![&payer];
+                let recent_hash = rpc_client.get_latest_blockhash()?;
+                let txn = Transaction::new_signed_with_payer(
+                    &set_range_order_instrs,
+                    Some(&payer.pubkey()),
+                    &signers,
+                    recent_hash,
+                );
+                let signature = send_txn(&rpc_client, &txn, true)?;
+                println!("{}", signature);
+            }
+            "settle_range_order" => {
+                if v.len() != 2 {
+                    panic!("invalild args")
+                }
+                let nft_mint = Pubkey::from_str(&v[1]).unwrap();
+                let program = anchor_client.program(pool_config.raydium_v3_program);
+                let (personal_position_key, __bump) = Pubkey::find_program_address(
+                    &[
+                        raydium_amm_v3::states::POSITION_SEED.as_bytes(),
+                        nft_mint.to_bytes().as_ref(),
+                    ],
+                    &program.id(),
+                );
+                let position: raydium_amm_v3::states::PersonalPositionState =
+                    program.account(personal_position_key)?;
+                let pool: raydium_amm_v3::states::PoolState = program.account(position.pool_id)?;
+                let settle_range_order_instrs = settle_range_order_instr(
+                    &pool_config.clone(),
+                    position.pool_id,
+                    pool.observation_key,
+                    nft_mint,
+                    position.tick_lower_index,
+                    position.tick_upper_index,
+                    raydium_amm_v3::states::TickArrayState::get_arrary_start_index(
+                        position.tick_lower_index,
+                        pool.tick_spacing.into(),
+                    ),
+                    raydium_amm_v3::states::TickArrayState::get_arrary_start_index(
+                        position.tick_upper_index,
+                        pool.tick_spacing.into(),
+                    ),
+                )
+                .unwrap();
+                // send
+                let signers = vec![&payer];
+                let recent_hash = rpc_client.get_latest_blockhash()?;
+                let txn = Transaction::new_signed_with_payer(
+                    &settle_range_order_instrs,
+                    Some(&payer.pubkey()),
+                    &signers,
+                    recent_hash,
+                );
+                let signature = send_txn(&rpc_client, &txn, true)?;
+                println!("{}", signature);
+            }
             "decrease_liquidity" => {
                 if v.len() == 7 {
                     let tick_lower_index = v[1].parse::<i32>().unwrap();
@@ -1411,25 +2201,46 @@ fn main() -> Result<()> {
                     if find_position.nft_mint != Pubkey::default()
                         && find_position.pool_id == pool_config.pool_id_account.unwrap()
                     {
-                        let mut reward_vault_with_user_vault: Vec<(Pubkey, Pubkey)> = Vec::new();
-                        for item in pool.reward_infos.into_iter() {
+                        let mut remaining_accounts = Vec::new();
+                        for (i, item) in pool.reward_infos.into_iter().enumerate() {
                             if item.token_mint != Pubkey::default() {
-                                reward_vault_with_user_vault.push((
-                                    item.token_vault,
+                                remaining_accounts.push(AccountMeta::new(item.token_vault, false));
+                                remaining_accounts.push(AccountMeta::new(
                                     get_associated_token_address(&payer.pubkey(), &item.token_mint),
+                                    false,
                                 ));
+                                remaining_accounts
+                                    .push(AccountMeta::new_readonly(item.token_mint, false));
+                                remaining_accounts.push(AccountMeta::new_readonly(
+                                    rpc_client.get_account(&item.token_mint)?.owner,
+                                    false,
+                                ));
+                                remaining_accounts.extend(get_transfer_hook_account_metas(
+                                    &rpc_client,
+                                    &item.token_mint,
+                                    &TransferHookTransfer {
+                                        source: item.token_vault,
+                                        destination: get_associated_token_address(
+                                            &payer.pubkey(),
+                                            &item.token_mint,
+                                        ),
+                                        authority: pool_config.pool_id_account.unwrap(),
+                                        amount: find_position.reward_infos[i].reward_amount_owed,
+                                    },
+                                )?);
                             }
                         }
-                        let remaining_accounts = reward_vault_with_user_vault
-                            .into_iter()
-                            .map(|item| AccountMeta::new(item.0, false))
-                            .collect();
                         // personal position exist
                         let mut decrease_instr = decrease_liquidity_instr(
                             &pool_config.clone(),
                             pool_config.pool_id_account.unwrap(),
+                            pool.observation_key,
                             pool.token_vault_0,
                             pool.token_vault_1,
+                            pool.token_mint_0,
+                            pool.token_mint_1,
+                            rpc_client.get_account(&pool.token_mint_0)?.owner,
+                            rpc_client.get_account(&pool.token_mint_1)?.owner,
                             find_position.nft_mint,
                             spl_associated_token_account::get_associated_token_address(
                                 &payer.pubkey(),
@@ -1451,7 +2262,10 @@ fn main() -> Result<()> {
                         if liquidity == find_position.liquidity {
                             let close_position_instr = close_personal_position_instr(
                                 &pool_config.clone(),
+                                pool_config.pool_id_account.unwrap(),
                                 find_position.nft_mint,
+                                tick_lower_index,
+                                tick_upper_index,
                             )?;
                             decrease_instr.extend(close_position_instr);
                         }
@@ -1553,10 +2367,16 @@ fn main() -> Result<()> {
                     let zero_for_one = user_input_state.mint == pool_state.token_mint_0
                         && user_output_state.mint == pool_state.token_mint_1;
                     // load tick_arrays
+                    let tick_array_bitmap_extension = utils::load_tick_array_bitmap_extension(
+                        &rpc_client,
+                        &pool_config.raydium_v3_program,
+                        &pool_config.pool_id_account.unwrap(),
+                    );
                     let mut tick_arrays = load_cur_and_next_five_tick_array(
                         &rpc_client,
                         &pool_config,
                         &pool_state,
+                        tick_array_bitmap_extension.as_ref(),
                         zero_for_one,
                     );
 
@@ -1578,6 +2398,7 @@ fn main() -> Result<()> {
                             is_base_input,
                             &amm_config_state,
                             &pool_state,
+                            tick_array_bitmap_extension.as_ref(),
                             &mut tick_arrays,
                         )
                             .unwrap();
@@ -1622,6 +2443,10 @@ fn main() -> Result<()> {
                         } else {
                             pool_state.token_vault_0
                         },
+                        user_input_state.mint,
+                        user_output_state.mint,
+                        user_input_account.as_ref().unwrap().owner,
+                        user_output_account.as_ref().unwrap().owner,
                         pool_state.observation_key,
                         user_input_token,
                         user_output_token,
@@ -1685,10 +2510,16 @@ fn main() -> Result<()> {
                     let zero_for_one = user_input_state.mint == pool_state.token_mint_0
                         && user_output_state.mint == pool_state.token_mint_1;
                     // load tick_arrays
+                    let tick_array_bitmap_extension = utils::load_tick_array_bitmap_extension(
+                        &rpc_client,
+                        &pool_config.raydium_v3_program,
+                        &pool_config.pool_id_account.unwrap(),
+                    );
                     let mut tick_arrays = load_cur_and_next_five_tick_array(
                         &rpc_client,
                         &pool_config,
                         &pool_state,
+                        tick_array_bitmap_extension.as_ref(),
                         zero_for_one,
                     );
 
@@ -1710,6 +2541,7 @@ fn main() -> Result<()> {
                             is_base_input,
                             &amm_config_state,
                             &pool_state,
+                            tick_array_bitmap_extension.as_ref(),
                             &mut tick_arrays,
                         )
                             .unwrap();
@@ -1754,6 +2586,10 @@ fn main() -> Result<()> {
                         } else {
                             pool_state.token_vault_0
                         },
+                        user_input_state.mint,
+                        user_output_state.mint,
+                        user_input_account.as_ref().unwrap().owner,
+                        user_output_account.as_ref().unwrap().owner,
                         pool_state.observation_key,
                         user_input_token,
                         user_output_token,
@@ -1985,14 +2821,17 @@ fn main() -> Result<()> {
                                 token_account_0,
                                 token_account_1,
                                 amount_0,
+                                transfer_fee_0,
                                 amount_1,
+                                transfer_fee_1,
                                 zero_for_one,
                                 sqrt_price_x64,
                                 liquidity,
                                 tick,
+                                fee_rate,
                             } = log;
                             let pool_f_price = sqrt_price_x64_to_price(sqrt_price_x64, 9, 6);
-                            println!("pool_state:{}, sender:{}, token_account_0:{}, token_account_1:{}, amount_0:{}, amount_1:{}, zero_for_one:{}, sqrt_price_x64:{}, pool_f_price:{}, liquidity:{}, tick:{}", pool_state, sender, token_account_0, token_account_1, amount_0, amount_1, zero_for_one, sqrt_price_x64, pool_f_price, liquidity, tick);
+                            println!("pool_state:{}, sender:{}, token_account_0:{}, token_account_1:{}, amount_0:{}, transfer_fee_0:{}, amount_1:{}, transfer_fee_1:{}, zero_for_one:{}, sqrt_price_x64:{}, pool_f_price:{}, liquidity:{}, tick:{}, fee_rate:{}", pool_state, sender, token_account_0, token_account_1, amount_0, transfer_fee_0, amount_1, transfer_fee_1, zero_for_one, sqrt_price_x64, pool_f_price, liquidity, tick, fee_rate);
                         }
                         [58, 222, 86, 58, 68, 50, 85, 56] => {
                             let log = raydium_amm_v3::states::DecreaseLiquidityEvent::deserialize(
@@ -2019,6 +2858,31 @@ fn main() -> Result<()> {
                     }
                 }
             }
+            "decode_observe_result" => {
+                if v.len() == 2 {
+                    let return_data = v[1];
+                    let borsh_bytes = match anchor_lang::__private::base64::decode(&return_data) {
+                        Ok(borsh_bytes) => borsh_bytes,
+                        _ => {
+                            println!("Could not base64 decode return data: {}", return_data);
+                            return Ok(());
+                        }
+                    };
+                    let result = raydium_amm_v3::states::ObserveResult::deserialize(
+                        &mut &borsh_bytes[..],
+                    )
+                    .map_err(|_| anchor_lang::error::ErrorCode::InstructionDidNotDeserialize)
+                    .unwrap();
+                    let raydium_amm_v3::states::ObserveResult {
+                        cumulative_time_price_x64s,
+                        time_weighted_prices_x64,
+                        tick_cumulatives,
+                        time_weighted_ticks,
+                        seconds_per_liquidity_cumulatives_x64,
+                    } = result;
+                    println!("cumulative_time_price_x64s:{:?}, time_weighted_prices_x64:{:?}, tick_cumulatives:{:?}, time_weighted_ticks:{:?}, seconds_per_liquidity_cumulatives_x64:{:?}", cumulative_time_price_x64s, time_weighted_prices_x64, tick_cumulatives, time_weighted_ticks, seconds_per_liquidity_cumulatives_x64);
+                }
+            }
             "transfer_reward_owner" => {
                 if v.len() != 3 {
                     panic!("invalild args")
@@ -2039,6 +2903,303 @@ fn main() -> Result<()> {
                 let signature = send_txn(&rpc_client, &txn, true)?;
                 println!("{}", signature);
             }
+            "accept_reward_owner" | "cancel_reward_owner" => {
+                if v.len() != 2 {
+                    panic!("invalild args")
+                }
+                let pool_id = Pubkey::from_str(&v[1]).unwrap();
+                let (signers, instrs) = if v[0] == "accept_reward_owner" {
+                    // the proposed owner signs as the payer
+                    (
+                        vec![&payer],
+                        accept_reward_owner_instr(&pool_config.clone(), pool_id)?,
+                    )
+                } else {
+                    (
+                        vec![&payer, &admin],
+                        cancel_reward_owner_instr(&pool_config.clone(), pool_id)?,
+                    )
+                };
+                // send
+                let recent_hash = rpc_client.get_latest_blockhash()?;
+                let txn = Transaction::new_signed_with_payer(
+                    &instrs,
+                    Some(&payer.pubkey()),
+                    &signers,
+                    recent_hash,
+                );
+                let signature = send_txn(&rpc_client, &txn, true)?;
+                println!("{}", signature);
+            }
+            "propose_reward_authority" => {
+                if v.len() != 4 {
+                    panic!("invalild args")
+                }
+                let pool_id = Pubkey::from_str(&v[1]).unwrap();
+                let reward_index = v[2].parse::<u8>().unwrap();
+                let new_authority = Pubkey::from_str(&v[3]).unwrap();
+                // the authority of the reward signs as the payer
+                let instrs = propose_reward_authority_instr(
+                    &pool_config.clone(),
+                    pool_id,
+                    reward_index,
+                    new_authority,
+                )?;
+                // send
+                let signers = vec![&payer];
+                let recent_hash = rpc_client.get_latest_blockhash()?;
+                let txn = Transaction::new_signed_with_payer(
+                    &instrs,
+                    Some(&payer.pubkey()),
+                    &signers,
+                    recent_hash,
+                );
+                let signature = send_txn(&rpc_client, &txn, true)?;
+                println!("{}", signature);
+            }
+            "accept_reward_authority" | "cancel_reward_authority" => {
+                if v.len() != 3 {
+                    panic!("invalild args")
+                }
+                let pool_id = Pubkey::from_str(&v[1]).unwrap();
+                let reward_index = v[2].parse::<u8>().unwrap();
+                // the proposed authority accepts and the authority of the reward cancels, as the
+                // payer
+                let instrs = if v[0] == "accept_reward_authority" {
+                    accept_reward_authority_instr(&pool_config.clone(), pool_id, reward_index)?
+                } else {
+                    cancel_reward_authority_instr(&pool_config.clone(), pool_id, reward_index)?
+                };
+                // send
+                let signers = vec![&payer];
+                let recent_hash = rpc_client.get_latest_blockhash()?;
+                let txn = Transaction::new_signed_with_payer(
+                    &instrs,
+                    Some(&payer.pubkey()),
+                    &signers,
+                    recent_hash,
+                );
+                let signature = send_txn(&rpc_client, &txn, true)?;
+                println!("{}", signature);
+            }
+            "update_observation_duration" => {
+                if v.len() != 3 {
+                    panic!("invalild args")
+                }
+                let pool_id = Pubkey::from_str(&v[1]).unwrap();
+                let observation_update_duration = v[2].parse::<u16>().unwrap();
+                let update_duration_instrs = update_observation_update_duration_instr(
+                    &pool_config.clone(),
+                    pool_id,
+                    observation_update_duration,
+                )
+                .unwrap();
+                // send
+                let signers = vec![&payer, &admin];
+                let recent_hash = rpc_client.get_latest_blockhash()?;
+                let txn = Transaction::new_signed_with_payer(
+                    &update_duration_instrs,
+                    Some(&payer.pubkey()),
+                    &signers,
+                    recent_hash,
+                );
+                let signature = send_txn(&rpc_client, &txn, true)?;
+                println!("{}", signature);
+            }
+            "set_dynamic_fee_params" => {
+                if v.len() != 8 {
+                    panic!("invalild args")
+                }
+                let pool_id = Pubkey::from_str(&v[1]).unwrap();
+                let param = raydium_amm_v3::instructions::DynamicFeeParam {
+                    max_fee_rate: v[2].parse::<u32>().unwrap(),
+                    dynamic_fee_control: v[3].parse::<u32>().unwrap(),
+                    max_volatility_accumulator: v[4].parse::<u32>().unwrap(),
+                    filter_period: v[5].parse::<u16>().unwrap(),
+                    decay_period: v[6].parse::<u16>().unwrap(),
+                    reduction_factor: v[7].parse::<u16>().unwrap(),
+                };
+                let pool_account = rpc_client.get_account(&pool_id)?;
+                let pool_state =
+                    deserialize_anchor_account::<raydium_amm_v3::states::PoolState>(&pool_account)?;
+                let set_dynamic_fee_instrs = set_dynamic_fee_params_instr(
+                    &pool_config.clone(),
+                    pool_id,
+                    pool_state.amm_config,
+                    param,
+                )
+                .unwrap();
+                // send
+                let signers = vec![&payer, &admin];
+                let recent_hash = rpc_client.get_latest_blockhash()?;
+                let txn = Transaction::new_signed_with_payer(
+                    &set_dynamic_fee_instrs,
+                    Some(&payer.pubkey()),
+                    &signers,
+                    recent_hash,
+                );
+                let signature = send_txn(&rpc_client, &txn, true)?;
+                println!("{}", signature);
+            }
+            "set_pool_trade_fee_rate" => {
+                if v.len() != 3 {
+                    panic!("invalild args")
+                }
+                let pool_id = Pubkey::from_str(&v[1]).unwrap();
+                let trade_fee_rate_override = v[2].parse::<u32>().unwrap();
+                let pool_account = rpc_client.get_account(&pool_id)?;
+                let pool_state =
+                    deserialize_anchor_account::<raydium_amm_v3::states::PoolState>(&pool_account)?;
+                let set_trade_fee_rate_instrs = set_pool_trade_fee_rate_instr(
+                    &pool_config.clone(),
+                    pool_id,
+                    pool_state.amm_config,
+                    trade_fee_rate_override,
+                )
+                .unwrap();
+                // send
+                let signers = vec![&payer, &admin];
+                let recent_hash = rpc_client.get_latest_blockhash()?;
+                let txn = Transaction::new_signed_with_payer(
+                    &set_trade_fee_rate_instrs,
+                    Some(&payer.pubkey()),
+                    &signers,
+                    recent_hash,
+                );
+                let signature = send_txn(&rpc_client, &txn, true)?;
+                println!("{}", signature);
+            }
+            "create_tick_array_bitmap_extension" => {
+                if v.len() != 1 {
+                    panic!("invalild args")
+                }
+                let create_instrs = create_tick_array_bitmap_extension_instr(
+                    &pool_config.clone(),
+                    pool_config.pool_id_account.unwrap(),
+                )
+                .unwrap();
+                // send
+                let signers = vec![&payer];
+                let recent_hash = rpc_client.get_latest_blockhash()?;
+                let txn = Transaction::new_signed_with_payer(
+                    &create_instrs,
+                    Some(&payer.pubkey()),
+                    &signers,
+                    recent_hash,
+                );
+                let signature = send_txn(&rpc_client, &txn, true)?;
+                println!("{}", signature);
+            }
+            "close_tick_array" => {
+                if v.len() != 2 {
+                    panic!("invalild args")
+                }
+                let tick_array_start_index = v[1].parse::<i32>().unwrap();
+                let program = anchor_client.program(pool_config.raydium_v3_program);
+                let (tick_array_key, __bump) = Pubkey::find_program_address(
+                    &[
+                        raydium_amm_v3::states::TICK_ARRAY_SEED.as_bytes(),
+                        pool_config.pool_id_account.unwrap().to_bytes().as_ref(),
+                        &tick_array_start_index.to_be_bytes(),
+                    ],
+                    &program.id(),
+                );
+                let tick_array: raydium_amm_v3::states::TickArrayState =
+                    program.account(tick_array_key)?;
+                let close_instrs = close_tick_array_instr(
+                    &pool_config.clone(),
+                    pool_config.pool_id_account.unwrap(),
+                    tick_array.payer,
+                    tick_array_start_index,
+                )
+                .unwrap();
+                // send
+                let signers = vec![&payer];
+                let recent_hash = rpc_client.get_latest_blockhash()?;
+                let txn = Transaction::new_signed_with_payer(
+                    &close_instrs,
+                    Some(&payer.pubkey()),
+                    &signers,
+                    recent_hash,
+                );
+                let signature = send_txn(&rpc_client, &txn, true)?;
+                println!("{}", signature);
+            }
+            "close_protocol_position" => {
+                if v.len() != 3 {
+                    panic!("invalild args")
+                }
+                let tick_lower_index = v[1].parse::<i32>().unwrap();
+                let tick_upper_index = v[2].parse::<i32>().unwrap();
+                let program = anchor_client.program(pool_config.raydium_v3_program);
+                let pool: raydium_amm_v3::states::PoolState =
+                    program.account(pool_config.pool_id_account.unwrap())?;
+                let (protocol_position_key, __bump) = Pubkey::find_program_address(
+                    &[
+                        raydium_amm_v3::states::POSITION_SEED.as_bytes(),
+                        pool_config.pool_id_account.unwrap().to_bytes().as_ref(),
+                        &tick_lower_index.to_be_bytes(),
+                        &tick_upper_index.to_be_bytes(),
+                    ],
+                    &program.id(),
+                );
+                let protocol_position: raydium_amm_v3::states::ProtocolPositionState =
+                    program.account(protocol_position_key)?;
+                let close_instrs = close_protocol_position_instr(
+                    &pool_config.clone(),
+                    pool_config.pool_id_account.unwrap(),
+                    protocol_position.payer,
+                    tick_lower_index,
+                    tick_upper_index,
+                    raydium_amm_v3::states::TickArrayState::get_arrary_start_index(
+                        tick_lower_index,
+                        pool.tick_spacing.into(),
+                    ),
+                    raydium_amm_v3::states::TickArrayState::get_arrary_start_index(
+                        tick_upper_index,
+                        pool.tick_spacing.into(),
+                    ),
+                )
+                .unwrap();
+                // send
+                let signers = vec![&payer];
+                let recent_hash = rpc_client.get_latest_blockhash()?;
+                let txn = Transaction::new_signed_with_payer(
+                    &close_instrs,
+                    Some(&payer.pubkey()),
+                    &signers,
+                    recent_hash,
+                );
+                let signature = send_txn(&rpc_client, &txn, true)?;
+                println!("{}", signature);
+            }
+            "increase_observation_num" => {
+                if v.len() != 2 {
+                    panic!("invalild args")
+                }
+                let observation_num_next = v[1].parse::<u16>().unwrap();
+                let program = anchor_client.program(pool_config.raydium_v3_program);
+                let pool: raydium_amm_v3::states::PoolState =
+                    program.account(pool_config.pool_id_account.unwrap())?;
+                let increase_instrs = increase_observation_num_instr(
+                    &pool_config.clone(),
+                    pool_config.pool_id_account.unwrap(),
+                    pool.observation_key,
+                    observation_num_next,
+                )
+                .unwrap();
+                // send
+                let signers = vec![&payer];
+                let recent_hash = rpc_client.get_latest_blockhash()?;
+                let txn = Transaction::new_signed_with_payer(
+                    &increase_instrs,
+                    Some(&payer.pubkey()),
+                    &signers,
+                    recent_hash,
+                );
+                let signature = send_txn(&rpc_client, &txn, true)?;
+                println!("{}", signature);
+            }
             _ => {
                 println!("command not exist");
             }
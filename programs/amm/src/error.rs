@@ -94,4 +94,120 @@ pub enum ErrorCode {
     NotApproveUpdateRewardEmissiones,
     #[msg("uninitialized reward info")]
     UnInitializedRewardInfo,
+
+    /// token program
+    #[msg("Token vault is not owned by the token program of its mint")]
+    InvalidVaultTokenProgram,
+    #[msg("Invalid token program 2022 mint extension data")]
+    InvalidMintExtensionData,
+    #[msg("Not support mint with permanent delegate extension")]
+    NotSupportMintWithPermanentDelegate,
+    #[msg("Not support non-transferable mint")]
+    NotSupportNonTransferableMint,
+    #[msg("Not support mint with frozen default account state")]
+    NotSupportMintWithDefaultFrozenState,
+    #[msg("Not support mint with confidential transfer extension")]
+    NotSupportMintWithConfidentialTransfer,
+    #[msg("Not support mint with transfer hook extension")]
+    NotSupportMintWithTransferHook,
+    #[msg("Invalid transfer hook accounts")]
+    InvalidTransferHookAccounts,
+
+    /// oracle errors
+    #[msg("Observation state is not initialized")]
+    ObservationNotInitialized,
+    #[msg("The target time is older than the oldest observation")]
+    ObservationTooOld,
+    #[msg("Seconds agos must be in descending order")]
+    InvalidSecondsAgos,
+    #[msg("Invalid observation update duration")]
+    InvalidObservationUpdateDuration,
+    #[msg("Invalid observation num")]
+    InvalidObservationNum,
+
+    /// fee errors
+    #[msg("Invalid dynamic fee param")]
+    InvalidDynamicFeeParam,
+    #[msg("Trade fee rate override exceeds the max")]
+    InvalidTradeFeeRateOverride,
+
+    /// range order errors
+    #[msg("Invalid range order type")]
+    InvalidRangeOrderType,
+    #[msg("Range order must span exactly one tick spacing")]
+    InvalidRangeOrderRange,
+    #[msg("Range order must only hold the token it sells when placed")]
+    InvalidRangeOrderPrice,
+    #[msg("Position is not a range order")]
+    NotRangeOrder,
+    #[msg("Range order is not filled")]
+    RangeOrderNotFilled,
+
+    /// flash errors
+    #[msg("Flash loan is not repaid with the fee")]
+    FlashNotRepaid,
+
+    /// tick array bitmap extension errors
+    #[msg("Missing the tick array bitmap extension account")]
+    MissingTickArrayBitmapExtensionAccount,
+
+    /// close account errors
+    #[msg("Tick array still has initialized ticks or ticks of protocol positions")]
+    TickArrayNotEmpty,
+    #[msg("Protocol position still has liquidity or personal positions")]
+    ProtocolPositionNotEmpty,
+    #[msg("Rent receiver is not the payer of the account")]
+    InvalidRentReceiver,
+
+    /// pool reward extension errors
+    #[msg("Missing the pool reward extension accounts")]
+    MissingPoolRewardExtensionAccount,
+    #[msg("Pool reward extension has not enough lamports for the rent of its entries")]
+    InsufficientPoolRewardExtensionRent,
+
+    /// reward phase errors
+    #[msg("Reward phases must start in order within the reward period with non-zero emissions")]
+    InvalidRewardPhases,
+    #[msg("Reward emission can not be changed while its phases are pending")]
+    RewardPhasesPending,
+
+    /// reward funding errors
+    #[msg("Reward emission has ended")]
+    RewardEmissionEnded,
+    #[msg("Reward fund amount must extend the emission by up to the max reward period")]
+    InvalidRewardFundAmount,
+
+    /// ownership errors
+    #[msg("The proposed owner must not be the default key")]
+    InvalidPendingOwner,
+    #[msg("No owner is proposed")]
+    NoPendingOwner,
+
+    /// admin errors
+    #[msg("Trade fee rate must be less than the fee rate denominator")]
+    InvalidTradeFeeRate,
+    #[msg("Protocol fee rate plus fund fee rate exceeds the fee rate denominator")]
+    InvalidProtocolFeeRate,
+    #[msg("Fund fee rate plus protocol fee rate exceeds the fee rate denominator")]
+    InvalidFundFeeRate,
+    #[msg("Keys must not be empty or contain the default key")]
+    InvalidOperationKeys,
+    #[msg("Operation owners exceed the max number")]
+    OperationOwnersFull,
+    #[msg("Whitelist mints exceed the max number")]
+    WhitelistMintsFull,
+
+    /// timelock errors
+    #[msg("Admin action must be queued behind the timelock")]
+    TimelockEnabled,
+    #[msg("Timelock delay of the admin action has not passed")]
+    TimelockNotElapsed,
+    #[msg("Timelock delay exceeds the max")]
+    InvalidTimelockDelay,
+    #[msg("Missing or mismatched target account of the admin action")]
+    InvalidAdminActionTarget,
+    #[msg("Account was created before its references were counted and can not be closed")]
+    UncountedAccount,
+    #[msg("Reward extensions of the pool exceed the max number")]
+    RewardExtensionsFull,
 }
@@ -0,0 +1,31 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AcceptRewardAuthority<'info> {
+    /// The proposed authority of the reward
+    pub pending_authority: Signer<'info>,
+
+    /// The pool of the reward
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn accept_reward_authority(
+    ctx: Context<AcceptRewardAuthority>,
+    reward_index: u8,
+) -> Result<()> {
+    assert!((reward_index as usize) < REWARD_NUM);
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.check_unlocked()?;
+    let new_authority = ctx.accounts.pending_authority.key();
+    let old_authority = pool_state.accept_reward_authority(reward_index as usize, new_authority)?;
+
+    emit!(AcceptRewardAuthorityEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        reward_index,
+        old_authority,
+        new_authority,
+    });
+    Ok(())
+}
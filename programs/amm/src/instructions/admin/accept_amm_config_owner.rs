@@ -0,0 +1,42 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AcceptAmmConfigOwner<'info> {
+    /// The proposed owner
+    pub pending_owner: Signer<'info>,
+
+    /// Amm config account whose owner changes
+    #[account(mut, address = proposal.amm_config)]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// The proposal to accept
+    #[account(
+        mut,
+        has_one = pending_owner @ ErrorCode::NotApproved,
+        has_one = payer,
+        close = payer
+    )]
+    pub proposal: Account<'info, AmmConfigOwnerProposal>,
+
+    /// Receives the rent of the proposal
+    /// CHECK: The payer recorded by the proposal
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+}
+
+pub fn accept_amm_config_owner(ctx: Context<AcceptAmmConfigOwner>) -> Result<()> {
+    let amm_config = &mut ctx.accounts.amm_config;
+    let owner_type = ctx.accounts.proposal.owner_type;
+    let new_owner = ctx.accounts.pending_owner.key();
+    let old_owner = amm_config.transfer_owner(owner_type, new_owner);
+
+    emit!(AcceptAmmConfigOwnerEvent {
+        amm_config: amm_config.key(),
+        owner_type,
+        old_owner,
+        new_owner,
+    });
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AcceptRewardOwner<'info> {
+    /// The proposed owner of the pool
+    pub pending_owner: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn accept_reward_owner(ctx: Context<AcceptRewardOwner>) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.check_unlocked()?;
+    let new_owner = ctx.accounts.pending_owner.key();
+    let old_owner = pool_state.accept_owner(new_owner)?;
+
+    emit!(AcceptRewardOwnerEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        old_owner,
+        new_owner,
+    });
+    Ok(())
+}
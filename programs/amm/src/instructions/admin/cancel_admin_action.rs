@@ -0,0 +1,32 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CancelAdminAction<'info> {
+    /// The admin withdrawing the action
+    #[account(address = crate::admin::id() @ ErrorCode::NotApproved)]
+    pub owner: Signer<'info>,
+
+    /// The queued action to cancel
+    #[account(
+        mut,
+        has_one = payer,
+        close = payer
+    )]
+    pub timelock_action: Account<'info, TimelockAction>,
+
+    /// Receives the rent of the action
+    /// CHECK: The payer recorded by the action
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+}
+
+pub fn cancel_admin_action(ctx: Context<CancelAdminAction>) -> Result<()> {
+    let timelock_action = &ctx.accounts.timelock_action;
+    emit!(CancelAdminActionEvent {
+        id: timelock_action.id,
+        action: timelock_action.action,
+    });
+    Ok(())
+}
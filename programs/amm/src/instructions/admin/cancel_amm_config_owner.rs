@@ -0,0 +1,33 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CancelAmmConfigOwner<'info> {
+    /// The admin withdrawing the proposal
+    #[account(address = crate::admin::id() @ ErrorCode::NotApproved)]
+    pub owner: Signer<'info>,
+
+    /// The proposal to cancel
+    #[account(
+        mut,
+        has_one = payer,
+        close = payer
+    )]
+    pub proposal: Account<'info, AmmConfigOwnerProposal>,
+
+    /// Receives the rent of the proposal
+    /// CHECK: The payer recorded by the proposal
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+}
+
+pub fn cancel_amm_config_owner(ctx: Context<CancelAmmConfigOwner>) -> Result<()> {
+    let proposal = &ctx.accounts.proposal;
+    emit!(CancelAmmConfigOwnerEvent {
+        amm_config: proposal.amm_config,
+        owner_type: proposal.owner_type,
+        pending_owner: proposal.pending_owner,
+    });
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CancelRewardOwner<'info> {
+    /// The admin withdrawing the proposal
+    #[account(address = crate::admin::id() @ ErrorCode::NotApproved)]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn cancel_reward_owner(ctx: Context<CancelRewardOwner>) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.check_unlocked()?;
+    let pending_owner = pool_state.cancel_owner_proposal()?;
+
+    emit!(CancelRewardOwnerEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        pending_owner,
+    });
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use anchor_spl::{token_interface::{Token2022, TokenAccount}};
+use anchor_spl::{token_interface::{Mint, TokenAccount, TokenInterface}};
 
 use anchor_lang::prelude::*;
 
@@ -35,6 +35,14 @@ pub struct CollectFundFee<'info> {
     )]
     pub token_vault_1: InterfaceAccount<'info, TokenAccount>,
 
+    /// The mint of token vault 0
+    #[account(address = token_vault_0.mint)]
+    pub vault_0_mint: InterfaceAccount<'info, Mint>,
+
+    /// The mint of token vault 1
+    #[account(address = token_vault_1.mint)]
+    pub vault_1_mint: InterfaceAccount<'info, Mint>,
+
     /// The address that receives the collected token_0 protocol fees
     #[account(mut)]
     pub recipient_token_account_0: InterfaceAccount<'info, TokenAccount>,
@@ -43,12 +51,15 @@ pub struct CollectFundFee<'info> {
     #[account(mut)]
     pub recipient_token_account_1: InterfaceAccount<'info, TokenAccount>,
 
-    /// The SPL program to perform token transfers
-    pub token_program: Program<'info, Token2022>,
+    /// The SPL program or token program 2022 to perform token_0 transfers
+    pub token_program_0: Interface<'info, TokenInterface>,
+
+    /// The SPL program or token program 2022 to perform token_1 transfers
+    pub token_program_1: Interface<'info, TokenInterface>,
 }
 
-pub fn collect_fund_fee(
-    ctx: Context<CollectFundFee>,
+pub fn collect_fund_fee<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CollectFundFee<'info>>,
     amount_0_requested: u64,
     amount_1_requested: u64,
 ) -> Result<()> {
@@ -56,17 +67,25 @@ pub fn collect_fund_fee(
     let amount_1: u64;
     {
         let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        pool_state.check_unlocked()?;
         amount_0 = amount_0_requested.min(pool_state.fund_fees_token_0);
         amount_1 = amount_1_requested.min(pool_state.fund_fees_token_1);
 
         pool_state.fund_fees_token_0 = pool_state.fund_fees_token_0.checked_sub(amount_0).unwrap();
         pool_state.fund_fees_token_1 = pool_state.fund_fees_token_1.checked_sub(amount_1).unwrap();
     }
+    let mut remaining_accounts = ctx.remaining_accounts;
+    let transfer_hook_accounts_0 =
+        get_transfer_hook_accounts(&ctx.accounts.vault_0_mint, &mut remaining_accounts)?;
+    let transfer_hook_accounts_1 =
+        get_transfer_hook_accounts(&ctx.accounts.vault_1_mint, &mut remaining_accounts)?;
     transfer_from_pool_vault_to_user(
         &ctx.accounts.pool_state,
         &ctx.accounts.token_vault_0,
         &ctx.accounts.recipient_token_account_0,
-        &ctx.accounts.token_program,
+        &ctx.accounts.vault_0_mint,
+        &ctx.accounts.token_program_0,
+        &transfer_hook_accounts_0,
         amount_0,
     )?;
 
@@ -74,7 +93,9 @@ pub fn collect_fund_fee(
         &ctx.accounts.pool_state,
         &ctx.accounts.token_vault_1,
         &ctx.accounts.recipient_token_account_1,
-        &ctx.accounts.token_program,
+        &ctx.accounts.vault_1_mint,
+        &ctx.accounts.token_program_1,
+        &transfer_hook_accounts_1,
         amount_1,
     )?;
 
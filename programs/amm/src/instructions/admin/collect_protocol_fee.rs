@@ -3,7 +3,7 @@ use crate::error::ErrorCode;
 use crate::states::*;
 use crate::util::*;
 use anchor_lang::prelude::*;
-use anchor_spl::{token_interface::{Token2022, TokenAccount}};
+use anchor_spl::{token_interface::{Mint, TokenAccount, TokenInterface}};
 
 #[derive(Accounts)]
 pub struct CollectProtocolFee<'info> {
@@ -35,6 +35,14 @@ pub struct CollectProtocolFee<'info> {
     )]
     pub token_vault_1: InterfaceAccount<'info, TokenAccount>,
 
+    /// The mint of token vault 0
+    #[account(address = token_vault_0.mint)]
+    pub vault_0_mint: InterfaceAccount<'info, Mint>,
+
+    /// The mint of token vault 1
+    #[account(address = token_vault_1.mint)]
+    pub vault_1_mint: InterfaceAccount<'info, Mint>,
+
     /// The address that receives the collected token_0 protocol fees
     #[account(mut)]
     pub recipient_token_account_0: InterfaceAccount<'info, TokenAccount>,
@@ -43,12 +51,15 @@ pub struct CollectProtocolFee<'info> {
     #[account(mut)]
     pub recipient_token_account_1: InterfaceAccount<'info, TokenAccount>,
 
-    /// The SPL program to perform token transfers
-    pub token_program: Program<'info, Token2022>,
+    /// The SPL program or token program 2022 to perform token_0 transfers
+    pub token_program_0: Interface<'info, TokenInterface>,
+
+    /// The SPL program or token program 2022 to perform token_1 transfers
+    pub token_program_1: Interface<'info, TokenInterface>,
 }
 
-pub fn collect_protocol_fee(
-    ctx: Context<CollectProtocolFee>,
+pub fn collect_protocol_fee<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CollectProtocolFee<'info>>,
     amount_0_requested: u64,
     amount_1_requested: u64,
 ) -> Result<()> {
@@ -56,6 +67,7 @@ pub fn collect_protocol_fee(
     let amount_1: u64;
     {
         let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        pool_state.check_unlocked()?;
 
         amount_0 = amount_0_requested.min(pool_state.protocol_fees_token_0);
         amount_1 = amount_1_requested.min(pool_state.protocol_fees_token_1);
@@ -69,11 +81,18 @@ pub fn collect_protocol_fee(
             .checked_sub(amount_1)
             .unwrap();
     }
+    let mut remaining_accounts = ctx.remaining_accounts;
+    let transfer_hook_accounts_0 =
+        get_transfer_hook_accounts(&ctx.accounts.vault_0_mint, &mut remaining_accounts)?;
+    let transfer_hook_accounts_1 =
+        get_transfer_hook_accounts(&ctx.accounts.vault_1_mint, &mut remaining_accounts)?;
     transfer_from_pool_vault_to_user(
         &ctx.accounts.pool_state,
         &ctx.accounts.token_vault_0,
         &ctx.accounts.recipient_token_account_0,
-        &ctx.accounts.token_program,
+        &ctx.accounts.vault_0_mint,
+        &ctx.accounts.token_program_0,
+        &transfer_hook_accounts_0,
         amount_0,
     )?;
 
@@ -81,7 +100,9 @@ pub fn collect_protocol_fee(
         &ctx.accounts.pool_state,
         &ctx.accounts.token_vault_1,
         &ctx.accounts.recipient_token_account_1,
-        &ctx.accounts.token_program,
+        &ctx.accounts.vault_1_mint,
+        &ctx.accounts.token_program_1,
+        &transfer_hook_accounts_1,
         amount_1,
     )?;
 
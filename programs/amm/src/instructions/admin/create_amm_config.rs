@@ -41,10 +41,10 @@ pub fn create_amm_config(
     amm_config.owner = ctx.accounts.owner.key();
     amm_config.bump = *ctx.bumps.get("amm_config").unwrap();
     amm_config.index = index;
-    amm_config.trade_fee_rate = trade_fee_rate;
-    amm_config.protocol_fee_rate = protocol_fee_rate;
+    amm_config.set_trade_fee_rate(trade_fee_rate)?;
+    amm_config.set_protocol_fee_rate(protocol_fee_rate)?;
     amm_config.tick_spacing = tick_spacing;
-    amm_config.fund_fee_rate = fund_fee_rate;
+    amm_config.set_fund_fee_rate(fund_fee_rate)?;
     amm_config.fund_owner = ctx.accounts.owner.key();
 
     emit!(CreateConfigEvent {
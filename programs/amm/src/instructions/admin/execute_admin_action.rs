@@ -0,0 +1,143 @@
+use super::emit_update_config_event;
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ExecuteAdminAction<'info> {
+    /// Anyone can execute an action once its delay passes
+    pub executor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            TIMELOCK_CONFIG_SEED.as_bytes(),
+        ],
+        bump = timelock_config.bump,
+    )]
+    pub timelock_config: Account<'info, TimelockConfig>,
+
+    /// The queued action to execute
+    #[account(
+        mut,
+        has_one = payer,
+        close = payer
+    )]
+    pub timelock_action: Account<'info, TimelockAction>,
+
+    /// Receives the rent of the action
+    /// CHECK: The payer recorded by the action
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+
+    /// The amm config the action changes, if any. The amm config of the pool when the trade fee
+    /// rate or the dynamic fee of the pool changes
+    #[account(mut)]
+    pub amm_config: Option<Account<'info, AmmConfig>>,
+
+    /// The pool the action changes, if any
+    #[account(mut)]
+    pub pool_state: Option<AccountLoader<'info, PoolState>>,
+}
+
+pub fn execute_admin_action(ctx: Context<ExecuteAdminAction>) -> Result<()> {
+    let current_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
+    let timelock_action = &ctx.accounts.timelock_action;
+    require_gte!(
+        current_timestamp,
+        timelock_action.execute_after,
+        ErrorCode::TimelockNotElapsed
+    );
+
+    let action = timelock_action.action;
+    match action {
+        AdminAction::SetTradeFeeRate {
+            amm_config,
+            trade_fee_rate,
+        } => {
+            let amm_config = get_amm_config(&mut ctx.accounts.amm_config, amm_config)?;
+            amm_config.set_trade_fee_rate(trade_fee_rate)?;
+            emit_update_config_event(amm_config);
+        }
+        AdminAction::SetProtocolFeeRate {
+            amm_config,
+            protocol_fee_rate,
+        } => {
+            let amm_config = get_amm_config(&mut ctx.accounts.amm_config, amm_config)?;
+            amm_config.set_protocol_fee_rate(protocol_fee_rate)?;
+            emit_update_config_event(amm_config);
+        }
+        AdminAction::SetFundFeeRate {
+            amm_config,
+            fund_fee_rate,
+        } => {
+            let amm_config = get_amm_config(&mut ctx.accounts.amm_config, amm_config)?;
+            amm_config.set_fund_fee_rate(fund_fee_rate)?;
+            emit_update_config_event(amm_config);
+        }
+        AdminAction::UpdatePoolStatus { pool_state, status } => {
+            let pool_state_loader = get_pool_state(&ctx.accounts.pool_state, pool_state)?;
+            pool_state_loader.load_mut()?.set_status(status);
+        }
+        AdminAction::SetPoolTradeFeeRate {
+            pool_state,
+            trade_fee_rate_override,
+        } => {
+            let pool_state_loader = get_pool_state(&ctx.accounts.pool_state, pool_state)?;
+            let mut pool_state = pool_state_loader.load_mut()?;
+            pool_state.check_unlocked()?;
+            let amm_config = get_amm_config(&mut ctx.accounts.amm_config, pool_state.amm_config)?;
+            let old_trade_fee_rate_override =
+                pool_state.set_trade_fee_rate_override(trade_fee_rate_override)?;
+
+            emit!(UpdatePoolTradeFeeRateEvent {
+                pool_state: pool_state_loader.key(),
+                authority: timelock_action.payer,
+                old_trade_fee_rate_override,
+                trade_fee_rate_override,
+                trade_fee_rate: pool_state.get_trade_fee_rate(amm_config.trade_fee_rate),
+            });
+        }
+        AdminAction::SetDynamicFeeParams { pool_state, param } => {
+            let pool_state_loader = get_pool_state(&ctx.accounts.pool_state, pool_state)?;
+            let mut pool_state = pool_state_loader.load_mut()?;
+            pool_state.check_unlocked()?;
+            let amm_config = get_amm_config(&mut ctx.accounts.amm_config, pool_state.amm_config)?;
+            param.apply(&mut pool_state, amm_config, current_timestamp)?;
+        }
+        AdminAction::SetTimelockDelay { delay } => {
+            let old_delay = ctx.accounts.timelock_config.delay;
+            ctx.accounts.timelock_config.delay = delay;
+            emit!(SetTimelockDelayEvent {
+                old_delay,
+                new_delay: delay,
+            });
+        }
+    }
+
+    emit!(ExecuteAdminActionEvent {
+        id: timelock_action.id,
+        action,
+    });
+    Ok(())
+}
+
+fn get_pool_state<'a, 'info>(
+    pool_state: &'a Option<AccountLoader<'info, PoolState>>,
+    expect_pool_state: Pubkey,
+) -> Result<&'a AccountLoader<'info, PoolState>> {
+    pool_state
+        .as_ref()
+        .filter(|pool_state| pool_state.key() == expect_pool_state)
+        .ok_or_else(|| error!(ErrorCode::InvalidAdminActionTarget))
+}
+
+fn get_amm_config<'a, 'info>(
+    amm_config: &'a mut Option<Account<'info, AmmConfig>>,
+    expect_amm_config: Pubkey,
+) -> Result<&'a mut Account<'info, AmmConfig>> {
+    amm_config
+        .as_mut()
+        .filter(|amm_config| amm_config.key() == expect_amm_config)
+        .ok_or_else(|| error!(ErrorCode::InvalidAdminActionTarget))
+}
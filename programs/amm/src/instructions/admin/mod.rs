@@ -16,8 +16,44 @@ pub use create_operation_account::*;
 pub mod update_operation_account;
 pub use update_operation_account::*;
 
+pub mod propose_amm_config_owner;
+pub use propose_amm_config_owner::*;
+
+pub mod accept_amm_config_owner;
+pub use accept_amm_config_owner::*;
+
+pub mod cancel_amm_config_owner;
+pub use cancel_amm_config_owner::*;
+
 pub mod transfer_reward_owner;
 pub use transfer_reward_owner::*;
 
+pub mod accept_reward_owner;
+pub use accept_reward_owner::*;
+
+pub mod cancel_reward_owner;
+pub use cancel_reward_owner::*;
+
 pub mod update_pool_status;
-pub use update_pool_status::*;
\ No newline at end of file
+pub use update_pool_status::*;
+
+pub mod set_timelock_delay;
+pub use set_timelock_delay::*;
+
+pub mod queue_admin_action;
+pub use queue_admin_action::*;
+
+pub mod cancel_admin_action;
+pub use cancel_admin_action::*;
+
+pub mod execute_admin_action;
+pub use execute_admin_action::*;
+
+pub mod update_observation_update_duration;
+pub use update_observation_update_duration::*;
+
+pub mod set_dynamic_fee_params;
+pub use set_dynamic_fee_params::*;
+
+pub mod set_pool_trade_fee_rate;
+pub use set_pool_trade_fee_rate::*;
\ No newline at end of file
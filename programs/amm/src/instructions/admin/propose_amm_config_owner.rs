@@ -0,0 +1,52 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(owner_type: AmmConfigOwnerType)]
+pub struct ProposeAmmConfigOwner<'info> {
+    /// The admin proposing the owner, pays the rent of the proposal
+    #[account(mut, address = crate::admin::id() @ ErrorCode::NotApproved)]
+    pub owner: Signer<'info>,
+
+    /// Amm config account whose owner is proposed
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// The proposal of the owner type, a pending proposal is replaced
+    #[account(
+        init_if_needed,
+        seeds = [
+            AMM_CONFIG_OWNER_PROPOSAL_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+            &[owner_type as u8],
+        ],
+        bump,
+        payer = owner,
+        space = AmmConfigOwnerProposal::LEN
+    )]
+    pub proposal: Account<'info, AmmConfigOwnerProposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn propose_amm_config_owner(
+    ctx: Context<ProposeAmmConfigOwner>,
+    owner_type: AmmConfigOwnerType,
+    new_owner: Pubkey,
+) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.propose(
+        *ctx.bumps.get("proposal").unwrap(),
+        ctx.accounts.amm_config.key(),
+        owner_type,
+        ctx.accounts.owner.key(),
+        new_owner,
+    )?;
+
+    emit!(ProposeAmmConfigOwnerEvent {
+        amm_config: proposal.amm_config,
+        owner_type,
+        pending_owner: new_owner,
+    });
+    Ok(())
+}
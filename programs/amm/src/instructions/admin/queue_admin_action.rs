@@ -0,0 +1,56 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct QueueAdminAction<'info> {
+    /// The admin, pays the rent of the queued action
+    #[account(mut, address = crate::admin::id() @ ErrorCode::NotApproved)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            TIMELOCK_CONFIG_SEED.as_bytes(),
+        ],
+        bump = timelock_config.bump,
+    )]
+    pub timelock_config: Account<'info, TimelockConfig>,
+
+    /// The queued action, its id is the number of actions queued before
+    #[account(
+        init,
+        seeds = [
+            TIMELOCK_ACTION_SEED.as_bytes(),
+            &timelock_config.action_num.to_be_bytes(),
+        ],
+        bump,
+        payer = owner,
+        space = TimelockAction::LEN
+    )]
+    pub timelock_action: Account<'info, TimelockAction>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn queue_admin_action(ctx: Context<QueueAdminAction>, action: AdminAction) -> Result<()> {
+    action.check()?;
+    let current_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
+    let timelock_config = &mut ctx.accounts.timelock_config;
+    let timelock_action = &mut ctx.accounts.timelock_action;
+    timelock_action.bump = *ctx.bumps.get("timelock_action").unwrap();
+    timelock_action.id = timelock_config.action_num;
+    timelock_action.action = action;
+    timelock_action.execute_after = current_timestamp
+        .checked_add(timelock_config.delay)
+        .unwrap();
+    timelock_action.payer = ctx.accounts.owner.key();
+    timelock_config.action_num = timelock_config.action_num.checked_add(1).unwrap();
+
+    emit!(QueueAdminActionEvent {
+        id: timelock_action.id,
+        action,
+        execute_after: timelock_action.execute_after,
+    });
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetDynamicFeeParams<'info> {
+    /// Only admin or operation owners can set the dynamic fee
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The amm config of the pool, the cap of the fee rate can't be below its trade fee rate
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// load info from the account to judge the permission
+    #[account(
+        seeds = [
+            OPERATION_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub operation_state: AccountLoader<'info, OperationState>,
+
+    /// The timelock config, the changes must be queued once its delay is set
+    /// CHECK: The timelock is disabled if the account is not created
+    #[account(
+        seeds = [
+            TIMELOCK_CONFIG_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub timelock_config: UncheckedAccount<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DynamicFeeParam {
+    /// The cap of the fee rate, 0 to disable the dynamic fee
+    pub max_fee_rate: u32,
+    /// Scales the square of the volatility to the dynamic part of the fee rate
+    pub dynamic_fee_control: u32,
+    /// The cap of the volatility accumulator
+    pub max_volatility_accumulator: u32,
+    /// Swaps within the filter period since the last swap don't update the references
+    pub filter_period: u16,
+    /// The volatility reference is reset if there is no swap within the decay period
+    pub decay_period: u16,
+    /// The part of the volatility accumulator kept by the volatility reference, in bps
+    pub reduction_factor: u16,
+}
+
+impl DynamicFeeParam {
+    /// `trade_fee_rate` is the base trade fee rate of the pool, an enabled cap below it would be
+    /// ignored by swaps
+    pub fn check(&self, trade_fee_rate: u32) -> Result<()> {
+        if self.max_fee_rate >= FEE_RATE_DENOMINATOR_VALUE
+            || self.filter_period > self.decay_period
+            || self.reduction_factor > REDUCTION_FACTOR_DENOMINATOR
+        {
+            return Err(ErrorCode::InvalidDynamicFeeParam.into());
+        }
+        if self.max_fee_rate != 0 {
+            require_gte!(
+                self.max_fee_rate,
+                trade_fee_rate,
+                ErrorCode::InvalidDynamicFeeParam
+            );
+        }
+        Ok(())
+    }
+
+    /// Sets the dynamic fee of the pool, the volatility is measured again from the current tick
+    pub fn apply(
+        &self,
+        pool_state: &mut PoolState,
+        amm_config: &AmmConfig,
+        current_timestamp: u64,
+    ) -> Result<()> {
+        self.check(pool_state.get_trade_fee_rate(amm_config.trade_fee_rate))?;
+        pool_state.dynamic_fee_info = DynamicFeeInfo {
+            max_fee_rate: self.max_fee_rate,
+            dynamic_fee_control: self.dynamic_fee_control,
+            max_volatility_accumulator: self.max_volatility_accumulator,
+            filter_period: self.filter_period,
+            decay_period: self.decay_period,
+            reduction_factor: self.reduction_factor,
+            tick_reference: pool_state.tick_current,
+            last_update_timestamp: current_timestamp,
+            ..Default::default()
+        };
+        Ok(())
+    }
+}
+
+pub fn set_dynamic_fee_params(
+    ctx: Context<SetDynamicFeeParams>,
+    param: DynamicFeeParam,
+) -> Result<()> {
+    let operation_state = ctx.accounts.operation_state.load()?;
+    require!(
+        ctx.accounts.authority.key() == crate::admin::id()
+            || operation_state.validate_operation_owner(ctx.accounts.authority.key()),
+        ErrorCode::NotApproved
+    );
+    TimelockConfig::check_disabled(&ctx.accounts.timelock_config)?;
+
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.check_unlocked()?;
+    param.apply(
+        &mut pool_state,
+        &ctx.accounts.amm_config,
+        Clock::get()?.unix_timestamp as u64,
+    )
+}
+
+#[cfg(test)]
+mod set_dynamic_fee_params_test {
+    use super::*;
+
+    #[test]
+    fn apply_test() {
+        let param = DynamicFeeParam {
+            max_fee_rate: 10_000,
+            dynamic_fee_control: 50_000,
+            max_volatility_accumulator: 350_000,
+            filter_period: 30,
+            decay_period: 600,
+            reduction_factor: 5_000,
+        };
+        let amm_config = AmmConfig {
+            trade_fee_rate: 10_000,
+            ..Default::default()
+        };
+        let mut pool_state = PoolState::default();
+        param.apply(&mut pool_state, &amm_config, 100).unwrap();
+        assert_eq!({ pool_state.dynamic_fee_info.max_fee_rate }, 10_000);
+
+        // the cap can't be below the trade fee rate of the pool
+        pool_state.trade_fee_rate_override = 20_000;
+        assert_eq!(
+            param.apply(&mut pool_state, &amm_config, 100).unwrap_err(),
+            ErrorCode::InvalidDynamicFeeParam.into()
+        );
+        let disabled = DynamicFeeParam {
+            max_fee_rate: 0,
+            ..param
+        };
+        disabled.apply(&mut pool_state, &amm_config, 100).unwrap();
+        assert_eq!({ pool_state.dynamic_fee_info.max_fee_rate }, 0);
+    }
+}
@@ -0,0 +1,62 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPoolTradeFeeRate<'info> {
+    /// Only admin or operation owners can override the trade fee rate
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The amm config of the pool, used to log the effective trade fee rate
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// load info from the account to judge the permission
+    #[account(
+        seeds = [
+            OPERATION_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub operation_state: AccountLoader<'info, OperationState>,
+
+    /// The timelock config, the changes must be queued once its delay is set
+    /// CHECK: The timelock is disabled if the account is not created
+    #[account(
+        seeds = [
+            TIMELOCK_CONFIG_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub timelock_config: UncheckedAccount<'info>,
+}
+
+pub fn set_pool_trade_fee_rate(
+    ctx: Context<SetPoolTradeFeeRate>,
+    trade_fee_rate_override: u32,
+) -> Result<()> {
+    let operation_state = ctx.accounts.operation_state.load()?;
+    require!(
+        ctx.accounts.authority.key() == crate::admin::id()
+            || operation_state.validate_operation_owner(ctx.accounts.authority.key()),
+        ErrorCode::NotApproved
+    );
+    TimelockConfig::check_disabled(&ctx.accounts.timelock_config)?;
+
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.check_unlocked()?;
+    let old_trade_fee_rate_override =
+        pool_state.set_trade_fee_rate_override(trade_fee_rate_override)?;
+
+    emit!(UpdatePoolTradeFeeRateEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        authority: ctx.accounts.authority.key(),
+        old_trade_fee_rate_override,
+        trade_fee_rate_override,
+        trade_fee_rate: pool_state.get_trade_fee_rate(ctx.accounts.amm_config.trade_fee_rate),
+    });
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetTimelockDelay<'info> {
+    /// The admin, pays the rent of the timelock config
+    #[account(mut, address = crate::admin::id() @ ErrorCode::NotApproved)]
+    pub owner: Signer<'info>,
+
+    /// The timelock config, created on the first call
+    #[account(
+        init_if_needed,
+        seeds = [
+            TIMELOCK_CONFIG_SEED.as_bytes(),
+        ],
+        bump,
+        payer = owner,
+        space = TimelockConfig::LEN
+    )]
+    pub timelock_config: Account<'info, TimelockConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn set_timelock_delay(ctx: Context<SetTimelockDelay>, delay: u64) -> Result<()> {
+    require_gte!(MAX_TIMELOCK_DELAY, delay, ErrorCode::InvalidTimelockDelay);
+    let timelock_config = &mut ctx.accounts.timelock_config;
+    // the delay can be raised at once, lowering it must wait the current delay
+    require_gte!(delay, timelock_config.delay, ErrorCode::TimelockEnabled);
+    timelock_config.bump = *ctx.bumps.get("timelock_config").unwrap();
+    let old_delay = timelock_config.delay;
+    timelock_config.delay = delay;
+
+    emit!(SetTimelockDelayEvent {
+        old_delay,
+        new_delay: delay,
+    });
+    Ok(())
+}
@@ -13,14 +13,18 @@ pub struct TransferRewardOwner<'info> {
     pub pool_state: AccountLoader<'info, PoolState>,
 }
 
+/// Proposes the new owner of the pool, which takes over once it accepts
 pub fn transfer_reward_owner<'a, 'b, 'c, 'info>(
     ctx: Context<'a, 'b, 'c, 'info, TransferRewardOwner<'info>>,
     new_owner: Pubkey,
 ) -> Result<()> {
     let mut pool_state = ctx.accounts.pool_state.load_mut()?;
-    for reward_info in &mut pool_state.reward_infos {
-        reward_info.authority = new_owner;
-    }
-    pool_state.owner = new_owner;
+    pool_state.check_unlocked()?;
+    pool_state.propose_owner(new_owner)?;
+
+    emit!(ProposeRewardOwnerEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        pending_owner: new_owner,
+    });
     Ok(())
 }
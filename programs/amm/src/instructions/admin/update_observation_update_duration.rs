@@ -0,0 +1,29 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateObservationUpdateDuration<'info> {
+    /// Only admin or pool owner can update the observation update duration
+    #[account(constraint = (authority.key() == pool_state.load()?.owner || authority.key() == crate::admin::id()) @ ErrorCode::NotApproved)]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn update_observation_update_duration(
+    ctx: Context<UpdateObservationUpdateDuration>,
+    observation_update_duration: u16,
+) -> Result<()> {
+    // observations written in the same second can't be interpolated
+    require_gt!(
+        observation_update_duration,
+        0,
+        ErrorCode::InvalidObservationUpdateDuration
+    );
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.check_unlocked()?;
+    pool_state.observation_update_duration = observation_update_duration;
+    Ok(())
+}
@@ -23,19 +23,28 @@ pub struct UpdateOperationAccount<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn update_operation_account(
+pub fn add_operation_owners(ctx: Context<UpdateOperationAccount>, keys: Vec<Pubkey>) -> Result<()> {
+    let mut operation_state = ctx.accounts.operation_state.load_mut()?;
+    operation_state.add_operation_owners(&keys)
+}
+
+pub fn remove_operation_owners(
+    ctx: Context<UpdateOperationAccount>,
+    keys: Vec<Pubkey>,
+) -> Result<()> {
+    let mut operation_state = ctx.accounts.operation_state.load_mut()?;
+    operation_state.remove_operation_owners(&keys)
+}
+
+pub fn add_whitelist_mints(ctx: Context<UpdateOperationAccount>, keys: Vec<Pubkey>) -> Result<()> {
+    let mut operation_state = ctx.accounts.operation_state.load_mut()?;
+    operation_state.add_whitelist_mints(&keys)
+}
+
+pub fn remove_whitelist_mints(
     ctx: Context<UpdateOperationAccount>,
-    param: u8,
     keys: Vec<Pubkey>,
 ) -> Result<()> {
     let mut operation_state = ctx.accounts.operation_state.load_mut()?;
-    let match_param = Some(param);
-    match match_param {
-        Some(0) => operation_state.update_operation_owner(keys),
-        Some(1) => operation_state.remove_operation_owner(keys),
-        Some(2) => operation_state.update_whitelist_mint(keys),
-        Some(3) => operation_state.remove_whitelist_mint(keys),
-        _ => return err!(ErrorCode::InvalidUpdateConfigFlag),
-    }
-    Ok(())
+    operation_state.remove_whitelist_mints(&keys)
 }
@@ -10,6 +10,17 @@ pub struct UpdatePoolStatus<'info> {
 
     #[account(mut)]
     pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The timelock config, the status changes but emergency pauses must be queued once its
+    /// delay is set
+    /// CHECK: The timelock is disabled if the account is not created
+    #[account(
+        seeds = [
+            TIMELOCK_CONFIG_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub timelock_config: UncheckedAccount<'info>,
 }
 
 pub fn update_pool_status(
@@ -18,6 +29,9 @@ pub fn update_pool_status(
 ) -> Result<()> {
     require_gte!(255, status);
     let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    if !pool_state.is_emergency_pause(status) {
+        TimelockConfig::check_disabled(&ctx.accounts.timelock_config)?;
+    }
     pool_state.set_status(status);
     Ok(())
 }
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use super::check_reward_authority;
+use crate::states::*;
+
+#[derive(Accounts)]
+pub struct CancelReward<'info> {
+    /// The authority of the reward or the admin
+    pub authority: Signer<'info>,
+
+    /// The pool of the reward
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn cancel_reward(ctx: Context<CancelReward>, reward_index: u8) -> Result<()> {
+    cancel_pool_reward(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.authority.key(),
+        reward_index,
+        u64::try_from(Clock::get()?.unix_timestamp).unwrap(),
+    )
+}
+
+/// Stops the emissions of the reward at `current_timestamp`, `authority` must be the authority of
+/// the reward or the admin
+pub fn cancel_pool_reward(
+    pool_state_loader: &AccountLoader<PoolState>,
+    authority: &Pubkey,
+    reward_index: u8,
+    current_timestamp: u64,
+) -> Result<()> {
+    assert!((reward_index as usize) < REWARD_NUM);
+    let mut pool_state = pool_state_loader.load_mut()?;
+    pool_state.check_unlocked()?;
+    check_reward_authority(&pool_state, reward_index, authority)?;
+
+    // settle the emissions until now before stopping them
+    pool_state.update_reward_infos(current_timestamp)?;
+    pool_state.cancel_reward(reward_index as usize, current_timestamp)
+}
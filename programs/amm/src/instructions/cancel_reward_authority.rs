@@ -0,0 +1,31 @@
+use super::check_reward_authority;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CancelRewardAuthority<'info> {
+    /// The authority of the reward or the admin
+    pub authority: Signer<'info>,
+
+    /// The pool of the reward
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn cancel_reward_authority(
+    ctx: Context<CancelRewardAuthority>,
+    reward_index: u8,
+) -> Result<()> {
+    assert!((reward_index as usize) < REWARD_NUM);
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.check_unlocked()?;
+    check_reward_authority(&pool_state, reward_index, &ctx.accounts.authority.key())?;
+    let pending_authority = pool_state.cancel_reward_authority(reward_index as usize)?;
+
+    emit!(CancelRewardAuthorityEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        reward_index,
+        pending_authority,
+    });
+    Ok(())
+}
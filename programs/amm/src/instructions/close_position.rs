@@ -4,7 +4,7 @@ use anchor_lang::prelude::*;
 
 use crate::error::ErrorCode;
 use crate::states::*;
-use crate::util::{burn, close_spl_account};
+use crate::util::{burn, close_spl_account, get_mint_close_authority};
 
 #[derive(Accounts)]
 pub struct ClosePosition<'info> {
@@ -42,6 +42,19 @@ pub struct ClosePosition<'info> {
     )]
     pub personal_position: Box<Account<'info, PersonalPositionState>>,
 
+    /// The protocol position of the range, no longer referenced by the personal position
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            personal_position.pool_id.as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump = protocol_position.bump,
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
     /// Program to create the position manager state account
     pub system_program: Program<'info, System>,
     /// Program to create mint account and mint tokens
@@ -51,30 +64,10 @@ pub struct ClosePosition<'info> {
 pub fn close_position<'a, 'b, 'c, 'info>(
     ctx: Context<'a, 'b, 'c, 'info, ClosePosition<'info>>,
 ) -> Result<()> {
-    if ctx.accounts.personal_position.liquidity != 0
-        || ctx.accounts.personal_position.token_fees_owed_0 != 0
-        || ctx.accounts.personal_position.token_fees_owed_1 != 0
-    {
-        msg!(
-            "remaing liquidity:{},token_fees_owed_0:{},token_fees_owed_1:{}",
-            ctx.accounts.personal_position.liquidity,
-            ctx.accounts.personal_position.token_fees_owed_0,
-            ctx.accounts.personal_position.token_fees_owed_1
-        );
-        return err!(ErrorCode::ClosePositionErr);
-    }
-
-    for i in 0..ctx.accounts.personal_position.reward_infos.len() {
-        if ctx.accounts.personal_position.reward_infos[i].reward_amount_owed != 0 {
-            msg!(
-                "remaing reward index:{},amount:{}",
-                i,
-                ctx.accounts.personal_position.reward_infos[i].reward_amount_owed,
-            );
-            return err!(ErrorCode::ClosePositionErr);
-        }
-    }
+    check_position_closable(&ctx.accounts.personal_position)?;
+    uncount_personal_position(&mut ctx.accounts.protocol_position);
 
+    let personal_position = &ctx.accounts.personal_position;
     burn(
         &ctx.accounts.nft_owner,
         &ctx.accounts.position_nft_mint,
@@ -93,5 +86,122 @@ pub fn close_position<'a, 'b, 'c, 'info>(
         &[],
     )?;
 
+    // the mint of a token program 2022 position NFT can be closed by the personal position,
+    // which returns the rent of the mint and its metadata to the owner
+    if get_mint_close_authority(&ctx.accounts.position_nft_mint)? == Some(personal_position.key()) {
+        close_spl_account(
+            &personal_position.to_account_info(),
+            &ctx.accounts.nft_owner.to_account_info(),
+            &ctx.accounts.position_nft_mint.to_account_info(),
+            &ctx.accounts.token_program,
+            &[&[
+                POSITION_SEED.as_bytes(),
+                ctx.accounts.position_nft_mint.key().as_ref(),
+                &[personal_position.bump],
+            ]],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// A position can be closed once its liquidity, fees, settled range order and rewards are withdrawn
+fn check_position_closable(personal_position: &PersonalPositionState) -> Result<()> {
+    if personal_position.liquidity != 0
+        || personal_position.token_fees_owed_0 != 0
+        || personal_position.token_fees_owed_1 != 0
+        || personal_position.range_order_amount_0 != 0
+        || personal_position.range_order_amount_1 != 0
+    {
+        msg!(
+            "remaing liquidity:{},token_fees_owed_0:{},token_fees_owed_1:{},range_order_amount_0:{},range_order_amount_1:{}",
+            personal_position.liquidity,
+            personal_position.token_fees_owed_0,
+            personal_position.token_fees_owed_1,
+            personal_position.range_order_amount_0,
+            personal_position.range_order_amount_1
+        );
+        return err!(ErrorCode::ClosePositionErr);
+    }
+
+    for i in 0..personal_position.reward_infos.len() {
+        if personal_position.reward_infos[i].reward_amount_owed != 0 {
+            msg!(
+                "remaing reward index:{},amount:{}",
+                i,
+                personal_position.reward_infos[i].reward_amount_owed,
+            );
+            return err!(ErrorCode::ClosePositionErr);
+        }
+    }
     Ok(())
 }
+
+/// Drops a closed personal position from the count of its protocol position. The protocol
+/// positions created before the personal positions were counted have no payer, their count is
+/// meaningless and they can never be closed, see `check_rent_receiver`
+fn uncount_personal_position(protocol_position: &mut ProtocolPositionState) {
+    if protocol_position.payer != Pubkey::default() {
+        protocol_position.personal_position_count = protocol_position
+            .personal_position_count
+            .checked_sub(1)
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod close_position_test {
+    use super::*;
+
+    #[test]
+    fn uncount_personal_position_test() {
+        let mut protocol_position = ProtocolPositionState {
+            payer: Pubkey::new_unique(),
+            personal_position_count: 2,
+            ..Default::default()
+        };
+        uncount_personal_position(&mut protocol_position);
+        assert_eq!(protocol_position.personal_position_count, 1);
+
+        // an uncounted protocol position keeps its count
+        let mut protocol_position = ProtocolPositionState::default();
+        uncount_personal_position(&mut protocol_position);
+        assert_eq!(protocol_position.personal_position_count, 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn uncount_personal_position_underflow_test() {
+        let mut protocol_position = ProtocolPositionState {
+            payer: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        uncount_personal_position(&mut protocol_position);
+    }
+
+    #[test]
+    fn check_position_closable_test() {
+        let personal_position = PersonalPositionState::default();
+        check_position_closable(&personal_position).unwrap();
+
+        let mut not_closable_positions = Vec::new();
+        for i in 0..8 {
+            let mut personal_position = PersonalPositionState::default();
+            match i {
+                0 => personal_position.liquidity = 1,
+                1 => personal_position.token_fees_owed_0 = 1,
+                2 => personal_position.token_fees_owed_1 = 1,
+                3 => personal_position.range_order_amount_0 = 1,
+                4 => personal_position.range_order_amount_1 = 1,
+                _ => personal_position.reward_infos[i - 5].reward_amount_owed = 1,
+            }
+            not_closable_positions.push(personal_position);
+        }
+        for personal_position in not_closable_positions {
+            assert_eq!(
+                check_position_closable(&personal_position).unwrap_err(),
+                ErrorCode::ClosePositionErr.into()
+            );
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use super::check_rent_receiver;
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseProtocolPosition<'info> {
+    /// Receives the rent of the protocol position
+    /// CHECK: The payer recorded by the protocol position
+    #[account(mut)]
+    pub rent_receiver: UncheckedAccount<'info>,
+
+    /// The pool the protocol position belongs to
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The protocol position to close, must hold no liquidity nor personal position
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &protocol_position.tick_lower_index.to_be_bytes(),
+            &protocol_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump = protocol_position.bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+        close = rent_receiver
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// The tick array of the lower tick of the protocol position
+    #[account(mut, constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// The tick array of the upper tick of the protocol position, may be the lower tick array
+    #[account(mut, constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+}
+
+pub fn close_protocol_position(ctx: Context<CloseProtocolPosition>) -> Result<()> {
+    let tick_spacing = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        pool_state.check_unlocked()?;
+        i32::from(pool_state.tick_spacing)
+    };
+    let protocol_position = &ctx.accounts.protocol_position;
+    check_protocol_position_closable(protocol_position)?;
+    check_rent_receiver(ctx.accounts.rent_receiver.key(), protocol_position.payer)?;
+
+    // the tick arrays no longer hold the ticks of the protocol position
+    for (tick_array_loader, tick_index) in [
+        (&ctx.accounts.tick_array_lower, protocol_position.tick_lower_index),
+        (&ctx.accounts.tick_array_upper, protocol_position.tick_upper_index),
+    ] {
+        let mut tick_array = tick_array_loader.load_mut()?;
+        require_eq!(
+            tick_array.start_tick_index,
+            TickArrayState::get_arrary_start_index(tick_index, tick_spacing),
+            ErrorCode::InvalidTickArray
+        );
+        tick_array.update_protocol_position_count(false)?;
+    }
+    Ok(())
+}
+
+/// A protocol position can be closed once no personal position is on its range
+fn check_protocol_position_closable(protocol_position: &ProtocolPositionState) -> Result<()> {
+    require_eq!(
+        protocol_position.liquidity,
+        0,
+        ErrorCode::ProtocolPositionNotEmpty
+    );
+    require_eq!(
+        protocol_position.personal_position_count,
+        0,
+        ErrorCode::ProtocolPositionNotEmpty
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod close_protocol_position_test {
+    use super::*;
+
+    #[test]
+    fn check_protocol_position_closable_test() {
+        check_protocol_position_closable(&ProtocolPositionState::default()).unwrap();
+
+        for protocol_position in [
+            ProtocolPositionState {
+                liquidity: 1,
+                ..Default::default()
+            },
+            ProtocolPositionState {
+                personal_position_count: 1,
+                ..Default::default()
+            },
+        ] {
+            assert_eq!(
+                check_protocol_position_closable(&protocol_position).unwrap_err(),
+                ErrorCode::ProtocolPositionNotEmpty.into()
+            );
+        }
+    }
+}
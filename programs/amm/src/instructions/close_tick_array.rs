@@ -0,0 +1,120 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseTickArray<'info> {
+    /// Receives the rent of the tick array
+    /// CHECK: The payer recorded by the tick array
+    #[account(mut)]
+    pub rent_receiver: UncheckedAccount<'info>,
+
+    /// The pool the tick array belongs to
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The tick array to close, must hold no initialized tick nor tick of a protocol position
+    #[account(
+        mut,
+        constraint = tick_array.load()?.pool_id == pool_state.key(),
+        close = rent_receiver
+    )]
+    pub tick_array: AccountLoader<'info, TickArrayState>,
+}
+
+pub fn close_tick_array<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CloseTickArray<'info>>,
+) -> Result<()> {
+    let mut remaining_accounts = ctx.remaining_accounts;
+    let tick_array_bitmap_extension =
+        get_tick_array_bitmap_extension(&ctx.accounts.pool_state.key(), &mut remaining_accounts)?;
+
+    let tick_array = ctx.accounts.tick_array.load()?;
+    check_tick_array_closable(&tick_array)?;
+    check_rent_receiver(ctx.accounts.rent_receiver.key(), tick_array.payer)?;
+
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.check_unlocked()?;
+    let mut tick_array_bitmap_extension = tick_array_bitmap_extension
+        .as_ref()
+        .map(|extension| extension.load_mut())
+        .transpose()?;
+    let tick_array_start_index = tick_array.start_tick_index;
+    // the bit is cleared when the last tick of the array is uninitialized, but the tick array
+    // must never stay in the bitmap once its account is gone
+    if pool_state.is_tick_array_initialized(
+        tick_array_bitmap_extension.as_deref(),
+        tick_array_start_index,
+    )? {
+        pool_state.flip_tick_array_bit(
+            tick_array_bitmap_extension.as_deref_mut(),
+            tick_array_start_index,
+        )?;
+    }
+    Ok(())
+}
+
+/// A tick array can be closed once none of its ticks is initialized or referenced by a protocol
+/// position
+fn check_tick_array_closable(tick_array: &TickArrayState) -> Result<()> {
+    require_eq!(
+        tick_array.initialized_tick_count,
+        0,
+        ErrorCode::TickArrayNotEmpty
+    );
+    require_eq!(
+        tick_array.protocol_position_count,
+        0,
+        ErrorCode::TickArrayNotEmpty
+    );
+    Ok(())
+}
+
+/// The rent of a closed account goes back to the payer recorded at creation. The accounts created
+/// before the payer was recorded didn't count their references either, they can't be proven
+/// unreferenced and stay open
+pub fn check_rent_receiver(rent_receiver: Pubkey, payer: Pubkey) -> Result<()> {
+    require_keys_neq!(payer, Pubkey::default(), ErrorCode::UncountedAccount);
+    require_keys_eq!(rent_receiver, payer, ErrorCode::InvalidRentReceiver);
+    Ok(())
+}
+
+#[cfg(test)]
+mod close_tick_array_test {
+    use super::*;
+
+    #[test]
+    fn check_tick_array_closable_test() {
+        check_tick_array_closable(&TickArrayState::default()).unwrap();
+
+        for tick_array in [
+            TickArrayState {
+                initialized_tick_count: 1,
+                ..Default::default()
+            },
+            TickArrayState {
+                protocol_position_count: 1,
+                ..Default::default()
+            },
+        ] {
+            assert_eq!(
+                check_tick_array_closable(&tick_array).unwrap_err(),
+                ErrorCode::TickArrayNotEmpty.into()
+            );
+        }
+    }
+
+    #[test]
+    fn check_rent_receiver_test() {
+        let payer = Pubkey::new_unique();
+        check_rent_receiver(payer, payer).unwrap();
+        assert_eq!(
+            check_rent_receiver(Pubkey::new_unique(), payer).unwrap_err(),
+            ErrorCode::InvalidRentReceiver.into()
+        );
+        assert_eq!(
+            check_rent_receiver(Pubkey::default(), Pubkey::default()).unwrap_err(),
+            ErrorCode::UncountedAccount.into()
+        );
+    }
+}
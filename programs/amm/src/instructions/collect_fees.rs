@@ -0,0 +1,259 @@
+use super::{burn_personal_position_liquidity, check_unclaimed_fees_and_vault};
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::{get_transfer_hook_accounts, transfer_from_pool_vault_to_user};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct CollectFees<'info> {
+    /// The position owner or delegated authority
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the tokenized position
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Collect fees of this position
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Stores init state for the lower tick, to accrue the latest fees
+    #[account(mut, constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick, to accrue the latest fees
+    #[account(mut, constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+
+    /// Token_0 vault
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_1 vault
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The recipient's token account to receive token_0 fees
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The recipient's token account to receive token_1 fees
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token vault 0
+    #[account(address = token_vault_0.mint)]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(address = token_vault_1.mint)]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// SPL program or token program 2022 to transfer out token_0
+    pub token_program_0: Interface<'info, TokenInterface>,
+
+    /// SPL program or token program 2022 to transfer out token_1
+    pub token_program_1: Interface<'info, TokenInterface>,
+}
+
+pub fn collect_fees<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CollectFees<'info>>,
+    amount_0_requested: u64,
+    amount_1_requested: u64,
+    recipient: Pubkey,
+) -> Result<()> {
+    require_keys_eq!(ctx.accounts.recipient_token_account_0.owner, recipient);
+    require_keys_eq!(ctx.accounts.recipient_token_account_1.owner, recipient);
+    let (amount_0, amount_1) = {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        pool_state.check_unlocked()?;
+        if !pool_state.get_status_by_bit(PoolStatusBitIndex::CollectFee) {
+            return err!(ErrorCode::NotApproved);
+        }
+        let personal_position = &mut ctx.accounts.personal_position;
+        // accrue the fees earned since the last action on the position
+        if personal_position.liquidity > 0 {
+            burn_personal_position_liquidity(
+                &mut pool_state,
+                &mut ctx.accounts.protocol_position,
+                personal_position,
+                &ctx.accounts.tick_array_lower,
+                &ctx.accounts.tick_array_upper,
+                // poking the position never flips a tick array, nor changes the liquidity the
+                // reward extensions accrue to
+                None,
+                &PoolRewardExtensions::default(),
+                0,
+            )?;
+        }
+
+        take_fees_owed(
+            &mut pool_state,
+            personal_position,
+            amount_0_requested,
+            amount_1_requested,
+        )?
+    };
+
+    // the transfer hook accounts of token_0 and token_1 are passed by remaining accounts
+    let mut remaining_accounts = ctx.remaining_accounts;
+    let transfer_hook_accounts_0 =
+        get_transfer_hook_accounts(&ctx.accounts.vault_0_mint, &mut remaining_accounts)?;
+    let transfer_hook_accounts_1 =
+        get_transfer_hook_accounts(&ctx.accounts.vault_1_mint, &mut remaining_accounts)?;
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0,
+        &ctx.accounts.recipient_token_account_0,
+        &ctx.accounts.vault_0_mint,
+        &ctx.accounts.token_program_0,
+        &transfer_hook_accounts_0,
+        amount_0,
+    )?;
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_1,
+        &ctx.accounts.recipient_token_account_1,
+        &ctx.accounts.vault_1_mint,
+        &ctx.accounts.token_program_1,
+        &transfer_hook_accounts_1,
+        amount_1,
+    )?;
+
+    check_unclaimed_fees_and_vault(
+        &ctx.accounts.pool_state,
+        &mut ctx.accounts.token_vault_0,
+        &mut ctx.accounts.token_vault_1,
+    )?;
+
+    emit!(CollectPersonalFeeEvent {
+        position_nft_mint: ctx.accounts.personal_position.nft_mint,
+        recipient_token_account_0: ctx.accounts.recipient_token_account_0.key(),
+        recipient_token_account_1: ctx.accounts.recipient_token_account_1.key(),
+        amount_0,
+        amount_1,
+    });
+    Ok(())
+}
+
+/// Takes up to the requested amounts of the fees owed to the position, returns the amounts taken
+fn take_fees_owed(
+    pool_state: &mut PoolState,
+    personal_position: &mut PersonalPositionState,
+    amount_0_requested: u64,
+    amount_1_requested: u64,
+) -> Result<(u64, u64)> {
+    let amount_0 = amount_0_requested.min(personal_position.token_fees_owed_0);
+    let amount_1 = amount_1_requested.min(personal_position.token_fees_owed_1);
+    require_gte!(
+        pool_state.total_fees_token_0 - pool_state.total_fees_claimed_token_0,
+        amount_0
+    );
+    require_gte!(
+        pool_state.total_fees_token_1 - pool_state.total_fees_claimed_token_1,
+        amount_1
+    );
+
+    personal_position.token_fees_owed_0 -= amount_0;
+    personal_position.token_fees_owed_1 -= amount_1;
+    pool_state.total_fees_claimed_token_0 = pool_state
+        .total_fees_claimed_token_0
+        .checked_add(amount_0)
+        .unwrap();
+    pool_state.total_fees_claimed_token_1 = pool_state
+        .total_fees_claimed_token_1
+        .checked_add(amount_1)
+        .unwrap();
+    Ok((amount_0, amount_1))
+}
+
+#[cfg(test)]
+mod collect_fees_test {
+    use super::*;
+
+    fn build_states() -> (PoolState, PersonalPositionState) {
+        let pool_state = PoolState {
+            total_fees_token_0: 1000,
+            total_fees_token_1: 2000,
+            ..Default::default()
+        };
+        let personal_position = PersonalPositionState {
+            token_fees_owed_0: 100,
+            token_fees_owed_1: 200,
+            ..Default::default()
+        };
+        (pool_state, personal_position)
+    }
+
+    #[test]
+    fn take_fees_owed_partial_request_test() {
+        let (mut pool_state, mut personal_position) = build_states();
+        let (amount_0, amount_1) =
+            take_fees_owed(&mut pool_state, &mut personal_position, 40, 0).unwrap();
+        assert_eq!((amount_0, amount_1), (40, 0));
+        assert_eq!(personal_position.token_fees_owed_0, 60);
+        assert_eq!(personal_position.token_fees_owed_1, 200);
+        assert_eq!({ pool_state.total_fees_claimed_token_0 }, 40);
+        assert_eq!({ pool_state.total_fees_claimed_token_1 }, 0);
+
+        // the rest is collected later
+        let (amount_0, amount_1) =
+            take_fees_owed(&mut pool_state, &mut personal_position, 60, 200).unwrap();
+        assert_eq!((amount_0, amount_1), (60, 200));
+        assert_eq!(personal_position.token_fees_owed_0, 0);
+        assert_eq!(personal_position.token_fees_owed_1, 0);
+        assert_eq!({ pool_state.total_fees_claimed_token_0 }, 100);
+        assert_eq!({ pool_state.total_fees_claimed_token_1 }, 200);
+    }
+
+    #[test]
+    fn take_fees_owed_capped_test() {
+        // more than owed is capped at the fees owed
+        let (mut pool_state, mut personal_position) = build_states();
+        let (amount_0, amount_1) =
+            take_fees_owed(&mut pool_state, &mut personal_position, u64::MAX, u64::MAX).unwrap();
+        assert_eq!((amount_0, amount_1), (100, 200));
+        assert_eq!(personal_position.token_fees_owed_0, 0);
+        assert_eq!(personal_position.token_fees_owed_1, 0);
+    }
+
+    #[test]
+    fn take_fees_owed_unclaimed_test() {
+        // the pool can't pay more than its unclaimed fees
+        let (mut pool_state, mut personal_position) = build_states();
+        pool_state.total_fees_claimed_token_1 = 1900;
+        assert!(take_fees_owed(&mut pool_state, &mut personal_position, 100, 200).is_err());
+        assert!(take_fees_owed(&mut pool_state, &mut personal_position, 100, 100).is_ok());
+    }
+}
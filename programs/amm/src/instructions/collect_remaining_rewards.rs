@@ -1,43 +1,54 @@
-use anchor_spl::token_interface::{TokenAccount, Token2022};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use anchor_lang::prelude::*;
 
 use crate::error::ErrorCode;
 use crate::states::*;
-use crate::util::transfer_from_pool_vault_to_user;
+use crate::util::{get_transfer_hook_accounts, transfer_from_pool_vault_to_user};
 
 #[derive(Accounts)]
 pub struct CollectRemainingRewards<'info> {
-    /// The founder who init reward info in berfore
+    /// The authority of the reward or the owner of the pool
     pub reward_funder: Signer<'info>,
-    /// The funder's reward token account
+    /// The reward token account of the reward authority, receiving the remaining reward
     #[account(mut)]
     pub funder_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
     /// Set reward for this pool
     #[account(mut)]
     pub pool_state: AccountLoader<'info, PoolState>,
     /// Reward vault transfer remaining token to founder token account
+    #[account(mut)]
     pub reward_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// The mint of reward token vault
+    #[account(address = reward_token_vault.mint)]
+    pub reward_vault_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    // #[account(address = token_interface::ID)]
-    pub token_program: Program<'info, Token2022>,
+    /// SPL program or token program 2022 of the reward mint
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
-pub fn collect_remaining_rewards(
-    ctx: Context<CollectRemainingRewards>,
+pub fn collect_remaining_rewards<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CollectRemainingRewards<'info>>,
     reward_index: u8,
 ) -> Result<()> {
     let amount_remaining = get_remaining_reward_amount(
         &ctx.accounts.pool_state,
         &ctx.accounts.reward_token_vault,
         &ctx.accounts.reward_funder.key(),
+        &ctx.accounts.funder_token_account.owner,
         reward_index,
+        u64::try_from(Clock::get()?.unix_timestamp).unwrap(),
     )?;
 
+    let mut remaining_accounts = ctx.remaining_accounts;
+    let transfer_hook_accounts =
+        get_transfer_hook_accounts(&ctx.accounts.reward_vault_mint, &mut remaining_accounts)?;
     transfer_from_pool_vault_to_user(
         &ctx.accounts.pool_state,
         &ctx.accounts.reward_token_vault,
         &ctx.accounts.funder_token_account,
+        &ctx.accounts.reward_vault_mint,
         &ctx.accounts.token_program,
+        &transfer_hook_accounts,
         amount_remaining,
     )?;
 
@@ -48,10 +59,12 @@ fn get_remaining_reward_amount(
     pool_state_loader: &AccountLoader<PoolState>,
     reward_token_vault: &InterfaceAccount<TokenAccount>,
     reward_funder: &Pubkey,
+    refund_receiver: &Pubkey,
     reward_index: u8,
+    current_timestamp: u64,
 ) -> Result<u64> {
-    let current_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
     let mut pool_state = pool_state_loader.load_mut()?;
+    pool_state.check_unlocked()?;
     pool_state.update_reward_infos(current_timestamp)?;
 
     let reward_info = pool_state.reward_infos[reward_index as usize];
@@ -63,13 +76,257 @@ fn get_remaining_reward_amount(
         reward_info.end_time,
         ErrorCode::NotApproved
     );
-    require_keys_eq!(reward_funder.key(), pool_state.owner);
+    require!(
+        *reward_funder == reward_info.authority || *reward_funder == pool_state.owner,
+        ErrorCode::NotApproved
+    );
+    // the remaining reward, of a cancelled reward too, goes back to the reward authority
+    require_keys_eq!(
+        *refund_receiver,
+        reward_info.authority,
+        ErrorCode::NotApproved
+    );
     require_keys_eq!(reward_token_vault.key(), reward_info.token_vault);
 
+    // the emitted reward not claimed yet stays in the vault for the positions
+    let reward_unclaimed = reward_info
+        .reward_total_emissioned
+        .checked_sub(reward_info.reward_claimed)
+        .unwrap();
     let amount_remaining = reward_token_vault
         .amount
-        .checked_sub(reward_info.reward_total_emissioned)
+        .checked_sub(reward_unclaimed)
         .unwrap();
 
     Ok(amount_remaining)
 }
+
+#[cfg(test)]
+mod collect_remaining_rewards_test {
+    use super::*;
+    use crate::instructions::cancel_pool_reward;
+    use crate::libraries::fixed_point_64;
+    use anchor_spl::token::spl_token::state::{Account as SplTokenAccount, AccountState};
+    use anchor_spl::token::Token;
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_lang::Discriminator;
+    use std::convert::identity;
+
+    /// An account whose `AccountInfo` borrows its fields
+    struct TestAccount {
+        key: Pubkey,
+        owner: Pubkey,
+        lamports: u64,
+        data: Vec<u8>,
+    }
+
+    impl TestAccount {
+        fn pool(pool_state: &PoolState) -> Self {
+            let mut data = PoolState::discriminator().to_vec();
+            data.extend_from_slice(bytemuck::bytes_of(pool_state));
+            Self {
+                key: Pubkey::new_unique(),
+                owner: crate::id(),
+                lamports: 0,
+                data,
+            }
+        }
+
+        fn token_account(key: Pubkey, mint: Pubkey, amount: u64) -> Self {
+            let mut data = vec![0u8; SplTokenAccount::LEN];
+            SplTokenAccount {
+                mint,
+                owner: Pubkey::new_unique(),
+                amount,
+                state: AccountState::Initialized,
+                ..Default::default()
+            }
+            .pack_into_slice(&mut data);
+            Self {
+                key,
+                owner: Token::id(),
+                lamports: 0,
+                data,
+            }
+        }
+
+        fn account_info(&mut self) -> AccountInfo<'_> {
+            AccountInfo::new(
+                &self.key,
+                false,
+                true,
+                &mut self.lamports,
+                &mut self.data,
+                &self.owner,
+                false,
+                0,
+            )
+        }
+    }
+
+    /// A pool whose first reward emitted 1000 tokens of which 400 were claimed, and ended at 2000
+    fn build_ended_reward_pool(owner: Pubkey, authority: Pubkey, reward_vault: Pubkey) -> PoolState {
+        let mut pool_state = PoolState {
+            owner,
+            ..Default::default()
+        };
+        pool_state.reward_infos[0] = RewardInfo {
+            reward_state: RewardState::Ended as u8,
+            open_time: 1000,
+            end_time: 2000,
+            last_update_time: 2000,
+            reward_total_emissioned: 1000,
+            reward_claimed: 400,
+            token_mint: Pubkey::new_unique(),
+            token_vault: reward_vault,
+            authority,
+            ..Default::default()
+        };
+        pool_state
+    }
+
+    /// Run `get_remaining_reward_amount` of the first reward on the given pool with a vault
+    /// holding `vault_amount`, refunding to `refund_receiver`
+    fn remaining_reward_amount(
+        pool_state: &PoolState,
+        reward_funder: Pubkey,
+        refund_receiver: Pubkey,
+        vault_amount: u64,
+    ) -> Result<u64> {
+        let reward_info = pool_state.reward_infos[0];
+        let mut pool_account = TestAccount::pool(pool_state);
+        let mut vault_account = TestAccount::token_account(
+            reward_info.token_vault,
+            reward_info.token_mint,
+            vault_amount,
+        );
+        let pool_info = pool_account.account_info();
+        let vault_info = vault_account.account_info();
+        let pool_state_loader = AccountLoader::<PoolState>::try_from(&pool_info)?;
+        let reward_token_vault = InterfaceAccount::<TokenAccount>::try_from(&vault_info)?;
+        get_remaining_reward_amount(
+            &pool_state_loader,
+            &reward_token_vault,
+            &reward_funder,
+            &refund_receiver,
+            0,
+            3000,
+        )
+    }
+
+    #[test]
+    fn get_remaining_reward_amount_test() {
+        let owner = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let pool_state = build_ended_reward_pool(owner, authority, Pubkey::new_unique());
+        // the 600 emitted but unclaimed stay in the vault
+        assert_eq!(
+            remaining_reward_amount(&pool_state, owner, authority, 1000).unwrap(),
+            400
+        );
+        assert_eq!(
+            remaining_reward_amount(&pool_state, authority, authority, 1000).unwrap(),
+            400
+        );
+        assert_eq!(
+            remaining_reward_amount(&pool_state, Pubkey::new_unique(), authority, 1000)
+                .unwrap_err(),
+            ErrorCode::NotApproved.into()
+        );
+        // the refund only goes to the reward authority, even when the pool owner collects it
+        assert_eq!(
+            remaining_reward_amount(&pool_state, owner, owner, 1000).unwrap_err(),
+            ErrorCode::NotApproved.into()
+        );
+    }
+
+    #[test]
+    fn get_remaining_reward_amount_locked_test() {
+        let owner = Pubkey::new_unique();
+        let mut pool_state =
+            build_ended_reward_pool(owner, Pubkey::new_unique(), Pubkey::new_unique());
+        pool_state.lock().unwrap();
+        assert_eq!(
+            remaining_reward_amount(&pool_state, owner, Pubkey::new_unique(), 1000).unwrap_err(),
+            ErrorCode::LOK.into()
+        );
+    }
+
+    #[test]
+    fn cancel_then_collect_remaining_rewards_test() {
+        // a reward emitting 1 token per second from 1000 to 3000, funded with 2000 tokens
+        let authority = Pubkey::new_unique();
+        let reward_vault = Pubkey::new_unique();
+        let mut pool_state = PoolState {
+            owner: Pubkey::new_unique(),
+            liquidity: fixed_point_64::Q64,
+            ..Default::default()
+        };
+        pool_state.reward_infos[0] = RewardInfo {
+            reward_state: RewardState::Opening as u8,
+            open_time: 1000,
+            end_time: 3000,
+            last_update_time: 1000,
+            emissions_per_second_x64: fixed_point_64::Q64,
+            token_mint: Pubkey::new_unique(),
+            token_vault: reward_vault,
+            authority,
+            ..Default::default()
+        };
+        let reward_mint = pool_state.reward_infos[0].token_mint;
+
+        let mut pool_account = TestAccount::pool(&pool_state);
+        let mut vault_account = TestAccount::token_account(reward_vault, reward_mint, 2000);
+        let pool_info = pool_account.account_info();
+        let vault_info = vault_account.account_info();
+        let pool_state_loader = AccountLoader::<PoolState>::try_from(&pool_info).unwrap();
+        let reward_token_vault = InterfaceAccount::<TokenAccount>::try_from(&vault_info).unwrap();
+
+        // only the authority or the admin cancels the reward
+        assert_eq!(
+            cancel_pool_reward(&pool_state_loader, &Pubkey::new_unique(), 0, 2000).unwrap_err(),
+            ErrorCode::NotApproved.into()
+        );
+        // the reward can not be collected while it is running
+        assert!(get_remaining_reward_amount(
+            &pool_state_loader,
+            &reward_token_vault,
+            &authority,
+            &authority,
+            0,
+            2000
+        )
+        .is_err());
+
+        cancel_pool_reward(&pool_state_loader, &authority, 0, 2000).unwrap();
+        let reward_info = pool_state_loader.load().unwrap().reward_infos[0];
+        assert_eq!(identity(reward_info.end_time), 2000);
+        assert_eq!(identity(reward_info.reward_total_emissioned), 1000);
+
+        // the half not emitted goes back to the reward authority
+        assert_eq!(
+            get_remaining_reward_amount(
+                &pool_state_loader,
+                &reward_token_vault,
+                &authority,
+                &authority,
+                0,
+                2000
+            )
+            .unwrap(),
+            1000
+        );
+        assert_eq!(
+            get_remaining_reward_amount(
+                &pool_state_loader,
+                &reward_token_vault,
+                &authority,
+                &Pubkey::new_unique(),
+                0,
+                2000
+            )
+            .unwrap_err(),
+            ErrorCode::NotApproved.into()
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use super::{
+    burn_personal_position_liquidity, collect_position_extension_rewards, collect_position_rewards,
+};
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct CollectRewards<'info> {
+    /// The position owner or delegated authority
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the tokenized position
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Collect rewards of this position
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Stores init state for the lower tick, to accrue the latest rewards
+    #[account(mut, constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick, to accrue the latest rewards
+    #[account(mut, constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+}
+
+pub fn collect_rewards<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CollectRewards<'info>>,
+    reward_mask: u8,
+) -> Result<()> {
+    // the reward extensions of the pool come before the reward accounts
+    let mut remaining_accounts = ctx.remaining_accounts;
+    let reward_extension_num = ctx.accounts.pool_state.load()?.reward_extension_num;
+    let reward_extensions = get_pool_reward_extensions_with_rent_payer(
+        &ctx.accounts.pool_state.key(),
+        reward_extension_num,
+        &mut remaining_accounts,
+    )?;
+    {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        pool_state.check_unlocked()?;
+        if !pool_state.get_status_by_bit(PoolStatusBitIndex::CollectReward) {
+            return err!(ErrorCode::NotApproved);
+        }
+        check_reward_mask(&pool_state, reward_mask, !remaining_accounts.is_empty())?;
+
+        // accrue the rewards earned since the last action on the position
+        if ctx.accounts.personal_position.liquidity > 0 {
+            burn_personal_position_liquidity(
+                &mut pool_state,
+                &mut ctx.accounts.protocol_position,
+                &mut ctx.accounts.personal_position,
+                &ctx.accounts.tick_array_lower,
+                &ctx.accounts.tick_array_upper,
+                // poking the position never flips a tick array
+                None,
+                &reward_extensions,
+                0,
+            )?;
+        }
+    }
+
+    // each reward takes the accounts [reward_token_vault, recipient_token_account, reward_vault_mint,
+    // reward_token_program], followed by the transfer hook accounts of the reward mint if any,
+    // the rewards of the reward extensions to collect follow the rewards of the pool
+    let personal_position = &mut ctx.accounts.personal_position;
+    let reward_amounts = collect_position_rewards(
+        &ctx.accounts.pool_state,
+        &mut remaining_accounts,
+        personal_position,
+        reward_mask,
+    )?;
+    collect_position_extension_rewards(
+        &ctx.accounts.pool_state,
+        &reward_extensions,
+        remaining_accounts,
+        personal_position,
+    )?;
+
+    emit!(CollectPersonalRewardEvent {
+        position_nft_mint: personal_position.nft_mint,
+        reward_mask,
+        reward_amounts,
+    });
+    Ok(())
+}
+
+/// Checks the rewards of `reward_mask` are initialized rewards of the pool, the rewards of
+/// the reward extensions are collected by their accounts only, so an empty mask needs
+/// `has_reward_accounts`
+fn check_reward_mask(
+    pool_state: &PoolState,
+    reward_mask: u8,
+    has_reward_accounts: bool,
+) -> Result<()> {
+    require!(
+        (reward_mask != 0 || has_reward_accounts) && reward_mask >> REWARD_NUM == 0,
+        ErrorCode::InvalidRewardIndex
+    );
+    // only the initialized rewards can be collected
+    for i in 0..REWARD_NUM {
+        if reward_mask & (1 << i) != 0 {
+            require!(
+                pool_state.reward_infos[i].initialized(),
+                ErrorCode::InvalidRewardIndex
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod collect_rewards_test {
+    use super::*;
+
+    #[test]
+    fn check_reward_mask_test() {
+        let mut pool_state = PoolState::default();
+        pool_state.reward_infos[0].token_mint = Pubkey::new_unique();
+        pool_state.reward_infos[2].token_mint = Pubkey::new_unique();
+
+        assert!(check_reward_mask(&pool_state, 0b001, false).is_ok());
+        assert!(check_reward_mask(&pool_state, 0b101, false).is_ok());
+        // the second reward isn't initialized
+        assert_eq!(
+            check_reward_mask(&pool_state, 0b011, false).unwrap_err(),
+            ErrorCode::InvalidRewardIndex.into()
+        );
+        // beyond the rewards of the pool
+        assert_eq!(
+            check_reward_mask(&pool_state, 0b1001, true).unwrap_err(),
+            ErrorCode::InvalidRewardIndex.into()
+        );
+        // nothing to collect without the accounts of reward extensions
+        assert_eq!(
+            check_reward_mask(&pool_state, 0, false).unwrap_err(),
+            ErrorCode::InvalidRewardIndex.into()
+        );
+        assert!(check_reward_mask(&pool_state, 0, true).is_ok());
+    }
+}
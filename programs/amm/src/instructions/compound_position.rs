@@ -0,0 +1,258 @@
+use super::{burn_personal_position_liquidity, check_unclaimed_fees_and_vault, mint_liquidity};
+use crate::error::ErrorCode;
+use crate::libraries::{liquidity_math, tick_math};
+use crate::states::*;
+use crate::util::{get_transfer_hook_accounts, transfer_from_pool_vault_to_user};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct CompoundPosition<'info> {
+    /// The position owner or the compound keeper approved by the current owner
+    pub authority: Signer<'info>,
+
+    /// The token account for the tokenized position, its owner receives the dust
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint,
+        constraint = nft_account.amount == 1
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Compound the fees of this position
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// Stores init state for the lower tick
+    #[account(mut, constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick
+    #[account(mut, constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+
+    /// Token_0 vault
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_1 vault
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The position owner's token account to receive the token_0 dust
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint,
+        token::authority = nft_account.owner
+    )]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The position owner's token account to receive the token_1 dust
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint,
+        token::authority = nft_account.owner
+    )]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token vault 0
+    #[account(address = token_vault_0.mint)]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(address = token_vault_1.mint)]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// SPL program or token program 2022 to transfer out token_0
+    pub token_program_0: Interface<'info, TokenInterface>,
+
+    /// SPL program or token program 2022 to transfer out token_1
+    pub token_program_1: Interface<'info, TokenInterface>,
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+}
+
+pub fn compound_position<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CompoundPosition<'info>>,
+    liquidity_min: u128,
+) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let nft_owner = ctx.accounts.nft_account.owner;
+    require!(
+        authority == nft_owner
+            || ctx
+                .accounts
+                .personal_position
+                .is_compound_keeper(&nft_owner, &authority),
+        ErrorCode::NotApproved
+    );
+
+    // the tick array bitmap extension, if needed, the reward extensions of the pool and the transfer
+    // hook accounts of token_0 and token_1 are passed by remaining accounts
+    let mut remaining_accounts = ctx.remaining_accounts;
+    let tick_array_bitmap_extension =
+        get_tick_array_bitmap_extension(&ctx.accounts.pool_state.key(), &mut remaining_accounts)?;
+    let reward_extension_num = ctx.accounts.pool_state.load()?.reward_extension_num;
+    let reward_extensions = get_pool_reward_extensions_with_rent_payer(
+        &ctx.accounts.pool_state.key(),
+        reward_extension_num,
+        &mut remaining_accounts,
+    )?;
+    let (liquidity, amount_0, amount_1, dust_amount_0, dust_amount_1) = {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        pool_state.check_unlocked()?;
+        if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)
+            || !pool_state.get_status_by_bit(PoolStatusBitIndex::CollectFee)
+        {
+            return err!(ErrorCode::NotApproved);
+        }
+        let personal_position = &mut ctx.accounts.personal_position;
+        let protocol_position = &mut ctx.accounts.protocol_position;
+        // accrue the fees earned since the last action on the position
+        burn_personal_position_liquidity(
+            &mut pool_state,
+            protocol_position,
+            personal_position,
+            &ctx.accounts.tick_array_lower,
+            &ctx.accounts.tick_array_upper,
+            None,
+            &reward_extensions,
+            0,
+        )?;
+        let fees_owed_0 = personal_position.token_fees_owed_0;
+        let fees_owed_1 = personal_position.token_fees_owed_1;
+        require_gte!(
+            pool_state.total_fees_token_0 - pool_state.total_fees_claimed_token_0,
+            fees_owed_0
+        );
+        require_gte!(
+            pool_state.total_fees_token_1 - pool_state.total_fees_claimed_token_1,
+            fees_owed_1
+        );
+
+        let liquidity = liquidity_math::get_liquidity_from_amounts(
+            pool_state.sqrt_price_x64,
+            tick_math::get_sqrt_price_at_tick(personal_position.tick_lower_index)?,
+            tick_math::get_sqrt_price_at_tick(personal_position.tick_upper_index)?,
+            fees_owed_0,
+            fees_owed_1,
+        );
+        require_gt!(liquidity, 0, ErrorCode::ZeroMintAmount);
+        // the liquidity the fees buy depends on the price, which the keeper could move first
+        require_gte!(liquidity, liquidity_min, ErrorCode::PriceSlippageCheck);
+        update_observation_before_liquidity_change(
+            &ctx.accounts.observation_state.to_account_info(),
+            &mut pool_state,
+            oracle::block_timestamp(),
+        )?;
+        let (amount_0, amount_1) = mint_liquidity(
+            &mut pool_state,
+            &ctx.accounts.tick_array_lower,
+            &ctx.accounts.tick_array_upper,
+            tick_array_bitmap_extension.as_ref(),
+            &reward_extensions,
+            protocol_position,
+            liquidity,
+            personal_position.tick_lower_index,
+            personal_position.tick_upper_index,
+        )?;
+        // the fees are already in the vaults, the rounded up amounts must be covered by them
+        require!(
+            amount_0 <= fees_owed_0 && amount_1 <= fees_owed_1,
+            ErrorCode::PriceSlippageCheck
+        );
+
+        // the fees accrued above are up to date, only the rewards and liquidity change
+        personal_position.update_rewards(protocol_position.reward_growth_inside, true)?;
+        let liquidity_after = personal_position.liquidity.checked_add(liquidity).unwrap();
+        reward_extensions.update_position(
+            personal_position,
+            &pool_state,
+            &ctx.accounts.tick_array_lower,
+            &ctx.accounts.tick_array_upper,
+            liquidity_after,
+        )?;
+        personal_position.liquidity = liquidity_after;
+
+        // all the fees owed are claimed, reinvested or returned to the owner
+        personal_position.token_fees_owed_0 = 0;
+        personal_position.token_fees_owed_1 = 0;
+        pool_state.total_fees_claimed_token_0 = pool_state
+            .total_fees_claimed_token_0
+            .checked_add(fees_owed_0)
+            .unwrap();
+        pool_state.total_fees_claimed_token_1 = pool_state
+            .total_fees_claimed_token_1
+            .checked_add(fees_owed_1)
+            .unwrap();
+        (
+            liquidity,
+            amount_0,
+            amount_1,
+            fees_owed_0 - amount_0,
+            fees_owed_1 - amount_1,
+        )
+    };
+
+    let transfer_hook_accounts_0 =
+        get_transfer_hook_accounts(&ctx.accounts.vault_0_mint, &mut remaining_accounts)?;
+    let transfer_hook_accounts_1 =
+        get_transfer_hook_accounts(&ctx.accounts.vault_1_mint, &mut remaining_accounts)?;
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0,
+        &ctx.accounts.recipient_token_account_0,
+        &ctx.accounts.vault_0_mint,
+        &ctx.accounts.token_program_0,
+        &transfer_hook_accounts_0,
+        dust_amount_0,
+    )?;
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_1,
+        &ctx.accounts.recipient_token_account_1,
+        &ctx.accounts.vault_1_mint,
+        &ctx.accounts.token_program_1,
+        &transfer_hook_accounts_1,
+        dust_amount_1,
+    )?;
+
+    check_unclaimed_fees_and_vault(
+        &ctx.accounts.pool_state,
+        &mut ctx.accounts.token_vault_0,
+        &mut ctx.accounts.token_vault_1,
+    )?;
+
+    emit!(CompoundPositionEvent {
+        position_nft_mint: ctx.accounts.personal_position.nft_mint,
+        authority,
+        liquidity,
+        amount_0,
+        amount_1,
+        dust_amount_0,
+        dust_amount_1,
+    });
+    Ok(())
+}
@@ -1,8 +1,10 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{Token2022, Mint, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenInterface};
 
+use crate::error::ErrorCode;
 use crate::libraries::tick_math;
 use crate::states::*;
+use crate::util::{check_mint_extensions, create_token_vault_account};
 
 #[derive(Accounts)]
 pub struct CreatePool<'info> {
@@ -37,41 +39,46 @@ pub struct CreatePool<'info> {
     /// Token_1 mint
     pub token_mint_1: Box<InterfaceAccount<'info, Mint>>,
 
-    /// Token_0 vault for the pool
+    /// Load the whitelist of mints to check the mint extensions
     #[account(
-    init,
+    seeds = [
+    OPERATION_SEED.as_bytes(),
+    ],
+    bump,
+    )]
+    pub operation_state: AccountLoader<'info, OperationState>,
+
+    /// CHECK: Token_0 vault for the pool, created inside the instruction with the token program of token_mint_0
+    #[account(
+    mut,
     seeds = [
     POOL_VAULT_SEED.as_bytes(),
     pool_state.key().as_ref(),
     token_mint_0.key().as_ref(),
     ],
     bump,
-    payer = pool_creator,
-    token::mint = token_mint_0,
-    token::authority = pool_state
     )]
-    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_vault_0: UncheckedAccount<'info>,
 
-    /// Token_1 vault for the pool
+    /// CHECK: Token_1 vault for the pool, created inside the instruction with the token program of token_mint_1
     #[account(
-    init,
+    mut,
     seeds = [
     POOL_VAULT_SEED.as_bytes(),
     pool_state.key().as_ref(),
     token_mint_1.key().as_ref(),
     ],
     bump,
-    payer = pool_creator,
-    token::mint = token_mint_1,
-    token::authority = pool_state
     )]
-    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_vault_1: UncheckedAccount<'info>,
 
     /// CHECK: Initialize an account to store oracle observations, the account must be created off-chain, constract will initialzied it
     pub observation_state: UncheckedAccount<'info>,
 
-    /// Spl token program
-    pub token_program: Program<'info, Token2022>,
+    /// Spl token program or token program 2022 of token_mint_0
+    pub token_program_0: Interface<'info, TokenInterface>,
+    /// Spl token program or token program 2022 of token_mint_1
+    pub token_program_1: Interface<'info, TokenInterface>,
     /// To create a new program account
     pub system_program: Program<'info, System>,
     /// Sysvar for program account
@@ -79,6 +86,54 @@ pub struct CreatePool<'info> {
 }
 
 pub fn create_pool(ctx: Context<CreatePool>, sqrt_price_x64: u128, open_time: u64) -> Result<()> {
+    {
+        let operation_state = ctx.accounts.operation_state.load()?;
+        check_mint_extensions(&ctx.accounts.token_mint_0, &operation_state)?;
+        check_mint_extensions(&ctx.accounts.token_mint_1, &operation_state)?;
+    }
+    let pool_state_key = ctx.accounts.pool_state.key();
+    let token_mint_0_key = ctx.accounts.token_mint_0.key();
+    let token_mint_1_key = ctx.accounts.token_mint_1.key();
+    create_token_vault_account(
+        &ctx.accounts.pool_creator,
+        &ctx.accounts.pool_state.to_account_info(),
+        &ctx.accounts.token_vault_0,
+        &ctx.accounts.token_mint_0,
+        &ctx.accounts.system_program,
+        &ctx.accounts.token_program_0,
+        &[
+            POOL_VAULT_SEED.as_bytes(),
+            pool_state_key.as_ref(),
+            token_mint_0_key.as_ref(),
+            &[*ctx.bumps.get("token_vault_0").unwrap()],
+        ],
+    )?;
+    create_token_vault_account(
+        &ctx.accounts.pool_creator,
+        &ctx.accounts.pool_state.to_account_info(),
+        &ctx.accounts.token_vault_1,
+        &ctx.accounts.token_mint_1,
+        &ctx.accounts.system_program,
+        &ctx.accounts.token_program_1,
+        &[
+            POOL_VAULT_SEED.as_bytes(),
+            pool_state_key.as_ref(),
+            token_mint_1_key.as_ref(),
+            &[*ctx.bumps.get("token_vault_1").unwrap()],
+        ],
+    )?;
+    // each vault must be owned by the token program of its mint
+    require_keys_eq!(
+        *ctx.accounts.token_vault_0.owner,
+        *ctx.accounts.token_mint_0.to_account_info().owner,
+        ErrorCode::InvalidVaultTokenProgram
+    );
+    require_keys_eq!(
+        *ctx.accounts.token_vault_1.owner,
+        *ctx.accounts.token_mint_1.to_account_info().owner,
+        ErrorCode::InvalidVaultTokenProgram
+    );
+
     let mut pool_state = ctx.accounts.pool_state.load_init()?;
     let observation_state_loader = initialize_observation_account(
         ctx.accounts.observation_state.to_account_info(),
@@ -0,0 +1,35 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CreateTickArrayBitmapExtension<'info> {
+    /// Pays the rent of the extension, can be anyone
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The pool the extension belongs to
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Initialize an account to store the tick array bitmap beyond the pool bitmap
+    #[account(
+        init,
+        seeds = [
+            TICK_ARRAY_BITMAP_EXTENSION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = TickArrayBitmapExtension::LEN
+    )]
+    pub tick_array_bitmap_extension: AccountLoader<'info, TickArrayBitmapExtension>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_tick_array_bitmap_extension(
+    ctx: Context<CreateTickArrayBitmapExtension>,
+) -> Result<()> {
+    let mut tick_array_bitmap_extension = ctx.accounts.tick_array_bitmap_extension.load_init()?;
+    tick_array_bitmap_extension.initialize(ctx.accounts.pool_state.key());
+    Ok(())
+}
@@ -2,9 +2,9 @@ use super::calculate_latest_token_fees;
 use super::modify_position;
 use crate::error::ErrorCode;
 use crate::states::*;
-use crate::util::transfer_from_pool_vault_to_user;
+use crate::util::{get_transfer_fee, get_transfer_hook_accounts, transfer_from_pool_vault_to_user};
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{TokenAccount, Token2022};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use std::cell::RefMut;
 
 #[derive(Accounts)]
@@ -74,8 +74,22 @@ pub struct DecreaseLiquidity<'info> {
     )]
     pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// SPL program to transfer out tokens
-    pub token_program: Program<'info, Token2022>,
+    /// The mint of token vault 0
+    #[account(address = token_vault_0.mint)]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(address = token_vault_1.mint)]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// SPL program or token program 2022 to transfer out token_0
+    pub token_program_0: Interface<'info, TokenInterface>,
+
+    /// SPL program or token program 2022 to transfer out token_1
+    pub token_program_1: Interface<'info, TokenInterface>,
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
 }
 
 pub fn decrease_liquidity<'a, 'b, 'c, 'info>(
@@ -87,6 +101,7 @@ pub fn decrease_liquidity<'a, 'b, 'c, 'info>(
     assert!(liquidity <= ctx.accounts.personal_position.liquidity);
     {
         let pool_state = ctx.accounts.pool_state.load()?;
+        pool_state.check_unlocked()?;
         if !pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity)
             && !pool_state.get_status_by_bit(PoolStatusBitIndex::CollectFee)
             && !pool_state.get_status_by_bit(PoolStatusBitIndex::CollectReward)
@@ -95,6 +110,24 @@ pub fn decrease_liquidity<'a, 'b, 'c, 'info>(
         }
     }
 
+    // the tick array bitmap extension, if needed, and the reward extensions of the pool come before
+    // the transfer hook accounts of token_0 and token_1 and the reward accounts
+    let mut remaining_accounts = ctx.remaining_accounts;
+    let tick_array_bitmap_extension =
+        get_tick_array_bitmap_extension(&ctx.accounts.pool_state.key(), &mut remaining_accounts)?;
+    let reward_extension_num = ctx.accounts.pool_state.load()?.reward_extension_num;
+    let reward_extensions = get_pool_reward_extensions_with_rent_payer(
+        &ctx.accounts.pool_state.key(),
+        reward_extension_num,
+        &mut remaining_accounts,
+    )?;
+    if liquidity > 0 {
+        update_observation_before_liquidity_change(
+            &ctx.accounts.observation_state.to_account_info(),
+            &mut *ctx.accounts.pool_state.load_mut()?,
+            oracle::block_timestamp(),
+        )?;
+    }
     let (decrease_amount_0, latest_fees_owed_0, decrease_amount_1, latest_fees_owed_1) =
         decrease_liquidity_and_update_position(
             &ctx.accounts.pool_state,
@@ -102,24 +135,46 @@ pub fn decrease_liquidity<'a, 'b, 'c, 'info>(
             &mut ctx.accounts.personal_position,
             &ctx.accounts.tick_array_lower,
             &ctx.accounts.tick_array_upper,
+            tick_array_bitmap_extension.as_ref(),
+            &reward_extensions,
             liquidity,
-            amount_0_min,
-            amount_1_min,
         )?;
+    // the liquidity and the fees are sent in one transfer, the transfer fee is charged on the total
+    let transfer_amount_0 = decrease_amount_0 + latest_fees_owed_0;
+    let transfer_amount_1 = decrease_amount_1 + latest_fees_owed_1;
+    if liquidity > 0 {
+        // slippage is checked against the amount received after transfer fee
+        let transfer_fee_0 = get_transfer_fee(&ctx.accounts.vault_0_mint, transfer_amount_0)?;
+        let transfer_fee_1 = get_transfer_fee(&ctx.accounts.vault_1_mint, transfer_amount_1)?;
+        require!(
+            transfer_amount_0.checked_sub(transfer_fee_0).unwrap() >= amount_0_min
+                && transfer_amount_1.checked_sub(transfer_fee_1).unwrap() >= amount_1_min,
+            ErrorCode::PriceSlippageCheck
+        );
+    }
+    // the transfer hook accounts of token_0 and token_1 come before the reward accounts
+    let transfer_hook_accounts_0 =
+        get_transfer_hook_accounts(&ctx.accounts.vault_0_mint, &mut remaining_accounts)?;
+    let transfer_hook_accounts_1 =
+        get_transfer_hook_accounts(&ctx.accounts.vault_1_mint, &mut remaining_accounts)?;
     transfer_from_pool_vault_to_user(
         &ctx.accounts.pool_state,
         &ctx.accounts.token_vault_0,
         &ctx.accounts.recipient_token_account_0,
-        &ctx.accounts.token_program,
-        decrease_amount_0 + latest_fees_owed_0,
+        &ctx.accounts.vault_0_mint,
+        &ctx.accounts.token_program_0,
+        &transfer_hook_accounts_0,
+        transfer_amount_0,
     )?;
 
     transfer_from_pool_vault_to_user(
         &ctx.accounts.pool_state,
         &ctx.accounts.token_vault_1,
         &ctx.accounts.recipient_token_account_1,
-        &ctx.accounts.token_program,
-        decrease_amount_1 + latest_fees_owed_1,
+        &ctx.accounts.vault_1_mint,
+        &ctx.accounts.token_program_1,
+        &transfer_hook_accounts_1,
+        transfer_amount_1,
     )?;
 
     check_unclaimed_fees_and_vault(
@@ -129,10 +184,17 @@ pub fn decrease_liquidity<'a, 'b, 'c, 'info>(
     )?;
 
     let personal_position = &mut ctx.accounts.personal_position;
-    let reward_amounts = collect_rewards(
+    // all the rewards are collected, the rewards of the reward extensions follow the rewards of the pool
+    let reward_amounts = collect_position_rewards(
+        &ctx.accounts.pool_state,
+        &mut remaining_accounts,
+        personal_position,
+        u8::MAX,
+    )?;
+    collect_position_extension_rewards(
         &ctx.accounts.pool_state,
-        ctx.remaining_accounts,
-        ctx.accounts.token_program.clone(),
+        &reward_extensions,
+        remaining_accounts,
         personal_position,
     )?;
 
@@ -164,50 +226,34 @@ pub fn decrease_liquidity_and_update_position<'a, 'b, 'c, 'info>(
     personal_position: &mut Box<Account<'info, PersonalPositionState>>,
     tick_array_lower: &AccountLoader<'info, TickArrayState>,
     tick_array_upper: &AccountLoader<'info, TickArrayState>,
+    tick_array_bitmap_extension: Option<&AccountLoader<'info, TickArrayBitmapExtension>>,
+    reward_extensions: &PoolRewardExtensions<'info>,
     liquidity: u128,
-    amount_0_min: u64,
-    amount_1_min: u64,
 ) -> Result<(u64, u64, u64, u64)> {
     let mut pool_state = pool_state_loader.load_mut()?;
     let mut decrease_amount_0 = 0;
     let mut decrease_amount_1 = 0;
     if pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity) {
-        (decrease_amount_0, decrease_amount_1) = burn_liquidity(
+        (decrease_amount_0, decrease_amount_1) = burn_personal_position_liquidity(
             &mut pool_state,
+            protocol_position,
+            personal_position,
             tick_array_lower,
             tick_array_upper,
-            protocol_position,
+            tick_array_bitmap_extension,
+            reward_extensions,
             liquidity,
         )?;
-        if liquidity > 0 {
-            require!(
-                decrease_amount_0 >= amount_0_min && decrease_amount_1 >= amount_1_min,
-                ErrorCode::PriceSlippageCheck
-            );
-        }
-
-        personal_position.token_fees_owed_0 = calculate_latest_token_fees(
-            personal_position.token_fees_owed_0,
-            personal_position.fee_growth_inside_0_last_x64,
-            protocol_position.fee_growth_inside_0_last_x64,
-            personal_position.liquidity,
-        );
-
-        personal_position.token_fees_owed_1 = calculate_latest_token_fees(
-            personal_position.token_fees_owed_1,
-            personal_position.fee_growth_inside_1_last_x64,
-            protocol_position.fee_growth_inside_1_last_x64,
-            personal_position.liquidity,
-        );
 
-        personal_position.fee_growth_inside_0_last_x64 =
-            protocol_position.fee_growth_inside_0_last_x64;
-        personal_position.fee_growth_inside_1_last_x64 =
-            protocol_position.fee_growth_inside_1_last_x64;
-
-        // update rewards, must update before decrease liquidity
-        personal_position.update_rewards(protocol_position.reward_growth_inside, true)?;
-        personal_position.liquidity = personal_position.liquidity.checked_sub(liquidity).unwrap();
+        // the settled range order is withdrawn along with the liquidity
+        decrease_amount_0 = decrease_amount_0
+            .checked_add(personal_position.range_order_amount_0)
+            .unwrap();
+        decrease_amount_1 = decrease_amount_1
+            .checked_add(personal_position.range_order_amount_1)
+            .unwrap();
+        personal_position.range_order_amount_0 = 0;
+        personal_position.range_order_amount_1 = 0;
     }
 
     let mut latest_fees_owed_0 = 0;
@@ -246,10 +292,64 @@ pub fn decrease_liquidity_and_update_position<'a, 'b, 'c, 'info>(
     ))
 }
 
+/// Burns liquidity of the personal position, the fees and rewards earned before are accrued to the position
+pub fn burn_personal_position_liquidity<'info>(
+    pool_state: &mut RefMut<PoolState>,
+    protocol_position: &mut ProtocolPositionState,
+    personal_position: &mut PersonalPositionState,
+    tick_array_lower: &AccountLoader<'info, TickArrayState>,
+    tick_array_upper: &AccountLoader<'info, TickArrayState>,
+    tick_array_bitmap_extension: Option<&AccountLoader<'info, TickArrayBitmapExtension>>,
+    reward_extensions: &PoolRewardExtensions<'info>,
+    liquidity: u128,
+) -> Result<(u64, u64)> {
+    let (amount_0, amount_1) = burn_liquidity(
+        pool_state,
+        tick_array_lower,
+        tick_array_upper,
+        tick_array_bitmap_extension,
+        reward_extensions,
+        protocol_position,
+        liquidity,
+    )?;
+
+    personal_position.token_fees_owed_0 = calculate_latest_token_fees(
+        personal_position.token_fees_owed_0,
+        personal_position.fee_growth_inside_0_last_x64,
+        protocol_position.fee_growth_inside_0_last_x64,
+        personal_position.liquidity,
+    );
+
+    personal_position.token_fees_owed_1 = calculate_latest_token_fees(
+        personal_position.token_fees_owed_1,
+        personal_position.fee_growth_inside_1_last_x64,
+        protocol_position.fee_growth_inside_1_last_x64,
+        personal_position.liquidity,
+    );
+
+    personal_position.fee_growth_inside_0_last_x64 = protocol_position.fee_growth_inside_0_last_x64;
+    personal_position.fee_growth_inside_1_last_x64 = protocol_position.fee_growth_inside_1_last_x64;
+
+    // update rewards, must update before decrease liquidity
+    personal_position.update_rewards(protocol_position.reward_growth_inside, true)?;
+    let liquidity_after = personal_position.liquidity.checked_sub(liquidity).unwrap();
+    reward_extensions.update_position(
+        personal_position,
+        pool_state,
+        tick_array_lower,
+        tick_array_upper,
+        liquidity_after,
+    )?;
+    personal_position.liquidity = liquidity_after;
+    Ok((amount_0, amount_1))
+}
+
 pub fn burn_liquidity<'b, 'info>(
     pool_state: &mut RefMut<PoolState>,
     tick_array_lower_loader: &AccountLoader<'info, TickArrayState>,
     tick_array_upper_loader: &AccountLoader<'info, TickArrayState>,
+    tick_array_bitmap_extension: Option<&AccountLoader<'info, TickArrayBitmapExtension>>,
+    reward_extensions: &PoolRewardExtensions<'info>,
     protocol_position: &mut ProtocolPositionState,
     liquidity: u128,
 ) -> Result<(u64, u64)> {
@@ -272,6 +372,7 @@ pub fn burn_liquidity<'b, 'info>(
         protocol_position,
         &mut tick_lower_state,
         &mut tick_upper_state,
+        reward_extensions,
         clock.unix_timestamp as u64,
     )?;
 
@@ -291,14 +392,26 @@ pub fn burn_liquidity<'b, 'info>(
         let mut tick_array_lower = tick_array_lower_loader.load_mut()?;
         tick_array_lower.update_initialized_tick_count(false)?;
         if tick_array_lower.initialized_tick_count == 0 {
-            pool_state.flip_tick_array_bit(tick_array_lower.start_tick_index)?;
+            pool_state.flip_tick_array_bit(
+                tick_array_bitmap_extension
+                    .map(|extension| extension.load_mut())
+                    .transpose()?
+                    .as_deref_mut(),
+                tick_array_lower.start_tick_index,
+            )?;
         }
     }
     if flip_tick_upper {
         let mut tick_array_upper = tick_array_upper_loader.load_mut()?;
         tick_array_upper.update_initialized_tick_count(false)?;
         if tick_array_upper.initialized_tick_count == 0 {
-            pool_state.flip_tick_array_bit(tick_array_upper.start_tick_index)?;
+            pool_state.flip_tick_array_bit(
+                tick_array_bitmap_extension
+                    .map(|extension| extension.load_mut())
+                    .transpose()?
+                    .as_deref_mut(),
+                tick_array_upper.start_tick_index,
+            )?;
         }
     }
 
@@ -316,11 +429,13 @@ pub fn burn_liquidity<'b, 'info>(
     Ok((amount_0, amount_1))
 }
 
-pub fn collect_rewards<'a, 'b, 'c, 'info>(
+/// Collects the rewards owed to the position, only the rewards whose bit is set in `reward_mask`,
+/// the accounts of the collected rewards are taken from the front of `remaining_accounts`
+pub fn collect_position_rewards<'a, 'b, 'c, 'info>(
     pool_state_loader: &AccountLoader<'info, PoolState>,
-    remaining_accounts: &[AccountInfo<'info>],
-    token_program: Program<'info, Token2022>,
+    remaining_accounts: &mut &[AccountInfo<'info>],
     personal_position_state: &mut PersonalPositionState,
+    reward_mask: u8,
 ) -> Result<[u64; REWARD_NUM]> {
     let mut reward_amounts: [u64; REWARD_NUM] = [0, 0, 0];
     if !pool_state_loader
@@ -329,16 +444,30 @@ pub fn collect_rewards<'a, 'b, 'c, 'info>(
     {
         return Ok(reward_amounts);
     }
-    check_required_accounts_length(pool_state_loader, remaining_accounts)?;
+    let valid_reward_count =
+        check_required_accounts_length(pool_state_loader, remaining_accounts, reward_mask)?;
 
-    let remaining_accounts_len = remaining_accounts.len();
-    let mut remaining_accounts = remaining_accounts.iter();
-    for i in 0..remaining_accounts_len / 2 {
-        let reward_token_vault =
-            InterfaceAccount::<TokenAccount>::try_from(&remaining_accounts.next().unwrap())?;
+    // each reward takes the accounts [reward_token_vault, recipient_token_account, reward_vault_mint,
+    // reward_token_program], followed by the transfer hook accounts of the reward mint if any
+    for i in 0..valid_reward_count {
+        if reward_mask & (1 << i) == 0 {
+            continue;
+        }
+        require_gte!(
+            remaining_accounts.len(),
+            4,
+            ErrorCode::InvalidRewardInputAccountNumber
+        );
+        let reward_token_vault = InterfaceAccount::<TokenAccount>::try_from(&remaining_accounts[0])?;
         let recipient_token_account =
-            InterfaceAccount::<TokenAccount>::try_from(&remaining_accounts.next().unwrap())?;
+            InterfaceAccount::<TokenAccount>::try_from(&remaining_accounts[1])?;
+        let reward_vault_mint = InterfaceAccount::<Mint>::try_from(&remaining_accounts[2])?;
+        let reward_token_program = Interface::<TokenInterface>::try_from(&remaining_accounts[3])?;
+        *remaining_accounts = &remaining_accounts[4..];
+        let transfer_hook_accounts =
+            get_transfer_hook_accounts(&reward_vault_mint, remaining_accounts)?;
         require_keys_eq!(reward_token_vault.mint, recipient_token_account.mint);
+        require_keys_eq!(reward_token_vault.mint, reward_vault_mint.key());
         require_keys_eq!(
             reward_token_vault.key(),
             pool_state_loader.load_mut()?.reward_infos[i].token_vault
@@ -375,7 +504,9 @@ pub fn collect_rewards<'a, 'b, 'c, 'info>(
                 &pool_state_loader,
                 &reward_token_vault,
                 &recipient_token_account,
-                &token_program,
+                &reward_vault_mint,
+                &reward_token_program,
+                &transfer_hook_accounts,
                 transfer_amount,
             )?;
         }
@@ -385,22 +516,94 @@ pub fn collect_rewards<'a, 'b, 'c, 'info>(
     Ok(reward_amounts)
 }
 
+/// Collects the rewards of the reward extensions owed to the position, `remaining_accounts` holds
+/// the accounts of each collected reward the same as `collect_position_rewards`, the reward extension
+/// is the one of the reward token vault
+pub fn collect_position_extension_rewards<'info>(
+    pool_state_loader: &AccountLoader<'info, PoolState>,
+    reward_extensions: &[AccountLoader<'info, PoolRewardExtension>],
+    remaining_accounts: &[AccountInfo<'info>],
+    personal_position_state: &PersonalPositionState,
+) -> Result<()> {
+    if !pool_state_loader
+        .load()?
+        .get_status_by_bit(PoolStatusBitIndex::CollectReward)
+    {
+        return Ok(());
+    }
+    let mut remaining_accounts = remaining_accounts;
+    while !remaining_accounts.is_empty() {
+        require_gte!(
+            remaining_accounts.len(),
+            4,
+            ErrorCode::InvalidRewardInputAccountNumber
+        );
+        let reward_token_vault = InterfaceAccount::<TokenAccount>::try_from(&remaining_accounts[0])?;
+        let recipient_token_account =
+            InterfaceAccount::<TokenAccount>::try_from(&remaining_accounts[1])?;
+        let reward_vault_mint = InterfaceAccount::<Mint>::try_from(&remaining_accounts[2])?;
+        let reward_token_program = Interface::<TokenInterface>::try_from(&remaining_accounts[3])?;
+        remaining_accounts = &remaining_accounts[4..];
+        let transfer_hook_accounts =
+            get_transfer_hook_accounts(&reward_vault_mint, &mut remaining_accounts)?;
+        require_keys_eq!(reward_token_vault.mint, recipient_token_account.mint);
+        require_keys_eq!(reward_token_vault.mint, reward_vault_mint.key());
+
+        let mut reward_extension = None;
+        for extension in reward_extensions {
+            if extension.load()?.reward_info.token_vault == reward_token_vault.key() {
+                reward_extension = Some(extension);
+                break;
+            }
+        }
+        let reward_extension = reward_extension.ok_or(ErrorCode::InvalidRewardIndex)?;
+
+        let transfer_amount = PoolRewardExtension::collect_position_reward(
+            reward_extension,
+            personal_position_state,
+            reward_token_vault.amount,
+        )?;
+        if transfer_amount > 0 {
+            transfer_from_pool_vault_to_user(
+                pool_state_loader,
+                &reward_token_vault,
+                &recipient_token_account,
+                &reward_vault_mint,
+                &reward_token_program,
+                &transfer_hook_accounts,
+                transfer_amount,
+            )?;
+        }
+        emit!(CollectExtensionRewardEvent {
+            position_nft_mint: personal_position_state.nft_mint,
+            reward_extension_index: reward_extension.load()?.index,
+            reward_amount: transfer_amount,
+        });
+    }
+    Ok(())
+}
+
 fn check_required_accounts_length(
     pool_state_loader: &AccountLoader<PoolState>,
     remaining_accounts: &[AccountInfo],
-) -> Result<()> {
+    reward_mask: u8,
+) -> Result<usize> {
     let pool_state = pool_state_loader.load()?;
     let mut valid_reward_count = 0;
-    for item in pool_state.reward_infos {
+    let mut collect_reward_count = 0;
+    for (i, item) in pool_state.reward_infos.iter().enumerate() {
         if item.initialized() {
             valid_reward_count = valid_reward_count + 1;
+            if reward_mask & (1 << i) != 0 {
+                collect_reward_count += 1;
+            }
         }
     }
     let remaining_accounts_len = remaining_accounts.len();
-    if remaining_accounts_len != valid_reward_count * 2 {
+    if remaining_accounts_len < collect_reward_count * 4 {
         return err!(ErrorCode::InvalidRewardInputAccountNumber);
     }
-    Ok(())
+    Ok(valid_reward_count)
 }
 
 pub fn check_unclaimed_fees_and_vault(
@@ -0,0 +1,143 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::{get_transfer_fee, get_transfer_hook_accounts, transfer_from_user_to_pool_vault};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct Donate<'info> {
+    /// The donor paying the tokens
+    pub donor: Signer<'info>,
+
+    /// The pool whose in-range liquidity receives the donation
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The donor's token account for token_0
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The donor's token account for token_1
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_0 vault
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_1 vault
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token vault 0
+    #[account(address = token_vault_0.mint)]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(address = token_vault_1.mint)]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// SPL program or token program 2022 to transfer token_0
+    pub token_program_0: Interface<'info, TokenInterface>,
+
+    /// SPL program or token program 2022 to transfer token_1
+    pub token_program_1: Interface<'info, TokenInterface>,
+}
+
+pub fn donate<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, Donate<'info>>,
+    amount_0: u64,
+    amount_1: u64,
+) -> Result<()> {
+    // the pool receives the amount after transfer fee
+    let received_amount_0 = amount_0 - get_transfer_fee(&ctx.accounts.vault_0_mint, amount_0)?;
+    let received_amount_1 = amount_1 - get_transfer_fee(&ctx.accounts.vault_1_mint, amount_1)?;
+    let liquidity = add_donation(
+        &mut *ctx.accounts.pool_state.load_mut()?,
+        received_amount_0,
+        received_amount_1,
+    )?;
+
+    // the transfer hook accounts of token_0 and token_1 are passed by remaining accounts
+    let mut remaining_accounts = ctx.remaining_accounts;
+    let transfer_hook_accounts_0 =
+        get_transfer_hook_accounts(&ctx.accounts.vault_0_mint, &mut remaining_accounts)?;
+    let transfer_hook_accounts_1 =
+        get_transfer_hook_accounts(&ctx.accounts.vault_1_mint, &mut remaining_accounts)?;
+    transfer_from_user_to_pool_vault(
+        &ctx.accounts.donor,
+        &ctx.accounts.token_account_0,
+        &ctx.accounts.token_vault_0,
+        &ctx.accounts.vault_0_mint,
+        &ctx.accounts.token_program_0,
+        &transfer_hook_accounts_0,
+        amount_0,
+    )?;
+    transfer_from_user_to_pool_vault(
+        &ctx.accounts.donor,
+        &ctx.accounts.token_account_1,
+        &ctx.accounts.token_vault_1,
+        &ctx.accounts.vault_1_mint,
+        &ctx.accounts.token_program_1,
+        &transfer_hook_accounts_1,
+        amount_1,
+    )?;
+
+    emit!(DonateEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        donor: ctx.accounts.donor.key(),
+        amount_0: received_amount_0,
+        amount_1: received_amount_1,
+        liquidity,
+    });
+    Ok(())
+}
+
+/// Credits the donation to the in-range liquidity like swap fees, so it is paused with swaps,
+/// returns the liquidity receiving it
+fn add_donation(pool_state: &mut PoolState, amount_0: u64, amount_1: u64) -> Result<u128> {
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Swap) {
+        return err!(ErrorCode::NotApproved);
+    }
+    // a donation while a flash loan is out would be taken as the repayment
+    pool_state.check_unlocked()?;
+    pool_state.add_liquidity_fees(amount_0, amount_1)?;
+    Ok(pool_state.liquidity)
+}
+
+#[cfg(test)]
+mod donate_test {
+    use super::*;
+    use crate::libraries::fixed_point_64::Q64;
+
+    #[test]
+    fn add_donation_test() {
+        let mut pool_state = PoolState {
+            liquidity: Q64,
+            ..Default::default()
+        };
+        assert_eq!(add_donation(&mut pool_state, 10, 20).unwrap(), Q64);
+        assert_eq!({ pool_state.fee_growth_global_0_x64 }, 10);
+        assert_eq!({ pool_state.fee_growth_global_1_x64 }, 20);
+
+        // a paused pool takes no donation
+        pool_state.set_status_by_bit(PoolStatusBitIndex::Swap, PoolStatusBitFlag::Disable);
+        assert_eq!(
+            add_donation(&mut pool_state, 10, 20).unwrap_err(),
+            ErrorCode::NotApproved.into()
+        );
+        assert_eq!({ pool_state.fee_growth_global_0_x64 }, 10);
+    }
+}
@@ -0,0 +1,195 @@
+use crate::error::ErrorCode;
+use crate::libraries::{big_num::U128, full_math::MulDiv};
+use crate::states::*;
+use crate::util::{get_transfer_hook_accounts, transfer_from_pool_vault_to_user};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::Instruction, program::invoke};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct Flash<'info> {
+    /// The borrower of the flash loan
+    pub borrower: Signer<'info>,
+
+    /// The factory state to read protocol fees
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The pool to borrow from
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Token_0 vault
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_1 vault
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token account to receive the borrowed token_0
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token account to receive the borrowed token_1
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token vault 0
+    #[account(address = token_vault_0.mint)]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(address = token_vault_1.mint)]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// SPL program or token program 2022 to transfer out token_0
+    pub token_program_0: Interface<'info, TokenInterface>,
+
+    /// SPL program or token program 2022 to transfer out token_1
+    pub token_program_1: Interface<'info, TokenInterface>,
+
+    /// CHECK: The program called back to use and repay the loan, it must transfer
+    /// the borrowed amounts plus the fees back to the pool vaults
+    #[account(executable)]
+    pub callback_program: UncheckedAccount<'info>,
+}
+
+pub fn flash<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, Flash<'info>>,
+    amount_0: u64,
+    amount_1: u64,
+    data: Vec<u8>,
+) -> Result<()> {
+    let (fee_0, fee_1) = {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        if !pool_state.get_status_by_bit(PoolStatusBitIndex::Flash) {
+            return err!(ErrorCode::NotApproved);
+        }
+        // the fees are distributed to the in-range liquidity
+        require_gt!(pool_state.liquidity, 0, ErrorCode::LiquidityInsufficient);
+        pool_state.lock()?;
+        let fee_rate = pool_state.get_trade_fee_rate(ctx.accounts.amm_config.trade_fee_rate);
+        (
+            get_flash_fee(amount_0, fee_rate),
+            get_flash_fee(amount_1, fee_rate),
+        )
+    };
+    let balance_0_before = ctx.accounts.token_vault_0.amount;
+    let balance_1_before = ctx.accounts.token_vault_1.amount;
+
+    // the transfer hook accounts of token_0 and token_1 come before the callback accounts
+    let mut remaining_accounts = ctx.remaining_accounts;
+    let transfer_hook_accounts_0 =
+        get_transfer_hook_accounts(&ctx.accounts.vault_0_mint, &mut remaining_accounts)?;
+    let transfer_hook_accounts_1 =
+        get_transfer_hook_accounts(&ctx.accounts.vault_1_mint, &mut remaining_accounts)?;
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0,
+        &ctx.accounts.recipient_token_account_0,
+        &ctx.accounts.vault_0_mint,
+        &ctx.accounts.token_program_0,
+        &transfer_hook_accounts_0,
+        amount_0,
+    )?;
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_1,
+        &ctx.accounts.recipient_token_account_1,
+        &ctx.accounts.vault_1_mint,
+        &ctx.accounts.token_program_1,
+        &transfer_hook_accounts_1,
+        amount_1,
+    )?;
+
+    // the callback receives the data of the borrower followed by the fees in little endian
+    let mut callback_data = data;
+    callback_data.extend_from_slice(&fee_0.to_le_bytes());
+    callback_data.extend_from_slice(&fee_1.to_le_bytes());
+    let callback_instruction = Instruction {
+        program_id: ctx.accounts.callback_program.key(),
+        accounts: remaining_accounts
+            .iter()
+            .map(|account| {
+                if account.is_writable {
+                    AccountMeta::new(account.key(), account.is_signer)
+                } else {
+                    AccountMeta::new_readonly(account.key(), account.is_signer)
+                }
+            })
+            .collect(),
+        data: callback_data,
+    };
+    let mut callback_account_infos = remaining_accounts.to_vec();
+    callback_account_infos.push(ctx.accounts.callback_program.to_account_info());
+    invoke(&callback_instruction, &callback_account_infos)?;
+
+    ctx.accounts.token_vault_0.reload()?;
+    ctx.accounts.token_vault_1.reload()?;
+    let balance_0_after = ctx.accounts.token_vault_0.amount;
+    let balance_1_after = ctx.accounts.token_vault_1.amount;
+    require!(
+        balance_0_after >= balance_0_before.checked_add(fee_0).unwrap()
+            && balance_1_after >= balance_1_before.checked_add(fee_1).unwrap(),
+        ErrorCode::FlashNotRepaid
+    );
+    let paid_0 = balance_0_after - balance_0_before;
+    let paid_1 = balance_1_after - balance_1_before;
+
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.unlock();
+    pool_state.add_flash_fees(
+        ctx.accounts.amm_config.protocol_fee_rate,
+        ctx.accounts.amm_config.fund_fee_rate,
+        paid_0,
+        paid_1,
+    )?;
+
+    emit!(FlashEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        borrower: ctx.accounts.borrower.key(),
+        callback_program: ctx.accounts.callback_program.key(),
+        amount_0,
+        amount_1,
+        fee_0,
+        fee_1,
+        paid_0,
+        paid_1,
+    });
+    Ok(())
+}
+
+/// The fee of a flash loan, charged at the trade fee rate and rounded up
+pub fn get_flash_fee(amount: u64, fee_rate: u32) -> u64 {
+    U128::from(amount)
+        .mul_div_ceil(U128::from(fee_rate), U128::from(FEE_RATE_DENOMINATOR_VALUE))
+        .unwrap()
+        .as_u64()
+}
+
+#[cfg(test)]
+mod flash_test {
+    use super::*;
+
+    #[test]
+    fn get_flash_fee_test() {
+        assert_eq!(get_flash_fee(0, 2500), 0);
+        assert_eq!(get_flash_fee(1, 2500), 1);
+        assert_eq!(get_flash_fee(1_000_000, 2500), 2500);
+        assert_eq!(get_flash_fee(1_000_001, 2500), 2501);
+        assert_eq!(get_flash_fee(u64::MAX, 0), 0);
+    }
+}
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::states::*;
+use crate::util::{
+    get_transfer_hook_accounts, get_transfer_inverse_fee, transfer_from_user_to_pool_vault,
+};
+
+#[derive(Accounts)]
+pub struct FundReward<'info> {
+    /// Anyone can fund a running reward
+    pub funder: Signer<'info>,
+
+    /// The funder's reward token account
+    #[account(
+        mut,
+        token::mint = reward_vault_mint
+    )]
+    pub funder_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The pool of the reward
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The reward vault receiving the fund
+    #[account(mut)]
+    pub reward_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of reward token vault
+    #[account(address = reward_token_vault.mint)]
+    pub reward_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// SPL program or token program 2022 of the reward mint
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn fund_reward<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, FundReward<'info>>,
+    reward_index: u8,
+    amount: u64,
+) -> Result<()> {
+    assert!((reward_index as usize) < REWARD_NUM);
+    let current_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
+    {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        pool_state.check_unlocked()?;
+        pool_state.update_reward_infos(current_timestamp)?;
+        require_keys_eq!(
+            ctx.accounts.reward_token_vault.key(),
+            pool_state.reward_infos[reward_index as usize].token_vault
+        );
+        pool_state.fund_reward(reward_index as usize, amount)?;
+    }
+
+    let mut remaining_accounts = ctx.remaining_accounts;
+    let transfer_hook_accounts =
+        get_transfer_hook_accounts(&ctx.accounts.reward_vault_mint, &mut remaining_accounts)?;
+    // the vault must receive the full fund amount, so the funder pays the transfer fee on top
+    let transfer_fee = get_transfer_inverse_fee(&ctx.accounts.reward_vault_mint, amount)?;
+    transfer_from_user_to_pool_vault(
+        &ctx.accounts.funder,
+        &ctx.accounts.funder_token_account,
+        &ctx.accounts.reward_token_vault,
+        &ctx.accounts.reward_vault_mint,
+        &ctx.accounts.token_program,
+        &transfer_hook_accounts,
+        amount.checked_add(transfer_fee).unwrap(),
+    )?;
+
+    Ok(())
+}
@@ -2,8 +2,9 @@ use super::{add_liquidity, AddLiquidityParam};
 use crate::error::ErrorCode;
 use crate::libraries::{big_num::U128, fixed_point_64, full_math::MulDiv};
 use crate::states::*;
+use crate::util::get_transfer_hook_accounts;
 use anchor_lang::prelude::*;
-use anchor_spl::{token_interface::{Token2022, TokenAccount}};
+use anchor_spl::{token_interface::{Mint, TokenAccount, TokenInterface}};
 
 #[derive(Accounts)]
 pub struct IncreaseLiquidity<'info> {
@@ -72,8 +73,22 @@ pub struct IncreaseLiquidity<'info> {
     )]
     pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Program to create mint account and mint tokens
-    pub token_program: Program<'info, Token2022>,
+    /// The mint of token vault 0
+    #[account(address = token_vault_0.mint)]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(address = token_vault_1.mint)]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// SPL program or token program 2022 to transfer token_0
+    pub token_program_0: Interface<'info, TokenInterface>,
+
+    /// SPL program or token program 2022 to transfer token_1
+    pub token_program_1: Interface<'info, TokenInterface>,
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
 }
 
 pub fn increase_liquidity<'a, 'b, 'c, 'info>(
@@ -83,21 +98,44 @@ pub fn increase_liquidity<'a, 'b, 'c, 'info>(
     amount_1_max: u64,
 ) -> Result<()> {
     let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.check_unlocked()?;
     if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity) {
         return err!(ErrorCode::NotApproved);
     }
     let tick_lower = ctx.accounts.personal_position.tick_lower_index;
     let tick_upper = ctx.accounts.personal_position.tick_upper_index;
+    // the tick array bitmap extension, if needed, the reward extensions of the pool and the transfer
+    // hook accounts of token_0 and token_1 are passed by remaining accounts
+    let mut remaining_accounts = ctx.remaining_accounts;
+    let tick_array_bitmap_extension =
+        get_tick_array_bitmap_extension(&ctx.accounts.pool_state.key(), &mut remaining_accounts)?;
+    let reward_extensions = get_pool_reward_extensions_with_rent_payer(
+        &ctx.accounts.pool_state.key(),
+        pool_state.reward_extension_num,
+        &mut remaining_accounts,
+    )?;
+    let transfer_hook_accounts_0 =
+        get_transfer_hook_accounts(&ctx.accounts.vault_0_mint, &mut remaining_accounts)?;
+    let transfer_hook_accounts_1 =
+        get_transfer_hook_accounts(&ctx.accounts.vault_1_mint, &mut remaining_accounts)?;
     let mut add_liquidity_context = AddLiquidityParam {
         payer: &ctx.accounts.nft_owner,
         token_account_0: &mut ctx.accounts.token_account_0,
         token_account_1: &mut ctx.accounts.token_account_1,
         token_vault_0: &mut ctx.accounts.token_vault_0,
         token_vault_1: &mut ctx.accounts.token_vault_1,
+        vault_0_mint: &ctx.accounts.vault_0_mint,
+        vault_1_mint: &ctx.accounts.vault_1_mint,
         tick_array_lower: &ctx.accounts.tick_array_lower,
         tick_array_upper: &ctx.accounts.tick_array_upper,
+        tick_array_bitmap_extension,
+        reward_extensions,
         protocol_position: &mut ctx.accounts.protocol_position,
-        token_program: ctx.accounts.token_program.clone(),
+        token_program_0: ctx.accounts.token_program_0.clone(),
+        token_program_1: ctx.accounts.token_program_1.clone(),
+        transfer_hook_accounts_0,
+        transfer_hook_accounts_1,
+        observation_state: &ctx.accounts.observation_state,
     };
     let (amount_0, amount_1) = add_liquidity(
         &mut add_liquidity_context,
@@ -131,7 +169,15 @@ pub fn increase_liquidity<'a, 'b, 'c, 'info>(
 
     // update rewards, must update before increase liquidity
     personal_position.update_rewards(updated_protocol_position.reward_growth_inside, true)?;
-    personal_position.liquidity = personal_position.liquidity.checked_add(liquidity).unwrap();
+    let liquidity_after = personal_position.liquidity.checked_add(liquidity).unwrap();
+    add_liquidity_context.reward_extensions.update_position(
+        personal_position,
+        &pool_state,
+        add_liquidity_context.tick_array_lower,
+        add_liquidity_context.tick_array_upper,
+        liquidity_after,
+    )?;
+    personal_position.liquidity = liquidity_after;
 
     emit!(IncreaseLiquidityEvent {
         position_nft_mint: personal_position.nft_mint,
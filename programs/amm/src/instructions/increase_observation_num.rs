@@ -0,0 +1,60 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::realloc_account;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
+
+#[derive(Accounts)]
+pub struct IncreaseObservationNum<'info> {
+    /// Pays the rent of the grown observation account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The pool whose oracle is grown
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The oracle observations of the pool
+    #[account(
+        mut,
+        address = pool_state.load()?.observation_key
+    )]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// To pay the rent
+    pub system_program: Program<'info, System>,
+}
+
+pub fn increase_observation_num(
+    ctx: Context<IncreaseObservationNum>,
+    observation_num_next: u16,
+) -> Result<()> {
+    let observation_num_next = observation_num_next as usize;
+    require_gt!(
+        observation_num_next,
+        ctx.accounts
+            .observation_state
+            .load()?
+            .observation_num_next(),
+        ErrorCode::InvalidObservationNum
+    );
+    let observation_info = ctx.accounts.observation_state.to_account_info();
+    let space = ObservationState::account_len(observation_num_next);
+    // an account can only grow by MAX_PERMITTED_DATA_INCREASE in an instruction
+    require_gte!(
+        MAX_PERMITTED_DATA_INCREASE,
+        space - observation_info.data_len(),
+        ErrorCode::InvalidObservationNum
+    );
+    realloc_account(
+        ctx.accounts.payer.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        observation_info,
+        space,
+    )?;
+    // the ring grows to it once the most recent observation is the end of the ring
+    ctx.accounts
+        .observation_state
+        .load_mut()?
+        .observation_num_next = observation_num_next as u16;
+    Ok(())
+}
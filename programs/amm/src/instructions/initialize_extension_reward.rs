@@ -0,0 +1,158 @@
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+use crate::libraries::{fixed_point_64, full_math::MulDiv, U256};
+use crate::states::*;
+use crate::util::{
+    check_mint_extensions, get_transfer_hook_accounts, get_transfer_inverse_fee,
+    transfer_from_user_to_pool_vault,
+};
+
+use super::InitializeRewardParam;
+
+#[derive(Accounts)]
+pub struct InitializeExtensionReward<'info> {
+    /// The founder deposit reward token to vault
+    #[account(mut)]
+    pub reward_funder: Signer<'info>,
+
+    // The funder's reward token account
+    #[account(
+        mut,
+        token::mint = reward_token_mint
+    )]
+    pub funder_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// For check the reward_funder authority
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// Set reward for this pool
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// load info from the account to judge reward permission
+    #[account(
+        seeds = [
+            OPERATION_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub operation_state: AccountLoader<'info, OperationState>,
+
+    /// The reward extension holding the reward, the next index of the pool
+    #[account(
+        init,
+        seeds = [
+            POOL_REWARD_EXTENSION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &pool_state.load()?.reward_extension_num.to_be_bytes(),
+        ],
+        bump,
+        payer = reward_funder,
+        space = PoolRewardExtension::LEN
+    )]
+    pub pool_reward_extension: AccountLoader<'info, PoolRewardExtension>,
+
+    /// Reward mint
+    pub reward_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// A pda, reward vault
+    #[account(
+        init,
+        seeds = [
+            POOL_REWARD_VAULT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            reward_token_mint.key().as_ref(),
+        ],
+        bump,
+        payer = reward_funder,
+        token::mint = reward_token_mint,
+        token::authority = pool_state
+    )]
+    pub reward_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program or token program 2022 of the reward mint
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn initialize_extension_reward<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, InitializeExtensionReward<'info>>,
+    param: InitializeRewardParam,
+) -> Result<()> {
+    let operation_state = ctx.accounts.operation_state.load()?;
+    require!(
+        ctx.accounts.reward_funder.key() == crate::admin::id()
+            || ctx.accounts.reward_funder.key() == ctx.accounts.pool_state.load()?.owner
+            || operation_state.validate_operation_owner(ctx.accounts.reward_funder.key()),
+        ErrorCode::NotApproved
+    );
+    check_mint_extensions(&ctx.accounts.reward_token_mint, &operation_state)?;
+
+    let clock = Clock::get()?;
+    param.check(clock.unix_timestamp as u64)?;
+
+    let reward_amount = U256::from(param.end_time - param.open_time)
+        .mul_div_ceil(
+            U256::from(param.emissions_per_second_x64),
+            U256::from(fixed_point_64::Q64),
+        )
+        .unwrap()
+        .as_u64();
+
+    // the vault must receive the full reward amount, so the funder pays the transfer fee on top
+    let transfer_fee = get_transfer_inverse_fee(&ctx.accounts.reward_token_mint, reward_amount)?;
+    let reward_amount_with_fee = reward_amount.checked_add(transfer_fee).unwrap();
+    require_gte!(
+        ctx.accounts.funder_token_account.amount,
+        reward_amount_with_fee
+    );
+
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.check_unlocked()?;
+    let token_mint = ctx.accounts.reward_token_mint.key();
+    // a reward mint already in use can't init its reward vault again, the rewards of the pool are
+    // checked first for a clearer error
+    require!(
+        pool_state
+            .reward_infos
+            .iter()
+            .all(|reward_info| reward_info.token_mint != token_mint),
+        ErrorCode::RewardTokenAlreadyInUse
+    );
+    let reward_extension_index = pool_state.add_reward_extension()?;
+    ctx.accounts.pool_reward_extension.load_init()?.initialize(
+        ctx.accounts.pool_state.key(),
+        reward_extension_index,
+        RewardInfo {
+            open_time: param.open_time,
+            end_time: param.end_time,
+            last_update_time: param.open_time,
+            emissions_per_second_x64: param.emissions_per_second_x64,
+            token_mint,
+            token_vault: ctx.accounts.reward_token_vault.key(),
+            authority: ctx.accounts.reward_funder.key(),
+            ..Default::default()
+        },
+    );
+
+    let mut remaining_accounts = ctx.remaining_accounts;
+    let transfer_hook_accounts =
+        get_transfer_hook_accounts(&ctx.accounts.reward_token_mint, &mut remaining_accounts)?;
+    transfer_from_user_to_pool_vault(
+        &ctx.accounts.reward_funder,
+        &ctx.accounts.funder_token_account,
+        &ctx.accounts.reward_token_vault,
+        &ctx.accounts.reward_token_mint,
+        &ctx.accounts.token_program,
+        &transfer_hook_accounts,
+        reward_amount_with_fee,
+    )?;
+
+    Ok(())
+}
@@ -1,11 +1,14 @@
-use anchor_spl::token_interface::{TokenAccount, Token2022};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use anchor_lang::prelude::*;
 
 use crate::error::ErrorCode;
 use crate::libraries::{fixed_point_64, full_math::MulDiv, U256};
 use crate::states::*;
-use crate::util::transfer_from_user_to_pool_vault;
+use crate::util::{
+    check_mint_extensions, get_transfer_hook_accounts, get_transfer_inverse_fee,
+    transfer_from_user_to_pool_vault,
+};
 
 #[derive(Accounts)]
 pub struct InitializeReward<'info> {
@@ -38,7 +41,7 @@ pub struct InitializeReward<'info> {
     pub operation_state: AccountLoader<'info, OperationState>,
 
     /// Reward mint
-    pub reward_token_mint: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub reward_token_mint: Box<InterfaceAccount<'info, Mint>>,
 
     /// A pda, reward vault
     #[account(
@@ -55,8 +58,8 @@ pub struct InitializeReward<'info> {
     )]
     pub reward_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    // #[account(address = token_interface::ID)]
-    pub token_program: Program<'info, Token2022>,
+    /// SPL program or token program 2022 of the reward mint
+    pub token_program: Interface<'info, TokenInterface>,
 
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
@@ -89,11 +92,73 @@ impl InitializeRewardParam {
         }
         Ok(())
     }
+
+    /// The phases must start in order after the open time and before the end time of the reward
+    pub fn check_phases(&self, phases: &[RewardPhaseParam]) -> Result<()> {
+        require_gte!(REWARD_PHASE_NUM, phases.len(), ErrorCode::InvalidRewardPhases);
+        let mut last_start_time = self.open_time;
+        for phase in phases {
+            require!(
+                phase.start_time > last_start_time
+                    && phase.start_time < self.end_time
+                    && phase.emissions_per_second_x64 != 0,
+                ErrorCode::InvalidRewardPhases
+            );
+            last_start_time = phase.start_time;
+        }
+        Ok(())
+    }
+
+    /// The amount the reward emits from the open time to the end time, switching the emission
+    /// rate at the start of each phase
+    pub fn get_reward_amount(&self, phases: &[RewardPhaseParam]) -> u64 {
+        let mut reward_amount: u64 = 0;
+        let mut start_time = self.open_time;
+        let mut emissions_per_second_x64 = self.emissions_per_second_x64;
+        for phase in phases
+            .iter()
+            .chain(std::iter::once(&RewardPhaseParam {
+                start_time: self.end_time,
+                emissions_per_second_x64: 0,
+            }))
+        {
+            reward_amount = reward_amount
+                .checked_add(
+                    U256::from(phase.start_time - start_time)
+                        .mul_div_ceil(
+                            U256::from(emissions_per_second_x64),
+                            U256::from(fixed_point_64::Q64),
+                        )
+                        .unwrap()
+                        .as_u64(),
+                )
+                .unwrap();
+            start_time = phase.start_time;
+            emissions_per_second_x64 = phase.emissions_per_second_x64;
+        }
+        reward_amount
+    }
+}
+
+#[derive(Copy, Clone, AnchorSerialize, AnchorDeserialize, Debug, PartialEq)]
+pub struct RewardPhaseParam {
+    /// The time the emission rate of the phase takes effect
+    pub start_time: u64,
+    /// Token reward per second are earned per unit of liquidity from the start time
+    pub emissions_per_second_x64: u128,
 }
 
-pub fn initialize_reward(
-    ctx: Context<InitializeReward>,
+pub fn initialize_reward<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, InitializeReward<'info>>,
     param: InitializeRewardParam,
+) -> Result<()> {
+    initialize_reward_schedule(ctx, param, Vec::new())
+}
+
+pub fn initialize_reward_schedule<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, InitializeReward<'info>>,
+    param: InitializeRewardParam,
+    phases: Vec<RewardPhaseParam>,
 ) -> Result<()> {
     let operation_state = ctx.accounts.operation_state.load()?;
     require!(
@@ -102,49 +167,106 @@ pub fn initialize_reward(
             || operation_state.validate_operation_owner(ctx.accounts.reward_funder.key()),
         ErrorCode::NotApproved
     );
+    check_mint_extensions(&ctx.accounts.reward_token_mint, &operation_state)?;
 
     // Clock
     let clock = Clock::get()?;
     #[cfg(feature = "enable-log")]
     msg!("current block timestamp:{}", clock.unix_timestamp);
     param.check(clock.unix_timestamp as u64)?;
+    param.check_phases(&phases)?;
 
-    let reward_amount = U256::from(param.end_time - param.open_time)
-        .mul_div_ceil(
-            U256::from(param.emissions_per_second_x64),
-            U256::from(fixed_point_64::Q64),
-        )
-        .unwrap()
-        .as_u64();
+    // the funder deposits the emissions of all the phases up front
+    let reward_amount = param.get_reward_amount(&phases);
 
-    require_gte!(ctx.accounts.funder_token_account.amount, reward_amount);
+    // the vault must receive the full reward amount, so the funder pays the transfer fee on top
+    let transfer_fee = get_transfer_inverse_fee(&ctx.accounts.reward_token_mint, reward_amount)?;
+    let reward_amount_with_fee = reward_amount.checked_add(transfer_fee).unwrap();
+    require_gte!(ctx.accounts.funder_token_account.amount, reward_amount_with_fee);
 
     let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.check_unlocked()?;
     pool_state.initialize_reward(
         param.open_time,
         param.end_time,
         param.emissions_per_second_x64,
+        &phases
+            .iter()
+            .map(|phase| RewardPhase {
+                start_time: phase.start_time,
+                emissions_per_second_x64: phase.emissions_per_second_x64,
+            })
+            .collect::<Vec<_>>(),
         &ctx.accounts.reward_token_mint.key(),
         &ctx.accounts.reward_token_vault.key(),
         &ctx.accounts.reward_funder.key(),
         &operation_state,
     )?;
 
-    let decimals;
-    if pool_state.token_mint_0.eq(&ctx.accounts.reward_token_mint.mint) {
-        decimals = pool_state.mint_decimals_0
-    } else {
-        decimals = pool_state.mint_decimals_1
-    }
-
+    let mut remaining_accounts = ctx.remaining_accounts;
+    let transfer_hook_accounts =
+        get_transfer_hook_accounts(&ctx.accounts.reward_token_mint, &mut remaining_accounts)?;
     transfer_from_user_to_pool_vault(
         &ctx.accounts.reward_funder,
         &ctx.accounts.funder_token_account,
         &ctx.accounts.reward_token_vault,
+        &ctx.accounts.reward_token_mint,
         &ctx.accounts.token_program,
-        reward_amount,
-        decimals,
+        &transfer_hook_accounts,
+        reward_amount_with_fee,
     )?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod initialize_reward_test {
+    use super::*;
+
+    #[test]
+    fn reward_phases_test() {
+        let param = InitializeRewardParam {
+            open_time: 1000,
+            end_time: 2000,
+            emissions_per_second_x64: 2 * fixed_point_64::Q64,
+        };
+        assert_eq!(param.get_reward_amount(&[]), 2000);
+
+        let phases = [
+            RewardPhaseParam {
+                start_time: 1100,
+                emissions_per_second_x64: 4 * fixed_point_64::Q64,
+            },
+            RewardPhaseParam {
+                start_time: 1500,
+                emissions_per_second_x64: fixed_point_64::Q64,
+            },
+        ];
+        param.check_phases(&phases).unwrap();
+        assert_eq!(param.get_reward_amount(&phases), 200 + 1600 + 500);
+
+        // phases out of order or outside the reward period
+        assert!(param.check_phases(&[phases[1], phases[0]]).is_err());
+        assert!(param
+            .check_phases(&[RewardPhaseParam {
+                start_time: 1000,
+                emissions_per_second_x64: 1,
+            }])
+            .is_err());
+        assert!(param
+            .check_phases(&[RewardPhaseParam {
+                start_time: 2000,
+                emissions_per_second_x64: 1,
+            }])
+            .is_err());
+        assert!(param
+            .check_phases(&[RewardPhaseParam {
+                start_time: 1100,
+                emissions_per_second_x64: 0,
+            }])
+            .is_err());
+        assert!(param
+            .check_phases(&[phases[0]; REWARD_PHASE_NUM + 1])
+            .is_err());
+    }
+}
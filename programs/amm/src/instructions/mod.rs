@@ -4,6 +4,9 @@ pub use create_pool::*;
 pub mod open_position;
 pub use open_position::*;
 
+pub mod open_position_with_token22_nft;
+pub use open_position_with_token22_nft::*;
+
 pub mod close_position;
 pub use close_position::*;
 
@@ -13,23 +16,83 @@ pub use increase_liquidity::*;
 pub mod decrease_liquidity;
 pub use decrease_liquidity::*;
 
+pub mod collect_fees;
+pub use collect_fees::*;
+
+pub mod collect_rewards;
+pub use collect_rewards::*;
+
+pub mod compound_position;
+pub use compound_position::*;
+
+pub mod set_compound_keeper;
+pub use set_compound_keeper::*;
+
+pub mod set_range_order;
+pub use set_range_order::*;
+
+pub mod settle_range_order;
+pub use settle_range_order::*;
+
 pub mod swap;
 pub use swap::*;
 
 pub mod swap_router_base_in;
 pub use swap_router_base_in::*;
 
+pub mod swap_router_base_out;
+pub use swap_router_base_out::*;
+
 pub mod update_reward_info;
 pub use update_reward_info::*;
 
 pub mod initialize_reward;
 pub use initialize_reward::*;
 
+pub mod initialize_extension_reward;
+pub use initialize_extension_reward::*;
+
 pub mod set_reward_params;
 pub use set_reward_params::*;
 
 pub mod collect_remaining_rewards;
 pub use collect_remaining_rewards::*;
 
+pub mod fund_reward;
+pub use fund_reward::*;
+
+pub mod cancel_reward;
+pub use cancel_reward::*;
+
+pub mod propose_reward_authority;
+pub use propose_reward_authority::*;
+
+pub mod accept_reward_authority;
+pub use accept_reward_authority::*;
+
+pub mod cancel_reward_authority;
+pub use cancel_reward_authority::*;
+
+pub mod create_tick_array_bitmap_extension;
+pub use create_tick_array_bitmap_extension::*;
+
+pub mod close_tick_array;
+pub use close_tick_array::*;
+
+pub mod close_protocol_position;
+pub use close_protocol_position::*;
+
+pub mod donate;
+pub use donate::*;
+
+pub mod flash;
+pub use flash::*;
+
+pub mod observe;
+pub use observe::*;
+
+pub mod increase_observation_num;
+pub use increase_observation_num::*;
+
 pub mod admin;
 pub use admin::*;
\ No newline at end of file
@@ -0,0 +1,66 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct Observe<'info> {
+    /// The pool whose oracle is read
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The oracle observations of the pool
+    #[account(address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+}
+
+pub fn observe(ctx: Context<Observe>, seconds_agos: Vec<u32>) -> Result<ObserveResult> {
+    for i in 1..seconds_agos.len() {
+        require_gt!(
+            seconds_agos[i - 1],
+            seconds_agos[i],
+            ErrorCode::InvalidSecondsAgos
+        );
+    }
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let observation_info = ctx.accounts.observation_state.to_account_info();
+    let (observation_state, observation_extension) = ObservationState::load(&observation_info)?;
+    let cumulatives = observation_state.observe(
+        &observation_extension,
+        oracle::block_timestamp(),
+        &seconds_agos,
+        pool_state.sqrt_price_x64,
+        pool_state.tick_current,
+        pool_state.liquidity,
+        pool_state.observation_index,
+    )?;
+
+    let mut result = ObserveResult::default();
+    for cumulative in &cumulatives {
+        result
+            .cumulative_time_price_x64s
+            .push(cumulative.cumulative_time_price_x64);
+        result.tick_cumulatives.push(cumulative.tick_cumulative);
+        result
+            .seconds_per_liquidity_cumulatives_x64
+            .push(cumulative.seconds_per_liquidity_cumulative_x64);
+    }
+    for (cumulatives, seconds) in cumulatives.windows(2).zip(seconds_agos.windows(2)) {
+        let delta_time = seconds[0] - seconds[1];
+        result.time_weighted_prices_x64.push(
+            cumulatives[1]
+                .cumulative_time_price_x64
+                .wrapping_sub(cumulatives[0].cumulative_time_price_x64)
+                / u128::from(delta_time),
+        );
+        let delta_tick = cumulatives[1]
+            .tick_cumulative
+            .wrapping_sub(cumulatives[0].tick_cumulative);
+        let mut time_weighted_tick = delta_tick / i64::from(delta_time);
+        // always round to negative infinity
+        if delta_tick < 0 && delta_tick % i64::from(delta_time) != 0 {
+            time_weighted_tick -= 1;
+        }
+        result.time_weighted_ticks.push(time_weighted_tick as i32);
+    }
+
+    Ok(result)
+}
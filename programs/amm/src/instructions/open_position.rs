@@ -5,7 +5,7 @@ use std::ops::Deref;
 
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_2022::spl_token_2022::instruction::AuthorityType;
-use anchor_spl::token_interface::{Mint, TokenAccount, Token2022};
+use anchor_spl::token_interface::{Mint, TokenAccount, Token2022, TokenInterface};
 use anchor_spl::token_interface;
 use mpl_token_metadata::{instruction::create_metadata_accounts_v3, state::Creator};
 
@@ -33,17 +33,41 @@ pub struct AddLiquidityParam<'b, 'info> {
     /// The address that holds pool tokens for token_1
     pub token_vault_1: &'b mut Box<InterfaceAccount<'info, TokenAccount>>,
 
+    /// The mint of token vault 0
+    pub vault_0_mint: &'b InterfaceAccount<'info, Mint>,
+
+    /// The mint of token vault 1
+    pub vault_1_mint: &'b InterfaceAccount<'info, Mint>,
+
     /// The bitmap storing initialization state of the lower tick
     pub tick_array_lower: &'b AccountLoader<'info, TickArrayState>,
 
+    /// The tick array bitmap extension of the pool, if passed
+    pub tick_array_bitmap_extension: Option<AccountLoader<'info, TickArrayBitmapExtension>>,
+
+    /// The reward extensions of the pool
+    pub reward_extensions: PoolRewardExtensions<'info>,
+
     /// The bitmap storing initialization state of the upper tick
     pub tick_array_upper: &'b AccountLoader<'info, TickArrayState>,
 
     /// The position into which liquidity is minted
     pub protocol_position: &'b mut Box<Account<'info, ProtocolPositionState>>,
 
-    /// The SPL program to perform token transfers
-    pub token_program: Program<'info, Token2022>,
+    /// SPL program or token program 2022 to perform token_0 transfers
+    pub token_program_0: Interface<'info, TokenInterface>,
+
+    /// SPL program or token program 2022 to perform token_1 transfers
+    pub token_program_1: Interface<'info, TokenInterface>,
+
+    /// The accounts required by the transfer hook of token_0
+    pub transfer_hook_accounts_0: Vec<AccountInfo<'info>>,
+
+    /// The accounts required by the transfer hook of token_1
+    pub transfer_hook_accounts_1: Vec<AccountInfo<'info>>,
+
+    /// The program account for the most recent oracle observation
+    pub observation_state: &'b AccountLoader<'info, ObservationState>,
 }
 
 #[derive(Accounts)]
@@ -160,6 +184,14 @@ pub struct OpenPosition<'info> {
     )]
     pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    /// The mint of token vault 0
+    #[account(address = token_vault_0.mint)]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(address = token_vault_1.mint)]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
     /// Sysvar for token mint and ATA creation
     pub rent: Sysvar<'info, Rent>,
 
@@ -169,6 +201,12 @@ pub struct OpenPosition<'info> {
     /// Program to create mint account and mint tokens
     pub token_program: Program<'info, Token2022>,
 
+    /// SPL program or token program 2022 of token_0
+    pub token_program_0: Interface<'info, TokenInterface>,
+
+    /// SPL program or token program 2022 of token_1
+    pub token_program_1: Interface<'info, TokenInterface>,
+
     /// Program to create an ATA for receiving position NFT
     pub associated_token_program: Program<'info, AssociatedToken>,
 
@@ -176,6 +214,9 @@ pub struct OpenPosition<'info> {
     /// CHECK: Metadata program address constraint applied
     #[account(address = mpl_token_metadata::ID)]
     pub metadata_program: UncheckedAccount<'info>,
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load() ?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
 }
 
 pub fn open_position<'a, 'b, 'c, 'info>(
@@ -188,123 +229,36 @@ pub fn open_position<'a, 'b, 'c, 'info>(
     tick_array_lower_start_index: i32,
     tick_array_upper_start_index: i32,
 ) -> Result<()> {
-    {
-        let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
-        if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity) {
-            return err!(ErrorCode::NotApproved);
-        }
-        check_ticks_order(tick_lower_index, tick_upper_index)?;
-        check_tick_array_start_index(
-            tick_array_lower_start_index,
-            tick_lower_index,
-            pool_state.tick_spacing,
-        )?;
-        check_tick_array_start_index(
-            tick_array_upper_start_index,
-            tick_upper_index,
-            pool_state.tick_spacing,
-        )?;
-
-        // Why not use anchor's `init-if-needed` to create?
-        // Beacuse `tick_array_lower` and `tick_array_upper` can be the same account, anchor can initialze tick_array_lower but it causes a crash when anchor to initialze the `tick_array_upper`,
-        // the problem is variable scope, tick_array_lower_loader not exit to save the discriminator while build tick_array_upper_loader.
-        let tick_array_lower_loader = TickArrayState::get_or_create_tick_array(
-            ctx.accounts.payer.to_account_info(),
-            ctx.accounts.tick_array_lower.to_account_info(),
-            ctx.accounts.system_program.to_account_info(),
-            &ctx.accounts.pool_state,
-            tick_array_lower_start_index,
-            pool_state.tick_spacing,
-        )?;
-
-        let tick_array_upper_loader =
-            if tick_array_lower_start_index == tick_array_upper_start_index {
-                AccountLoader::<TickArrayState>::try_from(
-                    &ctx.accounts.tick_array_upper.to_account_info(),
-                )?
-            } else {
-                TickArrayState::get_or_create_tick_array(
-                    ctx.accounts.payer.to_account_info(),
-                    ctx.accounts.tick_array_upper.to_account_info(),
-                    ctx.accounts.system_program.to_account_info(),
-                    &ctx.accounts.pool_state,
-                    tick_array_upper_start_index,
-                    pool_state.tick_spacing,
-                )?
-            };
-
-        // check if protocol position is initilized
-        if ctx.accounts.protocol_position.pool_id == Pubkey::default() {
-            let protocol_position = &mut ctx.accounts.protocol_position;
-            protocol_position.bump = *ctx.bumps.get("protocol_position").unwrap();
-            protocol_position.pool_id = ctx.accounts.pool_state.key();
-            protocol_position.tick_lower_index = tick_lower_index;
-            protocol_position.tick_upper_index = tick_upper_index;
-            tick_array_lower_loader
-                .load_mut()?
-                .get_tick_state_mut(tick_lower_index, i32::from(pool_state.tick_spacing))?
-                .tick = tick_lower_index;
-            tick_array_upper_loader
-                .load_mut()?
-                .get_tick_state_mut(tick_upper_index, i32::from(pool_state.tick_spacing))?
-                .tick = tick_upper_index;
-        }
-
-        let mut add_liquidity_context = AddLiquidityParam {
-            payer: &ctx.accounts.payer,
-            token_account_0: &mut ctx.accounts.token_account_0,
-            token_account_1: &mut ctx.accounts.token_account_1,
-            token_vault_0: &mut ctx.accounts.token_vault_0,
-            token_vault_1: &mut ctx.accounts.token_vault_1,
-            tick_array_lower: &tick_array_lower_loader,
-            tick_array_upper: &tick_array_upper_loader,
-            protocol_position: &mut ctx.accounts.protocol_position,
-            token_program: ctx.accounts.token_program.clone(),
-        };
-
-        let mut amount_0: u64 = 0;
-        let mut amount_1: u64 = 0;
-
-        if liquidity > 0 {
-            (amount_0, amount_1) = add_liquidity(
-                &mut add_liquidity_context,
-                pool_state,
-                liquidity,
-                amount_0_max,
-                amount_1_max,
-                tick_lower_index,
-                tick_upper_index,
-            )?;
-        }
-
-        let personal_position = &mut ctx.accounts.personal_position;
-        personal_position.bump = *ctx.bumps.get("personal_position").unwrap();
-        personal_position.nft_mint = ctx.accounts.position_nft_mint.key();
-        personal_position.pool_id = ctx.accounts.pool_state.key();
-        personal_position.tick_lower_index = tick_lower_index;
-        personal_position.tick_upper_index = tick_upper_index;
-
-        let updated_protocol_position = add_liquidity_context.protocol_position;
-        personal_position.fee_growth_inside_0_last_x64 =
-            updated_protocol_position.fee_growth_inside_0_last_x64;
-        personal_position.fee_growth_inside_1_last_x64 =
-            updated_protocol_position.fee_growth_inside_1_last_x64;
-
-        // update rewards, must update before update liquidity
-        personal_position.update_rewards(updated_protocol_position.reward_growth_inside, false)?;
-        personal_position.liquidity = liquidity;
-
-        emit!(CreatePersonalPositionEvent {
-            pool_state: ctx.accounts.pool_state.key(),
-            minter: ctx.accounts.payer.key(),
-            nft_owner: ctx.accounts.position_nft_owner.key(),
-            tick_lower_index: tick_lower_index,
-            tick_upper_index: tick_upper_index,
-            liquidity: liquidity,
-            deposit_amount_0: amount_0,
-            deposit_amount_1: amount_1,
-        });
-    }
+    open_personal_position(OpenPersonalPositionParam {
+        payer: &ctx.accounts.payer,
+        position_nft_owner: ctx.accounts.position_nft_owner.key(),
+        position_nft_mint: ctx.accounts.position_nft_mint.key(),
+        pool_state_loader: &ctx.accounts.pool_state,
+        protocol_position: &mut ctx.accounts.protocol_position,
+        protocol_position_bump: *ctx.bumps.get("protocol_position").unwrap(),
+        tick_array_lower: &ctx.accounts.tick_array_lower,
+        tick_array_upper: &ctx.accounts.tick_array_upper,
+        personal_position: &mut ctx.accounts.personal_position,
+        personal_position_bump: *ctx.bumps.get("personal_position").unwrap(),
+        token_account_0: &mut ctx.accounts.token_account_0,
+        token_account_1: &mut ctx.accounts.token_account_1,
+        token_vault_0: &mut ctx.accounts.token_vault_0,
+        token_vault_1: &mut ctx.accounts.token_vault_1,
+        vault_0_mint: &ctx.accounts.vault_0_mint,
+        vault_1_mint: &ctx.accounts.vault_1_mint,
+        token_program_0: &ctx.accounts.token_program_0,
+        token_program_1: &ctx.accounts.token_program_1,
+        system_program: &ctx.accounts.system_program,
+        observation_state: &ctx.accounts.observation_state,
+        remaining_accounts: ctx.remaining_accounts,
+        liquidity,
+        amount_0_max,
+        amount_1_max,
+        tick_lower_index,
+        tick_upper_index,
+        tick_array_lower_start_index,
+        tick_array_upper_start_index,
+    })?;
 
     create_nft_with_metadata(
         &ctx.accounts.payer.to_account_info(),
@@ -320,6 +274,266 @@ pub fn open_position<'a, 'b, 'c, 'info>(
     Ok(())
 }
 
+/// The accounts and arguments of `open_personal_position`
+pub struct OpenPersonalPositionParam<'b, 'info> {
+    /// Pays to mint the position
+    pub payer: &'b Signer<'info>,
+
+    /// The owner of the position NFT
+    pub position_nft_owner: Pubkey,
+
+    /// The mint of the position NFT
+    pub position_nft_mint: Pubkey,
+
+    /// The pool to add liquidity to
+    pub pool_state_loader: &'b AccountLoader<'info, PoolState>,
+
+    /// The protocol position of the range, initialized by the first position
+    pub protocol_position: &'b mut Box<Account<'info, ProtocolPositionState>>,
+
+    /// Bump of the protocol position PDA
+    pub protocol_position_bump: u8,
+
+    /// The tick array of the lower tick, created if needed
+    pub tick_array_lower: &'b UncheckedAccount<'info>,
+
+    /// The tick array of the upper tick, created if needed
+    pub tick_array_upper: &'b UncheckedAccount<'info>,
+
+    /// The personal position of the new position NFT
+    pub personal_position: &'b mut Box<Account<'info, PersonalPositionState>>,
+
+    /// Bump of the personal position PDA
+    pub personal_position_bump: u8,
+
+    /// The token account spending token_0 to mint the position
+    pub token_account_0: &'b mut Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token account spending token_1 to mint the position
+    pub token_account_1: &'b mut Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    pub token_vault_0: &'b mut Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    pub token_vault_1: &'b mut Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token vault 0
+    pub vault_0_mint: &'b InterfaceAccount<'info, Mint>,
+
+    /// The mint of token vault 1
+    pub vault_1_mint: &'b InterfaceAccount<'info, Mint>,
+
+    /// SPL program or token program 2022 of token_0
+    pub token_program_0: &'b Interface<'info, TokenInterface>,
+
+    /// SPL program or token program 2022 of token_1
+    pub token_program_1: &'b Interface<'info, TokenInterface>,
+
+    /// Program to create the tick arrays
+    pub system_program: &'b Program<'info, System>,
+
+    /// The program account for the most recent oracle observation
+    pub observation_state: &'b AccountLoader<'info, ObservationState>,
+
+    /// The remaining accounts of the instruction
+    pub remaining_accounts: &'b [AccountInfo<'info>],
+
+    pub liquidity: u128,
+    pub amount_0_max: u64,
+    pub amount_1_max: u64,
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+    pub tick_array_lower_start_index: i32,
+    pub tick_array_upper_start_index: i32,
+}
+
+/// Add liquidity to the protocol position and initialize the personal position of a new position NFT,
+/// shared by the open position instructions whatever the NFT flavor
+pub fn open_personal_position<'b, 'info>(
+    param: OpenPersonalPositionParam<'b, 'info>,
+) -> Result<()> {
+    let OpenPersonalPositionParam {
+        payer,
+        position_nft_owner,
+        position_nft_mint,
+        pool_state_loader,
+        protocol_position,
+        protocol_position_bump,
+        tick_array_lower,
+        tick_array_upper,
+        personal_position,
+        personal_position_bump,
+        token_account_0,
+        token_account_1,
+        token_vault_0,
+        token_vault_1,
+        vault_0_mint,
+        vault_1_mint,
+        token_program_0,
+        token_program_1,
+        system_program,
+        observation_state,
+        remaining_accounts,
+        liquidity,
+        amount_0_max,
+        amount_1_max,
+        tick_lower_index,
+        tick_upper_index,
+        tick_array_lower_start_index,
+        tick_array_upper_start_index,
+    } = param;
+    let pool_state = &mut pool_state_loader.load_mut()?;
+    pool_state.check_unlocked()?;
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity) {
+        return err!(ErrorCode::NotApproved);
+    }
+    check_ticks_order(tick_lower_index, tick_upper_index)?;
+    check_tick_array_start_index(
+        tick_array_lower_start_index,
+        tick_lower_index,
+        pool_state.tick_spacing,
+    )?;
+    check_tick_array_start_index(
+        tick_array_upper_start_index,
+        tick_upper_index,
+        pool_state.tick_spacing,
+    )?;
+
+    // Why not use anchor's `init-if-needed` to create?
+    // Beacuse `tick_array_lower` and `tick_array_upper` can be the same account, anchor can initialze tick_array_lower but it causes a crash when anchor to initialze the `tick_array_upper`,
+    // the problem is variable scope, tick_array_lower_loader not exit to save the discriminator while build tick_array_upper_loader.
+    let tick_array_lower_loader = TickArrayState::get_or_create_tick_array(
+        payer.to_account_info(),
+        tick_array_lower.to_account_info(),
+        system_program.to_account_info(),
+        pool_state_loader,
+        tick_array_lower_start_index,
+        pool_state.tick_spacing,
+    )?;
+
+    let tick_array_upper_loader = if tick_array_lower_start_index == tick_array_upper_start_index
+    {
+        AccountLoader::<TickArrayState>::try_from(&tick_array_upper.to_account_info())?
+    } else {
+        TickArrayState::get_or_create_tick_array(
+            payer.to_account_info(),
+            tick_array_upper.to_account_info(),
+            system_program.to_account_info(),
+            pool_state_loader,
+            tick_array_upper_start_index,
+            pool_state.tick_spacing,
+        )?
+    };
+
+    // check if protocol position is initilized
+    if protocol_position.pool_id == Pubkey::default() {
+        protocol_position.bump = protocol_position_bump;
+        protocol_position.payer = payer.key();
+        protocol_position.pool_id = pool_state_loader.key();
+        protocol_position.tick_lower_index = tick_lower_index;
+        protocol_position.tick_upper_index = tick_upper_index;
+        {
+            let mut tick_array_lower = tick_array_lower_loader.load_mut()?;
+            tick_array_lower
+                .get_tick_state_mut(tick_lower_index, i32::from(pool_state.tick_spacing))?
+                .tick = tick_lower_index;
+            tick_array_lower.update_protocol_position_count(true)?;
+        }
+        let mut tick_array_upper = tick_array_upper_loader.load_mut()?;
+        tick_array_upper
+            .get_tick_state_mut(tick_upper_index, i32::from(pool_state.tick_spacing))?
+            .tick = tick_upper_index;
+        tick_array_upper.update_protocol_position_count(true)?;
+    }
+    protocol_position.personal_position_count = protocol_position
+        .personal_position_count
+        .checked_add(1)
+        .unwrap();
+
+    // the tick array bitmap extension, if needed, the reward extensions of the pool and the transfer
+    // hook accounts of token_0 and token_1 are passed by remaining accounts
+    let mut remaining_accounts = remaining_accounts;
+    let tick_array_bitmap_extension =
+        get_tick_array_bitmap_extension(&pool_state_loader.key(), &mut remaining_accounts)?;
+    let reward_extensions = get_pool_reward_extensions_with_rent_payer(
+        &pool_state_loader.key(),
+        pool_state.reward_extension_num,
+        &mut remaining_accounts,
+    )?;
+    let transfer_hook_accounts_0 = get_transfer_hook_accounts(vault_0_mint, &mut remaining_accounts)?;
+    let transfer_hook_accounts_1 = get_transfer_hook_accounts(vault_1_mint, &mut remaining_accounts)?;
+    let mut add_liquidity_context = AddLiquidityParam {
+        payer,
+        token_account_0,
+        token_account_1,
+        token_vault_0,
+        token_vault_1,
+        vault_0_mint,
+        vault_1_mint,
+        tick_array_lower: &tick_array_lower_loader,
+        tick_array_upper: &tick_array_upper_loader,
+        tick_array_bitmap_extension,
+        reward_extensions,
+        protocol_position,
+        token_program_0: token_program_0.clone(),
+        token_program_1: token_program_1.clone(),
+        transfer_hook_accounts_0,
+        transfer_hook_accounts_1,
+        observation_state,
+    };
+
+    let mut amount_0: u64 = 0;
+    let mut amount_1: u64 = 0;
+
+    if liquidity > 0 {
+        (amount_0, amount_1) = add_liquidity(
+            &mut add_liquidity_context,
+            pool_state,
+            liquidity,
+            amount_0_max,
+            amount_1_max,
+            tick_lower_index,
+            tick_upper_index,
+        )?;
+    }
+
+    personal_position.bump = personal_position_bump;
+    personal_position.nft_mint = position_nft_mint;
+    personal_position.pool_id = pool_state_loader.key();
+    personal_position.tick_lower_index = tick_lower_index;
+    personal_position.tick_upper_index = tick_upper_index;
+
+    let updated_protocol_position = add_liquidity_context.protocol_position;
+    personal_position.fee_growth_inside_0_last_x64 =
+        updated_protocol_position.fee_growth_inside_0_last_x64;
+    personal_position.fee_growth_inside_1_last_x64 =
+        updated_protocol_position.fee_growth_inside_1_last_x64;
+
+    // update rewards, must update before update liquidity
+    personal_position.update_rewards(updated_protocol_position.reward_growth_inside, false)?;
+    add_liquidity_context.reward_extensions.update_position(
+        personal_position,
+        pool_state,
+        add_liquidity_context.tick_array_lower,
+        add_liquidity_context.tick_array_upper,
+        liquidity,
+    )?;
+    personal_position.liquidity = liquidity;
+
+    emit!(CreatePersonalPositionEvent {
+        pool_state: pool_state_loader.key(),
+        minter: payer.key(),
+        nft_owner: position_nft_owner,
+        tick_lower_index: tick_lower_index,
+        tick_upper_index: tick_upper_index,
+        liquidity: liquidity,
+        deposit_amount_0: amount_0,
+        deposit_amount_1: amount_1,
+    });
+    Ok(())
+}
+
 /// Add liquidity to an initialized pool
 pub fn add_liquidity<'b, 'info>(
     context: &mut AddLiquidityParam<'b, 'info>,
@@ -329,19 +543,86 @@ pub fn add_liquidity<'b, 'info>(
     amount_1_max: u64,
     tick_lower_index: i32,
     tick_upper_index: i32,
+) -> Result<(u64, u64)> {
+    update_observation_before_liquidity_change(
+        &context.observation_state.to_account_info(),
+        pool_state,
+        oracle::block_timestamp(),
+    )?;
+    let (amount_0, amount_1) = mint_liquidity(
+        pool_state,
+        context.tick_array_lower,
+        context.tick_array_upper,
+        context.tick_array_bitmap_extension.as_ref(),
+        &context.reward_extensions,
+        context.protocol_position.as_mut(),
+        liquidity,
+        tick_lower_index,
+        tick_upper_index,
+    )?;
+
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "amount_0:{},amount_1:{},amount_0_max:{},amount_1_max:{}",
+        amount_0,
+        amount_1,
+        amount_0_max,
+        amount_1_max
+    );
+    // the pool must receive amount_0 and amount_1, so the user pays the transfer fee on top
+    let transfer_fee_0 = get_transfer_inverse_fee(context.vault_0_mint, amount_0)?;
+    let transfer_fee_1 = get_transfer_inverse_fee(context.vault_1_mint, amount_1)?;
+    let amount_0_with_fee = amount_0.checked_add(transfer_fee_0).unwrap();
+    let amount_1_with_fee = amount_1.checked_add(transfer_fee_1).unwrap();
+    require!(
+        amount_0_with_fee <= amount_0_max && amount_1_with_fee <= amount_1_max,
+        ErrorCode::PriceSlippageCheck
+    );
+
+    transfer_from_user_to_pool_vault(
+        &context.payer,
+        &context.token_account_0,
+        &context.token_vault_0,
+        context.vault_0_mint,
+        &context.token_program_0,
+        &context.transfer_hook_accounts_0,
+        amount_0_with_fee,
+    )?;
+
+    transfer_from_user_to_pool_vault(
+        &context.payer,
+        &context.token_account_1,
+        &context.token_vault_1,
+        context.vault_1_mint,
+        &context.token_program_1,
+        &context.transfer_hook_accounts_1,
+        amount_1_with_fee,
+    )?;
+    Ok((amount_0, amount_1))
+}
+
+/// Adds liquidity of the protocol position to the pool, returns the amounts the pool must receive
+pub fn mint_liquidity<'info>(
+    pool_state: &mut RefMut<PoolState>,
+    tick_array_lower_loader: &AccountLoader<'info, TickArrayState>,
+    tick_array_upper_loader: &AccountLoader<'info, TickArrayState>,
+    tick_array_bitmap_extension: Option<&AccountLoader<'info, TickArrayBitmapExtension>>,
+    reward_extensions: &PoolRewardExtensions<'info>,
+    protocol_position: &mut ProtocolPositionState,
+    liquidity: u128,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
 ) -> Result<(u64, u64)> {
     assert!(liquidity > 0);
     let liquidity_before = pool_state.liquidity;
-    require_keys_eq!(context.tick_array_lower.load()?.pool_id, pool_state.key());
-    require_keys_eq!(context.tick_array_upper.load()?.pool_id, pool_state.key());
+    require_keys_eq!(tick_array_lower_loader.load()?.pool_id, pool_state.key());
+    require_keys_eq!(tick_array_upper_loader.load()?.pool_id, pool_state.key());
 
     // get tick_state
-    let mut tick_lower_state = *context
-        .tick_array_lower
+    let mut tick_lower_state = *tick_array_lower_loader
         .load_mut()?
         .get_tick_state_mut(tick_lower_index, i32::from(pool_state.tick_spacing))?;
-    let mut tick_upper_state = *context
-        .tick_array_upper
+    let mut tick_upper_state = *tick_array_upper_loader
         .load_mut()?
         .get_tick_state_mut(tick_upper_index, i32::from(pool_state.tick_spacing))?;
     if tick_lower_state.tick == 0 {
@@ -354,40 +635,53 @@ pub fn add_liquidity<'b, 'info>(
     let (amount_0_int, amount_1_int, flip_tick_lower, flip_tick_upper) = modify_position(
         i128::try_from(liquidity).unwrap(),
         pool_state,
-        context.protocol_position.as_mut(),
+        protocol_position,
         &mut tick_lower_state,
         &mut tick_upper_state,
+        reward_extensions,
         clock.unix_timestamp as u64,
     )?;
 
     // update tick_state
-    context.tick_array_lower.load_mut()?.update_tick_state(
+    tick_array_lower_loader.load_mut()?.update_tick_state(
         tick_lower_index,
         i32::from(pool_state.tick_spacing),
         tick_lower_state,
     )?;
-    context.tick_array_upper.load_mut()?.update_tick_state(
+    tick_array_upper_loader.load_mut()?.update_tick_state(
         tick_upper_index,
         i32::from(pool_state.tick_spacing),
         tick_upper_state,
     )?;
 
     if flip_tick_lower {
-        let mut tick_array_lower = context.tick_array_lower.load_mut()?;
+        let mut tick_array_lower = tick_array_lower_loader.load_mut()?;
         let before_init_tick_count = tick_array_lower.initialized_tick_count;
         tick_array_lower.update_initialized_tick_count(true)?;
 
         if before_init_tick_count == 0 {
-            pool_state.flip_tick_array_bit(tick_array_lower.start_tick_index)?;
+            pool_state.flip_tick_array_bit(
+                tick_array_bitmap_extension
+                    .map(|extension| extension.load_mut())
+                    .transpose()?
+                    .as_deref_mut(),
+                tick_array_lower.start_tick_index,
+            )?;
         }
     }
     if flip_tick_upper {
-        let mut tick_array_upper = context.tick_array_upper.load_mut()?;
+        let mut tick_array_upper = tick_array_upper_loader.load_mut()?;
         let before_init_tick_count = tick_array_upper.initialized_tick_count;
         tick_array_upper.update_initialized_tick_count(true)?;
 
         if before_init_tick_count == 0 {
-            pool_state.flip_tick_array_bit(tick_array_upper.start_tick_index)?;
+            pool_state.flip_tick_array_bit(
+                tick_array_bitmap_extension
+                    .map(|extension| extension.load_mut())
+                    .transpose()?
+                    .as_deref_mut(),
+                tick_array_upper.start_tick_index,
+            )?;
         }
     }
     require!(
@@ -395,43 +689,6 @@ pub fn add_liquidity<'b, 'info>(
         ErrorCode::ForbidBothZeroForSupplyLiquidity
     );
 
-    let amount_0 = u64::try_from(amount_0_int).unwrap();
-    let amount_1 = u64::try_from(amount_1_int).unwrap();
-
-    #[cfg(feature = "enable-log")]
-    msg!(
-        "amount_0:{},amount_1:{},amount_0_max:{},amount_1_max:{}",
-        amount_0,
-        amount_1,
-        amount_0_max,
-        amount_1_max
-    );
-    require!(
-        amount_0 <= amount_0_max && amount_1 <= amount_1_max,
-        ErrorCode::PriceSlippageCheck
-    );
-
-    let mut decimals;
-    if pool_state.token_mint_0.eq(&context.token_account_0.mint) { decimals = pool_state.mint_decimals_0 } else { decimals = pool_state.mint_decimals_1 }
-
-    transfer_from_user_to_pool_vault(
-        &context.payer,
-        &context.token_account_0,
-        &context.token_vault_0,
-        &context.token_program,
-        amount_0,
-        decimals,
-    )?;
-
-    if pool_state.token_mint_0.eq(&context.token_account_1.mint) { decimals = pool_state.mint_decimals_1 } else { decimals = pool_state.mint_decimals_0 }
-    transfer_from_user_to_pool_vault(
-        &context.payer,
-        &context.token_account_1,
-        &context.token_vault_1,
-        &context.token_program,
-        amount_1,
-        decimals,
-    )?;
     emit!(LiquidityChangeEvent {
         pool_state: pool_state.key(),
         tick: pool_state.tick_current,
@@ -440,23 +697,29 @@ pub fn add_liquidity<'b, 'info>(
         liquidity_before: liquidity_before,
         liquidity_after: pool_state.liquidity,
     });
+
+    let amount_0 = u64::try_from(amount_0_int).unwrap();
+    let amount_1 = u64::try_from(amount_1_int).unwrap();
     Ok((amount_0, amount_1))
 }
 
-pub fn modify_position(
+pub fn modify_position<'info>(
     liquidity_delta: i128,
     pool_state: &mut RefMut<PoolState>,
     protocol_position_state: &mut ProtocolPositionState,
     tick_lower_state: &mut TickState,
     tick_upper_state: &mut TickState,
+    reward_extensions: &PoolRewardExtensions<'info>,
     timestamp: u64,
 ) -> Result<(i64, i64, bool, bool)> {
+    pool_state.check_unlocked()?;
     let (flip_tick_lower, flip_tick_upper) = update_position(
         liquidity_delta,
         pool_state,
         protocol_position_state,
         tick_lower_state,
         tick_upper_state,
+        reward_extensions,
         timestamp,
     )?;
     let mut amount_0 = 0;
@@ -482,15 +745,19 @@ pub fn modify_position(
 }
 
 /// Updates a position with the given liquidity delta and tick
-pub fn update_position(
+pub fn update_position<'info>(
     liquidity_delta: i128,
     pool_state: &mut RefMut<PoolState>,
     protocol_position_state: &mut ProtocolPositionState,
     tick_lower_state: &mut TickState,
     tick_upper_state: &mut TickState,
+    reward_extensions: &PoolRewardExtensions<'info>,
     timestamp: u64,
 ) -> Result<(bool, bool)> {
     let updated_reward_infos = pool_state.update_reward_infos(timestamp)?;
+    for reward_extension in reward_extensions.iter() {
+        PoolRewardExtension::update_reward_info(reward_extension, timestamp, pool_state.liquidity)?;
+    }
 
     let mut flipped_lower = false;
     let mut flipped_upper = false;
@@ -520,6 +787,16 @@ pub fn update_position(
             identity(tick_upper_state.reward_growths_outside_x64),
             identity(tick_lower_state.reward_growths_outside_x64)
         );
+
+        // the reward growths outside of the reward extensions are set like the rewards of the pool
+        if liquidity_delta > 0 {
+            if flipped_lower {
+                reward_extensions.initialize_tick(pool_state.tick_current, tick_lower_state)?;
+            }
+            if flipped_upper {
+                reward_extensions.initialize_tick(pool_state.tick_current, tick_upper_state)?;
+            }
+        }
     }
 
     // Update fees
@@ -558,7 +835,7 @@ pub fn update_position(
     Ok((flipped_lower, flipped_upper))
 }
 
-const METADATA_URI: &str =
+pub const METADATA_URI: &str =
     "https://cloudflare-ipfs.com/ipfs/QmbzJafuKY3B4t25eq9zdKZMgXiMeW4jHLzf6KE6ZmHWn1/02.json";
 
 fn create_nft_with_metadata<'info>(
@@ -649,6 +926,7 @@ mod modify_position_test {
     use crate::libraries::tick_math;
     use crate::states::oracle::block_timestamp_mock;
     use crate::states::pool_test::build_pool;
+    use crate::states::PoolRewardExtensions;
     use crate::states::protocol_position::*;
     use crate::states::tick_array_test::build_tick;
 
@@ -667,6 +945,7 @@ mod modify_position_test {
             &mut ProtocolPositionState::default(),
             tick_lower_state,
             tick_upper_state,
+            &PoolRewardExtensions::default(),
             block_timestamp_mock(),
         );
         assert!(result.is_err());
@@ -698,6 +977,7 @@ mod modify_position_test {
             protocol_position,
             tick_lower_state,
             tick_upper_state,
+            &PoolRewardExtensions::default(),
             block_timestamp_mock(),
         )
             .unwrap();
@@ -765,6 +1045,7 @@ mod modify_position_test {
             protocol_position,
             tick_lower_state,
             tick_upper_state,
+            &PoolRewardExtensions::default(),
             block_timestamp_mock(),
         )
             .unwrap();
@@ -830,6 +1111,7 @@ mod modify_position_test {
             protocol_position,
             tick_lower_state,
             tick_upper_state,
+            &PoolRewardExtensions::default(),
             block_timestamp_mock(),
         )
             .unwrap();
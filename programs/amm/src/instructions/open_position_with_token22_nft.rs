@@ -0,0 +1,262 @@
+use anchor_spl::associated_token::{self, AssociatedToken};
+use anchor_spl::token_2022::spl_token_2022::instruction::AuthorityType;
+use anchor_spl::token_interface::{self, Mint, Token2022, TokenAccount, TokenInterface};
+
+use anchor_lang::prelude::*;
+
+use crate::states::*;
+use crate::util::*;
+
+use super::{open_personal_position, OpenPersonalPositionParam, METADATA_URI};
+
+#[derive(Accounts)]
+#[instruction(tick_lower_index: i32, tick_upper_index: i32, tick_array_lower_start_index: i32, tick_array_upper_start_index: i32)]
+pub struct OpenPositionWithToken22Nft<'info> {
+    /// Pays to mint the position
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Receives the position NFT
+    pub position_nft_owner: UncheckedAccount<'info>,
+
+    /// Unique token mint address, initialized with the metadata pointer and token metadata extensions
+    #[account(mut)]
+    pub position_nft_mint: Signer<'info>,
+
+    /// CHECK: Token account where position NFT will be minted, the associated token program checks the address
+    #[account(mut)]
+    pub position_nft_account: UncheckedAccount<'info>,
+
+    /// Add liquidity for this pool
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Store the information of market marking in range
+    #[account(
+    init_if_needed,
+    seeds = [
+    POSITION_SEED.as_bytes(),
+    pool_state.key().as_ref(),
+    & tick_lower_index.to_be_bytes(),
+    & tick_upper_index.to_be_bytes(),
+    ],
+    bump,
+    payer = payer,
+    space = ProtocolPositionState::LEN
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// CHECK: Account to mark the lower tick as initialized
+    #[account(
+    mut,
+    seeds = [
+    TICK_ARRAY_SEED.as_bytes(),
+    pool_state.key().as_ref(),
+    & tick_array_lower_start_index.to_be_bytes(),
+    ],
+    bump,
+    )]
+    pub tick_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK:Account to store data for the position's upper tick
+    #[account(
+    mut,
+    seeds = [
+    TICK_ARRAY_SEED.as_bytes(),
+    pool_state.key().as_ref(),
+    & tick_array_upper_start_index.to_be_bytes(),
+    ],
+    bump,
+    )]
+    pub tick_array_upper: UncheckedAccount<'info>,
+
+    /// personal position state
+    #[account(
+    init,
+    seeds = [POSITION_SEED.as_bytes(), position_nft_mint.key().as_ref()],
+    bump,
+    payer = payer,
+    space = PersonalPositionState::LEN
+    )]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// The token_0 account deposit token to the pool
+    #[account(
+    mut,
+    token::mint = token_vault_0.mint
+    )]
+    pub token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token_1 account deposit token to the pool
+    #[account(
+    mut,
+    token::mint = token_vault_1.mint
+    )]
+    pub token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+    mut,
+    constraint = token_vault_0.key() == pool_state.load() ?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+    mut,
+    constraint = token_vault_1.key() == pool_state.load() ?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token vault 0
+    #[account(address = token_vault_0.mint)]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(address = token_vault_1.mint)]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Program to create the position manager state account
+    pub system_program: Program<'info, System>,
+
+    /// Program to create the position NFT mint with extensions and mint it
+    pub token_program: Program<'info, Token2022>,
+
+    /// SPL program or token program 2022 of token_0
+    pub token_program_0: Interface<'info, TokenInterface>,
+
+    /// SPL program or token program 2022 of token_1
+    pub token_program_1: Interface<'info, TokenInterface>,
+
+    /// Program to create an ATA for receiving position NFT
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load() ?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+}
+
+pub fn open_position_with_token22_nft<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, OpenPositionWithToken22Nft<'info>>,
+    liquidity: u128,
+    amount_0_max: u64,
+    amount_1_max: u64,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    tick_array_lower_start_index: i32,
+    tick_array_upper_start_index: i32,
+) -> Result<()> {
+    open_personal_position(OpenPersonalPositionParam {
+        payer: &ctx.accounts.payer,
+        position_nft_owner: ctx.accounts.position_nft_owner.key(),
+        position_nft_mint: ctx.accounts.position_nft_mint.key(),
+        pool_state_loader: &ctx.accounts.pool_state,
+        protocol_position: &mut ctx.accounts.protocol_position,
+        protocol_position_bump: *ctx.bumps.get("protocol_position").unwrap(),
+        tick_array_lower: &ctx.accounts.tick_array_lower,
+        tick_array_upper: &ctx.accounts.tick_array_upper,
+        personal_position: &mut ctx.accounts.personal_position,
+        personal_position_bump: *ctx.bumps.get("personal_position").unwrap(),
+        token_account_0: &mut ctx.accounts.token_account_0,
+        token_account_1: &mut ctx.accounts.token_account_1,
+        token_vault_0: &mut ctx.accounts.token_vault_0,
+        token_vault_1: &mut ctx.accounts.token_vault_1,
+        vault_0_mint: &ctx.accounts.vault_0_mint,
+        vault_1_mint: &ctx.accounts.vault_1_mint,
+        token_program_0: &ctx.accounts.token_program_0,
+        token_program_1: &ctx.accounts.token_program_1,
+        system_program: &ctx.accounts.system_program,
+        observation_state: &ctx.accounts.observation_state,
+        remaining_accounts: ctx.remaining_accounts,
+        liquidity,
+        amount_0_max,
+        amount_1_max,
+        tick_lower_index,
+        tick_upper_index,
+        tick_array_lower_start_index,
+        tick_array_upper_start_index,
+    })?;
+
+    create_nft_with_token22_metadata(ctx.accounts, tick_lower_index, tick_upper_index)
+}
+
+/// Create the position NFT mint with the metadata stored in the mint itself, mint the NFT
+/// to the owner and disable minting. The personal position is the close authority of the mint,
+/// so that `close_position` can close the mint as well.
+fn create_nft_with_token22_metadata<'info>(
+    accounts: &OpenPositionWithToken22Nft<'info>,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+) -> Result<()> {
+    let payer = &accounts.payer;
+    let position_nft_owner = &accounts.position_nft_owner.to_account_info();
+    let pool_state_loader = &accounts.pool_state;
+    let personal_position = &accounts.personal_position.key();
+    let position_nft_mint = &accounts.position_nft_mint.to_account_info();
+    let position_nft_account = &accounts.position_nft_account.to_account_info();
+    let system_program = &accounts.system_program;
+    let token_program = &accounts.token_program;
+    let associated_token_program = &accounts.associated_token_program;
+    let pool_state = pool_state_loader.load()?;
+    let seeds = [
+        POOL_SEED.as_bytes(),
+        pool_state.amm_config.as_ref(),
+        pool_state.token_mint_0.as_ref(),
+        pool_state.token_mint_1.as_ref(),
+        &[pool_state.bump] as &[u8],
+    ];
+    create_mint_with_metadata(
+        payer,
+        position_nft_mint,
+        &pool_state_loader.to_account_info(),
+        personal_position,
+        system_program,
+        token_program,
+        String::from("Raydium Concentrated Liquidity"),
+        String::from("RCL"),
+        format!(
+            "{}?pool={}&tick_lower={}&tick_upper={}",
+            METADATA_URI,
+            pool_state_loader.key(),
+            tick_lower_index,
+            tick_upper_index
+        ),
+        &[&seeds[..]],
+    )?;
+    associated_token::create(CpiContext::new(
+        associated_token_program.to_account_info(),
+        associated_token::Create {
+            payer: payer.to_account_info(),
+            associated_token: position_nft_account.clone(),
+            authority: position_nft_owner.clone(),
+            mint: position_nft_mint.clone(),
+            system_program: system_program.to_account_info(),
+            token_program: token_program.to_account_info(),
+        },
+    ))?;
+    // Mint the NFT
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            token_interface::MintTo {
+                mint: position_nft_mint.clone(),
+                to: position_nft_account.clone(),
+                authority: pool_state_loader.to_account_info(),
+            },
+            &[&seeds[..]],
+        ),
+        1,
+    )?;
+    // Disable minting
+    token_interface::set_authority(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            token_interface::SetAuthority {
+                current_authority: pool_state_loader.to_account_info(),
+                account_or_mint: position_nft_mint.clone(),
+            },
+            &[&seeds[..]],
+        ),
+        AuthorityType::MintTokens,
+        None,
+    )
+}
@@ -0,0 +1,47 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ProposeRewardAuthority<'info> {
+    /// The authority of the reward or the admin
+    pub authority: Signer<'info>,
+
+    /// The pool of the reward
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+/// Proposes the new authority of the reward, which takes over the reward once it accepts
+pub fn propose_reward_authority(
+    ctx: Context<ProposeRewardAuthority>,
+    reward_index: u8,
+    new_authority: Pubkey,
+) -> Result<()> {
+    assert!((reward_index as usize) < REWARD_NUM);
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.check_unlocked()?;
+    check_reward_authority(&pool_state, reward_index, &ctx.accounts.authority.key())?;
+    pool_state.propose_reward_authority(reward_index as usize, new_authority)?;
+
+    emit!(ProposeRewardAuthorityEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        reward_index,
+        pending_authority: new_authority,
+    });
+    Ok(())
+}
+
+/// The authority of a reward or the admin manage the proposed authority of the reward
+pub fn check_reward_authority(
+    pool_state: &PoolState,
+    reward_index: u8,
+    signer: &Pubkey,
+) -> Result<()> {
+    require!(
+        *signer == pool_state.reward_infos[reward_index as usize].authority
+            || *signer == crate::admin::id(),
+        ErrorCode::NotApproved
+    );
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct SetCompoundKeeper<'info> {
+    /// The position owner
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the tokenized position
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Approve or revoke the compound keeper of this position
+    #[account(mut)]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+}
+
+pub fn set_compound_keeper(ctx: Context<SetCompoundKeeper>, compound_keeper: Pubkey) -> Result<()> {
+    let nft_owner = ctx.accounts.nft_owner.key();
+    let personal_position = &mut ctx.accounts.personal_position;
+    personal_position.set_compound_keeper(&nft_owner, &compound_keeper);
+
+    emit!(SetCompoundKeeperEvent {
+        position_nft_mint: personal_position.nft_mint,
+        nft_owner,
+        compound_keeper,
+    });
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct SetRangeOrder<'info> {
+    /// The position owner or delegated authority
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the tokenized position
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Place or cancel the range order of this position
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// The pool of the position
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn set_range_order(ctx: Context<SetRangeOrder>, range_order_type: u8) -> Result<()> {
+    require_gte!(
+        RangeOrderType::SellToken1 as u8,
+        range_order_type,
+        ErrorCode::InvalidRangeOrderType
+    );
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let personal_position = &mut ctx.accounts.personal_position;
+    personal_position.range_order_type = range_order_type;
+    personal_position
+        .check_range_order_placement(pool_state.tick_current, pool_state.tick_spacing)?;
+    personal_position.range_order_open_time = if range_order_type == RangeOrderType::None as u8 {
+        0
+    } else {
+        Clock::get()?.unix_timestamp as u64
+    };
+
+    emit!(SetRangeOrderEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        position_nft_mint: personal_position.nft_mint,
+        range_order_type,
+        tick_lower_index: personal_position.tick_lower_index,
+        tick_upper_index: personal_position.tick_upper_index,
+        liquidity: personal_position.liquidity,
+    });
+    Ok(())
+}
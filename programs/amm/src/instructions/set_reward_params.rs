@@ -2,9 +2,11 @@ use crate::error::ErrorCode;
 use crate::libraries::{fixed_point_64, full_math::MulDiv, U256};
 use crate::states::pool::{reward_period_limit, PoolState, REWARD_NUM};
 use crate::states::*;
-use crate::util::transfer_from_user_to_pool_vault;
+use crate::util::{
+    get_transfer_hook_accounts, get_transfer_inverse_fee, transfer_from_user_to_pool_vault,
+};
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{TokenAccount, Token2022};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 pub struct SetRewardParams<'info> {
@@ -48,6 +50,7 @@ pub fn set_reward_params<'a, 'b, 'c, 'info>(
     let current_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
 
     let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.check_unlocked()?;
 
     if !admin_operator {
         require_keys_eq!(ctx.accounts.authority.key(), pool_state.owner);
@@ -63,6 +66,11 @@ pub fn set_reward_params<'a, 'b, 'c, 'info>(
     if current_timestamp <= reward_info.open_time {
         return err!(ErrorCode::NotApproved);
     }
+    // the emissions of the pending phases are already deposited at their own rates
+    require!(
+        !pool_state.reward_phases[reward_index as usize][0].is_scheduled(),
+        ErrorCode::RewardPhasesPending
+    );
     let reward_amount = if admin_operator {
         admin_update(
             &mut reward_info,
@@ -92,25 +100,29 @@ pub fn set_reward_params<'a, 'b, 'c, 'info>(
             InterfaceAccount::<TokenAccount>::try_from(&remaining_accounts.next().unwrap())?;
         let authority_token_account =
             InterfaceAccount::<TokenAccount>::try_from(&remaining_accounts.next().unwrap())?;
-        let token_program = Program::<Token2022>::try_from(remaining_accounts.next().unwrap())?;
+        let token_program =
+            Interface::<TokenInterface>::try_from(remaining_accounts.next().unwrap())?;
+        let reward_vault_mint =
+            InterfaceAccount::<Mint>::try_from(remaining_accounts.next().unwrap())?;
+        // the transfer hook accounts of the reward mint follow the fixed accounts
+        let mut remaining_accounts = remaining_accounts.as_slice();
+        let transfer_hook_accounts =
+            get_transfer_hook_accounts(&reward_vault_mint, &mut remaining_accounts)?;
 
         require_keys_eq!(reward_token_vault.mint, authority_token_account.mint);
+        require_keys_eq!(reward_token_vault.mint, reward_vault_mint.key());
         require_keys_eq!(reward_token_vault.key(), reward_info.token_vault);
 
-        let decimals;
-        if pool_state.token_mint_0.eq(&authority_token_account.mint) {
-            decimals = pool_state.mint_decimals_0
-        } else {
-            decimals = pool_state.mint_decimals_1
-        }
-
+        // the vault must receive the full reward amount, so the authority pays the transfer fee on top
+        let transfer_fee = get_transfer_inverse_fee(&reward_vault_mint, reward_amount)?;
         transfer_from_user_to_pool_vault(
             &ctx.accounts.authority,
             &authority_token_account,
             &reward_token_vault,
+            &reward_vault_mint,
             &token_program,
-            reward_amount,
-            decimals
+            &transfer_hook_accounts,
+            reward_amount.checked_add(transfer_fee).unwrap(),
         )?;
     }
 
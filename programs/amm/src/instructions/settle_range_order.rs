@@ -0,0 +1,109 @@
+use super::burn_personal_position_liquidity;
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SettleRangeOrder<'info> {
+    /// The filled range order to settle, anyone can settle it
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Stores init state for the lower tick
+    #[account(mut, constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick
+    #[account(mut, constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+}
+
+pub fn settle_range_order<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SettleRangeOrder<'info>>,
+) -> Result<()> {
+    let personal_position = &mut ctx.accounts.personal_position;
+    let range_order_type = personal_position.range_order_type;
+    require!(
+        personal_position.get_range_order_type() != RangeOrderType::None,
+        ErrorCode::NotRangeOrder
+    );
+
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.check_unlocked()?;
+    require!(
+        pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity),
+        ErrorCode::NotApproved
+    );
+    require!(
+        personal_position.is_range_order_filled(pool_state.tick_current),
+        ErrorCode::RangeOrderNotFilled
+    );
+
+    // remove all the liquidity from the pool, so the filled side doesn't flip back
+    // when the price returns into the range
+    let liquidity = personal_position.liquidity;
+    // the tick array bitmap extension, if needed, and the reward extensions of the pool are passed
+    // by remaining accounts
+    let mut remaining_accounts = ctx.remaining_accounts;
+    let tick_array_bitmap_extension =
+        get_tick_array_bitmap_extension(&ctx.accounts.pool_state.key(), &mut remaining_accounts)?;
+    let reward_extensions = get_pool_reward_extensions_with_rent_payer(
+        &ctx.accounts.pool_state.key(),
+        pool_state.reward_extension_num,
+        &mut remaining_accounts,
+    )?;
+    update_observation_before_liquidity_change(
+        &ctx.accounts.observation_state.to_account_info(),
+        &mut pool_state,
+        oracle::block_timestamp(),
+    )?;
+    let (amount_0, amount_1) = burn_personal_position_liquidity(
+        &mut pool_state,
+        &mut ctx.accounts.protocol_position,
+        personal_position,
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+        tick_array_bitmap_extension.as_ref(),
+        &reward_extensions,
+        liquidity,
+    )?;
+    personal_position.range_order_amount_0 = personal_position
+        .range_order_amount_0
+        .checked_add(amount_0)
+        .unwrap();
+    personal_position.range_order_amount_1 = personal_position
+        .range_order_amount_1
+        .checked_add(amount_1)
+        .unwrap();
+    personal_position.range_order_type = RangeOrderType::None as u8;
+    personal_position.range_order_open_time = 0;
+
+    emit!(SettleRangeOrderEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        position_nft_mint: personal_position.nft_mint,
+        range_order_type,
+        liquidity,
+        amount_0,
+        amount_1,
+    });
+    Ok(())
+}
@@ -5,15 +5,15 @@ use std::convert::identity;
 use std::ops::Neg;
 
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::TokenAccount;
-use anchor_spl::token_interface::Token2022;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use anchor_spl::token_interface::TokenInterface;
 
 use crate::error::ErrorCode;
 use crate::libraries::{
     big_num::{U1024, U128},
     fixed_point_64,
     full_math::MulDiv,
-    liquidity_math, swap_math, tick_array_bit_map, tick_math,
+    liquidity_math, tick_array_bit_map, tick_math,
 };
 use crate::states::*;
 use crate::util::*;
@@ -47,12 +47,23 @@ pub struct SwapSingle<'info> {
     #[account(mut)]
     pub output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    /// The mint of input token vault
+    #[account(address = input_vault.mint)]
+    pub input_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of output token vault
+    #[account(address = output_vault.mint)]
+    pub output_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
     /// The program account for the most recent oracle observation
     #[account(mut, address = pool_state.load() ?.observation_key)]
     pub observation_state: AccountLoader<'info, ObservationState>,
 
-    /// SPL program for token transfers
-    pub token_program: Program<'info, Token2022>,
+    /// SPL program or token program 2022 for input token transfers
+    pub input_token_program: Interface<'info, TokenInterface>,
+
+    /// SPL program or token program 2022 for output token transfers
+    pub output_token_program: Interface<'info, TokenInterface>,
 
     #[account(mut, constraint = tick_array.load() ?.pool_id == pool_state.key())]
     pub tick_array: AccountLoader<'info, TickArrayState>,
@@ -74,8 +85,24 @@ pub struct SwapAccounts<'b, 'info> {
     /// The vault token account for output token
     pub output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// SPL program for token transfers
-    pub token_program: Program<'info, Token2022>,
+    /// The mint of input token vault
+    pub input_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of output token vault
+    pub output_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// SPL program or token program 2022 for input token transfers
+    pub input_token_program: Interface<'info, TokenInterface>,
+
+    /// SPL program or token program 2022 for output token transfers
+    pub output_token_program: Interface<'info, TokenInterface>,
+
+    /// The accounts required by the transfer hook of input token
+    pub input_transfer_hook_accounts: Vec<AccountInfo<'info>>,
+
+    /// The accounts required by the transfer hook of output token
+    pub output_transfer_hook_accounts: Vec<AccountInfo<'info>>,
+
     /// The factory state to read protocol fees
     pub amm_config: &'b Box<Account<'info, AmmConfig>>,
 
@@ -134,7 +161,10 @@ pub fn swap_internal<'b, 'info>(
     amm_config: &AmmConfig,
     pool_state: &mut RefMut<PoolState>,
     tick_array_states: &mut VecDeque<RefMut<TickArrayState>>,
+    tick_array_bitmap_extension: Option<&TickArrayBitmapExtension>,
+    reward_extensions: &PoolRewardExtensions<'info>,
     observation_state: &mut RefMut<ObservationState>,
+    observation_extension: &mut [Observation],
     amount_specified: u64,
     sqrt_price_limit_x64: u128,
     zero_for_one: bool,
@@ -145,6 +175,7 @@ pub fn swap_internal<'b, 'info>(
     if !pool_state.get_status_by_bit(PoolStatusBitIndex::Swap) {
         return err!(ErrorCode::NotApproved);
     }
+    pool_state.check_unlocked()?;
     require!(
         if zero_for_one {
             sqrt_price_limit_x64 < pool_state.sqrt_price_x64
@@ -159,6 +190,22 @@ pub fn swap_internal<'b, 'info>(
     let liquidity_start = pool_state.liquidity;
 
     let updated_reward_infos = pool_state.update_reward_infos(block_timestamp as u64)?;
+    for reward_extension in reward_extensions.iter() {
+        PoolRewardExtension::update_reward_info(
+            reward_extension,
+            block_timestamp as u64,
+            liquidity_start,
+        )?;
+    }
+
+    let trade_fee_rate = pool_state.get_trade_fee_rate(amm_config.trade_fee_rate);
+    let dynamic_fee_enabled = pool_state.dynamic_fee_info.is_enabled();
+    if dynamic_fee_enabled {
+        let tick_current = pool_state.tick_current;
+        pool_state
+            .dynamic_fee_info
+            .update_references(tick_current, block_timestamp as u64);
+    }
 
     let mut state = SwapState {
         amount_specified_remaining: amount_specified,
@@ -180,7 +227,7 @@ pub fn swap_internal<'b, 'info>(
     require_keys_eq!(observation_state.pool_id, pool_state.key());
 
     let (mut is_match_pool_current_tick_array, first_vaild_tick_array_start_index) =
-        pool_state.get_first_initialized_tick_array(zero_for_one)?;
+        pool_state.get_first_initialized_tick_array(tick_array_bitmap_extension, zero_for_one)?;
     let mut current_vaild_tick_array_start_index = first_vaild_tick_array_start_index;
 
     let expected_first_tick_array_address = Pubkey::find_program_address(
@@ -255,10 +302,11 @@ pub fn swap_internal<'b, 'info>(
             let next_initialized_tickarray_index =
                 tick_array_bit_map::next_initialized_tick_array_start_index(
                     U1024(pool_state.tick_array_bitmap),
+                    tick_array_bitmap_extension,
                     current_vaild_tick_array_start_index,
                     pool_state.tick_spacing.into(),
                     zero_for_one,
-                );
+                )?;
             if next_initialized_tickarray_index.is_none() {
                 return err!(ErrorCode::LiquidityInsufficient);
             }
@@ -311,15 +359,15 @@ pub fn swap_internal<'b, 'info>(
             require_gte!(step.sqrt_price_next_x64, step.sqrt_price_start_x64);
             require_gte!(target_price, step.sqrt_price_start_x64);
         }
-        let swap_step = swap_math::compute_swap_step(
+        let swap_step = pool_state.dynamic_fee_info.compute_swap_step(
+            trade_fee_rate,
             step.sqrt_price_start_x64,
             target_price,
             state.liquidity,
             state.amount_specified_remaining,
-            amm_config.trade_fee_rate,
             is_base_input,
             zero_for_one,
-        );
+        )?;
         state.sqrt_price_x64 = swap_step.sqrt_price_next_x64;
         step.amount_in = swap_step.amount_in;
         step.amount_out = swap_step.amount_out;
@@ -408,6 +456,7 @@ pub fn swap_internal<'b, 'info>(
                     },
                     &updated_reward_infos,
                 );
+                reward_extensions.cross_tick(&mut next_initialized_tick)?;
                 // update tick_state to tick_array account
                 tick_array_current.update_tick_state(
                     next_initialized_tick.tick,
@@ -461,23 +510,37 @@ pub fn swap_internal<'b, 'info>(
             zero_for_one,
         });
     }
-    // update tick
-    if state.tick != pool_state.tick_current {
-        pool_state.tick_current = state.tick;
-    }
-    // update the previous price to the observation
-    let next_observation_index = observation_state
-        .update_check(
+    // update the previous price, tick and liquidity to the observation, always if the swap crossed
+    // ticks changing the active liquidity
+    let next_observation_index = if liquidity_start != state.liquidity {
+        observation_state.update_before_liquidity_change(
+            observation_extension,
             block_timestamp,
             pool_state.sqrt_price_x64,
+            pool_state.tick_current,
+            pool_state.liquidity,
+            pool_state.observation_index,
+        )
+    } else {
+        observation_state.update_check(
+            observation_extension,
+            block_timestamp,
+            pool_state.sqrt_price_x64,
+            pool_state.tick_current,
+            pool_state.liquidity,
             pool_state.observation_index,
             pool_state.observation_update_duration.into(),
         )
-        .unwrap();
+    }
+    .unwrap();
     match next_observation_index {
         Option::Some(index) => pool_state.observation_index = index,
         Option::None => {}
     }
+    // update tick
+    if state.tick != pool_state.tick_current {
+        pool_state.tick_current = state.tick;
+    }
     pool_state.sqrt_price_x64 = state.sqrt_price_x64;
 
     if liquidity_start != state.liquidity {
@@ -560,7 +623,8 @@ pub fn swap_internal<'b, 'info>(
 }
 
 /// Performs a single exact input/output swap
-/// if is_base_input = true, return vaule is the max_amount_out, otherwise is min_amount_in
+/// if is_base_input = true, return vaule is the max_amount_out received by the user after transfer fee,
+/// otherwise is min_amount_in paid by the user including transfer fee
 pub fn exact_internal<'b, 'info>(
     ctx: &mut SwapAccounts<'b, 'info>,
     remaining_accounts: &[AccountInfo<'info>],
@@ -568,19 +632,53 @@ pub fn exact_internal<'b, 'info>(
     sqrt_price_limit_x64: u128,
     is_base_input: bool,
 ) -> Result<u64> {
+    let swap_amounts = exact_internal_swap(
+        ctx,
+        remaining_accounts,
+        amount_specified,
+        sqrt_price_limit_x64,
+        is_base_input,
+    )?;
+    exact_internal_settle(ctx, &swap_amounts, is_base_input)
+}
+
+/// The token amounts of a swap computed by the pool, excluding the transfer fees
+pub struct SwapAmounts {
+    pub amount_0: u64,
+    pub amount_1: u64,
+    pub zero_for_one: bool,
+    /// The pool price before the swap
+    pub sqrt_price_x64_before: u128,
+}
+
+/// Swap the pool, tick arrays and observation states without transferring any token.
+/// The swap must be settled with `exact_internal_settle` in the same instruction.
+pub fn exact_internal_swap<'b, 'info>(
+    ctx: &mut SwapAccounts<'b, 'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    amount_specified: u64,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+) -> Result<SwapAmounts> {
     let block_timestamp = Clock::get()?.unix_timestamp as u64;
 
     let amount_0;
     let amount_1;
     let zero_for_one;
     let swap_price_before;
-    let pool_state= &mut ctx.pool_state.load_mut()?;
 
-    let input_balance_before = ctx.input_vault.amount;
-    let output_balance_before = ctx.output_vault.amount;
+    // the amount actually swapped by the pool excludes the transfer fee of the token
+    let amount_specified = if is_base_input {
+        let transfer_fee = get_transfer_fee(&ctx.input_vault_mint, amount_specified)?;
+        amount_specified.checked_sub(transfer_fee).unwrap()
+    } else {
+        let transfer_fee = get_transfer_inverse_fee(&ctx.output_vault_mint, amount_specified)?;
+        amount_specified.checked_add(transfer_fee).unwrap()
+    };
 
     {
-        swap_price_before = ctx.pool_state.load()?.sqrt_price_x64;
+        let pool_state = &mut ctx.pool_state.load_mut()?;
+        swap_price_before = pool_state.sqrt_price_x64;
         zero_for_one = ctx.input_vault.mint == pool_state.token_mint_0;
 
         require_gt!(block_timestamp, pool_state.open_time);
@@ -596,17 +694,37 @@ pub fn exact_internal<'b, 'info>(
             ErrorCode::InvalidInputPoolVault
         );
 
+        // the tick array bitmap extension, if needed, and the reward extensions of the pool come
+        // before the tick arrays
+        let mut remaining_accounts = remaining_accounts;
+        let tick_array_bitmap_extension =
+            get_tick_array_bitmap_extension(&ctx.pool_state.key(), &mut remaining_accounts)?;
+        let reward_extensions = get_pool_reward_extensions(
+            &ctx.pool_state.key(),
+            pool_state.reward_extension_num,
+            &mut remaining_accounts,
+        )?;
+        let tick_array_bitmap_extension = tick_array_bitmap_extension
+            .as_ref()
+            .map(|extension| extension.load())
+            .transpose()?;
         let tick_array_states = &mut VecDeque::new();
         tick_array_states.push_back(ctx.tick_array_state.load_mut()?);
         for tick_array_info in remaining_accounts {
             tick_array_states.push_back(TickArrayState::load_mut(tick_array_info)?);
         }
 
+        let observation_info = ctx.observation_state.to_account_info();
+        let (mut observation_state, mut observation_extension) =
+            ObservationState::load_mut(&observation_info)?;
         (amount_0, amount_1) = swap_internal(
             &ctx.amm_config,
             pool_state,
             tick_array_states,
-            &mut ctx.observation_state.load_mut()?,
+            tick_array_bitmap_extension.as_deref(),
+            &reward_extensions,
+            &mut observation_state,
+            &mut observation_extension,
             amount_specified,
             if sqrt_price_limit_x64 == 0 {
                 if zero_for_one {
@@ -635,12 +753,57 @@ pub fn exact_internal<'b, 'info>(
         );
     }
 
-    let (token_account_0, token_account_1, vault_0, vault_1) = if zero_for_one {
+    Ok(SwapAmounts {
+        amount_0,
+        amount_1,
+        zero_for_one,
+        sqrt_price_x64_before: swap_price_before,
+    })
+}
+
+/// Transfer the tokens of a swap computed by `exact_internal_swap` between the user and the pool vaults,
+/// see `exact_internal` for the return value
+pub fn exact_internal_settle<'b, 'info>(
+    ctx: &mut SwapAccounts<'b, 'info>,
+    swap_amounts: &SwapAmounts,
+    is_base_input: bool,
+) -> Result<u64> {
+    let SwapAmounts {
+        amount_0,
+        amount_1,
+        zero_for_one,
+        sqrt_price_x64_before: swap_price_before,
+    } = *swap_amounts;
+
+    // a route may settle several swaps on the same vault
+    ctx.input_vault.reload()?;
+    ctx.output_vault.reload()?;
+    let input_balance_before = ctx.input_vault.amount;
+    let output_balance_before = ctx.output_vault.amount;
+
+    let (
+        token_account_0,
+        token_account_1,
+        vault_0,
+        vault_1,
+        vault_0_mint,
+        vault_1_mint,
+        token_program_0,
+        token_program_1,
+        transfer_hook_accounts_0,
+        transfer_hook_accounts_1,
+    ) = if zero_for_one {
         (
             ctx.input_token_account.clone(),
             ctx.output_token_account.clone(),
             ctx.input_vault.clone(),
             ctx.output_vault.clone(),
+            ctx.input_vault_mint.clone(),
+            ctx.output_vault_mint.clone(),
+            ctx.input_token_program.clone(),
+            ctx.output_token_program.clone(),
+            ctx.input_transfer_hook_accounts.clone(),
+            ctx.output_transfer_hook_accounts.clone(),
         )
     } else {
         (
@@ -648,21 +811,38 @@ pub fn exact_internal<'b, 'info>(
             ctx.input_token_account.clone(),
             ctx.output_vault.clone(),
             ctx.input_vault.clone(),
+            ctx.output_vault_mint.clone(),
+            ctx.input_vault_mint.clone(),
+            ctx.output_token_program.clone(),
+            ctx.input_token_program.clone(),
+            ctx.output_transfer_hook_accounts.clone(),
+            ctx.input_transfer_hook_accounts.clone(),
         )
     };
 
-    if zero_for_one {
-        let decimals;
-        if token_account_0.mint.eq(&pool_state.token_mint_0) { decimals = pool_state.mint_decimals_0 } else { decimals = pool_state.mint_decimals_1 }
+    // the user pays the transfer fee on the input side and receives the output less the transfer fee
+    let (transfer_fee_0, transfer_fee_1) = if zero_for_one {
+        (
+            get_transfer_inverse_fee(&vault_0_mint, amount_0)?,
+            get_transfer_fee(&vault_1_mint, amount_1)?,
+        )
+    } else {
+        (
+            get_transfer_fee(&vault_0_mint, amount_0)?,
+            get_transfer_inverse_fee(&vault_1_mint, amount_1)?,
+        )
+    };
 
+    if zero_for_one {
         //  x -> y, deposit x token from user to pool vault.
         transfer_from_user_to_pool_vault(
             &ctx.signer,
             &token_account_0,
             &vault_0,
-            &ctx.token_program,
-            amount_0,
-            decimals,
+            &vault_0_mint,
+            &token_program_0,
+            &transfer_hook_accounts_0,
+            amount_0.checked_add(transfer_fee_0).unwrap(),
         )?;
         if vault_1.amount <= amount_1 {
             // freeze pool, disable all instructions
@@ -674,20 +854,20 @@ pub fn exact_internal<'b, 'info>(
             &ctx.pool_state,
             &vault_1,
             &token_account_1,
-            &ctx.token_program,
+            &vault_1_mint,
+            &token_program_1,
+            &transfer_hook_accounts_1,
             amount_1,
         )?;
     } else {
-        let decimals;
-        if token_account_1.mint.eq(&pool_state.token_mint_0) { decimals = pool_state.mint_decimals_0 } else { decimals = pool_state.mint_decimals_1 }
-
         transfer_from_user_to_pool_vault(
             &ctx.signer,
             &token_account_1,
             &vault_1,
-            &ctx.token_program,
-            amount_1,
-            decimals,
+            &vault_1_mint,
+            &token_program_1,
+            &transfer_hook_accounts_1,
+            amount_1.checked_add(transfer_fee_1).unwrap(),
         )?;
         if vault_0.amount <= amount_0 {
             // freeze pool, disable all instructions
@@ -697,7 +877,9 @@ pub fn exact_internal<'b, 'info>(
             &ctx.pool_state,
             &vault_0,
             &token_account_0,
-            &ctx.token_program,
+            &vault_0_mint,
+            &token_program_0,
+            &transfer_hook_accounts_0,
             amount_0,
         )?;
     }
@@ -706,16 +888,21 @@ pub fn exact_internal<'b, 'info>(
 
     let pool_state = ctx.pool_state.load()?;
     emit!(SwapEvent {
-        pool_state: pool_state.key(),
+        pool_state: ctx.pool_state.key(),
         sender: ctx.signer.key(),
         token_account_0: token_account_0.key(),
         token_account_1: token_account_1.key(),
         amount_0,
+        transfer_fee_0,
         amount_1,
+        transfer_fee_1,
         zero_for_one,
         sqrt_price_x64: pool_state.sqrt_price_x64,
         liquidity: pool_state.liquidity,
-        tick: pool_state.tick_current
+        tick: pool_state.tick_current,
+        fee_rate: pool_state
+            .dynamic_fee_info
+            .get_fee_rate(pool_state.get_trade_fee_rate(ctx.amm_config.trade_fee_rate)),
     });
     if zero_for_one {
         require_gt!(swap_price_before, pool_state.sqrt_price_x64);
@@ -724,14 +911,28 @@ pub fn exact_internal<'b, 'info>(
     }
 
     if is_base_input {
+        let output_transfer_fee = if zero_for_one {
+            transfer_fee_1
+        } else {
+            transfer_fee_0
+        };
         Ok(output_balance_before
             .checked_sub(ctx.output_vault.amount)
+            .unwrap()
+            .checked_sub(output_transfer_fee)
             .unwrap())
     } else {
+        let input_transfer_fee = if zero_for_one {
+            transfer_fee_0
+        } else {
+            transfer_fee_1
+        };
         Ok(ctx
             .input_vault
             .amount
             .checked_sub(input_balance_before)
+            .unwrap()
+            .checked_add(input_transfer_fee)
             .unwrap())
     }
 }
@@ -743,6 +944,12 @@ pub fn swap<'a, 'b, 'c, 'info>(
     sqrt_price_limit_x64: u128,
     is_base_input: bool,
 ) -> Result<()> {
+    // the transfer hook accounts of input and output token come before the tick arrays
+    let mut remaining_accounts = ctx.remaining_accounts;
+    let input_transfer_hook_accounts =
+        get_transfer_hook_accounts(&ctx.accounts.input_vault_mint, &mut remaining_accounts)?;
+    let output_transfer_hook_accounts =
+        get_transfer_hook_accounts(&ctx.accounts.output_vault_mint, &mut remaining_accounts)?;
     let amount = exact_internal(
         &mut SwapAccounts {
             signer: ctx.accounts.payer.clone(),
@@ -751,12 +958,17 @@ pub fn swap<'a, 'b, 'c, 'info>(
             output_token_account: ctx.accounts.output_token_account.clone(),
             input_vault: ctx.accounts.input_vault.clone(),
             output_vault: ctx.accounts.output_vault.clone(),
-            token_program: ctx.accounts.token_program.clone(),
+            input_vault_mint: ctx.accounts.input_vault_mint.clone(),
+            output_vault_mint: ctx.accounts.output_vault_mint.clone(),
+            input_token_program: ctx.accounts.input_token_program.clone(),
+            output_token_program: ctx.accounts.output_token_program.clone(),
+            input_transfer_hook_accounts,
+            output_transfer_hook_accounts,
             pool_state: &mut ctx.accounts.pool_state,
             tick_array_state: &mut ctx.accounts.tick_array,
             observation_state: &mut ctx.accounts.observation_state,
         },
-        ctx.remaining_accounts,
+        remaining_accounts,
         amount,
         sqrt_price_limit_x64,
         is_base_input,
@@ -779,6 +991,7 @@ pub fn swap<'a, 'b, 'c, 'info>(
 #[cfg(test)]
 mod swap_test {
     use std::cell::RefCell;
+    use std::convert::identity;
     use std::vec;
 
     use crate::states::pool_test::build_pool;
@@ -831,7 +1044,7 @@ mod swap_test {
             ));
             pool_state
                 .borrow_mut()
-                .flip_tick_array_bit(tick_array_info.start_tick_index)
+                .flip_tick_array_bit(None, tick_array_info.start_tick_index)
                 .unwrap();
         }
 
@@ -879,7 +1092,10 @@ mod swap_test {
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                None,
+                &PoolRewardExtensions::default(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 12188240002,
                 3049500711113990606,
                 true,
@@ -909,7 +1125,10 @@ mod swap_test {
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                None,
+                &PoolRewardExtensions::default(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 121882400020,
                 3049500711113990606,
                 true,
@@ -936,7 +1155,10 @@ mod swap_test {
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                None,
+                &PoolRewardExtensions::default(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 60941200010,
                 3049500711113990606,
                 true,
@@ -992,7 +1214,10 @@ mod swap_test {
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                None,
+                &PoolRewardExtensions::default(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 477470480,
                 3049500711113990606,
                 true,
@@ -1022,7 +1247,10 @@ mod swap_test {
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                None,
+                &PoolRewardExtensions::default(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 4751002622,
                 3049500711113990606,
                 true,
@@ -1049,7 +1277,10 @@ mod swap_test {
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                None,
+                &PoolRewardExtensions::default(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 2358130642,
                 3049500711113990606,
                 true,
@@ -1105,7 +1336,10 @@ mod swap_test {
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                None,
+                &PoolRewardExtensions::default(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 887470480,
                 5882283448660210779,
                 false,
@@ -1134,7 +1368,10 @@ mod swap_test {
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                None,
+                &PoolRewardExtensions::default(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 3087470480,
                 5882283448660210779,
                 false,
@@ -1162,7 +1399,10 @@ mod swap_test {
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                None,
+                &PoolRewardExtensions::default(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 200941200010,
                 5882283448660210779,
                 false,
@@ -1218,7 +1458,10 @@ mod swap_test {
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                None,
+                &PoolRewardExtensions::default(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 22796232052,
                 5882283448660210779,
                 false,
@@ -1247,7 +1490,10 @@ mod swap_test {
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                None,
+                &PoolRewardExtensions::default(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 79023558189,
                 5882283448660210779,
                 false,
@@ -1275,7 +1521,10 @@ mod swap_test {
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                None,
+                &PoolRewardExtensions::default(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 4315086194758,
                 5882283448660210779,
                 false,
@@ -1324,7 +1573,10 @@ mod swap_test {
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                None,
+                &PoolRewardExtensions::default(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 12188240002,
                 tick_math::get_sqrt_price_at_tick(-32400).unwrap(),
                 true,
@@ -1368,7 +1620,10 @@ mod swap_test {
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                None,
+                &PoolRewardExtensions::default(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 12188240002,
                 tick_math::get_sqrt_price_at_tick(-28860).unwrap(),
                 false,
@@ -1414,7 +1669,10 @@ mod swap_test {
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                None,
+                &PoolRewardExtensions::default(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 12188240002,
                 tick_math::get_sqrt_price_at_tick(-32400).unwrap(),
                 true,
@@ -1451,7 +1709,10 @@ mod swap_test {
             &amm_config,
             &mut pool_state.borrow_mut(),
             &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+            None,
+            &PoolRewardExtensions::default(),
             &mut observation_state.borrow_mut(),
+            &mut [],
             25,
             tick_math::get_sqrt_price_at_tick(-32400).unwrap(),
             true,
@@ -1473,7 +1734,10 @@ mod swap_test {
             &amm_config,
             &mut pool_state.borrow_mut(),
             &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+            None,
+            &PoolRewardExtensions::default(),
             &mut observation_state.borrow_mut(),
+            &mut [],
             3,
             tick_math::get_sqrt_price_at_tick(-32400).unwrap(),
             true,
@@ -1498,7 +1762,10 @@ mod swap_test {
             &amm_config,
             &mut pool_state.borrow_mut(),
             &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+            None,
+            &PoolRewardExtensions::default(),
             &mut observation_state.borrow_mut(),
+            &mut [],
             50,
             tick_math::get_sqrt_price_at_tick(-32400).unwrap(),
             true,
@@ -1550,7 +1817,10 @@ mod swap_test {
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                None,
+                &PoolRewardExtensions::default(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 27,
                 tick_math::get_sqrt_price_at_tick(-32400).unwrap(),
                 true,
@@ -1576,7 +1846,10 @@ mod swap_test {
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                None,
+                &PoolRewardExtensions::default(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 1,
                 tick_math::get_sqrt_price_at_tick(-32400).unwrap(),
                 true,
@@ -1600,7 +1873,10 @@ mod swap_test {
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                None,
+                &PoolRewardExtensions::default(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 10,
                 tick_math::get_sqrt_price_at_tick(-28800).unwrap(),
                 false,
@@ -1617,4 +1893,119 @@ mod swap_test {
             );
         }
     }
+
+    #[test]
+    fn dynamic_fee_swap_test() {
+        let tick_current = -28859;
+        let liquidity = 121219;
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(tick_current).unwrap();
+        let block_timestamp = oracle::block_timestamp_mock() as u32;
+
+        let mut amounts_out = Vec::new();
+        for dynamic_fee_enabled in [false, true] {
+            let (amm_config, pool_state, tick_array_states, observation_state) = build_swap_param(
+                tick_current,
+                60,
+                sqrt_price_x64,
+                liquidity,
+                vec![TickArrayInfo {
+                    start_tick_index: -32400,
+                    ticks: vec![
+                        build_tick(-32400, 277065331032, -277065331032).take(),
+                        build_tick(-29220, 1330680689, -1330680689).take(),
+                        build_tick(-28860, 6408486554, -6408486554).take(),
+                    ],
+                }],
+            );
+            if dynamic_fee_enabled {
+                // the price has moved 100 ticks since the reference
+                pool_state.borrow_mut().dynamic_fee_info = DynamicFeeInfo {
+                    max_fee_rate: 100000,
+                    dynamic_fee_control: 10000,
+                    max_volatility_accumulator: 1000,
+                    filter_period: 30,
+                    decay_period: 600,
+                    reduction_factor: 5000,
+                    tick_reference: tick_current + 100,
+                    last_update_timestamp: u64::from(block_timestamp),
+                    ..Default::default()
+                };
+            }
+            let (_amount_0, amount_1) = swap_internal(
+                &amm_config,
+                &mut pool_state.borrow_mut(),
+                &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                None,
+                &PoolRewardExtensions::default(),
+                &mut observation_state.borrow_mut(),
+                &mut [],
+                1000000,
+                tick_math::get_sqrt_price_at_tick(-32400).unwrap(),
+                true,
+                true,
+                block_timestamp,
+            )
+            .unwrap();
+            amounts_out.push(amount_1);
+
+            let dynamic_fee_info = pool_state.borrow().dynamic_fee_info;
+            if dynamic_fee_enabled {
+                // the references are kept within the filter period, the volatility grows with the swap
+                assert_eq!(
+                    identity(dynamic_fee_info.tick_reference),
+                    tick_current + 100
+                );
+                assert!(identity(dynamic_fee_info.volatility_accumulator) >= 100);
+                assert!(dynamic_fee_info.get_fee_rate(amm_config.trade_fee_rate) > 1000 + 1000);
+            } else {
+                assert_eq!(dynamic_fee_info, DynamicFeeInfo::default());
+            }
+        }
+        assert!(amounts_out[1] < amounts_out[0]);
+    }
+
+    #[test]
+    fn trade_fee_rate_override_swap_test() {
+        let tick_current = -28859;
+        let liquidity = 121219;
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(tick_current).unwrap();
+
+        let mut amounts_out = Vec::new();
+        // not overridden, overridden to the rate of amm config, overridden to a higher rate
+        for trade_fee_rate_override in [0, 1000, 10000] {
+            let (amm_config, pool_state, tick_array_states, observation_state) = build_swap_param(
+                tick_current,
+                60,
+                sqrt_price_x64,
+                liquidity,
+                vec![TickArrayInfo {
+                    start_tick_index: -32400,
+                    ticks: vec![
+                        build_tick(-32400, 277065331032, -277065331032).take(),
+                        build_tick(-29220, 1330680689, -1330680689).take(),
+                        build_tick(-28860, 6408486554, -6408486554).take(),
+                    ],
+                }],
+            );
+            pool_state.borrow_mut().trade_fee_rate_override = trade_fee_rate_override;
+            let (_amount_0, amount_1) = swap_internal(
+                &amm_config,
+                &mut pool_state.borrow_mut(),
+                &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                None,
+                &PoolRewardExtensions::default(),
+                &mut observation_state.borrow_mut(),
+                &mut [],
+                1000000,
+                tick_math::get_sqrt_price_at_tick(-32400).unwrap(),
+                true,
+                true,
+                oracle::block_timestamp_mock() as u32,
+            )
+            .unwrap();
+            amounts_out.push(amount_1);
+        }
+        assert_eq!(amounts_out[0], amounts_out[1]);
+        assert!(amounts_out[2] < amounts_out[0]);
+    }
 }
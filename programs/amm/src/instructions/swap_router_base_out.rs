@@ -0,0 +1,378 @@
+use super::{exact_internal_settle, exact_internal_swap, SwapAccounts, SwapAmounts};
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::{get_transfer_hook_accounts, get_transfer_inverse_fee};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct SwapRouterBaseOut<'info> {
+    /// The user performing the swap
+    pub payer: Signer<'info>,
+
+    /// The token account that pays input tokens for the swap
+    #[account(mut)]
+    pub input_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The mint of input token
+    #[account(address = input_token_account.mint)]
+    pub input_token_mint: InterfaceAccount<'info, Mint>,
+
+    /// SPL program or token program 2022 of the input token
+    pub input_token_program: Interface<'info, TokenInterface>,
+}
+
+/// The accounts of a single pool of the route
+pub struct SwapHop<'info> {
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+    pub pool_state: AccountLoader<'info, PoolState>,
+    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub input_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub output_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub input_token_program: Interface<'info, TokenInterface>,
+    pub output_token_program: Interface<'info, TokenInterface>,
+    pub observation_state: AccountLoader<'info, ObservationState>,
+    pub tick_array: AccountLoader<'info, TickArrayState>,
+    /// The tick arrays after the first one
+    pub tick_array_accounts: Vec<AccountInfo<'info>>,
+    pub input_transfer_hook_accounts: Vec<AccountInfo<'info>>,
+    pub output_transfer_hook_accounts: Vec<AccountInfo<'info>>,
+}
+
+impl<'info> SwapHop<'info> {
+    pub fn swap_accounts<'b>(&'b mut self, signer: &Signer<'info>) -> SwapAccounts<'b, 'info> {
+        SwapAccounts {
+            signer: signer.clone(),
+            amm_config: &self.amm_config,
+            input_token_account: self.input_token_account.clone(),
+            output_token_account: self.output_token_account.clone(),
+            input_vault: self.input_vault.clone(),
+            output_vault: self.output_vault.clone(),
+            input_vault_mint: self.input_vault_mint.clone(),
+            output_vault_mint: self.output_vault_mint.clone(),
+            input_token_program: self.input_token_program.clone(),
+            output_token_program: self.output_token_program.clone(),
+            input_transfer_hook_accounts: self.input_transfer_hook_accounts.clone(),
+            output_transfer_hook_accounts: self.output_transfer_hook_accounts.clone(),
+            pool_state: &mut self.pool_state,
+            tick_array_state: &mut self.tick_array,
+            observation_state: &mut self.observation_state,
+        }
+    }
+}
+
+/// Parse the hops of a route from the remaining accounts, each hop is
+/// `[amm_config, pool_state, output_token_account, input_vault, output_vault, output_token_mint,
+/// output_token_program, observation_state, tick_array, ...input token transfer hook accounts,
+/// ...output token transfer hook accounts, tick_array_bitmap_extension if any,
+/// ...pool reward extensions, ...more tick arrays]`.
+/// `tick_array_counts` is the number of tick arrays of each hop, the first one included.
+/// The output token of a hop is the input token of the next hop.
+pub fn parse_swap_hops<'info>(
+    mut input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    mut input_token_mint: Box<InterfaceAccount<'info, Mint>>,
+    mut input_token_program: Interface<'info, TokenInterface>,
+    remaining_accounts: &[AccountInfo<'info>],
+    tick_array_counts: &[u8],
+) -> Result<Vec<SwapHop<'info>>> {
+    let mut hops = Vec::with_capacity(tick_array_counts.len());
+    let mut accounts = remaining_accounts;
+    for &tick_array_count in tick_array_counts {
+        require_gte!(tick_array_count, 1, ErrorCode::AccountLack);
+        require_gte!(accounts.len(), 9, ErrorCode::AccountLack);
+        let amm_config = Box::new(Account::<AmmConfig>::try_from(&accounts[0])?);
+        let pool_state = AccountLoader::<PoolState>::try_from(&accounts[1])?;
+        let output_token_account =
+            Box::new(InterfaceAccount::<TokenAccount>::try_from(&accounts[2])?);
+        let input_vault = Box::new(InterfaceAccount::<TokenAccount>::try_from(&accounts[3])?);
+        let output_vault = Box::new(InterfaceAccount::<TokenAccount>::try_from(&accounts[4])?);
+        let output_token_mint = Box::new(InterfaceAccount::<Mint>::try_from(&accounts[5])?);
+        let output_token_program = Interface::<TokenInterface>::try_from(&accounts[6])?;
+        let observation_state = AccountLoader::<ObservationState>::try_from(&accounts[7])?;
+        let tick_array = AccountLoader::<TickArrayState>::try_from(&accounts[8])?;
+        let reward_extension_num = {
+            let pool_state = pool_state.load()?;
+            // check observation account is owned by the pool
+            require_keys_eq!(pool_state.observation_key, observation_state.key());
+            // check ammConfig account is associate with the pool
+            require_keys_eq!(pool_state.amm_config, amm_config.key());
+            // check mints are the mints of the vaults
+            require_keys_eq!(input_vault.mint, input_token_mint.key());
+            require_keys_eq!(output_vault.mint, output_token_mint.key());
+            usize::from(pool_state.reward_extension_num)
+        };
+        accounts = &accounts[9..];
+        let input_transfer_hook_accounts =
+            get_transfer_hook_accounts(&input_token_mint, &mut accounts)?;
+        let output_transfer_hook_accounts =
+            get_transfer_hook_accounts(&output_token_mint, &mut accounts)?;
+        // the extensions of the pool are checked by the swap, followed by the other tick arrays
+        let extension_count = match accounts.first() {
+            Some(account_info)
+                if account_info.key() == TickArrayBitmapExtension::get_address(&pool_state.key()) =>
+            {
+                reward_extension_num + 1
+            }
+            _ => reward_extension_num,
+        };
+        let hop_account_count = extension_count + usize::from(tick_array_count) - 1;
+        require_gte!(accounts.len(), hop_account_count, ErrorCode::AccountLack);
+        let (tick_array_accounts, rest) = accounts.split_at(hop_account_count);
+        accounts = rest;
+
+        hops.push(SwapHop {
+            amm_config,
+            pool_state,
+            input_token_account,
+            output_token_account: output_token_account.clone(),
+            input_vault,
+            output_vault,
+            input_vault_mint: input_token_mint,
+            output_vault_mint: output_token_mint.clone(),
+            input_token_program,
+            output_token_program: output_token_program.clone(),
+            observation_state,
+            tick_array,
+            tick_array_accounts: tick_array_accounts.to_vec(),
+            input_transfer_hook_accounts,
+            output_transfer_hook_accounts,
+        });
+        // output token is the new swap input token
+        input_token_account = output_token_account;
+        input_token_mint = output_token_mint;
+        input_token_program = output_token_program;
+    }
+    require!(accounts.is_empty(), ErrorCode::AccountLack);
+    Ok(hops)
+}
+
+/// Walk a base output route from the output side: each hop must output exactly the amount the
+/// next hop pays in, the transfer fees of the output and of the next input included.
+/// `swap_hop` swaps a hop for an exact output amount received after the output transfer fee,
+/// `transfer_inverse_fee` is the fee to add to a transfer out of the hop, or into it if not
+/// `is_output`, to receive the amount.
+/// The amounts are returned in the route order.
+fn get_route_amounts_base_out<H>(
+    hops: &mut [H],
+    amount_out: u64,
+    mut swap_hop: impl FnMut(&mut H, u64) -> Result<SwapAmounts>,
+    mut transfer_inverse_fee: impl FnMut(&H, bool, u64) -> Result<u64>,
+) -> Result<Vec<SwapAmounts>> {
+    let mut swap_amounts: Vec<SwapAmounts> = Vec::with_capacity(hops.len());
+    let mut amount_out_internal = amount_out;
+    for hop in hops.iter_mut().rev() {
+        let output_transfer_fee = transfer_inverse_fee(hop, true, amount_out_internal)?;
+        let amounts = swap_hop(hop, amount_out_internal)?;
+        let (amount_in, amount_out_pool) = if amounts.zero_for_one {
+            (amounts.amount_0, amounts.amount_1)
+        } else {
+            (amounts.amount_1, amounts.amount_0)
+        };
+        // a partially filled hop can't pay the next hop
+        require_eq!(
+            amount_out_pool,
+            amount_out_internal.checked_add(output_transfer_fee).unwrap(),
+            ErrorCode::TooLittleOutputReceived
+        );
+        amount_out_internal = amount_in
+            .checked_add(transfer_inverse_fee(hop, false, amount_in)?)
+            .unwrap();
+        swap_amounts.push(amounts);
+    }
+    swap_amounts.reverse();
+    Ok(swap_amounts)
+}
+
+pub fn swap_router_base_out<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseOut<'info>>,
+    amount_out: u64,
+    amount_in_maximum: u64,
+    tick_array_counts: Vec<u8>,
+) -> Result<()> {
+    let mut hops = parse_swap_hops(
+        Box::new(ctx.accounts.input_token_account.clone()),
+        Box::new(ctx.accounts.input_token_mint.clone()),
+        ctx.accounts.input_token_program.clone(),
+        ctx.remaining_accounts,
+        &tick_array_counts,
+    )?;
+    require!(!hops.is_empty(), ErrorCode::AccountLack);
+
+    // The tokens of an intermediate hop don't exist before the previous hop is settled,
+    // so all the swaps are computed first and settled from the input side afterwards.
+    let payer = &ctx.accounts.payer;
+    let swap_amounts = get_route_amounts_base_out(
+        &mut hops,
+        amount_out,
+        |hop, amount_out_internal| {
+            let tick_array_accounts = hop.tick_array_accounts.clone();
+            exact_internal_swap(
+                &mut hop.swap_accounts(payer),
+                &tick_array_accounts,
+                amount_out_internal,
+                0,
+                false,
+            )
+        },
+        |hop, is_output, amount| {
+            if is_output {
+                get_transfer_inverse_fee(&hop.output_vault_mint, amount)
+            } else {
+                get_transfer_inverse_fee(&hop.input_vault_mint, amount)
+            }
+        },
+    )?;
+
+    let mut amount_in = 0;
+    for (i, (hop, amounts)) in hops
+        .iter_mut()
+        .zip(swap_amounts.iter())
+        .enumerate()
+    {
+        let amount_paid =
+            exact_internal_settle(&mut hop.swap_accounts(&ctx.accounts.payer), amounts, false)?;
+        if i == 0 {
+            amount_in = amount_paid;
+        }
+    }
+    require!(
+        amount_in <= amount_in_maximum,
+        ErrorCode::TooMuchInputPaid
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod swap_router_base_out_test {
+    use super::*;
+
+    /// A hop with a constant price, the output mint charges `output_fee_rate` per million
+    /// on transfers, the input mint `input_fee_rate`
+    struct TestHop {
+        zero_for_one: bool,
+        /// amount in per amount out
+        price: u64,
+        input_fee_rate: u64,
+        output_fee_rate: u64,
+        /// the pool can't output more
+        liquidity: u64,
+    }
+
+    fn inverse_fee(fee_rate: u64, amount: u64) -> u64 {
+        // the fee of the pre fee amount, rounded up
+        let pre_fee_amount = (amount * 1_000_000).div_ceil(1_000_000 - fee_rate);
+        pre_fee_amount - amount
+    }
+
+    fn walk(hops: &mut [TestHop], amount_out: u64) -> Result<Vec<SwapAmounts>> {
+        get_route_amounts_base_out(
+            hops,
+            amount_out,
+            |hop, amount_out| {
+                // the pool outputs the amount plus the transfer fee of the output mint
+                let amount_out_pool = amount_out + inverse_fee(hop.output_fee_rate, amount_out);
+                let amount_out_pool = amount_out_pool.min(hop.liquidity);
+                let amount_in = amount_out_pool * hop.price;
+                let (amount_0, amount_1) = if hop.zero_for_one {
+                    (amount_in, amount_out_pool)
+                } else {
+                    (amount_out_pool, amount_in)
+                };
+                Ok(SwapAmounts {
+                    amount_0,
+                    amount_1,
+                    zero_for_one: hop.zero_for_one,
+                    sqrt_price_x64_before: 0,
+                })
+            },
+            |hop, is_output, amount| {
+                Ok(if is_output {
+                    inverse_fee(hop.output_fee_rate, amount)
+                } else {
+                    inverse_fee(hop.input_fee_rate, amount)
+                })
+            },
+        )
+    }
+
+    #[test]
+    fn route_amounts_base_out_test() {
+        // A -> B -> C without transfer fee
+        let mut hops = [
+            TestHop {
+                zero_for_one: true,
+                price: 2,
+                input_fee_rate: 0,
+                output_fee_rate: 0,
+                liquidity: u64::MAX,
+            },
+            TestHop {
+                zero_for_one: false,
+                price: 3,
+                input_fee_rate: 0,
+                output_fee_rate: 0,
+                liquidity: u64::MAX,
+            },
+        ];
+        let amounts = walk(&mut hops, 100).unwrap();
+        // the last hop outputs 100 C for 300 B, the first outputs 300 B for 600 A
+        assert_eq!((amounts[0].amount_0, amounts[0].amount_1), (600, 300));
+        assert_eq!((amounts[1].amount_1, amounts[1].amount_0), (300, 100));
+    }
+
+    #[test]
+    fn route_amounts_base_out_with_transfer_fee_test() {
+        // B charges 1% on transfers, C 10%
+        let mut hops = [
+            TestHop {
+                zero_for_one: true,
+                price: 1,
+                input_fee_rate: 0,
+                output_fee_rate: 10_000,
+                liquidity: u64::MAX,
+            },
+            TestHop {
+                zero_for_one: true,
+                price: 1,
+                input_fee_rate: 10_000,
+                output_fee_rate: 100_000,
+                liquidity: u64::MAX,
+            },
+        ];
+        let amounts = walk(&mut hops, 900).unwrap();
+        // the last pool outputs 1000 C so that 900 are received, it takes 1000 B in, which must
+        // be sent with the 1% fee, so the first pool must deliver 1011 B after its 1% output fee
+        assert_eq!((amounts[1].amount_0, amounts[1].amount_1), (1000, 1000));
+        assert_eq!(amounts[0].amount_1, 1011 + inverse_fee(10_000, 1011));
+        assert_eq!(amounts[0].amount_0, amounts[0].amount_1);
+    }
+
+    #[test]
+    fn route_amounts_base_out_partial_fill_test() {
+        // the last pool can't output the whole amount
+        let mut hops = [
+            TestHop {
+                zero_for_one: true,
+                price: 1,
+                input_fee_rate: 0,
+                output_fee_rate: 0,
+                liquidity: u64::MAX,
+            },
+            TestHop {
+                zero_for_one: true,
+                price: 1,
+                input_fee_rate: 0,
+                output_fee_rate: 0,
+                liquidity: 99,
+            },
+        ];
+        assert_eq!(
+            walk(&mut hops, 100).err().unwrap(),
+            ErrorCode::TooLittleOutputReceived.into()
+        );
+    }
+}
@@ -13,8 +13,24 @@ pub fn update_reward_infos<'a, 'b, 'c, 'info>(
 ) -> Result<()> {
     let clock = Clock::get()?;
     let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.check_unlocked()?;
     let updated_reward_infos = pool_state.update_reward_infos(u64::try_from(clock.unix_timestamp).unwrap())?;
 
+    // the reward extensions of the pool are passed by remaining accounts
+    let mut remaining_accounts = ctx.remaining_accounts;
+    let reward_extensions = get_pool_reward_extensions(
+        &ctx.accounts.pool_state.key(),
+        pool_state.reward_extension_num,
+        &mut remaining_accounts,
+    )?;
+    for reward_extension in reward_extensions.iter() {
+        PoolRewardExtension::update_reward_info(
+            reward_extension,
+            u64::try_from(clock.unix_timestamp).unwrap(),
+            pool_state.liquidity,
+        )?;
+    }
+
     emit!(UpdateRewardInfosEvent {
         reward_growth_global_x64: RewardInfo::get_reward_growths(&updated_reward_infos)
     });
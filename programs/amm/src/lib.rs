@@ -45,10 +45,6 @@ pub mod amm_v3 {
         protocol_fee_rate: u32,
         fund_fee_rate: u32,
     ) -> Result<()> {
-        assert!(trade_fee_rate < FEE_RATE_DENOMINATOR_VALUE);
-        assert!(protocol_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
-        assert!(fund_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
-        assert!(fund_fee_rate + protocol_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
         instructions::create_amm_config(
             ctx,
             index,
@@ -59,21 +55,82 @@ pub mod amm_v3 {
         )
     }
 
-    /// Updates the owner of the amm config
-    /// Must be called by the current owner or admin
+    /// Sets the trade fee rate of the amm config, by admin
+    /// Must be queued through `queue_admin_action` once the timelock delay is set
     ///
     /// # Arguments
     ///
     /// * `ctx`- The context of accounts
-    /// * `trade_fee_rate`- The new trade fee rate of amm config, be set when `param` is 0
-    /// * `protocol_fee_rate`- The new protocol fee rate of amm config, be set when `param` is 1
-    /// * `fund_fee_rate`- The new fund fee rate of amm config, be set when `param` is 2
-    /// * `new_owner`- The config's new owner, be set when `param` is 3
-    /// * `new_fund_owner`- The config's new fund owner, be set when `param` is 4
-    /// * `param`- The vaule can be 0 | 1 | 2 | 3 | 4, otherwise will report a error
+    /// * `trade_fee_rate`- The new trade fee rate, must be less than the fee rate denominator
     ///
-    pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u8, value: u32) -> Result<()> {
-        instructions::update_amm_config(ctx, param, value)
+    pub fn set_trade_fee_rate(ctx: Context<UpdateAmmConfig>, trade_fee_rate: u32) -> Result<()> {
+        instructions::set_trade_fee_rate(ctx, trade_fee_rate)
+    }
+
+    /// Sets the protocol fee rate of the amm config, by admin
+    /// Must be queued through `queue_admin_action` once the timelock delay is set
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `protocol_fee_rate`- The new protocol fee rate, the share of the trade fee to the
+    ///    protocol, must not exceed the fee rate denominator with the fund fee rate
+    ///
+    pub fn set_protocol_fee_rate(
+        ctx: Context<UpdateAmmConfig>,
+        protocol_fee_rate: u32,
+    ) -> Result<()> {
+        instructions::set_protocol_fee_rate(ctx, protocol_fee_rate)
+    }
+
+    /// Sets the fund fee rate of the amm config, by admin
+    /// Must be queued through `queue_admin_action` once the timelock delay is set
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `fund_fee_rate`- The new fund fee rate, the share of the trade fee to the fund, must
+    ///    not exceed the fee rate denominator with the protocol fee rate
+    ///
+    pub fn set_fund_fee_rate(ctx: Context<UpdateAmmConfig>, fund_fee_rate: u32) -> Result<()> {
+        instructions::set_fund_fee_rate(ctx, fund_fee_rate)
+    }
+
+    /// Proposes a new owner or fund owner of the amm config, replacing the pending proposal
+    /// of the same owner type, by admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `owner_type`- The owner or the fund owner of the amm config
+    /// * `new_owner`- The proposed owner, takes over once it accepts
+    ///
+    pub fn propose_amm_config_owner(
+        ctx: Context<ProposeAmmConfigOwner>,
+        owner_type: AmmConfigOwnerType,
+        new_owner: Pubkey,
+    ) -> Result<()> {
+        instructions::propose_amm_config_owner(ctx, owner_type, new_owner)
+    }
+
+    /// Accepts the proposed owner or fund owner of the amm config, signed by the proposed owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn accept_amm_config_owner(ctx: Context<AcceptAmmConfigOwner>) -> Result<()> {
+        instructions::accept_amm_config_owner(ctx)
+    }
+
+    /// Cancels the proposed owner or fund owner of the amm config, by admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn cancel_amm_config_owner(ctx: Context<CancelAmmConfigOwner>) -> Result<()> {
+        instructions::cancel_amm_config_owner(ctx)
     }
 
     /// Creates a pool for the given token pair and the initial price
@@ -92,6 +149,8 @@ pub mod amm_v3 {
     }
 
     /// Update pool status for given vaule
+    /// Must be queued through `queue_admin_action` once the timelock delay is set, unless the
+    /// change only disables swap or open position
     ///
     /// # Arguments
     ///
@@ -102,6 +161,98 @@ pub mod amm_v3 {
         instructions::update_pool_status(ctx, status)
     }
 
+    /// Set the delay of the timelocked admin actions, by admin. Once the delay is set, the fee rate
+    /// changes and the pool status changes but emergency pauses must be queued. The delay can be
+    /// raised at once, lowering it must be queued
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `delay` - The seconds a queued action waits, up to `MAX_TIMELOCK_DELAY`
+    ///
+    pub fn set_timelock_delay(ctx: Context<SetTimelockDelay>, delay: u64) -> Result<()> {
+        instructions::set_timelock_delay(ctx, delay)
+    }
+
+    /// Queue an admin action behind the timelock, by admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `action` - The admin action, can be executed once the delay of the timelock passes
+    ///
+    pub fn queue_admin_action(ctx: Context<QueueAdminAction>, action: AdminAction) -> Result<()> {
+        instructions::queue_admin_action(ctx, action)
+    }
+
+    /// Cancel a queued admin action, by admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn cancel_admin_action(ctx: Context<CancelAdminAction>) -> Result<()> {
+        instructions::cancel_admin_action(ctx)
+    }
+
+    /// Execute a queued admin action once its delay passes, can be called for everyone
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn execute_admin_action(ctx: Context<ExecuteAdminAction>) -> Result<()> {
+        instructions::execute_admin_action(ctx)
+    }
+
+    /// Set the volatility based dynamic fee of the pool, by admin or operation owners.
+    /// The fee rate of a swap step is the trade fee rate of the amm config plus a dynamic part
+    /// growing with the square of the price movement, capped at `max_fee_rate`
+    /// Must be queued through `queue_admin_action` once the timelock delay is set
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `param`- The dynamic fee params, the dynamic fee is disabled if `max_fee_rate` is 0,
+    /// otherwise `max_fee_rate` must be at least the trade fee rate of the pool
+    ///
+    pub fn set_dynamic_fee_params(
+        ctx: Context<SetDynamicFeeParams>,
+        param: DynamicFeeParam,
+    ) -> Result<()> {
+        instructions::set_dynamic_fee_params(ctx, param)
+    }
+
+    /// Override the trade fee rate of the pool instead of the one of its amm config,
+    /// by admin or operation owners
+    /// Must be queued through `queue_admin_action` once the timelock delay is set
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `trade_fee_rate_override` - The trade fee rate of the pool, 0 to use the amm config's again
+    ///
+    pub fn set_pool_trade_fee_rate(
+        ctx: Context<SetPoolTradeFeeRate>,
+        trade_fee_rate_override: u32,
+    ) -> Result<()> {
+        instructions::set_pool_trade_fee_rate(ctx, trade_fee_rate_override)
+    }
+
+    /// Update the minimum duration between two observations of the pool's oracle, by admin or pool owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `observation_update_duration` - The minimum duration in seconds, must be positive
+    ///
+    pub fn update_observation_update_duration(
+        ctx: Context<UpdateObservationUpdateDuration>,
+        observation_update_duration: u16,
+    ) -> Result<()> {
+        instructions::update_observation_update_duration(ctx, observation_update_duration)
+    }
+
     /// Creates an operation account for the program
     ///
     /// # Arguments
@@ -112,26 +263,64 @@ pub mod amm_v3 {
         instructions::create_operation_account(ctx)
     }
 
-    /// Update the operation account
+    /// Add operation owners to the operation account, by admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `keys`- The owners to add, the operation account holds up to `OPERATION_SIZE_USIZE`
+    ///
+    pub fn add_operation_owners(
+        ctx: Context<UpdateOperationAccount>,
+        keys: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::add_operation_owners(ctx, keys)
+    }
+
+    /// Remove operation owners from the operation account, by admin
     ///
     /// # Arguments
     ///
     /// * `ctx`- The context of accounts
-    /// * `param`- The vaule can be 0 | 1 | 2 | 3, otherwise will report a error
-    /// * `keys`- update operation owner when the `param` is 0
-    ///           remove operation owner when the `param` is 1
-    ///           update whitelist mint when the `param` is 2
-    ///           remove whitelist mint when the `param` is 3
+    /// * `keys`- The owners to remove
     ///
-    pub fn update_operation_account(
+    pub fn remove_operation_owners(
         ctx: Context<UpdateOperationAccount>,
-        param: u8,
         keys: Vec<Pubkey>,
     ) -> Result<()> {
-        instructions::update_operation_account(ctx, param, keys)
+        instructions::remove_operation_owners(ctx, keys)
     }
 
-    /// Transfer reward owner
+    /// Add whitelist mints to the operation account, by admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `keys`- The mints to add, the operation account holds up to `WHITE_MINT_SIZE_USIZE`
+    ///
+    pub fn add_whitelist_mints(
+        ctx: Context<UpdateOperationAccount>,
+        keys: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::add_whitelist_mints(ctx, keys)
+    }
+
+    /// Remove whitelist mints from the operation account, by admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `keys`- The mints to remove
+    ///
+    pub fn remove_whitelist_mints(
+        ctx: Context<UpdateOperationAccount>,
+        keys: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::remove_whitelist_mints(ctx, keys)
+    }
+
+    /// Transfer reward owner, the new owner takes over the pool once it accepts. The authorities of
+    /// the rewards change hands through `propose_reward_authority`
     ///
     /// # Arguments
     ///
@@ -145,6 +334,26 @@ pub mod amm_v3 {
         instructions::transfer_reward_owner(ctx, new_owner)
     }
 
+    /// Accepts the owner of the pool, signed by the owner proposed by `transfer_reward_owner`
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn accept_reward_owner(ctx: Context<AcceptRewardOwner>) -> Result<()> {
+        instructions::accept_reward_owner(ctx)
+    }
+
+    /// Cancels the owner proposed by `transfer_reward_owner`, by admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn cancel_reward_owner(ctx: Context<CancelRewardOwner>) -> Result<()> {
+        instructions::cancel_reward_owner(ctx)
+    }
+
     /// Initialize a reward info for a given pool and reward index
     ///
     /// # Arguments
@@ -155,27 +364,141 @@ pub mod amm_v3 {
     /// * `end_time` - reward end timestamp
     /// * `emissions_per_second_x64` - Token reward per second are earned per unit of liquidity.
     ///
-    pub fn initialize_reward(
-        ctx: Context<InitializeReward>,
+    pub fn initialize_reward<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, InitializeReward<'info>>,
         param: InitializeRewardParam,
     ) -> Result<()> {
         instructions::initialize_reward(ctx, param)
     }
 
-    /// Collect remaining reward token for reward founder
+    /// Initialize a reward info whose emission rate changes at the start of each phase, the
+    /// funder deposits the emissions of all the phases
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `param` - the open time, end time and emission rate before the first phase
+    /// * `phases` - the start times and emission rates of the following phases, in order
+    ///
+    pub fn initialize_reward_schedule<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, InitializeReward<'info>>,
+        param: InitializeRewardParam,
+        phases: Vec<RewardPhaseParam>,
+    ) -> Result<()> {
+        instructions::initialize_reward_schedule(ctx, param, phases)
+    }
+
+    /// Initialize a reward beyond the rewards of the pool in a new reward extension of the pool,
+    /// the reward extensions of the pool must then be passed to every instruction accruing rewards,
+    /// the instructions changing positions take them followed by the system program and a writable
+    /// signer paying the rent of their new position entries,
+    /// a pool has at most `MAX_REWARD_EXTENSION_NUM` reward extensions
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `open_time` - reward open timestamp
+    /// * `end_time` - reward end timestamp
+    /// * `emissions_per_second_x64` - Token reward per second are earned per unit of liquidity.
+    ///
+    pub fn initialize_extension_reward<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, InitializeExtensionReward<'info>>,
+        param: InitializeRewardParam,
+    ) -> Result<()> {
+        instructions::initialize_extension_reward(ctx, param)
+    }
+
+    /// Collect remaining reward token for the reward authority or the pool owner, the reward not
+    /// emitted once the reward ends or is cancelled goes back to the reward authority
     ///
     /// # Arguments
     ///
     /// * `ctx`- The context of accounts
     /// * `reward_index` - the index to reward info
     ///
-    pub fn collect_remaining_rewards(
-        ctx: Context<CollectRemainingRewards>,
+    pub fn collect_remaining_rewards<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectRemainingRewards<'info>>,
         reward_index: u8,
     ) -> Result<()> {
         instructions::collect_remaining_rewards(ctx, reward_index)
     }
 
+    /// Fund a running reward, extending its end time by the time the amount lasts at the
+    /// emission rate the reward ends with, can be called for everyone
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `reward_index` - the index to reward info
+    /// * `amount` - the amount of reward token deposited to the reward vault
+    ///
+    pub fn fund_reward<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, FundReward<'info>>,
+        reward_index: u8,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::fund_reward(ctx, reward_index, amount)
+    }
+
+    /// Stop the emissions of a running reward now, by the authority of the reward or the admin.
+    /// The unemitted reward can then be collected through `collect_remaining_rewards`
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `reward_index` - the index to reward info
+    ///
+    pub fn cancel_reward(ctx: Context<CancelReward>, reward_index: u8) -> Result<()> {
+        instructions::cancel_reward(ctx, reward_index)
+    }
+
+    /// Propose the new authority of a reward, by the authority of the reward or the admin. The
+    /// new authority takes over the reward once it accepts
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `reward_index` - the index to reward info
+    /// * `new_authority` - the proposed authority, replaces the previous proposal if any
+    ///
+    pub fn propose_reward_authority(
+        ctx: Context<ProposeRewardAuthority>,
+        reward_index: u8,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::propose_reward_authority(ctx, reward_index, new_authority)
+    }
+
+    /// Accepts the authority of a reward, signed by the authority proposed by
+    /// `propose_reward_authority`
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `reward_index` - the index to reward info
+    ///
+    pub fn accept_reward_authority(
+        ctx: Context<AcceptRewardAuthority>,
+        reward_index: u8,
+    ) -> Result<()> {
+        instructions::accept_reward_authority(ctx, reward_index)
+    }
+
+    /// Cancels the authority proposed by `propose_reward_authority`, by the authority of the
+    /// reward or the admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `reward_index` - the index to reward info
+    ///
+    pub fn cancel_reward_authority(
+        ctx: Context<CancelRewardAuthority>,
+        reward_index: u8,
+    ) -> Result<()> {
+        instructions::cancel_reward_authority(ctx, reward_index)
+    }
+
     /// Update rewards info of the given pool, can be called for everyone
     ///
     /// # Arguments
@@ -223,8 +546,8 @@ pub mod amm_v3 {
     /// * `amount_0_requested` - The maximum amount of token_0 to send, can be 0 to collect fees in only token_1
     /// * `amount_1_requested` - The maximum amount of token_1 to send, can be 0 to collect fees in only token_0
     ///
-    pub fn collect_protocol_fee(
-        ctx: Context<CollectProtocolFee>,
+    pub fn collect_protocol_fee<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectProtocolFee<'info>>,
         amount_0_requested: u64,
         amount_1_requested: u64,
     ) -> Result<()> {
@@ -239,8 +562,8 @@ pub mod amm_v3 {
     /// * `amount_0_requested` - The maximum amount of token_0 to send, can be 0 to collect fees in only token_1
     /// * `amount_1_requested` - The maximum amount of token_1 to send, can be 0 to collect fees in only token_0
     ///
-    pub fn collect_fund_fee(
-        ctx: Context<CollectFundFee>,
+    pub fn collect_fund_fee<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectFundFee<'info>>,
         amount_0_requested: u64,
         amount_1_requested: u64,
     ) -> Result<()> {
@@ -257,8 +580,8 @@ pub mod amm_v3 {
     /// * `tick_array_lower_start_index` - The start index of tick array which include tick low
     /// * `tick_array_upper_start_index` - The start index of tick array which include tick upper
     /// * `liquidity` - The liquidity to be added
-    /// * `amount_0_max` - The max amount of token_0 to spend, which serves as a slippage check
-    /// * `amount_1_max` - The max amount of token_1 to spend, which serves as a slippage check
+    /// * `amount_0_max` - The max amount of token_0 to spend including transfer fee, which serves as a slippage check
+    /// * `amount_1_max` - The max amount of token_1 to spend including transfer fee, which serves as a slippage check
     ///
     pub fn open_position<'a, 'b, 'c, 'info>(
         ctx: Context<'a, 'b, 'c, 'info, OpenPosition<'info>>,
@@ -282,7 +605,45 @@ pub mod amm_v3 {
         )
     }
 
-    /// Close a position, the nft mint and nft account
+    /// Creates a new position wrapped in a token program 2022 NFT, whose metadata is stored in the mint
+    /// with the metadata pointer and token metadata extensions instead of a metaplex metadata account
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `tick_lower_index` - The low boundary of market
+    /// * `tick_upper_index` - The upper boundary of market
+    /// * `tick_array_lower_start_index` - The start index of tick array which include tick low
+    /// * `tick_array_upper_start_index` - The start index of tick array which include tick upper
+    /// * `liquidity` - The liquidity to be added
+    /// * `amount_0_max` - The max amount of token_0 to spend including transfer fee, which serves as a slippage check
+    /// * `amount_1_max` - The max amount of token_1 to spend including transfer fee, which serves as a slippage check
+    ///
+    pub fn open_position_with_token22_nft<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, OpenPositionWithToken22Nft<'info>>,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        tick_array_lower_start_index: i32,
+        tick_array_upper_start_index: i32,
+        liquidity: u128,
+        amount_0_max: u64,
+        amount_1_max: u64,
+    ) -> Result<()> {
+        instructions::open_position_with_token22_nft(
+            ctx,
+            liquidity,
+            amount_0_max,
+            amount_1_max,
+            tick_lower_index,
+            tick_upper_index,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+        )
+    }
+
+    /// Close a position, the nft mint and nft account.
+    /// Takes the protocol position of the range after the personal position, to drop the personal
+    /// position from its count, clients built before must add it
     ///
     /// # Arguments
     ///
@@ -300,8 +661,8 @@ pub mod amm_v3 {
     ///
     /// * `ctx` - The context of accounts
     /// * `liquidity` - The desired liquidity to be added
-    /// * `amount_0_max` - The max amount of token_0 to spend, which serves as a slippage check
-    /// * `amount_1_max` - The max amount of token_1 to spend, which serves as a slippage check
+    /// * `amount_0_max` - The max amount of token_0 to spend including transfer fee, which serves as a slippage check
+    /// * `amount_1_max` - The max amount of token_1 to spend including transfer fee, which serves as a slippage check
     ///
     #[access_control(is_authorized_for_token(& ctx.accounts.nft_owner, & ctx.accounts.nft_account))]
     pub fn increase_liquidity<'a, 'b, 'c, 'info>(
@@ -319,8 +680,8 @@ pub mod amm_v3 {
     ///
     /// * `ctx` -  The context of accounts
     /// * `liquidity` - The amount by which liquidity will be decreased
-    /// * `amount_0_min` - The minimum amount of token_0 that should be accounted for the burned liquidity
-    /// * `amount_1_min` - The minimum amount of token_1 that should be accounted for the burned liquidity
+    /// * `amount_0_min` - The minimum amount of token_0 that should be received for the burned liquidity after transfer fee
+    /// * `amount_1_min` - The minimum amount of token_1 that should be received for the burned liquidity after transfer fee
     ///
     #[access_control(is_authorized_for_token(& ctx.accounts.nft_owner, & ctx.accounts.nft_account))]
     pub fn decrease_liquidity<'a, 'b, 'c, 'info>(
@@ -332,13 +693,145 @@ pub mod amm_v3 {
         instructions::decrease_liquidity(ctx, liquidity, amount_0_min, amount_1_min)
     }
 
+    /// Collects the fees owed to a position without touching its liquidity or rewards
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_0_requested` - The max amount of token_0 fees to collect
+    /// * `amount_1_requested` - The max amount of token_1 fees to collect
+    /// * `recipient` - The owner of the token accounts receiving the fees
+    ///
+    #[access_control(is_authorized_for_token(& ctx.accounts.nft_owner, & ctx.accounts.nft_account))]
+    pub fn collect_fees<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectFees<'info>>,
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+        recipient: Pubkey,
+    ) -> Result<()> {
+        instructions::collect_fees(ctx, amount_0_requested, amount_1_requested, recipient)
+    }
+
+    /// Collects the rewards owed to a position without touching its liquidity or fees
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `reward_mask` - The bits of the reward indexes to collect, the accounts of each
+    /// collected reward are passed by remaining accounts in the order of reward index, after the
+    /// reward extensions of the pool, and followed by the accounts of the extension rewards to collect
+    ///
+    #[access_control(is_authorized_for_token(& ctx.accounts.nft_owner, & ctx.accounts.nft_account))]
+    pub fn collect_rewards<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectRewards<'info>>,
+        reward_mask: u8,
+    ) -> Result<()> {
+        instructions::collect_rewards(ctx, reward_mask)
+    }
+
+    /// Reinvests the fees owed to a position into its liquidity at the current price, the fees
+    /// that can't be added as liquidity are returned to the position owner.
+    /// Called by the position owner or the compound keeper approved by the current owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `liquidity_min` - The minimum liquidity the fees must add, for slippage
+    ///
+    pub fn compound_position<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CompoundPosition<'info>>,
+        liquidity_min: u128,
+    ) -> Result<()> {
+        instructions::compound_position(ctx, liquidity_min)
+    }
+
+    /// Approves a keeper to compound the fees of a position, or revokes it, the approval lapses once
+    /// the position changes hands
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `compound_keeper` - The keeper to approve, the default pubkey to revoke
+    ///
+    #[access_control(is_authorized_for_token(& ctx.accounts.nft_owner, & ctx.accounts.nft_account))]
+    pub fn set_compound_keeper(
+        ctx: Context<SetCompoundKeeper>,
+        compound_keeper: Pubkey,
+    ) -> Result<()> {
+        instructions::set_compound_keeper(ctx, compound_keeper)
+    }
+
+    /// Places or cancels a range order on a position spanning a single tick spacing, which sells
+    /// token_0 once the price moves above the range, or token_1 once the price moves below it
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `range_order_type` - 1 to sell token_0, 2 to sell token_1, 0 to cancel the range order
+    ///
+    #[access_control(is_authorized_for_token(& ctx.accounts.nft_owner, & ctx.accounts.nft_account))]
+    pub fn set_range_order(ctx: Context<SetRangeOrder>, range_order_type: u8) -> Result<()> {
+        instructions::set_range_order(ctx, range_order_type)
+    }
+
+    /// Settles a filled range order, anyone can settle it. The liquidity is removed from the pool
+    /// and the filled amount is owed to the position, withdrawn by decrease liquidity
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn settle_range_order<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SettleRangeOrder<'info>>,
+    ) -> Result<()> {
+        instructions::settle_range_order(ctx)
+    }
+
+    /// Donates token_0 and token_1 to the liquidity in range of the pool, which accrues to the
+    /// positions in range like swap fees, paused with swaps
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_0` - The amount of token_0 to donate, including transfer fee
+    /// * `amount_1` - The amount of token_1 to donate, including transfer fee
+    ///
+    pub fn donate<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, Donate<'info>>,
+        amount_0: u64,
+        amount_1: u64,
+    ) -> Result<()> {
+        instructions::donate(ctx, amount_0, amount_1)
+    }
+
+    /// Lends token_0 and token_1 of the pool to the borrower and calls back the borrower's program,
+    /// which must repay the amounts plus the fees to the pool vaults before returning
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts, the transfer hook accounts of token_0 and token_1
+    /// come first in remaining accounts, followed by the accounts of the callback
+    /// * `amount_0` - The amount of token_0 to borrow
+    /// * `amount_1` - The amount of token_1 to borrow
+    /// * `data` - The instruction data of the callback, the fees of token_0 and token_1
+    /// are appended as little endian u64
+    ///
+    pub fn flash<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, Flash<'info>>,
+        amount_0: u64,
+        amount_1: u64,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        instructions::flash(ctx, amount_0, amount_1, data)
+    }
+
     /// Swaps one token for as much as possible of another token across a single pool
     ///
     /// # Arguments
     ///
     /// * `ctx` - The context of accounts
     /// * `amount` - Arranged in pairs with other_amount_threshold. (amount_in, amount_out_minimum) or (amount_out, amount_in_maximum)
-    /// * `other_amount_threshold` - For slippage check
+    /// * `other_amount_threshold` - For slippage check, compared with the amount after transfer fee
     /// * `sqrt_price_limit` - The Q64.64 sqrt price √P limit. If zero for one, the price cannot
     /// * `is_base_input` - swap base input or swap base output
     ///
@@ -358,19 +851,115 @@ pub mod amm_v3 {
         )
     }
 
-    /// Swap token for as much as possible of another token across the path provided, base input
+    /// Swap token for as much as possible of another token across the path provided, base input.
+    /// The remaining accounts of each pool of the path are `[amm_config, pool_state, output_token_account,
+    /// input_vault, output_vault, output_token_mint, output_token_program, observation_state, tick_array,
+    /// ...transfer hook accounts of input token, ...transfer hook accounts of output token,
+    /// tick_array_bitmap_extension if any, ...pool reward extensions, ...more tick arrays in swap direction]`.
     ///
     /// # Arguments
     ///
     /// * `ctx` - The context of accounts
     /// * `amount_in` - Token amount to be swapped in
-    /// * `amount_out_minimum` - Panic if output amount is below minimum amount. For slippage.
+    /// * `amount_out_minimum` - Panic if output amount received after transfer fee is below minimum amount. For slippage.
+    /// * `tick_array_counts` - The number of tick arrays of each pool of the path, the first one included
     ///
     pub fn swap_router_base_in<'a, 'b, 'c, 'info>(
         ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseIn<'info>>,
         amount_in: u64,
         amount_out_minimum: u64,
+        tick_array_counts: Vec<u8>,
+    ) -> Result<()> {
+        instructions::swap_router_base_in(ctx, amount_in, amount_out_minimum, tick_array_counts)
+    }
+
+    /// Swap token for an exact amount of another token across the path provided, base output.
+    /// The remaining accounts of the path have the same layout as `swap_router_base_in`.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_out` - Token amount to be received after transfer fee from the last pool of the path
+    /// * `amount_in_maximum` - Panic if input amount paid including transfer fee is above maximum amount. For slippage.
+    /// * `tick_array_counts` - The number of tick arrays of each pool of the path, the first one included
+    ///
+    pub fn swap_router_base_out<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseOut<'info>>,
+        amount_out: u64,
+        amount_in_maximum: u64,
+        tick_array_counts: Vec<u8>,
+    ) -> Result<()> {
+        instructions::swap_router_base_out(ctx, amount_out, amount_in_maximum, tick_array_counts)
+    }
+
+    /// Read the oracle of the pool, the cumulatives of price, tick and seconds per liquidity as of
+    /// each `seconds_agos` and the time weighted average price and tick between them are returned
+    /// by the return data
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `seconds_agos` - From how long ago each cumulative should be returned, in descending order
+    ///
+    pub fn observe(ctx: Context<Observe>, seconds_agos: Vec<u32>) -> Result<ObserveResult> {
+        instructions::observe(ctx, seconds_agos)
+    }
+
+    /// Grow the oracle of the pool to hold more observations, the observation account is
+    /// reallocated and the ring grows once its most recent observation is the last one.
+    /// The account can grow by about 196 observations in an instruction.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `observation_num_next` - The number of observations the oracle should hold
+    ///
+    pub fn increase_observation_num(
+        ctx: Context<IncreaseObservationNum>,
+        observation_num_next: u16,
     ) -> Result<()> {
-        instructions::swap_router_base_in(ctx, amount_in, amount_out_minimum)
+        instructions::increase_observation_num(ctx, observation_num_next)
+    }
+
+    /// Creates the tick array bitmap extension of the pool, which narrow tick spacing pools need
+    /// to open positions beyond the tick arrays the bitmap of the pool covers.
+    /// The extension is passed as the first remaining account of the instructions that need it.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn create_tick_array_bitmap_extension(
+        ctx: Context<CreateTickArrayBitmapExtension>,
+    ) -> Result<()> {
+        instructions::create_tick_array_bitmap_extension(ctx)
+    }
+
+    /// Closes a tick array without initialized tick nor tick of a protocol position, anyone can
+    /// call it. The rent is refunded to the payer of the tick array, the tick arrays created
+    /// before the payer was recorded can't be closed.
+    /// The tick array bitmap extension is passed as the first remaining account when the tick
+    /// array is beyond the bitmap of the pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn close_tick_array<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CloseTickArray<'info>>,
+    ) -> Result<()> {
+        instructions::close_tick_array(ctx)
+    }
+
+    /// Closes a protocol position without liquidity nor personal position, anyone can call it.
+    /// The rent is refunded to the payer of the protocol position, the protocol positions created
+    /// before the payer was recorded can't be closed.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn close_protocol_position(ctx: Context<CloseProtocolPosition>) -> Result<()> {
+        instructions::close_protocol_position(ctx)
     }
 }
@@ -324,5 +324,35 @@ mod liquidity_math_test {
                 get_delta_amounts_signed(current_tick, current_price, -6960, 4080, 100000).unwrap();
             println!("amount0:{}, amount1:{}", amount0, amount1)
         }
+
+        #[test]
+        fn get_amounts_delta_of_liquidity_from_amounts_test() {
+            // the amounts required by the liquidity from amounts never exceed the amounts
+            let current_tick = -1860;
+            let current_price = tick_math::get_sqrt_price_at_tick(current_tick).unwrap();
+            for (tick_lower, tick_upper) in
+                [(-6960, 4080), (-1920, -1800), (60, 120), (-4000, -2000)]
+            {
+                for (amount_0, amount_1) in [(1, 1), (1000, 7), (12345678, 87654321), (0, 999)] {
+                    let liquidity = get_liquidity_from_amounts(
+                        current_price,
+                        tick_math::get_sqrt_price_at_tick(tick_lower).unwrap(),
+                        tick_math::get_sqrt_price_at_tick(tick_upper).unwrap(),
+                        amount_0,
+                        amount_1,
+                    );
+                    let (amount_0_delta, amount_1_delta) = get_delta_amounts_signed(
+                        current_tick,
+                        current_price,
+                        tick_lower,
+                        tick_upper,
+                        i128::try_from(liquidity).unwrap(),
+                    )
+                    .unwrap();
+                    assert!(amount_0_delta as u64 <= amount_0);
+                    assert!(amount_1_delta as u64 <= amount_1);
+                }
+            }
+        }
     }
 }
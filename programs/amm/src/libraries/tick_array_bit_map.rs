@@ -5,8 +5,12 @@ use crate::libraries::tick_math;
 use crate::states::tick_array::{
     MAX_TICK_ARRAY_START_INDEX, MIN_TICK_ARRAY_START_INDEX, TICK_ARRAY_SIZE,
 };
+use crate::states::TickArrayBitmapExtension;
 use anchor_lang::{require, Result};
 
+/// Tick arrays each side of zero the bitmap of the pool covers
+pub const TICK_ARRAY_BITMAP_SIZE: i32 = 512;
+
 pub fn most_significant_bit(x: U1024) -> Option<u16> {
     if x.is_zero() {
         None
@@ -51,51 +55,144 @@ pub fn check_current_tick_array_is_initialized(
     return Ok((false, (compressed - 512) * multiplier));
 }
 
+/// Input a tick array start index or an arbitrary tick, output the index of the tick array it sits on
+pub fn get_tick_array_index(tick_index: i32, tick_spacing: i32) -> i32 {
+    let multiplier = tick_spacing * TICK_ARRAY_SIZE;
+    let mut tick_array_index = tick_index / multiplier;
+    if tick_index < 0 && tick_index % multiplier != 0 {
+        // round towards negative infinity
+        tick_array_index -= 1;
+    }
+    tick_array_index
+}
+
+/// Whether the tick array is beyond the bitmap of the pool and must be in the bitmap extension
+pub fn is_overflow_default_tickarray_bitmap(
+    tick_array_start_index: i32,
+    tick_spacing: i32,
+) -> bool {
+    let tick_array_index = get_tick_array_index(tick_array_start_index, tick_spacing);
+    !(-TICK_ARRAY_BITMAP_SIZE..TICK_ARRAY_BITMAP_SIZE).contains(&tick_array_index)
+}
+
+/// Returns the lowest set bit of the bitmap from the bit offset, inclusive
+pub fn next_set_bit<const N: usize>(bitmap: &[[u64; N]], from: usize) -> Option<usize> {
+    let word_count = bitmap.len() * N;
+    let mut word_index = from / 64;
+    if word_index >= word_count {
+        return None;
+    }
+    let mut word = bitmap[word_index / N][word_index % N] & (u64::MAX << (from % 64));
+    loop {
+        if word != 0 {
+            return Some(word_index * 64 + word.trailing_zeros() as usize);
+        }
+        word_index += 1;
+        if word_index == word_count {
+            return None;
+        }
+        word = bitmap[word_index / N][word_index % N];
+    }
+}
+
+/// Returns the highest set bit of the bitmap to the bit offset, inclusive
+pub fn previous_set_bit<const N: usize>(bitmap: &[[u64; N]], to: usize) -> Option<usize> {
+    let word_count = bitmap.len() * N;
+    let (mut word_index, mut word) = if to / 64 >= word_count {
+        (word_count - 1, bitmap[bitmap.len() - 1][N - 1])
+    } else {
+        let word_index = to / 64;
+        (
+            word_index,
+            bitmap[word_index / N][word_index % N] & (u64::MAX >> (63 - to % 64)),
+        )
+    };
+    loop {
+        if word != 0 {
+            return Some(word_index * 64 + 63 - word.leading_zeros() as usize);
+        }
+        if word_index == 0 {
+            return None;
+        }
+        word_index -= 1;
+        word = bitmap[word_index / N][word_index % N];
+    }
+}
+
+/// Search the next initialized tick array from the tick array the start index sits on, exclusive,
+/// across the bitmap of the pool and the bitmap extension.
+/// The extension is required once the search goes beyond the bitmap of the pool within the tick range.
 pub fn next_initialized_tick_array_start_index(
     bit_map: U1024,
+    tick_array_bitmap_extension: Option<&TickArrayBitmapExtension>,
     tick_array_start_index: i32,
     tick_spacing: i32,
     zero_for_one: bool,
-) -> Option<i32> {
+) -> Result<Option<i32>> {
     assert!(
         tick_array_start_index >= MIN_TICK_ARRAY_START_INDEX
             && tick_array_start_index <= MAX_TICK_ARRAY_START_INDEX
     );
-    let multiplier = i32::from(tick_spacing) * TICK_ARRAY_SIZE;
-    let mut compressed = tick_array_start_index / multiplier + 512;
-    if tick_array_start_index < 0 && tick_array_start_index % multiplier != 0 {
-        // round towards negative infinity
-        compressed -= 1;
-    }
-    let bit_pos = compressed.abs();
+    let multiplier = tick_spacing * TICK_ARRAY_SIZE;
+    let mut tick_array_index = get_tick_array_index(tick_array_start_index, tick_spacing);
+    let bit_map = [bit_map.0];
+    let get_extension =
+        || tick_array_bitmap_extension.ok_or(ErrorCode::MissingTickArrayBitmapExtensionAccount);
 
-    if zero_for_one {
+    let next_tick_array_index = if zero_for_one {
         // tick from upper to lower
-        // find from highter bits to lower bits
-        let offset_bit_map = bit_map << (1024 - bit_pos).try_into().unwrap();
-        let next_bit = most_significant_bit(offset_bit_map);
-        if next_bit.is_some() {
-            let next_array_start_index =
-                (bit_pos - 1 - i32::from(next_bit.unwrap()) - 512) * multiplier;
-            Some(next_array_start_index)
-        } else {
-            // not found til to the end
-            None
+        if tick_array_index > TICK_ARRAY_BITMAP_SIZE {
+            let next = get_extension()?.previous_positive_tick_array_index(tick_array_index);
+            if next.is_some() {
+                return Ok(next.map(|index| index * multiplier));
+            }
+            tick_array_index = TICK_ARRAY_BITMAP_SIZE;
+        }
+        if tick_array_index > -TICK_ARRAY_BITMAP_SIZE {
+            if (tick_array_index - 1) * multiplier < MIN_TICK_ARRAY_START_INDEX {
+                return Ok(None);
+            }
+            let next = previous_set_bit(
+                &bit_map,
+                (tick_array_index - 1 + TICK_ARRAY_BITMAP_SIZE) as usize,
+            );
+            if next.is_some() {
+                return Ok(next.map(|bit| (bit as i32 - TICK_ARRAY_BITMAP_SIZE) * multiplier));
+            }
+            tick_array_index = -TICK_ARRAY_BITMAP_SIZE;
+        }
+        if (tick_array_index - 1) * multiplier < MIN_TICK_ARRAY_START_INDEX {
+            return Ok(None);
         }
+        get_extension()?.previous_negative_tick_array_index(tick_array_index)
     } else {
         // tick from lower to upper
-        // find from lower bits to highter bits
-        let offset_bit_map = bit_map >> (bit_pos + 1).try_into().unwrap();
-        let next_bit = least_significant_bit(offset_bit_map);
-        if next_bit.is_some() {
-            let next_array_start_index =
-                (bit_pos + 1 + i32::from(next_bit.unwrap()) - 512) * multiplier;
-            Some(next_array_start_index)
-        } else {
-            // not found til to the end
-            None
+        if tick_array_index < -TICK_ARRAY_BITMAP_SIZE - 1 {
+            let next = get_extension()?.next_negative_tick_array_index(tick_array_index);
+            if next.is_some() {
+                return Ok(next.map(|index| index * multiplier));
+            }
+            tick_array_index = -TICK_ARRAY_BITMAP_SIZE - 1;
         }
-    }
+        if tick_array_index < TICK_ARRAY_BITMAP_SIZE - 1 {
+            if (tick_array_index + 1) * multiplier > MAX_TICK_ARRAY_START_INDEX {
+                return Ok(None);
+            }
+            let next = next_set_bit(
+                &bit_map,
+                (tick_array_index + 1 + TICK_ARRAY_BITMAP_SIZE) as usize,
+            );
+            if next.is_some() {
+                return Ok(next.map(|bit| (bit as i32 - TICK_ARRAY_BITMAP_SIZE) * multiplier));
+            }
+            tick_array_index = TICK_ARRAY_BITMAP_SIZE - 1;
+        }
+        if (tick_array_index + 1) * multiplier > MAX_TICK_ARRAY_START_INDEX {
+            return Ok(None);
+        }
+        get_extension()?.next_positive_tick_array_index(tick_array_index)
+    };
+    Ok(next_tick_array_index.map(|index| index * multiplier))
 }
 
 #[cfg(test)]
@@ -144,10 +241,12 @@ mod test {
         for _i in 0..5 {
             let array_start_index = next_initialized_tick_array_start_index(
                 bit_map,
+                None,
                 tick_array_start_index,
                 tick_spacing,
                 true,
-            );
+            )
+            .unwrap();
             println!("{:?}", array_start_index);
             if array_start_index.is_none() {
                 break;
@@ -164,10 +263,12 @@ mod test {
         for _i in 0..5 {
             let array_start_index = next_initialized_tick_array_start_index(
                 bit_map,
+                None,
                 tick_array_start_index,
                 tick_spacing,
                 true,
-            );
+            )
+            .unwrap();
             println!("{:?}", array_start_index);
             if array_start_index.is_none() {
                 break;
@@ -184,10 +285,12 @@ mod test {
         for _i in 0..5 {
             let array_start_index = next_initialized_tick_array_start_index(
                 bit_map,
+                None,
                 tick_array_start_index,
                 tick_spacing,
                 true,
-            );
+            )
+            .unwrap();
             println!("{:?}", array_start_index);
             if array_start_index.is_none() {
                 break;
@@ -205,10 +308,12 @@ mod test {
         for _i in 0..5 {
             let array_start_index = next_initialized_tick_array_start_index(
                 bit_map,
+                None,
                 tick_array_start_index,
                 tick_spacing,
                 false,
-            );
+            )
+            .unwrap();
             println!("{:?}", array_start_index);
             if array_start_index.is_none() {
                 break;
@@ -225,10 +330,12 @@ mod test {
         for _i in 0..5 {
             let array_start_index = next_initialized_tick_array_start_index(
                 bit_map,
+                None,
                 tick_array_start_index,
                 tick_spacing,
                 false,
-            );
+            )
+            .unwrap();
             println!("{:?}", array_start_index);
             if array_start_index.is_none() {
                 break;
@@ -245,10 +352,12 @@ mod test {
         for _i in 0..5 {
             let array_start_index = next_initialized_tick_array_start_index(
                 bit_map,
+                None,
                 tick_array_start_index,
                 tick_spacing,
                 false,
-            );
+            )
+            .unwrap();
             println!("{:?}", array_start_index);
             if array_start_index.is_none() {
                 break;
@@ -280,35 +389,75 @@ mod test {
             9223372036854775808,
         ];
         let mut array_start_index =
-            next_initialized_tick_array_start_index(U1024(bit_map), 0, tick_spacing, true);
+            next_initialized_tick_array_start_index(U1024(bit_map), None, 0, tick_spacing, true)
+                .unwrap();
         assert_eq!(array_start_index.unwrap(), -600);
         array_start_index =
-            next_initialized_tick_array_start_index(U1024(bit_map), -600, tick_spacing, true);
+            next_initialized_tick_array_start_index(U1024(bit_map), None, -600, tick_spacing, true)
+                .unwrap();
         assert_eq!(array_start_index.unwrap(), -1200);
-        array_start_index =
-            next_initialized_tick_array_start_index(U1024(bit_map), -1200, tick_spacing, true);
+        array_start_index = next_initialized_tick_array_start_index(
+            U1024(bit_map),
+            None,
+            -1200,
+            tick_spacing,
+            true,
+        )
+        .unwrap();
         assert_eq!(array_start_index.unwrap(), -1800);
-        array_start_index =
-            next_initialized_tick_array_start_index(U1024(bit_map), -1800, tick_spacing, true);
+        array_start_index = next_initialized_tick_array_start_index(
+            U1024(bit_map),
+            None,
+            -1800,
+            tick_spacing,
+            true,
+        )
+        .unwrap();
         assert_eq!(array_start_index.unwrap(), -38400);
-        array_start_index =
-            next_initialized_tick_array_start_index(U1024(bit_map), -38400, tick_spacing, true);
+        array_start_index = next_initialized_tick_array_start_index(
+            U1024(bit_map),
+            None,
+            -38400,
+            tick_spacing,
+            true,
+        )
+        .unwrap();
         assert_eq!(array_start_index.unwrap(), -39000);
-        array_start_index =
-            next_initialized_tick_array_start_index(U1024(bit_map), -39000, tick_spacing, true);
+        array_start_index = next_initialized_tick_array_start_index(
+            U1024(bit_map),
+            None,
+            -39000,
+            tick_spacing,
+            true,
+        )
+        .unwrap();
         assert_eq!(array_start_index.unwrap(), -307200);
 
         array_start_index =
-            next_initialized_tick_array_start_index(U1024(bit_map), 0, tick_spacing, false);
+            next_initialized_tick_array_start_index(U1024(bit_map), None, 0, tick_spacing, false)
+                .unwrap();
         assert_eq!(array_start_index.unwrap(), 600);
         array_start_index =
-            next_initialized_tick_array_start_index(U1024(bit_map), 600, tick_spacing, false);
+            next_initialized_tick_array_start_index(U1024(bit_map), None, 600, tick_spacing, false)
+                .unwrap();
         assert_eq!(array_start_index.unwrap(), 1200);
-        array_start_index =
-            next_initialized_tick_array_start_index(U1024(bit_map), 1200, tick_spacing, false);
+        array_start_index = next_initialized_tick_array_start_index(
+            U1024(bit_map),
+            None,
+            1200,
+            tick_spacing,
+            false,
+        )
+        .unwrap();
         assert_eq!(array_start_index.unwrap(), 38400);
-        array_start_index =
-            next_initialized_tick_array_start_index(U1024(bit_map), 38400, tick_spacing, false);
+        array_start_index = next_initialized_tick_array_start_index(
+            U1024(bit_map),
+            None,
+            38400,
+            tick_spacing,
+            false,
+        )
+        .unwrap();
         assert_eq!(array_start_index.unwrap(), 306600);
     }
 
@@ -319,19 +468,95 @@ mod test {
         let mut tick_array_start_index = MAX_TICK_ARRAY_START_INDEX;
         let array_start_index = next_initialized_tick_array_start_index(
             bit_map,
+            None,
             tick_array_start_index,
             tick_spacing,
             false,
-        );
+        )
+        .unwrap();
         assert!(array_start_index.is_none());
 
         tick_array_start_index = MIN_TICK_ARRAY_START_INDEX;
         let array_start_index = next_initialized_tick_array_start_index(
             bit_map,
+            None,
             tick_array_start_index,
             tick_spacing,
             true,
-        );
+        )
+        .unwrap();
         assert!(array_start_index.is_none());
     }
+
+    #[test]
+    fn next_initialized_tick_array_start_index_with_extension_test() {
+        let tick_spacing = 1;
+        let mut bit_map = U1024::default();
+        // tick arrays of -60 and 0 in the pool bitmap
+        bit_map.0[7] = 1 << 63;
+        bit_map.0[8] = 1;
+        let mut extension = TickArrayBitmapExtension::default();
+        for start_index in [-300000, -30780, 30720, 306600] {
+            extension
+                .flip_tick_array_bit(start_index, tick_spacing as u16)
+                .unwrap();
+        }
+
+        let mut expected = vec![-30780, -60, 0, 30720, 306600];
+        let mut start_index = -300000;
+        for next in expected.iter() {
+            start_index = next_initialized_tick_array_start_index(
+                bit_map,
+                Some(&extension),
+                start_index,
+                tick_spacing,
+                false,
+            )
+            .unwrap()
+            .unwrap();
+            assert_eq!(start_index, *next);
+        }
+        assert!(next_initialized_tick_array_start_index(
+            bit_map,
+            Some(&extension),
+            start_index,
+            tick_spacing,
+            false,
+        )
+        .unwrap()
+        .is_none());
+
+        expected.reverse();
+        expected.push(-300000);
+        for next in expected.iter().skip(1) {
+            start_index = next_initialized_tick_array_start_index(
+                bit_map,
+                Some(&extension),
+                start_index,
+                tick_spacing,
+                true,
+            )
+            .unwrap()
+            .unwrap();
+            assert_eq!(start_index, *next);
+        }
+        assert!(next_initialized_tick_array_start_index(
+            bit_map,
+            Some(&extension),
+            start_index,
+            tick_spacing,
+            true,
+        )
+        .unwrap()
+        .is_none());
+
+        // the search beyond the pool bitmap requires the extension
+        assert!(
+            next_initialized_tick_array_start_index(bit_map, None, 0, tick_spacing, false).is_err()
+        );
+        assert_eq!(
+            next_initialized_tick_array_start_index(bit_map, None, 0, tick_spacing, true).unwrap(),
+            Some(-60)
+        );
+    }
 }
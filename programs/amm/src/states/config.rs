@@ -2,6 +2,7 @@ use crate::error::ErrorCode;
 use anchor_lang::prelude::*;
 
 pub const AMM_CONFIG_SEED: &str = "amm_config";
+pub const AMM_CONFIG_OWNER_PROPOSAL_SEED: &str = "amm_config_owner_proposal";
 
 pub const FEE_RATE_DENOMINATOR_VALUE: u32 = 1_000_000;
 
@@ -42,6 +43,99 @@ impl AmmConfig {
         );
         Ok(())
     }
+
+    /// Sets the trade fee rate, must be less than the fee rate denominator
+    pub fn set_trade_fee_rate(&mut self, trade_fee_rate: u32) -> Result<()> {
+        require_gt!(
+            FEE_RATE_DENOMINATOR_VALUE,
+            trade_fee_rate,
+            ErrorCode::InvalidTradeFeeRate
+        );
+        self.trade_fee_rate = trade_fee_rate;
+        Ok(())
+    }
+
+    /// Sets the protocol fee rate, the protocol and fund fees are shares of the trade fee
+    pub fn set_protocol_fee_rate(&mut self, protocol_fee_rate: u32) -> Result<()> {
+        require_gte!(
+            u64::from(FEE_RATE_DENOMINATOR_VALUE),
+            u64::from(protocol_fee_rate) + u64::from(self.fund_fee_rate),
+            ErrorCode::InvalidProtocolFeeRate
+        );
+        self.protocol_fee_rate = protocol_fee_rate;
+        Ok(())
+    }
+
+    /// Sets the fund fee rate, the protocol and fund fees are shares of the trade fee
+    pub fn set_fund_fee_rate(&mut self, fund_fee_rate: u32) -> Result<()> {
+        require_gte!(
+            u64::from(FEE_RATE_DENOMINATOR_VALUE),
+            u64::from(fund_fee_rate) + u64::from(self.protocol_fee_rate),
+            ErrorCode::InvalidFundFeeRate
+        );
+        self.fund_fee_rate = fund_fee_rate;
+        Ok(())
+    }
+
+    /// Hands the owner or the fund owner over to `new_owner`, returns the previous one
+    pub fn transfer_owner(&mut self, owner_type: AmmConfigOwnerType, new_owner: Pubkey) -> Pubkey {
+        match owner_type {
+            AmmConfigOwnerType::Owner => std::mem::replace(&mut self.owner, new_owner),
+            AmmConfigOwnerType::FundOwner => std::mem::replace(&mut self.fund_owner, new_owner),
+        }
+    }
+}
+
+/// The owners of an amm config that change hands through a proposal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum AmmConfigOwnerType {
+    /// The owner of the config, collects the protocol fees
+    #[default]
+    Owner,
+    /// The fund owner of the config, collects the fund fees
+    FundOwner,
+}
+
+/// The owner proposed for an amm config, the proposed key takes over once it signs to accept.
+/// PDA of `[AMM_CONFIG_OWNER_PROPOSAL_SEED, amm_config, owner_type]`
+#[account]
+#[derive(Default, Debug)]
+pub struct AmmConfigOwnerProposal {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The config whose owner is proposed
+    pub amm_config: Pubkey,
+    /// The owner the proposal changes
+    pub owner_type: AmmConfigOwnerType,
+    /// The proposed owner
+    pub pending_owner: Pubkey,
+    /// Pays the rent of the proposal and receives it back once accepted or cancelled
+    pub payer: Pubkey,
+}
+
+impl AmmConfigOwnerProposal {
+    pub const LEN: usize = 8 + 1 + 32 + 1 + 32 + 32;
+
+    /// Proposes `new_owner`, a new proposal records its config, owner type and payer while a
+    /// pending one only has its proposed owner replaced
+    pub fn propose(
+        &mut self,
+        bump: u8,
+        amm_config: Pubkey,
+        owner_type: AmmConfigOwnerType,
+        payer: Pubkey,
+        new_owner: Pubkey,
+    ) -> Result<()> {
+        require_keys_neq!(new_owner, Pubkey::default(), ErrorCode::InvalidPendingOwner);
+        if self.payer == Pubkey::default() {
+            self.bump = bump;
+            self.amm_config = amm_config;
+            self.owner_type = owner_type;
+            self.payer = payer;
+        }
+        self.pending_owner = new_owner;
+        Ok(())
+    }
 }
 
 /// Emitted when create a config
@@ -66,3 +160,120 @@ pub struct UpdaterConfigEvent {
     pub fund_fee_rate: u32,
     pub fund_owner: Pubkey,
 }
+
+/// Emitted when a new owner or fund owner is proposed for a config
+#[event]
+pub struct ProposeAmmConfigOwnerEvent {
+    #[index]
+    pub amm_config: Pubkey,
+    pub owner_type: AmmConfigOwnerType,
+    pub pending_owner: Pubkey,
+}
+
+/// Emitted when the proposed owner or fund owner of a config accepts
+#[event]
+pub struct AcceptAmmConfigOwnerEvent {
+    #[index]
+    pub amm_config: Pubkey,
+    pub owner_type: AmmConfigOwnerType,
+    pub old_owner: Pubkey,
+    pub new_owner: Pubkey,
+}
+
+/// Emitted when the proposed owner or fund owner of a config is withdrawn before it accepts
+#[event]
+pub struct CancelAmmConfigOwnerEvent {
+    #[index]
+    pub amm_config: Pubkey,
+    pub owner_type: AmmConfigOwnerType,
+    pub pending_owner: Pubkey,
+}
+
+#[cfg(test)]
+mod amm_config_test {
+    use super::*;
+
+    #[test]
+    fn set_fee_rates_test() {
+        let mut amm_config = AmmConfig::default();
+        amm_config.set_trade_fee_rate(2500).unwrap();
+        assert!(amm_config
+            .set_trade_fee_rate(FEE_RATE_DENOMINATOR_VALUE)
+            .is_err());
+        assert_eq!(amm_config.trade_fee_rate, 2500);
+
+        amm_config.set_protocol_fee_rate(120000).unwrap();
+        amm_config.set_fund_fee_rate(880000).unwrap();
+        // the protocol and fund fee rates exceed the trade fee in total
+        assert!(amm_config.set_fund_fee_rate(880001).is_err());
+        assert!(amm_config.set_protocol_fee_rate(u32::MAX).is_err());
+        assert_eq!(amm_config.protocol_fee_rate, 120000);
+        assert_eq!(amm_config.fund_fee_rate, 880000);
+    }
+
+    #[test]
+    fn propose_and_accept_owner_test() {
+        let amm_config_key = Pubkey::new_unique();
+        let admin = Pubkey::new_unique();
+        let first_owner = Pubkey::new_unique();
+        let new_owner = Pubkey::new_unique();
+
+        let mut proposal = AmmConfigOwnerProposal::default();
+        assert_eq!(
+            proposal
+                .propose(
+                    1,
+                    amm_config_key,
+                    AmmConfigOwnerType::FundOwner,
+                    admin,
+                    Pubkey::default()
+                )
+                .unwrap_err(),
+            ErrorCode::InvalidPendingOwner.into()
+        );
+        proposal
+            .propose(
+                1,
+                amm_config_key,
+                AmmConfigOwnerType::FundOwner,
+                admin,
+                first_owner,
+            )
+            .unwrap();
+        // proposing again only replaces the proposed owner
+        proposal
+            .propose(
+                2,
+                Pubkey::new_unique(),
+                AmmConfigOwnerType::Owner,
+                Pubkey::new_unique(),
+                new_owner,
+            )
+            .unwrap();
+        assert_eq!(proposal.bump, 1);
+        assert_eq!(proposal.amm_config, amm_config_key);
+        assert_eq!(proposal.owner_type, AmmConfigOwnerType::FundOwner);
+        assert_eq!(proposal.payer, admin);
+        assert_eq!(proposal.pending_owner, new_owner);
+
+        let old_fund_owner = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mut amm_config = AmmConfig {
+            owner,
+            fund_owner: old_fund_owner,
+            ..Default::default()
+        };
+        assert_eq!(
+            amm_config.transfer_owner(proposal.owner_type, proposal.pending_owner),
+            old_fund_owner
+        );
+        assert_eq!(amm_config.fund_owner, new_owner);
+        assert_eq!(amm_config.owner, owner);
+
+        assert_eq!(
+            amm_config.transfer_owner(AmmConfigOwnerType::Owner, new_owner),
+            owner
+        );
+        assert_eq!(amm_config.owner, new_owner);
+    }
+}
@@ -3,14 +3,20 @@ pub use operation_account::*;
 pub use oracle::*;
 pub use personal_position::*;
 pub use pool::*;
+pub use pool_reward_extension::*;
 pub use protocol_position::*;
 pub use tick_array::*;
+pub use tick_array_bitmap_extension::*;
+pub use timelock::*;
 
 pub mod config;
 pub mod operation_account;
 pub mod oracle;
 pub mod personal_position;
 pub mod pool;
+pub mod pool_reward_extension;
 pub mod protocol_position;
 pub mod tick_array;
+pub mod tick_array_bitmap_extension;
+pub mod timelock;
 
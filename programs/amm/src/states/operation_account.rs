@@ -1,3 +1,4 @@
+use crate::error::ErrorCode;
 use anchor_lang::prelude::*;
 use std::collections::HashSet;
 
@@ -79,6 +80,57 @@ impl OperationState {
         // update
         self.whitelist_mints[0..whitelist_mints.len()].copy_from_slice(whitelist_mints.as_slice());
     }
+
+    /// Adds operation owners, the owners must fit in `OPERATION_SIZE_USIZE` after the update
+    pub fn add_operation_owners(&mut self, keys: &[Pubkey]) -> Result<()> {
+        check_keys_fit(
+            &self.operation_owners,
+            keys,
+            ErrorCode::OperationOwnersFull,
+        )?;
+        self.update_operation_owner(keys.to_vec());
+        Ok(())
+    }
+
+    pub fn remove_operation_owners(&mut self, keys: &[Pubkey]) -> Result<()> {
+        check_keys(keys)?;
+        self.remove_operation_owner(keys.to_vec());
+        Ok(())
+    }
+
+    /// Adds whitelist mints, the mints must fit in `WHITE_MINT_SIZE_USIZE` after the update
+    pub fn add_whitelist_mints(&mut self, keys: &[Pubkey]) -> Result<()> {
+        check_keys_fit(&self.whitelist_mints, keys, ErrorCode::WhitelistMintsFull)?;
+        self.update_whitelist_mint(keys.to_vec());
+        Ok(())
+    }
+
+    pub fn remove_whitelist_mints(&mut self, keys: &[Pubkey]) -> Result<()> {
+        check_keys(keys)?;
+        self.remove_whitelist_mint(keys.to_vec());
+        Ok(())
+    }
+}
+
+fn check_keys(keys: &[Pubkey]) -> Result<()> {
+    require!(
+        !keys.is_empty() && !keys.contains(&Pubkey::default()),
+        ErrorCode::InvalidOperationKeys
+    );
+    Ok(())
+}
+
+/// The distinct keys after adding `keys` to `slots` must fit in the slots
+fn check_keys_fit(slots: &[Pubkey], keys: &[Pubkey], full_error: ErrorCode) -> Result<()> {
+    check_keys(keys)?;
+    let key_num = slots
+        .iter()
+        .chain(keys)
+        .filter(|key| **key != Pubkey::default())
+        .collect::<HashSet<_>>()
+        .len();
+    require_gte!(slots.len(), key_num, full_error);
+    Ok(())
 }
 
 #[cfg(test)]
@@ -212,4 +264,40 @@ mod test {
         operation_state.remove_operation_owner(keys.clone());
         println!("{:?}", operation_state.operation_owners);
     }
+
+    #[test]
+    fn test_add_and_remove_keys() {
+        let mut operation_state = OperationState {
+            bump: 0,
+            operation_owners: [Pubkey::default(); OPERATION_SIZE_USIZE],
+            whitelist_mints: [Pubkey::default(); WHITE_MINT_SIZE_USIZE],
+        };
+        let keys: Vec<Pubkey> = (0..OPERATION_SIZE_USIZE)
+            .map(|_| Pubkey::new_unique())
+            .collect();
+        assert!(operation_state.add_operation_owners(&[]).is_err());
+        assert!(operation_state
+            .add_operation_owners(&[Pubkey::default()])
+            .is_err());
+
+        // the repeated keys fit
+        operation_state.add_operation_owners(&keys[..5]).unwrap();
+        operation_state.add_operation_owners(&keys).unwrap();
+        assert!(keys
+            .iter()
+            .all(|key| operation_state.validate_operation_owner(*key)));
+        assert!(operation_state
+            .add_operation_owners(&[Pubkey::new_unique()])
+            .is_err());
+
+        operation_state.remove_operation_owners(&keys[..1]).unwrap();
+        assert!(!operation_state.validate_operation_owner(keys[0]));
+        assert!(operation_state.remove_operation_owners(&[]).is_err());
+
+        let mint = Pubkey::new_unique();
+        operation_state.add_whitelist_mints(&[mint]).unwrap();
+        assert!(operation_state.validate_whitelist_mint(mint));
+        operation_state.remove_whitelist_mints(&[mint]).unwrap();
+        assert!(!operation_state.validate_whitelist_mint(mint));
+    }
 }
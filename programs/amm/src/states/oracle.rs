@@ -1,7 +1,10 @@
-use crate::libraries::{big_num::U128, fixed_point_64, full_math::MulDiv};
+use crate::error::ErrorCode;
+use crate::libraries::{big_num::U128, big_num::U256, fixed_point_64, full_math::MulDiv};
+use crate::states::PoolState;
 /// Oracle provides price data useful for a wide variety of system designs
 ///
 use anchor_lang::prelude::*;
+use std::cell::{Ref, RefMut};
 #[cfg(test)]
 use std::time::{SystemTime, UNIX_EPOCH};
 /// Seed to derive account address and signature
@@ -20,11 +23,85 @@ pub struct Observation {
     pub sqrt_price_x64: u128,
     /// the cumulative of price during the duration time, Q64.64
     pub cumulative_time_price_x64: u128,
-    /// padding for feature update
-    pub padding: u128,
+    /// the cumulative of tick during the duration time, may be flipped
+    pub tick_cumulative: i64,
+    /// the cumulative of seconds per liquidity during the duration time, the lower 64 bits of a Q64.64,
+    /// the difference of two of them is right as long as it is less than one second per liquidity
+    pub seconds_per_liquidity_cumulative_x64: u64,
 }
 impl Observation {
-    pub const LEN: usize = 4 + 16 + 16 + 16;
+    pub const LEN: usize = 4 + 16 + 16 + 8 + 8;
+}
+
+/// The cumulatives of an observation as of a given time
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ObservationCumulative {
+    pub cumulative_time_price_x64: u128,
+    pub tick_cumulative: i64,
+    pub seconds_per_liquidity_cumulative_x64: u64,
+}
+
+impl ObservationCumulative {
+    fn from_observation(observation: &Observation) -> Self {
+        Self {
+            cumulative_time_price_x64: observation.cumulative_time_price_x64,
+            tick_cumulative: observation.tick_cumulative,
+            seconds_per_liquidity_cumulative_x64: observation.seconds_per_liquidity_cumulative_x64,
+        }
+    }
+
+    /// Accumulate the price, tick and liquidity held during `delta_time` seconds, all the cumulatives may be flipped
+    fn accumulate(
+        &self,
+        delta_time: u32,
+        sqrt_price_x64: u128,
+        tick: i32,
+        liquidity: u128,
+    ) -> Self {
+        let price_x64 = U128::from(sqrt_price_x64)
+            .mul_div_floor(U128::from(sqrt_price_x64), U128::from(fixed_point_64::Q64))
+            .unwrap()
+            .as_u128();
+        // only the lower 64 bits are kept, the same as a flipped cumulative
+        let delta_seconds_per_liquidity_x64 =
+            ((u128::from(delta_time) << fixed_point_64::RESOLUTION) / liquidity.max(1)) as u64;
+        Self {
+            cumulative_time_price_x64: self
+                .cumulative_time_price_x64
+                .wrapping_add(price_x64.checked_mul(delta_time.into()).unwrap()),
+            tick_cumulative: self
+                .tick_cumulative
+                .wrapping_add(i64::from(tick) * i64::from(delta_time)),
+            seconds_per_liquidity_cumulative_x64: self
+                .seconds_per_liquidity_cumulative_x64
+                .wrapping_add(delta_seconds_per_liquidity_x64),
+        }
+    }
+
+    /// Interpolate linearly `elapsed` seconds of the `duration` seconds from `self` to `after`
+    fn interpolate(&self, after: &Self, elapsed: u32, duration: u32) -> Self {
+        let delta_price = after
+            .cumulative_time_price_x64
+            .wrapping_sub(self.cumulative_time_price_x64);
+        let delta_tick = after.tick_cumulative.wrapping_sub(self.tick_cumulative);
+        let delta_seconds_per_liquidity = after
+            .seconds_per_liquidity_cumulative_x64
+            .wrapping_sub(self.seconds_per_liquidity_cumulative_x64);
+        Self {
+            cumulative_time_price_x64: self.cumulative_time_price_x64.wrapping_add(
+                (U256::from(delta_price) * U256::from(elapsed) / U256::from(duration)).as_u128(),
+            ),
+            tick_cumulative: self.tick_cumulative.wrapping_add(
+                (i128::from(delta_tick) * i128::from(elapsed) / i128::from(duration)) as i64,
+            ),
+            seconds_per_liquidity_cumulative_x64: self
+                .seconds_per_liquidity_cumulative_x64
+                .wrapping_add(
+                    (u128::from(delta_seconds_per_liquidity) * u128::from(elapsed)
+                        / u128::from(duration)) as u64,
+                ),
+        }
+    }
 }
 
 #[account(zero_copy(unsafe))]
@@ -35,8 +112,14 @@ pub struct ObservationState {
     pub pool_id: Pubkey,
     /// observation array
     pub observations: [Observation; OBSERVATION_NUM],
+    /// The number of observations in the ring, 0 means OBSERVATION_NUM.
+    /// Observations beyond OBSERVATION_NUM are the extension appended to the end of the account
+    pub observation_num: u16,
+    /// The number of observations the account holds, the ring grows to it once the
+    /// most recent observation is the last one of the ring
+    pub observation_num_next: u16,
     /// padding for feature update
-    pub padding: [u128; 5],
+    pub padding: [u8; 76],
 }
 
 impl Default for ObservationState {
@@ -46,23 +129,111 @@ impl Default for ObservationState {
             initialized: false,
             pool_id: Pubkey::default(),
             observations: [Observation::default(); OBSERVATION_NUM],
-            padding: [0u128; 5],
+            observation_num: 0,
+            observation_num_next: 0,
+            padding: [0u8; 76],
         }
     }
 }
 
 impl ObservationState {
-    pub const LEN: usize = 8 + 1 + 32 + (Observation::LEN * OBSERVATION_NUM) + 16 * 5;
+    pub const LEN: usize = 8 + 1 + 32 + (Observation::LEN * OBSERVATION_NUM) + 2 + 2 + 76;
+
+    /// Load the observation state with the extension observations appended after it
+    pub fn load_mut<'a>(
+        account_info: &'a AccountInfo,
+    ) -> Result<(RefMut<'a, Self>, RefMut<'a, [Observation]>)> {
+        let observation_loader = AccountLoader::<ObservationState>::try_from(account_info)?;
+        let observation_num = observation_loader.load()?.observation_num_next();
+        require_eq!(
+            account_info.data_len(),
+            Self::account_len(observation_num),
+            ErrorCode::InvalidObservationNum
+        );
+        let data = account_info.try_borrow_mut_data()?;
+        Ok(RefMut::map_split(data, |data| {
+            let (state, extension) = data.split_at_mut(Self::LEN);
+            (
+                bytemuck::from_bytes_mut(&mut state[8..]),
+                bytemuck::cast_slice_mut(extension),
+            )
+        }))
+    }
+
+    /// Load the observation state with the extension observations appended after it, read only
+    pub fn load<'a>(
+        account_info: &'a AccountInfo,
+    ) -> Result<(Ref<'a, Self>, Ref<'a, [Observation]>)> {
+        let observation_loader = AccountLoader::<ObservationState>::try_from(account_info)?;
+        let observation_num = observation_loader.load()?.observation_num_next();
+        require_eq!(
+            account_info.data_len(),
+            Self::account_len(observation_num),
+            ErrorCode::InvalidObservationNum
+        );
+        let data = account_info.try_borrow_data()?;
+        Ok(Ref::map_split(data, |data| {
+            let (state, extension) = data.split_at(Self::LEN);
+            (
+                bytemuck::from_bytes(&state[8..]),
+                bytemuck::cast_slice(extension),
+            )
+        }))
+    }
+
+    /// The account length to hold `observation_num` observations
+    pub fn account_len(observation_num: usize) -> usize {
+        Self::LEN + Observation::LEN * (observation_num.max(OBSERVATION_NUM) - OBSERVATION_NUM)
+    }
+
+    /// The number of observations in the ring
+    pub fn observation_num(&self) -> usize {
+        if self.observation_num == 0 {
+            OBSERVATION_NUM
+        } else {
+            self.observation_num as usize
+        }
+    }
+
+    /// The number of observations the account holds
+    pub fn observation_num_next(&self) -> usize {
+        self.observation_num()
+            .max(self.observation_num_next as usize)
+    }
+
+    fn observation(&self, extension: &[Observation], index: usize) -> Observation {
+        if index < OBSERVATION_NUM {
+            self.observations[index]
+        } else {
+            extension[index - OBSERVATION_NUM]
+        }
+    }
+
+    fn observation_mut<'a>(
+        &'a mut self,
+        extension: &'a mut [Observation],
+        index: usize,
+    ) -> &'a mut Observation {
+        if index < OBSERVATION_NUM {
+            &mut self.observations[index]
+        } else {
+            &mut extension[index - OBSERVATION_NUM]
+        }
+    }
 
     // Writes an oracle observation to the account, returning the next observation_index.
     /// Writable at most once per second. Index represents the most recently written element.
-    /// If the index is at the end of the allowable array length (1000 - 1), the next index will turn to 0.
+    /// If the index is at the end of the ring, the next index will turn to 0, unless the account holds more
+    /// observations, then the ring grows and the next index is the first observation of the grown part.
     ///
     /// # Arguments
     ///
     /// * `self` - The ObservationState account to write in
+    /// * `extension` - The observations appended after the ObservationState account
     /// * `block_timestamp` - The current timestamp of to update
     /// * `sqrt_price_x64` - The sqrt_price_x64 at the time of the new observation
+    /// * `tick` - The tick at the time of the new observation
+    /// * `liquidity` - The active liquidity at the time of the new observation
     /// * `observation_index` - The last update index of element in the oracle array
     ///
     /// # Return
@@ -70,47 +241,243 @@ impl ObservationState {
     ///
     pub fn update_check(
         &mut self,
+        extension: &mut [Observation],
         block_timestamp: u32,
         sqrt_price_x64: u128,
+        tick: i32,
+        liquidity: u128,
         observation_index: u16,
         observation_update_duration: u32,
     ) -> Result<Option<u16>> {
-        if !self.initialized {
-            self.initialized = true;
-            self.observations[observation_index as usize].block_timestamp = block_timestamp;
-            self.observations[observation_index as usize].sqrt_price_x64 = sqrt_price_x64;
-            self.observations[observation_index as usize].cumulative_time_price_x64 = 0;
-            Ok(Some(observation_index))
-        } else {
-            let observation = self.observations[observation_index as usize];
+        if self.initialized {
+            let observation = self.observation(extension, observation_index as usize);
             let delta_time = block_timestamp.saturating_sub(observation.block_timestamp);
             if delta_time < observation_update_duration
                 || sqrt_price_x64 == observation.sqrt_price_x64
             {
                 return Ok(None);
             }
-            let cur_price_x64 = U128::from(sqrt_price_x64)
-                .mul_div_floor(U128::from(sqrt_price_x64), U128::from(fixed_point_64::Q64))
-                .unwrap()
-                .as_u128();
-            let delta_price_x64 = cur_price_x64.checked_mul(delta_time.into()).unwrap();
-            let next_observation_index = if observation_index as usize == OBSERVATION_NUM - 1 {
-                0
+        }
+        self.write(
+            extension,
+            block_timestamp,
+            sqrt_price_x64,
+            tick,
+            liquidity,
+            observation_index,
+        )
+    }
+
+    /// Writes an oracle observation before the active liquidity changes, regardless of the update
+    /// duration and of the price, so that the liquidity is the same between two observations and the
+    /// seconds per liquidity cumulative is right. Writable at most once per second, the arguments
+    /// and the return are the ones of `update_check`.
+    pub fn update_before_liquidity_change(
+        &mut self,
+        extension: &mut [Observation],
+        block_timestamp: u32,
+        sqrt_price_x64: u128,
+        tick: i32,
+        liquidity: u128,
+        observation_index: u16,
+    ) -> Result<Option<u16>> {
+        if self.initialized
+            && block_timestamp
+                <= self
+                    .observation(extension, observation_index as usize)
+                    .block_timestamp
+        {
+            return Ok(None);
+        }
+        self.write(
+            extension,
+            block_timestamp,
+            sqrt_price_x64,
+            tick,
+            liquidity,
+            observation_index,
+        )
+    }
+
+    fn write(
+        &mut self,
+        extension: &mut [Observation],
+        block_timestamp: u32,
+        sqrt_price_x64: u128,
+        tick: i32,
+        liquidity: u128,
+        observation_index: u16,
+    ) -> Result<Option<u16>> {
+        if !self.initialized {
+            self.initialized = true;
+            let observation = self.observation_mut(extension, observation_index as usize);
+            observation.block_timestamp = block_timestamp;
+            observation.sqrt_price_x64 = sqrt_price_x64;
+            observation.cumulative_time_price_x64 = 0;
+            observation.tick_cumulative = 0;
+            observation.seconds_per_liquidity_cumulative_x64 = 0;
+            Ok(Some(observation_index))
+        } else {
+            let observation = self.observation(extension, observation_index as usize);
+            let delta_time = block_timestamp.saturating_sub(observation.block_timestamp);
+            let next_observation_index = if observation_index as usize == self.observation_num() - 1
+            {
+                if self.observation_num_next() > self.observation_num() {
+                    // the most recent observation is the end of the ring, the ring can grow
+                    // without breaking the order of observations
+                    self.observation_num = self.observation_num_next() as u16;
+                    observation_index + 1
+                } else {
+                    0
+                }
             } else {
                 observation_index + 1
             };
-            self.observations[next_observation_index as usize].block_timestamp = block_timestamp;
-            self.observations[next_observation_index as usize].sqrt_price_x64 = sqrt_price_x64;
             // cumulative_time_price_x64 may be flipped because of 'observation.cumulative_time_price_x64 + delta_price_x64' is larger than std::u128::MAX;
             // if the current observation's cumulative_time_price_x64 is smaller then the previous's,
-            // the previous's real cumulative_time_price_x64 will be "cumulative_time_price_x64 + std::u128::MAX"
-            self.observations[next_observation_index as usize].cumulative_time_price_x64 =
-                observation
-                    .cumulative_time_price_x64
-                    .wrapping_add(delta_price_x64);
+            // the previous's real cumulative_time_price_x64 will be "cumulative_time_price_x64 + std::u128::MAX",
+            // tick_cumulative and seconds_per_liquidity_cumulative_x64 are flipped in the same way
+            let cumulative = ObservationCumulative::from_observation(&observation).accumulate(
+                delta_time,
+                sqrt_price_x64,
+                tick,
+                liquidity,
+            );
+            let next_observation = self.observation_mut(extension, next_observation_index as usize);
+            next_observation.block_timestamp = block_timestamp;
+            next_observation.sqrt_price_x64 = sqrt_price_x64;
+            next_observation.cumulative_time_price_x64 = cumulative.cumulative_time_price_x64;
+            next_observation.tick_cumulative = cumulative.tick_cumulative;
+            next_observation.seconds_per_liquidity_cumulative_x64 =
+                cumulative.seconds_per_liquidity_cumulative_x64;
             Ok(Some(next_observation_index))
         }
     }
+
+    /// Returns the cumulatives of price, tick and seconds per liquidity as of each `seconds_agos`
+    /// from `current_timestamp`. A cumulative between two observations is interpolated, a cumulative
+    /// after the most recent observation is extrapolated with the current state of the pool.
+    /// Like the cumulatives of observations, the returned values may be flipped, only the difference
+    /// between two of them is meaningful.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The ObservationState account to read
+    /// * `extension` - The observations appended after the ObservationState account
+    /// * `current_timestamp` - The current block timestamp
+    /// * `seconds_agos` - The amount of time to look back, in seconds, for each returned cumulative
+    /// * `sqrt_price_x64` - The current price of the pool
+    /// * `tick` - The current tick of the pool
+    /// * `liquidity` - The current active liquidity of the pool
+    /// * `observation_index` - The index of the most recently written observation
+    ///
+    pub fn observe(
+        &self,
+        extension: &[Observation],
+        current_timestamp: u32,
+        seconds_agos: &[u32],
+        sqrt_price_x64: u128,
+        tick: i32,
+        liquidity: u128,
+        observation_index: u16,
+    ) -> Result<Vec<ObservationCumulative>> {
+        require!(self.initialized, ErrorCode::ObservationNotInitialized);
+        let observation_num = self.observation_num();
+        let observation_index = observation_index as usize;
+        // the ring is full once the slot after the latest one has been written
+        let (oldest_index, count) = if self
+            .observation(extension, (observation_index + 1) % observation_num)
+            .block_timestamp
+            != 0
+        {
+            ((observation_index + 1) % observation_num, observation_num)
+        } else {
+            (0, observation_index + 1)
+        };
+        // block timestamps are truncated to 32 bits, ages are wrapping differences with the current time
+        let age = |i: usize| -> u32 {
+            current_timestamp.wrapping_sub(
+                self.observation(extension, (oldest_index + i) % observation_num)
+                    .block_timestamp,
+            )
+        };
+
+        let mut cumulatives = Vec::with_capacity(seconds_agos.len());
+        for &seconds_ago in seconds_agos {
+            let latest = self.observation(extension, observation_index);
+            let latest_age = age(count - 1);
+            let cumulative = if seconds_ago <= latest_age {
+                ObservationCumulative::from_observation(&latest).accumulate(
+                    latest_age - seconds_ago,
+                    sqrt_price_x64,
+                    tick,
+                    liquidity,
+                )
+            } else {
+                require_gte!(age(0), seconds_ago, ErrorCode::ObservationTooOld);
+                // binary search the most recent observation at or before the target time
+                let (mut low, mut high) = (0, count - 1);
+                while high - low > 1 {
+                    let mid = (low + high) / 2;
+                    if age(mid) >= seconds_ago {
+                        low = mid;
+                    } else {
+                        high = mid;
+                    }
+                }
+                let before = self.observation(extension, (oldest_index + low) % observation_num);
+                let after = self.observation(extension, (oldest_index + high) % observation_num);
+                let (before_age, after_age) = (age(low), age(high));
+                ObservationCumulative::from_observation(&before).interpolate(
+                    &ObservationCumulative::from_observation(&after),
+                    before_age - seconds_ago,
+                    before_age - after_age,
+                )
+            };
+            cumulatives.push(cumulative);
+        }
+        Ok(cumulatives)
+    }
+}
+
+/// The return data of the observe instruction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ObserveResult {
+    /// The cumulative of price as of each `seconds_agos`, Q64.64, may be flipped
+    pub cumulative_time_price_x64s: Vec<u128>,
+    /// The time weighted average price between each two consecutive `seconds_agos`, Q64.64
+    pub time_weighted_prices_x64: Vec<u128>,
+    /// The cumulative of tick as of each `seconds_agos`, may be flipped
+    pub tick_cumulatives: Vec<i64>,
+    /// The time weighted average tick between each two consecutive `seconds_agos`, rounded to negative infinity,
+    /// the price at this tick is the geometric mean price
+    pub time_weighted_ticks: Vec<i32>,
+    /// The cumulative of seconds per liquidity as of each `seconds_agos`, the lower 64 bits of a Q64.64, may be flipped
+    pub seconds_per_liquidity_cumulatives_x64: Vec<u64>,
+}
+
+/// Checkpoints the oracle of the pool with its current price, tick and active liquidity, to be called
+/// before the active liquidity of the pool changes
+pub fn update_observation_before_liquidity_change(
+    observation_info: &AccountInfo,
+    pool_state: &mut PoolState,
+    block_timestamp: u32,
+) -> Result<()> {
+    let (mut observation_state, mut observation_extension) =
+        ObservationState::load_mut(observation_info)?;
+    // check observation account is owned by the pool
+    require_keys_eq!(observation_state.pool_id, pool_state.key());
+    if let Some(index) = observation_state.update_before_liquidity_change(
+        &mut observation_extension,
+        block_timestamp,
+        pool_state.sqrt_price_x64,
+        pool_state.tick_current,
+        pool_state.liquidity,
+        pool_state.observation_index,
+    )? {
+        pool_state.observation_index = index;
+    }
+    Ok(())
 }
 
 /// Returns the block timestamp truncated to 32 bits, i.e. mod 2**32
@@ -130,8 +497,10 @@ pub fn block_timestamp_mock() -> u64 {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::libraries::{big_num::U256, get_sqrt_price_at_tick};
+    use crate::libraries::{big_num::U256, get_sqrt_price_at_tick, get_tick_at_sqrt_price};
     use crate::states::pool::OBSERVATION_UPDATE_DURATION_DEFAULT;
+
+    const LIQUIDITY: u128 = 1_000_000_000;
     #[test]
     fn test_update_check_init() {
         let block_timestamp = 1647424834 as u32;
@@ -141,8 +510,11 @@ mod test {
         let mut observation_state = ObservationState::default();
         let next_observation_index = observation_state
             .update_check(
+                &mut [],
                 block_timestamp,
                 sqrt_price_x64,
+                get_tick_at_sqrt_price(sqrt_price_x64).unwrap(),
+                LIQUIDITY,
                 observation_index,
                 observation_update_duration.into(),
             )
@@ -171,8 +543,11 @@ mod test {
         let mut observation_state = ObservationState::default();
         let next_observation_index = observation_state
             .update_check(
+                &mut [],
                 block_timestamp,
                 sqrt_price_x64,
+                get_tick_at_sqrt_price(sqrt_price_x64).unwrap(),
+                LIQUIDITY,
                 observation_index,
                 observation_update_duration.into(),
             )
@@ -202,8 +577,11 @@ mod test {
         let mut observation_state = ObservationState::default();
         let next_observation_index = observation_state
             .update_check(
+                &mut [],
                 block_timestamp,
                 sqrt_price_x64,
+                get_tick_at_sqrt_price(sqrt_price_x64).unwrap(),
+                LIQUIDITY,
                 observation_index,
                 observation_update_duration.into(),
             )
@@ -228,8 +606,11 @@ mod test {
         observation_index = next_observation_index.unwrap();
         let next_observation_index = observation_state
             .update_check(
+                &mut [],
                 block_timestamp,
                 sqrt_price_x64,
+                get_tick_at_sqrt_price(sqrt_price_x64).unwrap(),
+                LIQUIDITY,
                 observation_index,
                 observation_update_duration.into(),
             )
@@ -247,8 +628,11 @@ mod test {
         let mut observation_state = ObservationState::default();
         let next_observation_index = observation_state
             .update_check(
+                &mut [],
                 block_timestamp,
                 sqrt_price_x64,
+                get_tick_at_sqrt_price(sqrt_price_x64).unwrap(),
+                LIQUIDITY,
                 observation_index,
                 observation_update_duration.into(),
             )
@@ -273,8 +657,11 @@ mod test {
         observation_index = next_observation_index.unwrap();
         let next_observation_index = observation_state
             .update_check(
+                &mut [],
                 block_timestamp,
                 sqrt_price_x64,
+                get_tick_at_sqrt_price(sqrt_price_x64).unwrap(),
+                LIQUIDITY,
                 observation_index,
                 observation_update_duration.into(),
             )
@@ -292,8 +679,11 @@ mod test {
         let mut observation_state = ObservationState::default();
         let mut next_observation_index = observation_state
             .update_check(
+                &mut [],
                 block_timestamp,
                 sqrt_price_x64,
+                get_tick_at_sqrt_price(sqrt_price_x64).unwrap(),
+                LIQUIDITY,
                 observation_index,
                 observation_update_duration.into(),
             )
@@ -333,8 +723,11 @@ mod test {
         observation_index = next_observation_index.unwrap();
         next_observation_index = observation_state
             .update_check(
+                &mut [],
                 block_timestamp,
                 sqrt_price_x64,
+                get_tick_at_sqrt_price(sqrt_price_x64).unwrap(),
+                LIQUIDITY,
                 observation_index,
                 observation_update_duration.into(),
             )
@@ -365,8 +758,11 @@ mod test {
         let mut observation_state = ObservationState::default();
         let mut next_observation_index = observation_state
             .update_check(
+                &mut [],
                 block_timestamp,
                 sqrt_price_x64,
+                get_tick_at_sqrt_price(sqrt_price_x64).unwrap(),
+                LIQUIDITY,
                 observation_index,
                 observation_update_duration.into(),
             )
@@ -423,8 +819,11 @@ mod test {
         observation_index = next_observation_index.unwrap();
         next_observation_index = observation_state
             .update_check(
+                &mut [],
                 block_timestamp,
                 sqrt_price_x64,
+                get_tick_at_sqrt_price(sqrt_price_x64).unwrap(),
+                LIQUIDITY,
                 observation_index,
                 observation_update_duration.into(),
             )
@@ -444,4 +843,440 @@ mod test {
                 == expected
         );
     }
+
+    fn price_x64(sqrt_price_x64: u128) -> u128 {
+        U128::from(sqrt_price_x64)
+            .mul_div_floor(U128::from(sqrt_price_x64), U128::from(fixed_point_64::Q64))
+            .unwrap()
+            .as_u128()
+    }
+
+    /// Build an observation state with observations written at `block_timestamp + 15 * i`
+    /// for each price, return the index of the most recent observation
+    fn build_observation_state(
+        observation_state: &mut ObservationState,
+        block_timestamp: u32,
+        sqrt_prices_x64: &[u128],
+    ) -> u16 {
+        let mut observation_index = 0u16;
+        for (i, sqrt_price_x64) in sqrt_prices_x64.iter().enumerate() {
+            if let Some(next_observation_index) = observation_state
+                .update_check(
+                    &mut [],
+                    block_timestamp + 15 * i as u32,
+                    *sqrt_price_x64,
+                    get_tick_at_sqrt_price(*sqrt_price_x64).unwrap(),
+                    LIQUIDITY,
+                    observation_index,
+                    OBSERVATION_UPDATE_DURATION_DEFAULT.into(),
+                )
+                .unwrap()
+            {
+                observation_index = next_observation_index;
+            }
+        }
+        observation_index
+    }
+
+    #[test]
+    fn test_observe_interpolate() {
+        let block_timestamp: u32 = 1647424834;
+        let sqrt_prices_x64 = [
+            get_sqrt_price_at_tick(1000).unwrap(),
+            get_sqrt_price_at_tick(1010).unwrap(),
+            get_sqrt_price_at_tick(1020).unwrap(),
+        ];
+        let mut observation_state = ObservationState::default();
+        let observation_index =
+            build_observation_state(&mut observation_state, block_timestamp, &sqrt_prices_x64);
+        assert_eq!(observation_index, 2);
+
+        let cumulatives: Vec<u128> = observation_state
+            .observe(
+                &[],
+                block_timestamp + 30,
+                &[30, 15, 5, 0],
+                sqrt_prices_x64[2],
+                get_tick_at_sqrt_price(sqrt_prices_x64[2]).unwrap(),
+                LIQUIDITY,
+                observation_index,
+            )
+            .unwrap()
+            .iter()
+            .map(|cumulative| cumulative.cumulative_time_price_x64)
+            .collect();
+        let observations = observation_state.observations;
+        assert_eq!(cumulatives[0], 0);
+        assert_eq!(cumulatives[1], {
+            observations[1].cumulative_time_price_x64
+        });
+        assert_eq!(
+            cumulatives[2],
+            observations[1].cumulative_time_price_x64 + price_x64(sqrt_prices_x64[2]) * 10
+        );
+        assert_eq!(cumulatives[3], {
+            observations[2].cumulative_time_price_x64
+        });
+    }
+
+    #[test]
+    fn test_observe_extrapolate() {
+        let block_timestamp: u32 = 1647424834;
+        let sqrt_prices_x64 = [
+            get_sqrt_price_at_tick(1000).unwrap(),
+            get_sqrt_price_at_tick(1010).unwrap(),
+        ];
+        let mut observation_state = ObservationState::default();
+        let observation_index =
+            build_observation_state(&mut observation_state, block_timestamp, &sqrt_prices_x64);
+
+        let current_sqrt_price_x64 = get_sqrt_price_at_tick(1005).unwrap();
+        let cumulatives: Vec<u128> = observation_state
+            .observe(
+                &[],
+                block_timestamp + 25,
+                &[4, 0],
+                current_sqrt_price_x64,
+                get_tick_at_sqrt_price(current_sqrt_price_x64).unwrap(),
+                LIQUIDITY,
+                observation_index,
+            )
+            .unwrap()
+            .iter()
+            .map(|cumulative| cumulative.cumulative_time_price_x64)
+            .collect();
+        let latest_cumulative = observation_state.observations[1].cumulative_time_price_x64;
+        assert_eq!(
+            cumulatives[0],
+            latest_cumulative + price_x64(current_sqrt_price_x64) * 6
+        );
+        assert_eq!(
+            cumulatives[1],
+            latest_cumulative + price_x64(current_sqrt_price_x64) * 10
+        );
+    }
+
+    #[test]
+    fn test_observe_too_old() {
+        let block_timestamp: u32 = 1647424834;
+        let sqrt_prices_x64 = [
+            get_sqrt_price_at_tick(1000).unwrap(),
+            get_sqrt_price_at_tick(1010).unwrap(),
+        ];
+        let mut observation_state = ObservationState::default();
+        let observation_index =
+            build_observation_state(&mut observation_state, block_timestamp, &sqrt_prices_x64);
+
+        let result = observation_state.observe(
+            &[],
+            block_timestamp + 15,
+            &[16],
+            sqrt_prices_x64[1],
+            get_tick_at_sqrt_price(sqrt_prices_x64[1]).unwrap(),
+            LIQUIDITY,
+            observation_index,
+        );
+        assert_eq!(result.unwrap_err(), ErrorCode::ObservationTooOld.into());
+    }
+
+    #[test]
+    fn test_observe_flipped_and_turn_around() {
+        let block_timestamp: u32 = 1647424834;
+        let sqrt_price_x64 = get_sqrt_price_at_tick(10).unwrap();
+        let mut observation_state = ObservationState::default();
+        // the latest observation is the last element, the oldest one is the first element
+        let observation_index = (OBSERVATION_NUM - 1) as u16;
+        for i in 0..OBSERVATION_NUM {
+            observation_state.observations[i].block_timestamp = block_timestamp + 15 * i as u32;
+            observation_state.observations[i].cumulative_time_price_x64 =
+                (u128::MAX - 100).wrapping_add(price_x64(sqrt_price_x64) * 15 * i as u128);
+        }
+        observation_state.initialized = true;
+        let current_timestamp = block_timestamp + 15 * (OBSERVATION_NUM - 1) as u32;
+        let cumulatives: Vec<u128> = observation_state
+            .observe(
+                &[],
+                current_timestamp,
+                &[15 * (OBSERVATION_NUM - 1) as u32, 20, 0],
+                sqrt_price_x64,
+                get_tick_at_sqrt_price(sqrt_price_x64).unwrap(),
+                LIQUIDITY,
+                observation_index,
+            )
+            .unwrap()
+            .iter()
+            .map(|cumulative| cumulative.cumulative_time_price_x64)
+            .collect();
+        assert_eq!(cumulatives[0], u128::MAX - 100);
+        assert_eq!(
+            cumulatives[2].wrapping_sub(cumulatives[1]),
+            price_x64(sqrt_price_x64) * 20
+        );
+        assert_eq!(
+            cumulatives[2].wrapping_sub(cumulatives[0]),
+            price_x64(sqrt_price_x64) * 15 * (OBSERVATION_NUM - 1) as u128
+        );
+
+        // write one more observation, the oldest one is now the second element
+        observation_state.observations[0].block_timestamp = current_timestamp + 15;
+        let result = observation_state.observe(
+            &[],
+            current_timestamp + 15,
+            &[15 * (OBSERVATION_NUM - 1) as u32 + 1],
+            sqrt_price_x64,
+            get_tick_at_sqrt_price(sqrt_price_x64).unwrap(),
+            LIQUIDITY,
+            0,
+        );
+        assert_eq!(result.unwrap_err(), ErrorCode::ObservationTooOld.into());
+    }
+
+    #[test]
+    fn test_observe_tick_and_seconds_per_liquidity() {
+        let block_timestamp: u32 = 1647424834;
+        let sqrt_prices_x64 = [
+            get_sqrt_price_at_tick(-1000).unwrap(),
+            get_sqrt_price_at_tick(-1010).unwrap(),
+            get_sqrt_price_at_tick(-1020).unwrap(),
+        ];
+        let mut observation_state = ObservationState::default();
+        let observation_index =
+            build_observation_state(&mut observation_state, block_timestamp, &sqrt_prices_x64);
+        let observations = observation_state.observations;
+        let seconds_per_liquidity_x64 = ((15u128 << 64) / LIQUIDITY) as u64;
+        assert_eq!({ observations[1].tick_cumulative }, -1010 * 15);
+        assert_eq!({ observations[2].tick_cumulative }, -1010 * 15 - 1020 * 15);
+        assert_eq!(
+            { observations[2].seconds_per_liquidity_cumulative_x64 },
+            seconds_per_liquidity_x64 * 2
+        );
+
+        let current_tick = -1030;
+        let cumulatives = observation_state
+            .observe(
+                &[],
+                block_timestamp + 40,
+                &[40, 20, 5, 0],
+                get_sqrt_price_at_tick(current_tick).unwrap(),
+                current_tick,
+                LIQUIDITY * 2,
+                observation_index,
+            )
+            .unwrap();
+        assert_eq!(cumulatives[0], ObservationCumulative::default());
+        // interpolated between the second and the third observation
+        assert_eq!(cumulatives[1].tick_cumulative, -1010 * 15 - 1020 * 5);
+        assert_eq!(
+            cumulatives[1].seconds_per_liquidity_cumulative_x64,
+            seconds_per_liquidity_x64 + seconds_per_liquidity_x64 / 3
+        );
+        // extrapolated with the current tick and liquidity
+        assert_eq!(
+            cumulatives[3].tick_cumulative - cumulatives[2].tick_cumulative,
+            current_tick as i64 * 5
+        );
+        assert_eq!(
+            cumulatives[3].seconds_per_liquidity_cumulative_x64
+                - cumulatives[2].seconds_per_liquidity_cumulative_x64,
+            ((5u128 << 64) / (LIQUIDITY * 2)) as u64
+        );
+    }
+
+    #[test]
+    fn test_update_check_tick_and_seconds_per_liquidity_flipped() {
+        let block_timestamp: u32 = 1647424834;
+        let tick = -100;
+        let sqrt_price_x64 = get_sqrt_price_at_tick(tick).unwrap();
+        let mut observation_state = ObservationState::default();
+        observation_state
+            .update_check(
+                &mut [],
+                block_timestamp,
+                sqrt_price_x64,
+                tick,
+                0,
+                0,
+                OBSERVATION_UPDATE_DURATION_DEFAULT.into(),
+            )
+            .unwrap();
+        observation_state.observations[0].tick_cumulative = i64::MIN + 100;
+        observation_state.observations[0].seconds_per_liquidity_cumulative_x64 = u64::MAX - 100;
+
+        let next_observation_index = observation_state
+            .update_check(
+                &mut [],
+                block_timestamp + 15,
+                get_sqrt_price_at_tick(tick - 1).unwrap(),
+                tick - 1,
+                1 << 60,
+                0,
+                OBSERVATION_UPDATE_DURATION_DEFAULT.into(),
+            )
+            .unwrap();
+        assert_eq!(next_observation_index, Some(1));
+        let observation = observation_state.observations[1];
+        assert_eq!(
+            { observation.tick_cumulative },
+            (i64::MIN + 100).wrapping_add((tick as i64 - 1) * 15)
+        );
+        // 15 seconds per 2^60 liquidity is 15 << 4 in Q64.64
+        assert_eq!({ observation.seconds_per_liquidity_cumulative_x64 }, 139);
+    }
+
+    #[test]
+    fn test_update_check_grow_observation_num() {
+        let block_timestamp: u32 = 1647424834;
+        let mut observation_state = ObservationState::default();
+        let mut extension = [Observation::default(); 2];
+        let mut observation_index = 0u16;
+        // write OBSERVATION_NUM + 3 observations, the ring grows after the OBSERVATION_NUM-th one
+        // and turns around after the last extension observation
+        for i in 0..OBSERVATION_NUM + 3 {
+            if i == OBSERVATION_NUM / 2 {
+                observation_state.observation_num_next = (OBSERVATION_NUM + 2) as u16;
+            }
+            let tick = (i % 2) as i32;
+            if let Some(next_observation_index) = observation_state
+                .update_check(
+                    &mut extension,
+                    block_timestamp + 15 * i as u32,
+                    get_sqrt_price_at_tick(tick).unwrap(),
+                    tick,
+                    LIQUIDITY,
+                    observation_index,
+                    OBSERVATION_UPDATE_DURATION_DEFAULT.into(),
+                )
+                .unwrap()
+            {
+                observation_index = next_observation_index;
+            }
+            if i == OBSERVATION_NUM - 1 {
+                assert_eq!(observation_index as usize, OBSERVATION_NUM - 1);
+                assert_eq!(observation_state.observation_num(), OBSERVATION_NUM);
+            }
+        }
+        assert_eq!(observation_index, 0);
+        assert_eq!(observation_state.observation_num(), OBSERVATION_NUM + 2);
+        assert_eq!(
+            { extension[1].block_timestamp },
+            block_timestamp + 15 * (OBSERVATION_NUM + 1) as u32
+        );
+
+        // the oldest observation is the second one of the ring
+        let current_timestamp = block_timestamp + 15 * (OBSERVATION_NUM + 2) as u32;
+        let cumulatives = observation_state
+            .observe(
+                &extension,
+                current_timestamp,
+                &[15 * (OBSERVATION_NUM + 1) as u32, 20, 0],
+                get_sqrt_price_at_tick(0).unwrap(),
+                0,
+                LIQUIDITY,
+                observation_index,
+            )
+            .unwrap();
+        assert_eq!(cumulatives[0].cumulative_time_price_x64, {
+            observation_state.observations[1].cumulative_time_price_x64
+        });
+        // interpolated between the two extension observations
+        assert_eq!(
+            cumulatives[1].tick_cumulative - extension[0].tick_cumulative,
+            10
+        );
+        assert_eq!(cumulatives[2].tick_cumulative, {
+            observation_state.observations[0].tick_cumulative
+        });
+        let result = observation_state.observe(
+            &extension,
+            current_timestamp,
+            &[15 * (OBSERVATION_NUM + 1) as u32 + 1],
+            get_sqrt_price_at_tick(0).unwrap(),
+            0,
+            LIQUIDITY,
+            observation_index,
+        );
+        assert_eq!(result.unwrap_err(), ErrorCode::ObservationTooOld.into());
+    }
+
+    #[test]
+    fn test_update_before_liquidity_change() {
+        let block_timestamp: u32 = 1647424834;
+        let tick = 1000;
+        let sqrt_price_x64 = get_sqrt_price_at_tick(tick).unwrap();
+        let mut observation_state = ObservationState::default();
+        let observation_index = observation_state
+            .update_before_liquidity_change(
+                &mut [],
+                block_timestamp,
+                sqrt_price_x64,
+                tick,
+                LIQUIDITY,
+                0,
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(observation_index, 0);
+        assert!(observation_state.initialized);
+
+        // not twice in the same second
+        let next_observation_index = observation_state
+            .update_before_liquidity_change(
+                &mut [],
+                block_timestamp,
+                sqrt_price_x64,
+                tick,
+                LIQUIDITY,
+                observation_index,
+            )
+            .unwrap();
+        assert_eq!(next_observation_index, None);
+
+        // written within the update duration and at the same price, unlike update_check
+        let next_observation_index = observation_state
+            .update_check(
+                &mut [],
+                block_timestamp + 5,
+                sqrt_price_x64,
+                tick,
+                LIQUIDITY,
+                observation_index,
+                OBSERVATION_UPDATE_DURATION_DEFAULT.into(),
+            )
+            .unwrap();
+        assert_eq!(next_observation_index, None);
+        let observation_index = observation_state
+            .update_before_liquidity_change(
+                &mut [],
+                block_timestamp + 5,
+                sqrt_price_x64,
+                tick,
+                LIQUIDITY,
+                observation_index,
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(observation_index, 1);
+
+        // the liquidity doubled at the previous observation, the seconds since are accumulated
+        // with the doubled liquidity
+        let observation_index = observation_state
+            .update_before_liquidity_change(
+                &mut [],
+                block_timestamp + 15,
+                sqrt_price_x64,
+                tick,
+                LIQUIDITY * 2,
+                observation_index,
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(observation_index, 2);
+        let observations = observation_state.observations;
+        assert_eq!({ observations[2].block_timestamp }, block_timestamp + 15);
+        assert_eq!({ observations[2].tick_cumulative }, i64::from(tick) * 15);
+        assert_eq!(
+            { observations[2].seconds_per_liquidity_cumulative_x64 },
+            ((5u128 << 64) / LIQUIDITY) as u64 + ((10u128 << 64) / (LIQUIDITY * 2)) as u64
+        );
+    }
 }
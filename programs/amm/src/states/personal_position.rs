@@ -1,6 +1,8 @@
+use crate::error::ErrorCode;
 use crate::libraries::{big_num::U256, fixed_point_64, full_math::MulDiv};
 use crate::pool::REWARD_NUM;
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
 
 #[account]
 #[derive(Default, Debug)]
@@ -37,16 +39,96 @@ pub struct PersonalPositionState {
 
     // Position reward info
     pub reward_infos: [PositionRewardInfo; REWARD_NUM],
+
+    /// The range order type of the position, see `RangeOrderType`, 0 means not a range order
+    pub range_order_type: u8,
+
+    /// The time the range order was placed
+    pub range_order_open_time: u64,
+
+    /// The amount of token_0 of the settled range order, withdrawn by decrease liquidity
+    pub range_order_amount_0: u64,
+
+    /// The amount of token_1 of the settled range order, withdrawn by decrease liquidity
+    pub range_order_amount_1: u64,
+
+    /// The hash of the keeper approved to compound the fees of the position and of the owner who
+    /// approved it, so the approval lapses once the position changes hands, zero means none
+    pub compound_keeper_approval: [u8; 32],
+
     // Unused bytes for future upgrades.
-    pub padding: [u64; 8],
-    // pub range_order_type: u8,
-    // pub range_order_open_time: u64,
+    pub padding: [u8; 7],
+}
+
+/// A range order is a position spanning a single tick spacing which sells one token for the other
+/// as the price crosses it, and is settled once the price has fully crossed the range
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RangeOrderType {
+    /// Not a range order
+    None,
+    /// Sells token_0 for token_1, filled once the price moves above the upper tick
+    SellToken0,
+    /// Sells token_1 for token_0, filled once the price moves below the lower tick
+    SellToken1,
 }
 
 impl PersonalPositionState {
     pub const LEN: usize =
         8 + 1 + 32 + 32 + 4 + 4 + 16 + 16 + 16 + 8 + 8 + PositionRewardInfo::LEN * REWARD_NUM + 64;
 
+    pub fn get_range_order_type(&self) -> RangeOrderType {
+        match self.range_order_type {
+            1 => RangeOrderType::SellToken0,
+            2 => RangeOrderType::SellToken1,
+            _ => RangeOrderType::None,
+        }
+    }
+
+    /// Whether the range order can be placed at the current tick, it must span a single
+    /// tick spacing and only hold the token it sells
+    pub fn check_range_order_placement(&self, tick_current: i32, tick_spacing: u16) -> Result<()> {
+        require_eq!(
+            self.tick_upper_index - self.tick_lower_index,
+            i32::from(tick_spacing),
+            ErrorCode::InvalidRangeOrderRange
+        );
+        let placeable = match self.get_range_order_type() {
+            RangeOrderType::None => true,
+            RangeOrderType::SellToken0 => tick_current < self.tick_lower_index,
+            RangeOrderType::SellToken1 => tick_current >= self.tick_upper_index,
+        };
+        require!(placeable, ErrorCode::InvalidRangeOrderPrice);
+        Ok(())
+    }
+
+    /// Whether the price has fully crossed the range order, so it only holds the token it buys
+    pub fn is_range_order_filled(&self, tick_current: i32) -> bool {
+        match self.get_range_order_type() {
+            RangeOrderType::None => false,
+            RangeOrderType::SellToken0 => tick_current >= self.tick_upper_index,
+            RangeOrderType::SellToken1 => tick_current < self.tick_lower_index,
+        }
+    }
+
+    fn hash_compound_keeper(owner: &Pubkey, compound_keeper: &Pubkey) -> [u8; 32] {
+        hashv(&[owner.as_ref(), compound_keeper.as_ref()]).to_bytes()
+    }
+
+    /// Approves the keeper on behalf of the owner, the default pubkey revokes it
+    pub fn set_compound_keeper(&mut self, owner: &Pubkey, compound_keeper: &Pubkey) {
+        self.compound_keeper_approval = if *compound_keeper == Pubkey::default() {
+            [0; 32]
+        } else {
+            Self::hash_compound_keeper(owner, compound_keeper)
+        };
+    }
+
+    /// Whether `authority` is the keeper approved by the current owner of the position
+    pub fn is_compound_keeper(&self, owner: &Pubkey, authority: &Pubkey) -> bool {
+        self.compound_keeper_approval != [0; 32]
+            && self.compound_keeper_approval == Self::hash_compound_keeper(owner, authority)
+    }
+
     pub fn update_rewards(
         &mut self,
         reward_growths_inside: [u128; REWARD_NUM],
@@ -181,9 +263,183 @@ pub struct CollectPersonalFeeEvent {
     pub amount_1: u64,
 }
 
+/// Emitted when rewards are collected by the owner of a position
+#[event]
+pub struct CollectPersonalRewardEvent {
+    /// The ID of the token for which rewards were collected
+    #[index]
+    pub position_nft_mint: Pubkey,
+
+    /// The bits of the reward indexes requested to collect
+    pub reward_mask: u8,
+
+    /// The amount of each reward collected
+    pub reward_amounts: [u64; REWARD_NUM],
+}
+
 /// Emitted when Reward are updated for a pool
 #[event]
 pub struct UpdateRewardInfosEvent {
     /// Reward info
     pub reward_growth_global_x64: [u128; REWARD_NUM],
 }
+
+/// Emitted when a range order is placed or canceled
+#[event]
+pub struct SetRangeOrderEvent {
+    /// The pool of the range order
+    #[index]
+    pub pool_state: Pubkey,
+
+    /// The ID of the token of the position
+    #[index]
+    pub position_nft_mint: Pubkey,
+
+    /// The range order type after the update, 0 means canceled
+    pub range_order_type: u8,
+
+    /// The lower tick of the range order
+    pub tick_lower_index: i32,
+
+    /// The upper tick of the range order
+    pub tick_upper_index: i32,
+
+    /// The liquidity of the position
+    pub liquidity: u128,
+}
+
+/// Emitted when a filled range order is settled
+#[event]
+pub struct SettleRangeOrderEvent {
+    /// The pool of the range order
+    #[index]
+    pub pool_state: Pubkey,
+
+    /// The ID of the token of the position
+    #[index]
+    pub position_nft_mint: Pubkey,
+
+    /// The settled range order type
+    pub range_order_type: u8,
+
+    /// The liquidity removed from the pool
+    pub liquidity: u128,
+
+    /// The amount of token_0 owed to the position for the removed liquidity
+    pub amount_0: u64,
+
+    /// The amount of token_1 owed to the position for the removed liquidity
+    pub amount_1: u64,
+}
+
+/// Emitted when the owner approves or revokes the compound keeper of a position
+#[event]
+pub struct SetCompoundKeeperEvent {
+    /// The ID of the token of the position
+    #[index]
+    pub position_nft_mint: Pubkey,
+
+    /// The owner approving the keeper, the approval lapses once the position changes hands
+    pub nft_owner: Pubkey,
+
+    /// The approved keeper, default means revoked
+    pub compound_keeper: Pubkey,
+}
+
+/// Emitted when the fees of a position are compounded into its liquidity
+#[event]
+pub struct CompoundPositionEvent {
+    /// The ID of the token of the position
+    #[index]
+    pub position_nft_mint: Pubkey,
+
+    /// The owner or the approved keeper that compounded the position
+    pub authority: Pubkey,
+
+    /// The amount by which liquidity of the position was increased
+    pub liquidity: u128,
+
+    /// The amount of token_0 fees reinvested into the liquidity
+    pub amount_0: u64,
+
+    /// The amount of token_1 fees reinvested into the liquidity
+    pub amount_1: u64,
+
+    /// The amount of token_0 fees left over and returned to the owner
+    pub dust_amount_0: u64,
+
+    /// The amount of token_1 fees left over and returned to the owner
+    pub dust_amount_1: u64,
+}
+
+#[cfg(test)]
+mod range_order_test {
+    use super::*;
+
+    fn range_order(range_order_type: RangeOrderType) -> PersonalPositionState {
+        PersonalPositionState {
+            tick_lower_index: 600,
+            tick_upper_index: 660,
+            liquidity: 1000,
+            range_order_type: range_order_type as u8,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn range_order_placement_test() {
+        let position = range_order(RangeOrderType::SellToken0);
+        assert!(position.check_range_order_placement(599, 60).is_ok());
+        // the range already holds token_1
+        assert!(position.check_range_order_placement(600, 60).is_err());
+        // the range spans more than one tick spacing
+        assert!(position.check_range_order_placement(599, 10).is_err());
+
+        let position = range_order(RangeOrderType::SellToken1);
+        assert!(position.check_range_order_placement(660, 60).is_ok());
+        assert!(position.check_range_order_placement(659, 60).is_err());
+
+        // canceling is always allowed within a single tick spacing
+        let position = range_order(RangeOrderType::None);
+        assert!(position.check_range_order_placement(630, 60).is_ok());
+    }
+
+    #[test]
+    fn range_order_filled_test() {
+        let position = range_order(RangeOrderType::SellToken0);
+        assert!(!position.is_range_order_filled(600));
+        assert!(!position.is_range_order_filled(659));
+        assert!(position.is_range_order_filled(660));
+
+        let position = range_order(RangeOrderType::SellToken1);
+        assert!(!position.is_range_order_filled(659));
+        assert!(!position.is_range_order_filled(600));
+        assert!(position.is_range_order_filled(599));
+
+        let position = range_order(RangeOrderType::None);
+        assert!(!position.is_range_order_filled(599));
+        assert!(!position.is_range_order_filled(660));
+    }
+}
+
+#[cfg(test)]
+mod compound_keeper_test {
+    use super::*;
+
+    #[test]
+    fn compound_keeper_approval_test() {
+        let mut position = PersonalPositionState::default();
+        let (owner, compound_keeper) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert!(!position.is_compound_keeper(&owner, &Pubkey::default()));
+
+        position.set_compound_keeper(&owner, &compound_keeper);
+        assert!(position.is_compound_keeper(&owner, &compound_keeper));
+        assert!(!position.is_compound_keeper(&owner, &owner));
+        // the position changed hands
+        assert!(!position.is_compound_keeper(&Pubkey::new_unique(), &compound_keeper));
+
+        position.set_compound_keeper(&owner, &Pubkey::default());
+        assert!(!position.is_compound_keeper(&owner, &compound_keeper));
+        assert!(!position.is_compound_keeper(&owner, &Pubkey::default()));
+    }
+}
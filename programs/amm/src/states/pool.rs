@@ -3,9 +3,9 @@ use crate::libraries::{
     big_num::{U1024, U128},
     check_current_tick_array_is_initialized, fixed_point_64,
     full_math::MulDiv,
-    next_initialized_tick_array_start_index,
+    is_overflow_default_tickarray_bitmap, next_initialized_tick_array_start_index,
 };
-use crate::libraries::{tick_math, U256};
+use crate::libraries::{swap_math::{self, SwapStep}, tick_math, U256};
 use crate::states::*;
 use crate::states::{MAX_TICK_ARRAY_START_INDEX, MIN_TICK_ARRAY_START_INDEX, TICK_ARRAY_SIZE};
 use anchor_lang::prelude::*;
@@ -20,7 +20,15 @@ pub const POOL_VAULT_SEED: &str = "pool_vault";
 pub const POOL_REWARD_VAULT_SEED: &str = "pool_reward_vault";
 // Number of rewards Token
 pub const REWARD_NUM: usize = 3;
+// Number of emission phases a reward can schedule after its current one
+pub const REWARD_PHASE_NUM: usize = 3;
 pub const OBSERVATION_UPDATE_DURATION_DEFAULT: u16 = 15;
+// The denominator of the dynamic fee control
+pub const DYNAMIC_FEE_CONTROL_DENOMINATOR: u128 = 100_000;
+// The denominator of the volatility reduction factor
+pub const REDUCTION_FACTOR_DENOMINATOR: u16 = 10_000;
+// The max trade fee rate a pool can override, 10%
+pub const MAX_TRADE_FEE_RATE_OVERRIDE: u32 = 100_000;
 #[cfg(feature = "paramset")]
 pub mod reward_period_limit {
     pub const MIN_REWARD_PERIOD: u64 = 1 * 60 * 60;
@@ -40,6 +48,7 @@ pub enum PoolStatusBitIndex {
     CollectFee,
     CollectReward,
     Swap,
+    Flash,
 }
 
 #[derive(PartialEq, Eq)]
@@ -112,6 +121,7 @@ pub struct PoolState {
     /// bit2, 1: disable collect fee, 0: normal
     /// bit3, 1: disable collect reward, 0: normal
     /// bit4, 1: disable swap, 0: normal
+    /// bit5, 1: disable flash loan, 0: normal
     pub status: u8,
     /// Leave blank for future use
     pub padding: [u8; 7],
@@ -134,9 +144,34 @@ pub struct PoolState {
     // The timestamp allowed for swap in the pool.
     pub open_time: u64,
 
+    /// The volatility based dynamic fee of the pool, disabled by default
+    pub dynamic_fee_info: DynamicFeeInfo,
+
+    /// The trade fee rate of the pool instead of the one of amm config, 0 means not overridden
+    pub trade_fee_rate_override: u32,
+
+    /// The reentrancy lock, non-zero while a flash loan of the pool is out
+    pub locked: u16,
+
+    /// The number of pool reward extensions, the reward streams beyond the `REWARD_NUM` of the pool
+    pub reward_extension_num: u16,
+
+    /// The authority proposed for each reward, takes over the reward once it accepts, default if
+    /// none
+    pub reward_pending_authorities: [Pubkey; REWARD_NUM],
+
+    // Unused bytes for future upgrades.
+    pub padding1: [u64; 7],
+
+    /// The emission phases scheduled after the current emission rate of each reward, ordered by
+    /// start time, the unused ones are zero
+    pub reward_phases: [[RewardPhase; REWARD_PHASE_NUM]; REWARD_NUM],
+
+    /// The owner proposed for the pool, takes over once it accepts, default if none
+    pub pending_owner: Pubkey,
+
     // Unused bytes for future upgrades.
-    pub padding1: [u64; 25],
-    pub padding2: [u64; 32],
+    pub padding2: [u64; 1],
 }
 
 impl PoolState {
@@ -226,8 +261,15 @@ impl PoolState {
         self.fund_fees_token_0 = 0;
         self.fund_fees_token_1 = 0;
         self.open_time = open_time;
-        self.padding1 = [0; 25];
-        self.padding2 = [0; 32];
+        self.dynamic_fee_info = DynamicFeeInfo::default();
+        self.trade_fee_rate_override = 0;
+        self.locked = 0;
+        self.reward_extension_num = 0;
+        self.reward_pending_authorities = [Pubkey::default(); REWARD_NUM];
+        self.padding1 = [0; 7];
+        self.reward_phases = [[RewardPhase::default(); REWARD_PHASE_NUM]; REWARD_NUM];
+        self.pending_owner = Pubkey::default();
+        self.padding2 = [0; 1];
 
         let mut observation_state = observation_state_loader.load_mut()?;
         require_eq!(observation_state.initialized, false);
@@ -271,11 +313,17 @@ impl PoolState {
         open_time: u64,
         end_time: u64,
         reward_per_second_x64: u128,
+        reward_phases: &[RewardPhase],
         token_mint: &Pubkey,
         token_vault: &Pubkey,
         authority: &Pubkey,
         operation_state: &OperationState,
     ) -> Result<()> {
+        require_gte!(
+            REWARD_PHASE_NUM,
+            reward_phases.len(),
+            ErrorCode::InvalidRewardPhases
+        );
         let reward_infos = self.reward_infos;
         let lowest_index = match reward_infos.iter().position(|r| !r.initialized()) {
             Some(lowest_index) => lowest_index,
@@ -328,6 +376,8 @@ impl PoolState {
         self.reward_infos[lowest_index].token_mint = *token_mint;
         self.reward_infos[lowest_index].token_vault = *token_vault;
         self.reward_infos[lowest_index].authority = *authority;
+        self.reward_phases[lowest_index] = [RewardPhase::default(); REWARD_PHASE_NUM];
+        self.reward_phases[lowest_index][..reward_phases.len()].copy_from_slice(reward_phases);
         #[cfg(feature = "enable-log")]
         msg!(
             "reward_index:{}, reward_infos:{:?}",
@@ -347,69 +397,16 @@ impl PoolState {
         msg!("current block timestamp:{}", curr_timestamp);
 
         let mut next_reward_infos = self.reward_infos;
+        let mut next_reward_phases = self.reward_phases;
 
-        for i in 0..REWARD_NUM {
-            let reward_info = &mut next_reward_infos[i];
-            if !reward_info.initialized() {
-                continue;
-            }
-            if curr_timestamp <= reward_info.open_time {
-                continue;
-            }
-            let latest_update_timestamp = curr_timestamp.min(reward_info.end_time);
-
-            if self.liquidity != 0 {
-                let time_delta = latest_update_timestamp
-                    .checked_sub(reward_info.last_update_time)
-                    .unwrap();
-
-                let reward_growth_delta = U256::from(time_delta)
-                    .mul_div_floor(
-                        U256::from(reward_info.emissions_per_second_x64),
-                        U256::from(self.liquidity),
-                    )
-                    .unwrap();
-
-                reward_info.reward_growth_global_x64 = reward_info
-                    .reward_growth_global_x64
-                    .checked_add(reward_growth_delta.as_u128())
-                    .unwrap();
-
-                reward_info.reward_total_emissioned = reward_info
-                    .reward_total_emissioned
-                    .checked_add(
-                        U128::from(time_delta)
-                            .mul_div_ceil(
-                                U128::from(reward_info.emissions_per_second_x64),
-                                U128::from(fixed_point_64::Q64),
-                            )
-                            .unwrap()
-                            .as_u64(),
-                    )
-                    .unwrap();
-                #[cfg(feature = "enable-log")]
-                msg!(
-                    "reward_index:{},latest_update_timestamp:{},reward_info.reward_last_update_time:{},time_delta:{},reward_emission_per_second_x64:{},reward_growth_delta:{},reward_info.reward_growth_global_x64:{}",
-                    i,
-                    latest_update_timestamp,
-                    identity(reward_info.last_update_time),
-                    time_delta,
-                    identity(reward_info.emissions_per_second_x64),
-                    reward_growth_delta,
-                    identity(reward_info.reward_growth_global_x64)
-                );
-            }
-            reward_info.last_update_time = latest_update_timestamp;
-            // update reward state
-            if latest_update_timestamp >= reward_info.open_time
-                && latest_update_timestamp < reward_info.end_time
-            {
-                reward_info.reward_state = RewardState::Opening as u8;
-            } else if latest_update_timestamp == next_reward_infos[i].end_time {
-                next_reward_infos[i].reward_state = RewardState::Ended as u8;
-            }
+        for (reward_info, reward_phases) in next_reward_infos
+            .iter_mut()
+            .zip(next_reward_phases.iter_mut())
+        {
+            reward_info.update_with_phases(reward_phases, curr_timestamp, self.liquidity);
         }
         self.reward_infos = next_reward_infos;
+        self.reward_phases = next_reward_phases;
         #[cfg(feature = "enable-log")]
         msg!("update pool reward info, reward_0_total_emissioned:{}, reward_1_total_emissioned:{}, reward_2_total_emissioned:{}, pool.liquidity:{}",
         identity(self.reward_infos[0].reward_total_emissioned),identity(self.reward_infos[1].reward_total_emissioned),identity(self.reward_infos[2].reward_total_emissioned), identity(self.liquidity));
@@ -435,7 +432,161 @@ impl PoolState {
         Ok(())
     }
 
-    pub fn flip_tick_array_bit(&mut self, tick_array_start_index: i32) -> Result<()> {
+    /// Extends the end time of a running reward by the time the amount lasts at the emission rate
+    /// the reward ends with, the reward infos must be updated to the current time
+    pub fn fund_reward(&mut self, index: usize, amount: u64) -> Result<()> {
+        assert!(index < REWARD_NUM);
+        let mut reward_info = self.reward_infos[index];
+        require!(reward_info.initialized(), ErrorCode::UnInitializedRewardInfo);
+        require_gt!(
+            reward_info.end_time,
+            reward_info.last_update_time,
+            ErrorCode::RewardEmissionEnded
+        );
+        let emissions_per_second_x64 = self.reward_phases[index]
+            .iter()
+            .rev()
+            .find(|phase| phase.is_scheduled())
+            .map_or(reward_info.emissions_per_second_x64, |phase| {
+                phase.emissions_per_second_x64
+            });
+        let extend_period = U256::from(amount)
+            .mul_div_floor(
+                U256::from(fixed_point_64::Q64),
+                U256::from(emissions_per_second_x64),
+            )
+            .unwrap();
+        require!(
+            !extend_period.is_zero()
+                && extend_period <= U256::from(reward_period_limit::MAX_REWARD_PERIOD),
+            ErrorCode::InvalidRewardFundAmount
+        );
+        reward_info.end_time = reward_info
+            .end_time
+            .checked_add(extend_period.as_u64())
+            .unwrap();
+        self.reward_infos[index] = reward_info;
+        Ok(())
+    }
+
+    /// Stops the emissions of a running reward at the current time and drops its pending phases,
+    /// the reward infos must be updated to the current time
+    pub fn cancel_reward(&mut self, index: usize, curr_timestamp: u64) -> Result<()> {
+        assert!(index < REWARD_NUM);
+        let mut reward_info = self.reward_infos[index];
+        require!(reward_info.initialized(), ErrorCode::UnInitializedRewardInfo);
+        require_gt!(
+            reward_info.end_time,
+            reward_info.last_update_time,
+            ErrorCode::RewardEmissionEnded
+        );
+        if curr_timestamp < reward_info.open_time {
+            // the reward never opens
+            reward_info.open_time = curr_timestamp;
+            reward_info.last_update_time = curr_timestamp;
+        }
+        reward_info.end_time = reward_info.last_update_time;
+        reward_info.reward_state = RewardState::Ended as u8;
+        self.reward_infos[index] = reward_info;
+        self.reward_phases[index] = [RewardPhase::default(); REWARD_PHASE_NUM];
+        Ok(())
+    }
+
+    /// Counts a new reward extension of the pool, returns its index
+    pub fn add_reward_extension(&mut self) -> Result<u16> {
+        let reward_extension_index = self.reward_extension_num;
+        require_gt!(
+            MAX_REWARD_EXTENSION_NUM,
+            usize::from(reward_extension_index),
+            ErrorCode::RewardExtensionsFull
+        );
+        self.reward_extension_num = reward_extension_index + 1;
+        Ok(reward_extension_index)
+    }
+
+    /// Proposes the new owner of the pool, which takes over once it accepts
+    pub fn propose_owner(&mut self, new_owner: Pubkey) -> Result<()> {
+        require_keys_neq!(new_owner, Pubkey::default(), ErrorCode::InvalidPendingOwner);
+        self.pending_owner = new_owner;
+        Ok(())
+    }
+
+    /// Hands the pool over to its proposed owner, `signer` must be the proposed owner. Returns the
+    /// previous owner
+    pub fn accept_owner(&mut self, signer: Pubkey) -> Result<Pubkey> {
+        require_keys_neq!(
+            self.pending_owner,
+            Pubkey::default(),
+            ErrorCode::NoPendingOwner
+        );
+        require_keys_eq!(signer, self.pending_owner, ErrorCode::NotApproved);
+        let old_owner = self.owner;
+        self.owner = self.pending_owner;
+        self.pending_owner = Pubkey::default();
+        Ok(old_owner)
+    }
+
+    /// Withdraws the proposed owner of the pool, returns it
+    pub fn cancel_owner_proposal(&mut self) -> Result<Pubkey> {
+        let pending_owner = self.pending_owner;
+        require_keys_neq!(pending_owner, Pubkey::default(), ErrorCode::NoPendingOwner);
+        self.pending_owner = Pubkey::default();
+        Ok(pending_owner)
+    }
+
+    /// Proposes the new authority of the reward, which takes over the reward once it accepts
+    pub fn propose_reward_authority(&mut self, index: usize, new_authority: Pubkey) -> Result<()> {
+        assert!(index < REWARD_NUM);
+        require!(
+            self.reward_infos[index].initialized(),
+            ErrorCode::UnInitializedRewardInfo
+        );
+        require_keys_neq!(
+            new_authority,
+            Pubkey::default(),
+            ErrorCode::InvalidPendingOwner
+        );
+        self.reward_pending_authorities[index] = new_authority;
+        Ok(())
+    }
+
+    /// Hands the reward over to its proposed authority, `signer` must be the proposed authority.
+    /// Returns the previous authority
+    pub fn accept_reward_authority(&mut self, index: usize, signer: Pubkey) -> Result<Pubkey> {
+        assert!(index < REWARD_NUM);
+        let pending_authority = self.reward_pending_authorities[index];
+        require_keys_neq!(
+            pending_authority,
+            Pubkey::default(),
+            ErrorCode::NoPendingOwner
+        );
+        require_keys_eq!(signer, pending_authority, ErrorCode::NotApproved);
+        let old_authority = self.reward_infos[index].authority;
+        self.reward_infos[index].authority = pending_authority;
+        self.reward_pending_authorities[index] = Pubkey::default();
+        Ok(old_authority)
+    }
+
+    /// Withdraws the proposed authority of the reward, returns it
+    pub fn cancel_reward_authority(&mut self, index: usize) -> Result<Pubkey> {
+        assert!(index < REWARD_NUM);
+        let pending_authority = self.reward_pending_authorities[index];
+        require_keys_neq!(
+            pending_authority,
+            Pubkey::default(),
+            ErrorCode::NoPendingOwner
+        );
+        self.reward_pending_authorities[index] = Pubkey::default();
+        Ok(pending_authority)
+    }
+
+    /// Flips the initialized bit of the tick array, the tick arrays beyond the bitmap of the pool
+    /// are in the bitmap extension
+    pub fn flip_tick_array_bit(
+        &mut self,
+        tick_array_bitmap_extension: Option<&mut TickArrayBitmapExtension>,
+        tick_array_start_index: i32,
+    ) -> Result<()> {
         require!(
             tick_array_start_index >= MIN_TICK_ARRAY_START_INDEX
                 && tick_array_start_index <= MAX_TICK_ARRAY_START_INDEX,
@@ -445,6 +596,11 @@ impl PoolState {
             0,
             tick_array_start_index % (TICK_ARRAY_SIZE * i32::from(self.tick_spacing))
         );
+        if is_overflow_default_tickarray_bitmap(tick_array_start_index, self.tick_spacing.into()) {
+            return tick_array_bitmap_extension
+                .ok_or(ErrorCode::MissingTickArrayBitmapExtensionAccount)?
+                .flip_tick_array_bit(tick_array_start_index, self.tick_spacing);
+        }
         let tick_array_offset_in_bitmap =
             tick_array_start_index / (i32::from(self.tick_spacing) * TICK_ARRAY_SIZE) + 512;
         let tick_array_bitmap = U1024(self.tick_array_bitmap);
@@ -453,29 +609,160 @@ impl PoolState {
         Ok(())
     }
 
-    /// Search the first initialized tick array from pool current tick, if current tick array is initialized then direct return,
-    /// else find next according to the direction
-    pub fn get_first_initialized_tick_array(&self, zero_for_one: bool) -> Result<(bool, i32)> {
-        let (is_initialized, start_index) = check_current_tick_array_is_initialized(
+    /// Whether the bit of the tick array is set in the bitmap of the pool or the extension
+    pub fn is_tick_array_initialized(
+        &self,
+        tick_array_bitmap_extension: Option<&TickArrayBitmapExtension>,
+        tick_array_start_index: i32,
+    ) -> Result<bool> {
+        if is_overflow_default_tickarray_bitmap(tick_array_start_index, self.tick_spacing.into()) {
+            return tick_array_bitmap_extension
+                .ok_or(ErrorCode::MissingTickArrayBitmapExtensionAccount)?
+                .is_tick_array_initialized(tick_array_start_index, self.tick_spacing);
+        }
+        let (is_initialized, _) = check_current_tick_array_is_initialized(
             U1024(self.tick_array_bitmap),
-            self.tick_current,
+            tick_array_start_index,
             self.tick_spacing.into(),
         )?;
+        Ok(is_initialized)
+    }
+
+    /// Search the first initialized tick array from pool current tick, if current tick array is initialized then direct return,
+    /// else find next according to the direction
+    pub fn get_first_initialized_tick_array(
+        &self,
+        tick_array_bitmap_extension: Option<&TickArrayBitmapExtension>,
+        zero_for_one: bool,
+    ) -> Result<(bool, i32)> {
+        let (is_initialized, start_index) =
+            if is_overflow_default_tickarray_bitmap(self.tick_current, self.tick_spacing.into()) {
+                let start_index = TickArrayState::get_arrary_start_index(
+                    self.tick_current,
+                    self.tick_spacing.into(),
+                );
+                let is_initialized = tick_array_bitmap_extension
+                    .ok_or(ErrorCode::MissingTickArrayBitmapExtensionAccount)?
+                    .is_tick_array_initialized(start_index, self.tick_spacing)?;
+                (is_initialized, start_index)
+            } else {
+                check_current_tick_array_is_initialized(
+                    U1024(self.tick_array_bitmap),
+                    self.tick_current,
+                    self.tick_spacing.into(),
+                )?
+            };
         if is_initialized {
             return Ok((is_initialized, start_index));
         }
         let start_index = next_initialized_tick_array_start_index(
             U1024(self.tick_array_bitmap),
+            tick_array_bitmap_extension,
             self.tick_current,
             self.tick_spacing.into(),
             zero_for_one,
-        );
+        )?;
         if start_index.is_none() {
             return err!(ErrorCode::LiquidityInsufficient);
         }
         Ok((is_initialized, start_index.unwrap()))
     }
 
+    /// Overrides the trade fee rate of amm config, 0 removes the override. Returns the previous
+    /// override
+    pub fn set_trade_fee_rate_override(&mut self, trade_fee_rate_override: u32) -> Result<u32> {
+        require_gte!(
+            MAX_TRADE_FEE_RATE_OVERRIDE,
+            trade_fee_rate_override,
+            ErrorCode::InvalidTradeFeeRateOverride
+        );
+        let old_trade_fee_rate_override = self.trade_fee_rate_override;
+        self.trade_fee_rate_override = trade_fee_rate_override;
+        Ok(old_trade_fee_rate_override)
+    }
+
+    /// Returns the trade fee rate of the pool, the override of the pool if any,
+    /// otherwise the trade fee rate of amm config
+    pub fn get_trade_fee_rate(&self, amm_config_trade_fee_rate: u32) -> u32 {
+        if self.trade_fee_rate_override != 0 {
+            self.trade_fee_rate_override
+        } else {
+            amm_config_trade_fee_rate
+        }
+    }
+
+    /// Locks the pool, nothing but the lock owner can modify the pool until it is unlocked
+    pub fn lock(&mut self) -> Result<()> {
+        self.check_unlocked()?;
+        self.locked = 1;
+        Ok(())
+    }
+
+    pub fn unlock(&mut self) {
+        self.locked = 0;
+    }
+
+    pub fn check_unlocked(&self) -> Result<()> {
+        require!(self.locked == 0, ErrorCode::LOK);
+        Ok(())
+    }
+
+    /// Accrues the fees paid by a flash loan like swap fees, the protocol and fund
+    /// parts are split off and the rest goes to the in-range liquidity
+    pub fn add_flash_fees(
+        &mut self,
+        protocol_fee_rate: u32,
+        fund_fee_rate: u32,
+        fee_amount_0: u64,
+        fee_amount_1: u64,
+    ) -> Result<()> {
+        let split_fee = |fee_amount: u64, fee_rate: u32| {
+            U128::from(fee_amount)
+                .checked_mul(fee_rate.into())
+                .unwrap()
+                .checked_div(FEE_RATE_DENOMINATOR_VALUE.into())
+                .unwrap()
+                .as_u64()
+        };
+        let protocol_fee_0 = split_fee(fee_amount_0, protocol_fee_rate);
+        let fund_fee_0 = split_fee(fee_amount_0, fund_fee_rate);
+        let protocol_fee_1 = split_fee(fee_amount_1, protocol_fee_rate);
+        let fund_fee_1 = split_fee(fee_amount_1, fund_fee_rate);
+        self.add_liquidity_fees(
+            fee_amount_0 - protocol_fee_0 - fund_fee_0,
+            fee_amount_1 - protocol_fee_1 - fund_fee_1,
+        )?;
+
+        self.protocol_fees_token_0 = self.protocol_fees_token_0.checked_add(protocol_fee_0).unwrap();
+        self.fund_fees_token_0 = self.fund_fees_token_0.checked_add(fund_fee_0).unwrap();
+        self.protocol_fees_token_1 = self.protocol_fees_token_1.checked_add(protocol_fee_1).unwrap();
+        self.fund_fees_token_1 = self.fund_fees_token_1.checked_add(fund_fee_1).unwrap();
+        Ok(())
+    }
+
+    /// Distributes the fees to the in-range liquidity by the global fee growth, like swap fees
+    pub fn add_liquidity_fees(&mut self, fee_amount_0: u64, fee_amount_1: u64) -> Result<()> {
+        require_gt!(self.liquidity, 0, ErrorCode::LiquidityInsufficient);
+        let liquidity = self.liquidity;
+        let fee_growth_delta = |fee_amount: u64| {
+            U128::from(fee_amount)
+                .mul_div_floor(U128::from(fixed_point_64::Q64), U128::from(liquidity))
+                .unwrap()
+                .as_u128()
+        };
+        self.total_fees_token_0 = self.total_fees_token_0.checked_add(fee_amount_0).unwrap();
+        self.fee_growth_global_0_x64 = self
+            .fee_growth_global_0_x64
+            .checked_add(fee_growth_delta(fee_amount_0))
+            .unwrap();
+        self.total_fees_token_1 = self.total_fees_token_1.checked_add(fee_amount_1).unwrap();
+        self.fee_growth_global_1_x64 = self
+            .fee_growth_global_1_x64
+            .checked_add(fee_growth_delta(fee_amount_1))
+            .unwrap();
+        Ok(())
+    }
+
     pub fn set_status(&mut self, status: u8) {
         self.status = status
     }
@@ -490,6 +777,14 @@ impl PoolState {
         }
     }
 
+    /// Whether the status only disables more of swap and open position or increase liquidity,
+    /// the emergency pauses the admin can apply without the timelock
+    pub fn is_emergency_pause(&self, status: u8) -> bool {
+        let pause_bits = (1u8 << PoolStatusBitIndex::Swap as u8)
+            | (1u8 << PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity as u8);
+        status & self.status == self.status && (status ^ self.status) & !pause_bits == 0
+    }
+
     /// Get status by bit, if it is `noraml` status, return true
     pub fn get_status_by_bit(&self, bit: PoolStatusBitIndex) -> bool {
         let status = u8::from(1) << (bit as u8);
@@ -556,6 +851,87 @@ impl RewardInfo {
         self.token_mint.ne(&Pubkey::default())
     }
 
+    /// Accrues the emissions of the reward since the last update to the reward growth of the
+    /// given in-range liquidity, nothing to do if the reward is not initialized or not open yet
+    pub fn update(&mut self, curr_timestamp: u64, liquidity: u128) {
+        if !self.initialized() {
+            return;
+        }
+        if curr_timestamp <= self.open_time {
+            return;
+        }
+        let latest_update_timestamp = curr_timestamp.min(self.end_time);
+
+        if liquidity != 0 {
+            let time_delta = latest_update_timestamp
+                .checked_sub(self.last_update_time)
+                .unwrap();
+
+            let reward_growth_delta = U256::from(time_delta)
+                .mul_div_floor(
+                    U256::from(self.emissions_per_second_x64),
+                    U256::from(liquidity),
+                )
+                .unwrap();
+
+            self.reward_growth_global_x64 = self
+                .reward_growth_global_x64
+                .checked_add(reward_growth_delta.as_u128())
+                .unwrap();
+
+            self.reward_total_emissioned = self
+                .reward_total_emissioned
+                .checked_add(
+                    U128::from(time_delta)
+                        .mul_div_ceil(
+                            U128::from(self.emissions_per_second_x64),
+                            U128::from(fixed_point_64::Q64),
+                        )
+                        .unwrap()
+                        .as_u64(),
+                )
+                .unwrap();
+            #[cfg(feature = "enable-log")]
+            msg!(
+                "latest_update_timestamp:{},reward_info.reward_last_update_time:{},time_delta:{},reward_emission_per_second_x64:{},reward_growth_delta:{},reward_info.reward_growth_global_x64:{}",
+                latest_update_timestamp,
+                identity(self.last_update_time),
+                time_delta,
+                identity(self.emissions_per_second_x64),
+                reward_growth_delta,
+                identity(self.reward_growth_global_x64)
+            );
+        }
+        self.last_update_time = latest_update_timestamp;
+        // update reward state
+        if latest_update_timestamp >= self.open_time && latest_update_timestamp < self.end_time {
+            self.reward_state = RewardState::Opening as u8;
+        } else if latest_update_timestamp == self.end_time {
+            self.reward_state = RewardState::Ended as u8;
+        }
+    }
+
+    /// Accrues the emissions of the reward like `update`, switching the emission rate at the start
+    /// of each passed phase, the passed phases are removed from the schedule
+    pub fn update_with_phases(
+        &mut self,
+        reward_phases: &mut [RewardPhase; REWARD_PHASE_NUM],
+        curr_timestamp: u64,
+        liquidity: u128,
+    ) {
+        let passed_phase_num = reward_phases
+            .iter()
+            .take_while(|phase| phase.is_scheduled() && phase.start_time <= curr_timestamp)
+            .count();
+        for phase in reward_phases[..passed_phase_num].iter() {
+            self.update(phase.start_time, liquidity);
+            self.emissions_per_second_x64 = phase.emissions_per_second_x64;
+        }
+        reward_phases.rotate_left(passed_phase_num);
+        reward_phases[REWARD_PHASE_NUM - passed_phase_num..].fill(RewardPhase::default());
+        self.update(curr_timestamp, liquidity);
+    }
+
     pub fn get_reward_growths(reward_infos: &[RewardInfo; REWARD_NUM]) -> [u128; REWARD_NUM] {
         let mut reward_growths = [0u128; REWARD_NUM];
         for i in 0..REWARD_NUM {
@@ -565,6 +941,242 @@ impl RewardInfo {
     }
 }
 
+/// An emission rate of a reward taking effect at its start time, until the next phase or the end
+/// time of the reward
+#[zero_copy(unsafe)]
+#[repr(packed)]
+#[derive(Default, Debug, PartialEq, Eq)]
+pub struct RewardPhase {
+    /// The time the phase starts, 0 if the phase is not scheduled
+    pub start_time: u64,
+    /// Q64.64 number indicates how many tokens per second are earned per unit of liquidity.
+    pub emissions_per_second_x64: u128,
+}
+
+impl RewardPhase {
+    pub const LEN: usize = 8 + 16;
+
+    pub fn is_scheduled(&self) -> bool {
+        self.start_time != 0
+    }
+}
+
+/// The volatility based dynamic fee, the volatility is the distance in ticks the price moves from
+/// a reference tick, the reference decays over time after the price stops moving.
+/// The fee rate of a swap step is `trade_fee_rate + dynamic_fee_control * volatility^2 / DYNAMIC_FEE_CONTROL_DENOMINATOR`,
+/// capped at `max_fee_rate`.
+#[zero_copy(unsafe)]
+#[repr(packed)]
+#[derive(Default, Debug, PartialEq, Eq)]
+pub struct DynamicFeeInfo {
+    /// The cap of the fee rate, hundredths of a bip (10^-6), the dynamic fee is disabled if 0
+    pub max_fee_rate: u32,
+    /// Scales the square of the volatility to the dynamic part of the fee rate
+    pub dynamic_fee_control: u32,
+    /// The cap of the volatility accumulator
+    pub max_volatility_accumulator: u32,
+    /// Swaps within the filter period since the last swap don't update the references
+    pub filter_period: u16,
+    /// The volatility reference is reset if there is no swap within the decay period
+    pub decay_period: u16,
+    /// The part of the volatility accumulator kept by the volatility reference, in bps
+    pub reduction_factor: u16,
+    /// The tick that the volatility is measured from
+    pub tick_reference: i32,
+    /// The decayed volatility of the previous swaps
+    pub volatility_reference: u32,
+    /// The volatility of the most recent swap step
+    pub volatility_accumulator: u32,
+    /// The timestamp of the most recent swap
+    pub last_update_timestamp: u64,
+    /// Leave blank for future use
+    pub padding: [u8; 2],
+}
+
+impl DynamicFeeInfo {
+    pub const LEN: usize = 4 + 4 + 4 + 2 + 2 + 2 + 4 + 4 + 4 + 8 + 2;
+
+    pub fn is_enabled(&self) -> bool {
+        self.max_fee_rate != 0
+    }
+
+    /// Update the references at the beginning of a swap
+    pub fn update_references(&mut self, tick_current: i32, block_timestamp: u64) {
+        let elapsed = block_timestamp.saturating_sub(self.last_update_timestamp);
+        if elapsed >= self.filter_period.into() {
+            self.tick_reference = tick_current;
+            self.volatility_reference = if elapsed < self.decay_period.into() {
+                (u64::from(self.volatility_accumulator) * u64::from(self.reduction_factor)
+                    / u64::from(REDUCTION_FACTOR_DENOMINATOR)) as u32
+            } else {
+                0
+            };
+        }
+        self.last_update_timestamp = block_timestamp;
+    }
+
+    /// Update the volatility accumulator with the tick a swap step ends at
+    pub fn update_volatility_accumulator(&mut self, tick: i32) {
+        let delta_tick = (i64::from(tick) - i64::from(self.tick_reference)).unsigned_abs();
+        self.volatility_accumulator = (u64::from(self.volatility_reference) + delta_tick)
+            .min(self.max_volatility_accumulator.into())
+            as u32;
+    }
+
+    /// Computes a swap step charging the fee rate of the volatility at the tick the step ends.
+    /// The step is computed at the volatility of the target tick first, if it stops short of the
+    /// target, it is computed again at the volatility of the tick it stopped at.
+    pub fn compute_swap_step(
+        &mut self,
+        trade_fee_rate: u32,
+        sqrt_price_current_x64: u128,
+        sqrt_price_target_x64: u128,
+        liquidity: u128,
+        amount_remaining: u64,
+        is_base_input: bool,
+        zero_for_one: bool,
+    ) -> Result<SwapStep> {
+        let compute_swap_step = |fee_rate| {
+            swap_math::compute_swap_step(
+                sqrt_price_current_x64,
+                sqrt_price_target_x64,
+                liquidity,
+                amount_remaining,
+                fee_rate,
+                is_base_input,
+                zero_for_one,
+            )
+        };
+        if !self.is_enabled() {
+            return Ok(compute_swap_step(trade_fee_rate));
+        }
+        self.update_volatility_accumulator(tick_math::get_tick_at_sqrt_price(
+            sqrt_price_target_x64,
+        )?);
+        let swap_step = compute_swap_step(self.get_fee_rate(trade_fee_rate));
+        if swap_step.sqrt_price_next_x64 == sqrt_price_target_x64 {
+            return Ok(swap_step);
+        }
+        self.update_volatility_accumulator(tick_math::get_tick_at_sqrt_price(
+            swap_step.sqrt_price_next_x64,
+        )?);
+        Ok(compute_swap_step(self.get_fee_rate(trade_fee_rate)))
+    }
+
+    /// Returns the fee rate with the dynamic fee added to `trade_fee_rate`
+    pub fn get_fee_rate(&self, trade_fee_rate: u32) -> u32 {
+        if !self.is_enabled() {
+            return trade_fee_rate;
+        }
+        let volatility = u128::from(self.volatility_accumulator);
+        let dynamic_fee_rate = u128::from(self.dynamic_fee_control) * volatility * volatility
+            / DYNAMIC_FEE_CONTROL_DENOMINATOR;
+        (u128::from(trade_fee_rate) + dynamic_fee_rate)
+            .min(self.max_fee_rate.max(trade_fee_rate).into()) as u32
+    }
+}
+
+/// Emitted when the trade fee rate of a pool is overridden or the override is removed
+#[event]
+pub struct UpdatePoolTradeFeeRateEvent {
+    /// The pool whose trade fee rate is updated
+    #[index]
+    pub pool_state: Pubkey,
+
+    /// The admin or operation owner that updated the trade fee rate
+    pub authority: Pubkey,
+
+    /// The override before the update, 0 means not overridden
+    pub old_trade_fee_rate_override: u32,
+
+    /// The override after the update, 0 means not overridden
+    pub trade_fee_rate_override: u32,
+
+    /// The trade fee rate the pool charges after the update, not including the dynamic fee
+    pub trade_fee_rate: u32,
+}
+
+/// Emitted when a new owner is proposed for a pool
+#[event]
+pub struct ProposeRewardOwnerEvent {
+    /// The pool whose owner is proposed
+    #[index]
+    pub pool_state: Pubkey,
+
+    /// The proposed owner, replaces the previous proposal if any
+    pub pending_owner: Pubkey,
+}
+
+/// Emitted when the proposed owner of a pool accepts the pool
+#[event]
+pub struct AcceptRewardOwnerEvent {
+    /// The pool whose owner changes
+    #[index]
+    pub pool_state: Pubkey,
+
+    /// The owner before the change
+    pub old_owner: Pubkey,
+
+    /// The owner after the change
+    pub new_owner: Pubkey,
+}
+
+/// Emitted when the proposed owner of a pool is withdrawn before it accepts
+#[event]
+pub struct CancelRewardOwnerEvent {
+    /// The pool whose proposal is cancelled
+    #[index]
+    pub pool_state: Pubkey,
+
+    /// The proposed owner that can no longer accept
+    pub pending_owner: Pubkey,
+}
+
+/// Emitted when a new authority is proposed for a reward of a pool
+#[event]
+pub struct ProposeRewardAuthorityEvent {
+    /// The pool of the reward
+    #[index]
+    pub pool_state: Pubkey,
+
+    /// The index of the reward in the pool
+    pub reward_index: u8,
+
+    /// The proposed authority, replaces the previous proposal if any
+    pub pending_authority: Pubkey,
+}
+
+/// Emitted when the proposed authority of a reward accepts the reward
+#[event]
+pub struct AcceptRewardAuthorityEvent {
+    /// The pool of the reward
+    #[index]
+    pub pool_state: Pubkey,
+
+    /// The index of the reward in the pool
+    pub reward_index: u8,
+
+    /// The authority before the change
+    pub old_authority: Pubkey,
+
+    /// The authority after the change
+    pub new_authority: Pubkey,
+}
+
+/// Emitted when the proposed authority of a reward is withdrawn before it accepts
+#[event]
+pub struct CancelRewardAuthorityEvent {
+    /// The pool of the reward
+    #[index]
+    pub pool_state: Pubkey,
+
+    /// The index of the reward in the pool
+    pub reward_index: u8,
+
+    /// The proposed authority that can no longer accept
+    pub pending_authority: Pubkey,
+}
+
 /// Emitted when a pool is created and initialized with a starting price
 ///
 #[event]
@@ -615,6 +1227,58 @@ pub struct CollectProtocolFeeEvent {
     pub amount_1: u64,
 }
 
+/// Emitted when a flash loan of a pool is repaid
+#[event]
+pub struct FlashEvent {
+    /// The pool that lent the tokens
+    #[index]
+    pub pool_state: Pubkey,
+
+    /// The account that borrowed the tokens
+    pub borrower: Pubkey,
+
+    /// The program called back while the tokens were lent
+    pub callback_program: Pubkey,
+
+    /// The amount of token_0 lent
+    pub amount_0: u64,
+
+    /// The amount of token_1 lent
+    pub amount_1: u64,
+
+    /// The fee of token_0 required to repay
+    pub fee_0: u64,
+
+    /// The fee of token_1 required to repay
+    pub fee_1: u64,
+
+    /// The amount of token_0 paid on top of the lent amount, not less than the fee
+    pub paid_0: u64,
+
+    /// The amount of token_1 paid on top of the lent amount, not less than the fee
+    pub paid_1: u64,
+}
+
+/// Emitted when tokens are donated to the in-range liquidity of a pool
+#[event]
+pub struct DonateEvent {
+    /// The pool that receives the donation
+    #[index]
+    pub pool_state: Pubkey,
+
+    /// The donor
+    pub donor: Pubkey,
+
+    /// The amount of token_0 received by the pool vault after transfer fee
+    pub amount_0: u64,
+
+    /// The amount of token_1 received by the pool vault after transfer fee
+    pub amount_1: u64,
+
+    /// The in-range liquidity sharing the donation
+    pub liquidity: u128,
+}
+
 /// Emitted by when a swap is performed for a pool
 #[event]
 pub struct SwapEvent {
@@ -639,9 +1303,15 @@ pub struct SwapEvent {
     /// The delta of the token_0 balance of the pool
     pub amount_0: u64,
 
+    /// The transfer fee charged by the token_0 mint
+    pub transfer_fee_0: u64,
+
     /// The delta of the token_1 balance of the pool
     pub amount_1: u64,
 
+    /// The transfer fee charged by the token_1 mint
+    pub transfer_fee_1: u64,
+
     /// if true, amount_0 is negtive and amount_1 is positive
     pub zero_for_one: bool,
 
@@ -653,6 +1323,9 @@ pub struct SwapEvent {
 
     /// The log base 1.0001 of price of the pool after the swap
     pub tick: i32,
+
+    /// The trade fee rate of the last step of the swap, including the dynamic fee
+    pub fee_rate: u32,
 }
 
 /// Emitted pool liquidity change when increase and decrease liquidity
@@ -751,7 +1424,7 @@ pub mod pool_test {
         fn get_arrary_start_index_negative() {
             let mut pool_state = PoolState::default();
             pool_state.tick_spacing = 10;
-            pool_state.flip_tick_array_bit(-600).unwrap();
+            pool_state.flip_tick_array_bit(None, -600).unwrap();
             assert_eq!(
                 identity(pool_state.tick_array_bitmap),
                 [
@@ -773,7 +1446,7 @@ pub mod pool_test {
                     0
                 ]
             );
-            pool_state.flip_tick_array_bit(-1200).unwrap();
+            pool_state.flip_tick_array_bit(None, -1200).unwrap();
             assert_eq!(
                 identity(pool_state.tick_array_bitmap),
                 [
@@ -795,7 +1468,7 @@ pub mod pool_test {
                     0
                 ]
             );
-            pool_state.flip_tick_array_bit(-1800).unwrap();
+            pool_state.flip_tick_array_bit(None, -1800).unwrap();
             assert_eq!(
                 identity(pool_state.tick_array_bitmap),
                 [
@@ -817,7 +1490,7 @@ pub mod pool_test {
                     0
                 ]
             );
-            pool_state.flip_tick_array_bit(-38400).unwrap();
+            pool_state.flip_tick_array_bit(None, -38400).unwrap();
             assert_eq!(
                 identity(pool_state.tick_array_bitmap),
                 [
@@ -839,7 +1512,7 @@ pub mod pool_test {
                     0
                 ]
             );
-            pool_state.flip_tick_array_bit(-39000).unwrap();
+            pool_state.flip_tick_array_bit(None, -39000).unwrap();
             assert_eq!(
                 identity(pool_state.tick_array_bitmap),
                 [
@@ -861,7 +1534,7 @@ pub mod pool_test {
                     0
                 ]
             );
-            pool_state.flip_tick_array_bit(-307200).unwrap();
+            pool_state.flip_tick_array_bit(None, -307200).unwrap();
             assert_eq!(
                 identity(pool_state.tick_array_bitmap),
                 [
@@ -883,7 +1556,7 @@ pub mod pool_test {
                     0
                 ]
             );
-            pool_state.flip_tick_array_bit(-307200).unwrap();
+            pool_state.flip_tick_array_bit(None, -307200).unwrap();
             assert_eq!(
                 identity(pool_state.tick_array_bitmap),
                 [
@@ -911,27 +1584,27 @@ pub mod pool_test {
         fn get_arrary_start_index_positive() {
             let mut pool_state = PoolState::default();
             pool_state.tick_spacing = 10;
-            pool_state.flip_tick_array_bit(0).unwrap();
+            pool_state.flip_tick_array_bit(None, 0).unwrap();
             assert_eq!(
                 identity(pool_state.tick_array_bitmap),
                 [0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0]
             );
-            pool_state.flip_tick_array_bit(600).unwrap();
+            pool_state.flip_tick_array_bit(None, 600).unwrap();
             assert_eq!(
                 identity(pool_state.tick_array_bitmap),
                 [0, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0]
             );
-            pool_state.flip_tick_array_bit(1200).unwrap();
+            pool_state.flip_tick_array_bit(None, 1200).unwrap();
             assert_eq!(
                 identity(pool_state.tick_array_bitmap),
                 [0, 0, 0, 0, 0, 0, 0, 0, 7, 0, 0, 0, 0, 0, 0, 0]
             );
-            pool_state.flip_tick_array_bit(38400).unwrap();
+            pool_state.flip_tick_array_bit(None, 38400).unwrap();
             assert_eq!(
                 identity(pool_state.tick_array_bitmap),
                 [0, 0, 0, 0, 0, 0, 0, 0, 7, 1, 0, 0, 0, 0, 0, 0]
             );
-            pool_state.flip_tick_array_bit(306600).unwrap();
+            pool_state.flip_tick_array_bit(None, 306600).unwrap();
             assert_eq!(
                 identity(pool_state.tick_array_bitmap),
                 [
@@ -953,12 +1626,36 @@ pub mod pool_test {
                     9223372036854775808
                 ]
             );
-            pool_state.flip_tick_array_bit(306600).unwrap();
+            pool_state.flip_tick_array_bit(None, 306600).unwrap();
             assert_eq!(
                 identity(pool_state.tick_array_bitmap),
                 [0, 0, 0, 0, 0, 0, 0, 0, 7, 1, 0, 0, 0, 0, 0, 0]
             )
         }
+
+        #[test]
+        fn is_tick_array_initialized_test() {
+            let mut pool_state = PoolState {
+                tick_spacing: 1,
+                ..Default::default()
+            };
+            let mut extension = TickArrayBitmapExtension::default();
+            pool_state.flip_tick_array_bit(None, -60).unwrap();
+            pool_state
+                .flip_tick_array_bit(Some(&mut extension), 30720)
+                .unwrap();
+
+            assert!(pool_state.is_tick_array_initialized(None, -60).unwrap());
+            assert!(!pool_state.is_tick_array_initialized(None, 0).unwrap());
+            assert!(pool_state
+                .is_tick_array_initialized(Some(&extension), 30720)
+                .unwrap());
+            assert!(!pool_state
+                .is_tick_array_initialized(Some(&extension), -30780)
+                .unwrap());
+            // beyond the pool bitmap without the extension
+            assert!(pool_state.is_tick_array_initialized(None, 30720).is_err());
+        }
     }
 
     mod pool_status_test {
@@ -1019,6 +1716,23 @@ pub mod pool_test {
                 false
             );
         }
+
+        #[test]
+        fn is_emergency_pause_test() {
+            let mut pool_state = PoolState::default();
+            pool_state.set_status(2); // 00000010
+            assert!(pool_state.is_emergency_pause(2));
+            // disable swap, open position or both
+            assert!(pool_state.is_emergency_pause(18));
+            assert!(pool_state.is_emergency_pause(3));
+            assert!(pool_state.is_emergency_pause(19));
+            // disable other operations
+            assert!(!pool_state.is_emergency_pause(6));
+            assert!(!pool_state.is_emergency_pause(22));
+            // enable operations
+            assert!(!pool_state.is_emergency_pause(0));
+            assert!(!pool_state.is_emergency_pause(17));
+        }
     }
 
     mod update_reward_infos_test {
@@ -1040,6 +1754,7 @@ pub mod pool_test {
                     1665982800,
                     1666069200,
                     10,
+                    &[],
                     &Pubkey::from_str("So11111111111111111111111111111111111111112").unwrap(),
                     &Pubkey::default(),
                     &Pubkey::default(),
@@ -1076,5 +1791,566 @@ pub mod pool_test {
                 1666069200
             );
         }
+
+        #[test]
+        fn reward_phases_test() {
+            let pool_state = &mut PoolState::default();
+            let operation_state = OperationState {
+                bump: 0,
+                operation_owners: [Pubkey::default(); OPERATION_SIZE_USIZE],
+                whitelist_mints: [Pubkey::default(); WHITE_MINT_SIZE_USIZE],
+            };
+            let phases = [
+                RewardPhase {
+                    start_time: 1200,
+                    emissions_per_second_x64: 20,
+                },
+                RewardPhase {
+                    start_time: 1500,
+                    emissions_per_second_x64: 5,
+                },
+            ];
+            pool_state
+                .initialize_reward(
+                    1000,
+                    2000,
+                    10,
+                    &phases,
+                    &Pubkey::new_unique(),
+                    &Pubkey::default(),
+                    &Pubkey::default(),
+                    &operation_state,
+                )
+                .unwrap();
+            pool_state.liquidity = 1;
+
+            // within the initial rate
+            let updated_reward_infos = pool_state.update_reward_infos(1100).unwrap();
+            assert_eq!(
+                identity(updated_reward_infos[0].reward_growth_global_x64),
+                1000
+            );
+            assert_eq!(pool_state.reward_phases[0][..2], phases);
+
+            // across both phase boundaries at once
+            let updated_reward_infos = pool_state.update_reward_infos(1600).unwrap();
+            assert_eq!(
+                identity(updated_reward_infos[0].reward_growth_global_x64),
+                1000 + 100 * 10 + 300 * 20 + 100 * 5
+            );
+            assert_eq!(
+                identity(updated_reward_infos[0].emissions_per_second_x64),
+                5
+            );
+            assert!(pool_state.reward_phases[0]
+                .iter()
+                .all(|phase| !phase.is_scheduled()));
+
+            // the last phase lasts until the end time
+            let updated_reward_infos = pool_state.update_reward_infos(2100).unwrap();
+            assert_eq!(
+                identity(updated_reward_infos[0].reward_growth_global_x64),
+                8500 + 400 * 5
+            );
+            assert_eq!(identity(updated_reward_infos[0].last_update_time), 2000);
+
+            // more phases than the pool holds
+            assert!(pool_state
+                .initialize_reward(
+                    1000,
+                    2000,
+                    10,
+                    &[RewardPhase::default(); REWARD_PHASE_NUM + 1],
+                    &Pubkey::new_unique(),
+                    &Pubkey::default(),
+                    &Pubkey::default(),
+                    &operation_state,
+                )
+                .is_err());
+        }
+    }
+
+    mod fund_and_cancel_reward_test {
+        use super::*;
+        use std::convert::identity;
+
+        fn build_reward_pool(reward_phases: &[RewardPhase]) -> PoolState {
+            let mut pool_state = PoolState::default();
+            let operation_state = OperationState {
+                bump: 0,
+                operation_owners: [Pubkey::default(); OPERATION_SIZE_USIZE],
+                whitelist_mints: [Pubkey::default(); WHITE_MINT_SIZE_USIZE],
+            };
+            pool_state
+                .initialize_reward(
+                    1000,
+                    2000,
+                    2 * fixed_point_64::Q64,
+                    reward_phases,
+                    &Pubkey::new_unique(),
+                    &Pubkey::default(),
+                    &Pubkey::default(),
+                    &operation_state,
+                )
+                .unwrap();
+            pool_state.liquidity = fixed_point_64::Q64;
+            pool_state
+        }
+
+        #[test]
+        fn fund_reward_test() {
+            let mut pool_state = build_reward_pool(&[]);
+            pool_state.update_reward_infos(1500).unwrap();
+            pool_state.fund_reward(0, 201).unwrap();
+            assert_eq!(identity(pool_state.reward_infos[0].end_time), 2100);
+
+            // too small to extend a second
+            assert!(pool_state.fund_reward(0, 1).is_err());
+            // longer than the max reward period
+            assert!(pool_state
+                .fund_reward(0, 2 * reward_period_limit::MAX_REWARD_PERIOD + 2)
+                .is_err());
+
+            // the reward emits until the extended end time
+            pool_state.update_reward_infos(3000).unwrap();
+            assert_eq!(identity(pool_state.reward_infos[0].last_update_time), 2100);
+            assert_eq!(
+                identity(pool_state.reward_infos[0].reward_total_emissioned),
+                2200
+            );
+            assert!(pool_state.fund_reward(0, 200).is_err());
+
+            // extended at the rate of the last phase
+            let mut pool_state = build_reward_pool(&[RewardPhase {
+                start_time: 1800,
+                emissions_per_second_x64: 4 * fixed_point_64::Q64,
+            }]);
+            pool_state.update_reward_infos(1500).unwrap();
+            pool_state.fund_reward(0, 400).unwrap();
+            assert_eq!(identity(pool_state.reward_infos[0].end_time), 2100);
+        }
+
+        #[test]
+        fn cancel_reward_test() {
+            let mut pool_state = build_reward_pool(&[RewardPhase {
+                start_time: 1800,
+                emissions_per_second_x64: 4 * fixed_point_64::Q64,
+            }]);
+            pool_state.update_reward_infos(1500).unwrap();
+            pool_state.cancel_reward(0, 1500).unwrap();
+            assert_eq!(identity(pool_state.reward_infos[0].end_time), 1500);
+            assert!(!pool_state.reward_phases[0][0].is_scheduled());
+            assert!(pool_state.cancel_reward(0, 1600).is_err());
+
+            // no emission after the cancel
+            pool_state.update_reward_infos(1900).unwrap();
+            assert_eq!(
+                identity(pool_state.reward_infos[0].reward_total_emissioned),
+                1000
+            );
+
+            // cancelled before the reward opens
+            let mut pool_state = build_reward_pool(&[]);
+            pool_state.update_reward_infos(900).unwrap();
+            pool_state.cancel_reward(0, 900).unwrap();
+            assert_eq!(identity(pool_state.reward_infos[0].open_time), 900);
+            assert_eq!(identity(pool_state.reward_infos[0].end_time), 900);
+            pool_state.update_reward_infos(1500).unwrap();
+            assert_eq!(
+                identity(pool_state.reward_infos[0].reward_total_emissioned),
+                0
+            );
+        }
+    }
+
+    mod owner_handover_test {
+        use super::*;
+        use std::convert::identity;
+
+        #[test]
+        fn pool_owner_test() {
+            let owner = Pubkey::new_unique();
+            let reward_authority = Pubkey::new_unique();
+            let mut pool_state = PoolState {
+                owner,
+                ..Default::default()
+            };
+            pool_state.reward_infos[0].authority = reward_authority;
+            assert_eq!(
+                pool_state.accept_owner(Pubkey::new_unique()).unwrap_err(),
+                ErrorCode::NoPendingOwner.into()
+            );
+            assert_eq!(
+                pool_state.propose_owner(Pubkey::default()).unwrap_err(),
+                ErrorCode::InvalidPendingOwner.into()
+            );
+
+            // a proposal replaces the previous one, which can no longer accept
+            let first_owner = Pubkey::new_unique();
+            let new_owner = Pubkey::new_unique();
+            pool_state.propose_owner(first_owner).unwrap();
+            pool_state.propose_owner(new_owner).unwrap();
+            assert_eq!(
+                pool_state.accept_owner(first_owner).unwrap_err(),
+                ErrorCode::NotApproved.into()
+            );
+            assert_eq!(identity(pool_state.owner), owner);
+
+            assert_eq!(pool_state.accept_owner(new_owner).unwrap(), owner);
+            assert_eq!(identity(pool_state.owner), new_owner);
+            assert_eq!(identity(pool_state.pending_owner), Pubkey::default());
+            // the rewards keep their authorities
+            assert_eq!(
+                identity(pool_state.reward_infos[0].authority),
+                reward_authority
+            );
+
+            // a cancelled proposal can not be accepted
+            let cancelled_owner = Pubkey::new_unique();
+            pool_state.propose_owner(cancelled_owner).unwrap();
+            assert_eq!(pool_state.cancel_owner_proposal().unwrap(), cancelled_owner);
+            assert_eq!(
+                pool_state.cancel_owner_proposal().unwrap_err(),
+                ErrorCode::NoPendingOwner.into()
+            );
+            assert_eq!(
+                pool_state.accept_owner(cancelled_owner).unwrap_err(),
+                ErrorCode::NoPendingOwner.into()
+            );
+            assert_eq!(identity(pool_state.owner), new_owner);
+        }
+
+        #[test]
+        fn reward_authority_test() {
+            let owner = Pubkey::new_unique();
+            let authorities = [Pubkey::new_unique(), Pubkey::new_unique()];
+            let mut pool_state = PoolState {
+                owner,
+                ..Default::default()
+            };
+            for (reward_info, authority) in pool_state.reward_infos.iter_mut().zip(authorities) {
+                reward_info.token_mint = Pubkey::new_unique();
+                reward_info.authority = authority;
+            }
+            // the last reward is not initialized
+            assert_eq!(
+                pool_state
+                    .propose_reward_authority(2, Pubkey::new_unique())
+                    .unwrap_err(),
+                ErrorCode::UnInitializedRewardInfo.into()
+            );
+            assert_eq!(
+                pool_state
+                    .propose_reward_authority(0, Pubkey::default())
+                    .unwrap_err(),
+                ErrorCode::InvalidPendingOwner.into()
+            );
+
+            let new_authority = Pubkey::new_unique();
+            pool_state
+                .propose_reward_authority(0, new_authority)
+                .unwrap();
+            // only the proposed authority accepts, and only the reward it is proposed for
+            assert_eq!(
+                pool_state
+                    .accept_reward_authority(0, Pubkey::new_unique())
+                    .unwrap_err(),
+                ErrorCode::NotApproved.into()
+            );
+            assert_eq!(
+                pool_state
+                    .accept_reward_authority(1, new_authority)
+                    .unwrap_err(),
+                ErrorCode::NoPendingOwner.into()
+            );
+            assert_eq!(
+                pool_state
+                    .accept_reward_authority(0, new_authority)
+                    .unwrap(),
+                authorities[0]
+            );
+            assert_eq!(
+                identity(pool_state.reward_infos[0].authority),
+                new_authority
+            );
+            assert_eq!(
+                identity(pool_state.reward_pending_authorities[0]),
+                Pubkey::default()
+            );
+            // the pool and the other rewards keep their owners
+            assert_eq!(identity(pool_state.owner), owner);
+            assert_eq!(
+                identity(pool_state.reward_infos[1].authority),
+                authorities[1]
+            );
+
+            // a cancelled proposal can not be accepted
+            let cancelled_authority = Pubkey::new_unique();
+            pool_state
+                .propose_reward_authority(1, cancelled_authority)
+                .unwrap();
+            assert_eq!(
+                pool_state.cancel_reward_authority(1).unwrap(),
+                cancelled_authority
+            );
+            assert_eq!(
+                pool_state.cancel_reward_authority(1).unwrap_err(),
+                ErrorCode::NoPendingOwner.into()
+            );
+            assert_eq!(
+                pool_state
+                    .accept_reward_authority(1, cancelled_authority)
+                    .unwrap_err(),
+                ErrorCode::NoPendingOwner.into()
+            );
+            assert_eq!(
+                identity(pool_state.reward_infos[1].authority),
+                authorities[1]
+            );
+        }
+    }
+
+    #[test]
+    fn add_reward_extension_test() {
+        let mut pool_state = PoolState::default();
+        for index in 0..MAX_REWARD_EXTENSION_NUM {
+            assert_eq!(
+                usize::from(pool_state.add_reward_extension().unwrap()),
+                index
+            );
+        }
+        assert_eq!(
+            pool_state.add_reward_extension().unwrap_err(),
+            ErrorCode::RewardExtensionsFull.into()
+        );
+        assert_eq!(
+            usize::from(pool_state.reward_extension_num),
+            MAX_REWARD_EXTENSION_NUM
+        );
+    }
+
+    #[test]
+    fn pool_state_len_test() {
+        // the fields added since the first release are carved out of the padding
+        assert_eq!(PoolState::LEN, 8 + std::mem::size_of::<PoolState>());
+    }
+
+    #[test]
+    fn get_trade_fee_rate_test() {
+        let mut pool_state = PoolState::default();
+        assert_eq!(pool_state.get_trade_fee_rate(2500), 2500);
+        assert_eq!(pool_state.set_trade_fee_rate_override(100).unwrap(), 0);
+        assert_eq!(pool_state.get_trade_fee_rate(2500), 100);
+        assert_eq!(
+            pool_state
+                .set_trade_fee_rate_override(MAX_TRADE_FEE_RATE_OVERRIDE + 1)
+                .unwrap_err(),
+            ErrorCode::InvalidTradeFeeRateOverride.into()
+        );
+        assert_eq!(pool_state.set_trade_fee_rate_override(0).unwrap(), 100);
+        assert_eq!(pool_state.get_trade_fee_rate(2500), 2500);
+    }
+
+    #[test]
+    fn lock_test() {
+        let mut pool_state = PoolState::default();
+        assert!(pool_state.check_unlocked().is_ok());
+        pool_state.lock().unwrap();
+        assert!(pool_state.check_unlocked().is_err());
+        assert!(pool_state.lock().is_err());
+        pool_state.unlock();
+        assert!(pool_state.check_unlocked().is_ok());
+    }
+
+    #[test]
+    fn add_flash_fees_test() {
+        let mut pool_state = PoolState::default();
+        // no in-range liquidity to receive the fees
+        assert!(pool_state.add_flash_fees(120000, 40000, 1000, 1000).is_err());
+
+        pool_state.liquidity = fixed_point_64::Q64;
+        // 12% to protocol and 4% to fund
+        pool_state.add_flash_fees(120000, 40000, 1000, 10).unwrap();
+        assert_eq!({ pool_state.protocol_fees_token_0 }, 120);
+        assert_eq!({ pool_state.fund_fees_token_0 }, 40);
+        assert_eq!({ pool_state.total_fees_token_0 }, 840);
+        assert_eq!({ pool_state.fee_growth_global_0_x64 }, 840);
+        assert_eq!({ pool_state.protocol_fees_token_1 }, 1);
+        assert_eq!({ pool_state.fund_fees_token_1 }, 0);
+        assert_eq!({ pool_state.total_fees_token_1 }, 9);
+        assert_eq!({ pool_state.fee_growth_global_1_x64 }, 9);
+    }
+
+    #[test]
+    fn add_liquidity_fees_test() {
+        assert!(PoolState::default().add_liquidity_fees(1000, 1000).is_err());
+
+        let mut pool_state = PoolState {
+            liquidity: 2 * fixed_point_64::Q64,
+            ..Default::default()
+        };
+        pool_state.add_liquidity_fees(1000, 0).unwrap();
+        assert_eq!({ pool_state.total_fees_token_0 }, 1000);
+        assert_eq!({ pool_state.fee_growth_global_0_x64 }, 500);
+        assert_eq!({ pool_state.total_fees_token_1 }, 0);
+        assert_eq!({ pool_state.fee_growth_global_1_x64 }, 0);
+        assert_eq!({ pool_state.protocol_fees_token_0 }, 0);
+    }
+
+    mod dynamic_fee_test {
+        use super::*;
+        use std::convert::identity;
+
+        fn dynamic_fee_info() -> DynamicFeeInfo {
+            DynamicFeeInfo {
+                max_fee_rate: 10000,
+                dynamic_fee_control: 40000,
+                max_volatility_accumulator: 300,
+                filter_period: 30,
+                decay_period: 600,
+                reduction_factor: 5000,
+                tick_reference: 100,
+                last_update_timestamp: 1665982800,
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn disabled_fee_rate_test() {
+            let mut dynamic_fee_info = DynamicFeeInfo::default();
+            dynamic_fee_info.update_volatility_accumulator(100);
+            assert_eq!(dynamic_fee_info.get_fee_rate(2500), 2500);
+        }
+
+        #[test]
+        fn fee_rate_test() {
+            let mut dynamic_fee_info = dynamic_fee_info();
+            // no price movement, only the trade fee
+            dynamic_fee_info.update_volatility_accumulator(100);
+            assert_eq!(dynamic_fee_info.get_fee_rate(2500), 2500);
+
+            // 20 ticks away from the reference, 40000 * 20^2 / 100000 = 160
+            dynamic_fee_info.update_volatility_accumulator(80);
+            assert_eq!(identity(dynamic_fee_info.volatility_accumulator), 20);
+            assert_eq!(dynamic_fee_info.get_fee_rate(2500), 2660);
+
+            // the volatility accumulator is capped, and the fee rate is capped
+            dynamic_fee_info.update_volatility_accumulator(1100);
+            assert_eq!(identity(dynamic_fee_info.volatility_accumulator), 300);
+            assert_eq!(dynamic_fee_info.get_fee_rate(2500), 10000);
+
+            // the trade fee rate is never reduced by the cap
+            assert_eq!(dynamic_fee_info.get_fee_rate(20000), 20000);
+        }
+
+        #[test]
+        fn update_references_test() {
+            let mut dynamic_fee_info = dynamic_fee_info();
+            dynamic_fee_info.update_volatility_accumulator(80);
+
+            // within the filter period, the references are kept
+            dynamic_fee_info.update_references(60, 1665982810);
+            assert_eq!(identity(dynamic_fee_info.tick_reference), 100);
+            assert_eq!(identity(dynamic_fee_info.volatility_reference), 0);
+
+            // after the filter period, the volatility reference decays
+            dynamic_fee_info.update_references(60, 1665982850);
+            assert_eq!(identity(dynamic_fee_info.tick_reference), 60);
+            assert_eq!(identity(dynamic_fee_info.volatility_reference), 10);
+            dynamic_fee_info.update_volatility_accumulator(70);
+            assert_eq!(identity(dynamic_fee_info.volatility_accumulator), 20);
+
+            // after the decay period, the volatility reference is reset
+            dynamic_fee_info.update_references(70, 1665983500);
+            assert_eq!(identity(dynamic_fee_info.tick_reference), 70);
+            assert_eq!(identity(dynamic_fee_info.volatility_reference), 0);
+            assert_eq!(identity(dynamic_fee_info.last_update_timestamp), 1665983500);
+        }
+
+        #[test]
+        fn compute_swap_step_test() {
+            let liquidity = 1_000_000_000_000;
+            let sqrt_price_current_x64 = tick_math::get_sqrt_price_at_tick(100).unwrap();
+            let sqrt_price_target_x64 = tick_math::get_sqrt_price_at_tick(80).unwrap();
+
+            // the step reaches the target, the volatility is the one of the target tick
+            let mut fee_info = dynamic_fee_info();
+            let swap_step = fee_info
+                .compute_swap_step(
+                    2500,
+                    sqrt_price_current_x64,
+                    sqrt_price_target_x64,
+                    liquidity,
+                    u64::MAX,
+                    true,
+                    true,
+                )
+                .unwrap();
+            assert_eq!(swap_step.sqrt_price_next_x64, sqrt_price_target_x64);
+            assert_eq!(identity(fee_info.volatility_accumulator), 20);
+            let expected = swap_math::compute_swap_step(
+                sqrt_price_current_x64,
+                sqrt_price_target_x64,
+                liquidity,
+                u64::MAX,
+                2660,
+                true,
+                true,
+            );
+            assert_eq!(swap_step.fee_amount, expected.fee_amount);
+
+            // the step stops short of the target, the volatility is the one of the tick it ends at
+            let mut fee_info = dynamic_fee_info();
+            let swap_step = fee_info
+                .compute_swap_step(
+                    2500,
+                    sqrt_price_current_x64,
+                    sqrt_price_target_x64,
+                    liquidity,
+                    500_000_000,
+                    true,
+                    true,
+                )
+                .unwrap();
+            assert!(swap_step.sqrt_price_next_x64 > sqrt_price_target_x64);
+            let tick_end =
+                tick_math::get_tick_at_sqrt_price(swap_step.sqrt_price_next_x64).unwrap();
+            assert!(tick_end > 80 && tick_end < 100);
+            assert_eq!(
+                identity(fee_info.volatility_accumulator),
+                (100 - tick_end) as u32
+            );
+            let expected = swap_math::compute_swap_step(
+                sqrt_price_current_x64,
+                sqrt_price_target_x64,
+                liquidity,
+                500_000_000,
+                fee_info.get_fee_rate(2500),
+                true,
+                true,
+            );
+            assert_eq!(swap_step.fee_amount, expected.fee_amount);
+            assert!(fee_info.get_fee_rate(2500) > 2500);
+
+            // a step of an exact output ends at the same tick whatever the fee rate
+            let mut fee_info = dynamic_fee_info();
+            let swap_step = fee_info
+                .compute_swap_step(
+                    2500,
+                    sqrt_price_current_x64,
+                    sqrt_price_target_x64,
+                    liquidity,
+                    500_000_000,
+                    false,
+                    true,
+                )
+                .unwrap();
+            let tick_end =
+                tick_math::get_tick_at_sqrt_price(swap_step.sqrt_price_next_x64).unwrap();
+            assert_eq!(
+                identity(fee_info.volatility_accumulator),
+                (100 - tick_end) as u32
+            );
+        }
     }
 }
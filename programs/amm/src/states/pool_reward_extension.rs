@@ -0,0 +1,790 @@
+use crate::error::ErrorCode;
+use crate::libraries::{big_num::U256, fixed_point_64, full_math::MulDiv};
+use crate::states::personal_position::PersonalPositionState;
+use crate::states::pool::{PoolState, RewardInfo};
+use crate::states::tick_array::{TickArrayState, TickState};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use std::cell::RefMut;
+use std::ops::Deref;
+
+pub const POOL_REWARD_EXTENSION_SEED: &str = "pool_reward_extension";
+/// The max number of reward extensions of a pool, every instruction accruing rewards takes all of
+/// them so they must fit in a transaction with the other accounts
+pub const MAX_REWARD_EXTENSION_NUM: usize = 3;
+
+/// The reward of a personal position of the pool
+#[account(zero_copy(unsafe))]
+#[repr(packed)]
+#[derive(Default, Debug, PartialEq, Eq)]
+pub struct PositionRewardGrowth {
+    /// The nft mint of the personal position
+    pub position_nft_mint: Pubkey,
+    /// Q64.64, the reward growth inside the range of the position as of the last update
+    pub growth_inside_last_x64: u128,
+    /// The reward owed to the position as of the last update
+    pub reward_amount_owed: u64,
+}
+
+impl PositionRewardGrowth {
+    pub const LEN: usize = 32 + 16 + 8;
+}
+
+/// A reward stream of the pool beyond the `REWARD_NUM` rewards of `PoolState`, the pool keeps
+/// `reward_extension_num` of them and every instruction accruing rewards takes all of them.
+/// The reward growth outside of the ticks is kept by the ticks themselves at the index of the
+/// extension, so swaps never grow the account.
+/// The reward growth of the positions are entries appended after the account, sorted by position
+/// nft mint, a missing entry is a zero reward growth.
+/// The account grows as entries are added, the rent of the grown size is paid by the rent payer
+/// passed after the reward extensions when the lamports of the account itself fall short, anyone
+/// can top it up.
+///
+/// PDA of `[POOL_REWARD_EXTENSION_SEED, pool_state, index]`
+#[account(zero_copy(unsafe))]
+#[repr(packed)]
+#[derive(Default, Debug)]
+pub struct PoolRewardExtension {
+    /// The pool the extension belongs to
+    pub pool_id: Pubkey,
+    /// The index of the extension in the pool
+    pub index: u16,
+    pub reward_info: RewardInfo,
+    /// The number of position entries appended after the account
+    pub position_num: u32,
+    /// padding for feature update
+    pub padding: [u64; 8],
+}
+
+impl PoolRewardExtension {
+    pub const LEN: usize = 8 + 32 + 2 + RewardInfo::LEN + 4 + 8 * 8;
+
+    pub fn initialize(&mut self, pool_id: Pubkey, index: u16, reward_info: RewardInfo) {
+        self.pool_id = pool_id;
+        self.index = index;
+        self.reward_info = reward_info;
+        self.position_num = 0;
+        self.padding = [0; 8];
+    }
+
+    pub fn get_address(pool_id: &Pubkey, index: u16) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                POOL_REWARD_EXTENSION_SEED.as_bytes(),
+                pool_id.as_ref(),
+                &index.to_be_bytes(),
+            ],
+            &crate::id(),
+        )
+        .0
+    }
+
+    /// Load the extension with the entries appended after it
+    pub fn load_mut<'a>(
+        account_info: &'a AccountInfo,
+    ) -> Result<(RefMut<'a, Self>, RefMut<'a, [u8]>)> {
+        let extension_loader = AccountLoader::<PoolRewardExtension>::try_from(account_info)?;
+        let entries_len = extension_loader.load()?.entries_len();
+        require_gte!(account_info.data_len(), Self::LEN + entries_len);
+        let data = account_info.try_borrow_mut_data()?;
+        Ok(RefMut::map_split(data, |data| {
+            let (state, entries) = data.split_at_mut(Self::LEN);
+            (bytemuck::from_bytes_mut(&mut state[8..]), entries)
+        }))
+    }
+
+    /// Grows the account if it has no room for `len` more bytes of entries, the rent payer pays
+    /// the rent of the grown size the lamports of the account fall short of
+    fn reserve<'info>(
+        account_info: &AccountInfo<'info>,
+        rent_payer: Option<&RentPayer<'info>>,
+        len: usize,
+    ) -> Result<()> {
+        let required_len = {
+            let (extension, _) = Self::load_mut(account_info)?;
+            Self::LEN + extension.entries_len() + len
+        };
+        if account_info.data_len() >= required_len {
+            return Ok(());
+        }
+        let rent_lack = Rent::get()?
+            .minimum_balance(required_len)
+            .saturating_sub(account_info.lamports());
+        if rent_lack > 0 {
+            let rent_payer = rent_payer.ok_or(ErrorCode::InsufficientPoolRewardExtensionRent)?;
+            system_program::transfer(
+                CpiContext::new(
+                    rent_payer.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: rent_payer.payer.clone(),
+                        to: account_info.clone(),
+                    },
+                ),
+                rent_lack,
+            )?;
+        }
+        account_info.realloc(required_len, false)?;
+        Ok(())
+    }
+
+    /// The bytes of all the entries appended after the account
+    pub fn entries_len(&self) -> usize {
+        self.position_num as usize * PositionRewardGrowth::LEN
+    }
+
+    pub fn position_reward_growths<'a>(&self, entries: &'a [u8]) -> &'a [PositionRewardGrowth] {
+        bytemuck::cast_slice(&entries[..self.entries_len()])
+    }
+
+    fn search_position(
+        &self,
+        entries: &[u8],
+        position_nft_mint: &Pubkey,
+    ) -> std::result::Result<usize, usize> {
+        self.position_reward_growths(entries)
+            .binary_search_by_key(position_nft_mint, |entry| entry.position_nft_mint)
+    }
+
+    /// Inserts `entry` at `offset` of the entries, shifting the entries after it
+    fn insert_entry(&self, entries: &mut [u8], offset: usize, entry: &[u8]) -> Result<()> {
+        let entries_len = self.entries_len();
+        require_gte!(
+            entries.len(),
+            entries_len + entry.len(),
+            ErrorCode::InsufficientPoolRewardExtensionRent
+        );
+        entries.copy_within(offset..entries_len, offset + entry.len());
+        entries[offset..offset + entry.len()].copy_from_slice(entry);
+        Ok(())
+    }
+
+    /// Removes `len` bytes of entry at `offset` of the entries, shifting the entries after it
+    fn remove_entry(&self, entries: &mut [u8], offset: usize, len: usize) {
+        entries.copy_within(offset + len..self.entries_len(), offset);
+    }
+
+    pub fn get_position_reward_growth(
+        &self,
+        entries: &[u8],
+        position_nft_mint: &Pubkey,
+    ) -> PositionRewardGrowth {
+        match self.search_position(entries, position_nft_mint) {
+            Ok(index) => self.position_reward_growths(entries)[index],
+            Err(_) => PositionRewardGrowth {
+                position_nft_mint: *position_nft_mint,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Sets the reward of the position holding `liquidity`, the entry is removed once its owed
+    /// reward is zero and either its reward growth or its liquidity is zero, a position without
+    /// liquidity accrues nothing and its reward growth is reset by its next update
+    pub fn set_position_reward_growth(
+        &mut self,
+        entries: &mut [u8],
+        position: PositionRewardGrowth,
+        liquidity: u128,
+    ) -> Result<()> {
+        let is_empty = position.reward_amount_owed == 0
+            && (position.growth_inside_last_x64 == 0 || liquidity == 0);
+        match self.search_position(entries, &position.position_nft_mint) {
+            Ok(index) => {
+                let offset = index * PositionRewardGrowth::LEN;
+                if is_empty {
+                    self.remove_entry(entries, offset, PositionRewardGrowth::LEN);
+                    self.position_num -= 1;
+                } else {
+                    entries[offset..offset + PositionRewardGrowth::LEN]
+                        .copy_from_slice(bytemuck::bytes_of(&position));
+                }
+            }
+            Err(index) => {
+                if !is_empty {
+                    let offset = index * PositionRewardGrowth::LEN;
+                    self.insert_entry(entries, offset, bytemuck::bytes_of(&position))?;
+                    self.position_num += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The reward growth outside of the tick kept at the index of the extension
+    fn get_tick_reward_growth_outside(&self, tick_state: &TickState) -> u128 {
+        tick_state.extension_reward_growths_outside_x64[usize::from(self.index)]
+    }
+
+    /// Sets the reward growth outside of a tick just initialized by a liquidity change, all the
+    /// reward growth so far is taken as outside if the tick is at or below the current tick
+    pub fn initialize_tick(&self, tick_current: i32, tick_state: &mut TickState) {
+        tick_state.extension_reward_growths_outside_x64[usize::from(self.index)] =
+            if tick_state.tick <= tick_current {
+                self.reward_info.reward_growth_global_x64
+            } else {
+                0
+            };
+    }
+
+    /// Flips the reward growth outside of the tick a swap crosses
+    pub fn cross_tick(&self, tick_state: &mut TickState) {
+        tick_state.extension_reward_growths_outside_x64[usize::from(self.index)] = self
+            .reward_info
+            .reward_growth_global_x64
+            .checked_sub(self.get_tick_reward_growth_outside(tick_state))
+            .unwrap();
+    }
+
+    /// The reward growth inside the range, the same as `get_reward_growths_inside` of the rewards of the pool
+    pub fn get_reward_growth_inside(
+        &self,
+        tick_lower_state: &TickState,
+        tick_upper_state: &TickState,
+        tick_current: i32,
+    ) -> u128 {
+        let reward_growth_global_x64 = self.reward_info.reward_growth_global_x64;
+        let reward_growth_outside_lower = self.get_tick_reward_growth_outside(tick_lower_state);
+        let reward_growth_below = if tick_current >= tick_lower_state.tick {
+            reward_growth_outside_lower
+        } else {
+            reward_growth_global_x64
+                .checked_sub(reward_growth_outside_lower)
+                .unwrap()
+        };
+        let reward_growth_outside_upper = self.get_tick_reward_growth_outside(tick_upper_state);
+        let reward_growth_above = if tick_current < tick_upper_state.tick {
+            reward_growth_outside_upper
+        } else {
+            reward_growth_global_x64
+                .checked_sub(reward_growth_outside_upper)
+                .unwrap()
+        };
+        reward_growth_global_x64
+            .wrapping_sub(reward_growth_below)
+            .wrapping_sub(reward_growth_above)
+    }
+
+    /// Accrues the reward of the position since its last update, must be called with the
+    /// liquidity of the position before it changes and `liquidity_after` the change
+    pub fn update_position_reward(
+        &mut self,
+        entries: &mut [u8],
+        position_nft_mint: &Pubkey,
+        tick_lower_state: &TickState,
+        tick_upper_state: &TickState,
+        tick_current: i32,
+        liquidity: u128,
+        liquidity_after: u128,
+    ) -> Result<()> {
+        let reward_growth_inside =
+            self.get_reward_growth_inside(tick_lower_state, tick_upper_state, tick_current);
+        let mut position = self.get_position_reward_growth(entries, position_nft_mint);
+        let reward_growth_delta =
+            reward_growth_inside.wrapping_sub(position.growth_inside_last_x64);
+        let amount_owed_delta = U256::from(reward_growth_delta)
+            .mul_div_floor(U256::from(liquidity), U256::from(fixed_point_64::Q64))
+            .unwrap()
+            .to_underflow_u64();
+        position.reward_amount_owed = position
+            .reward_amount_owed
+            .checked_add(amount_owed_delta)
+            .unwrap();
+        position.growth_inside_last_x64 = reward_growth_inside;
+        self.set_position_reward_growth(entries, position, liquidity_after)
+    }
+
+    /// Accrues the emissions of the extension since the last update to the in-range liquidity
+    pub fn update_reward_info(
+        reward_extension: &AccountLoader<PoolRewardExtension>,
+        curr_timestamp: u64,
+        liquidity: u128,
+    ) -> Result<()> {
+        reward_extension
+            .load_mut()?
+            .reward_info
+            .update(curr_timestamp, liquidity);
+        Ok(())
+    }
+
+    /// Accrues the reward of the personal position, must be called with the liquidity of the
+    /// position before it changes and `liquidity_after` the change
+    pub fn update_position<'info>(
+        reward_extension: &AccountLoader<'info, PoolRewardExtension>,
+        rent_payer: Option<&RentPayer<'info>>,
+        personal_position: &PersonalPositionState,
+        tick_lower_state: &TickState,
+        tick_upper_state: &TickState,
+        tick_current: i32,
+        liquidity_after: u128,
+    ) -> Result<()> {
+        let account_info = reward_extension.to_account_info();
+        let need_entry = {
+            let (extension, entries) = Self::load_mut(&account_info)?;
+            extension
+                .search_position(&entries, &personal_position.nft_mint)
+                .is_err()
+        };
+        if need_entry {
+            Self::reserve(&account_info, rent_payer, PositionRewardGrowth::LEN)?;
+        }
+        let (mut extension, mut entries) = Self::load_mut(&account_info)?;
+        extension.update_position_reward(
+            &mut entries,
+            &personal_position.nft_mint,
+            tick_lower_state,
+            tick_upper_state,
+            tick_current,
+            personal_position.liquidity,
+            liquidity_after,
+        )
+    }
+
+    /// Takes the reward owed to the personal position, at most `amount_max`
+    pub fn collect_position_reward(
+        reward_extension: &AccountLoader<PoolRewardExtension>,
+        personal_position: &PersonalPositionState,
+        amount_max: u64,
+    ) -> Result<u64> {
+        let account_info = reward_extension.to_account_info();
+        let (mut extension, mut entries) = Self::load_mut(&account_info)?;
+        let mut position =
+            extension.get_position_reward_growth(&entries, &personal_position.nft_mint);
+        let amount = position.reward_amount_owed.min(amount_max);
+        let unclaimed_reward = extension
+            .reward_info
+            .reward_total_emissioned
+            .checked_sub(extension.reward_info.reward_claimed)
+            .unwrap();
+        require_gte!(unclaimed_reward, amount);
+        position.reward_amount_owed -= amount;
+        extension.set_position_reward_growth(&mut entries, position, personal_position.liquidity)?;
+        extension.reward_info.reward_claimed = extension
+            .reward_info
+            .reward_claimed
+            .checked_add(amount)
+            .unwrap();
+        Ok(amount)
+    }
+}
+
+/// Emitted when the reward of a reward extension is collected by the owner of a position
+#[event]
+pub struct CollectExtensionRewardEvent {
+    /// The ID of the token for which the reward was collected
+    #[index]
+    pub position_nft_mint: Pubkey,
+
+    /// The index of the reward extension
+    pub reward_extension_index: u16,
+
+    /// The amount of the reward collected
+    pub reward_amount: u64,
+}
+
+/// The signer paying the rent of the position entries the reward extensions grow by
+pub struct RentPayer<'info> {
+    pub system_program: Program<'info, System>,
+    pub payer: AccountInfo<'info>,
+}
+
+/// The reward extensions of the pool in the order of their index, with the optional rent payer of
+/// their new position entries
+#[derive(Default)]
+pub struct PoolRewardExtensions<'info> {
+    pub reward_extensions: Vec<AccountLoader<'info, PoolRewardExtension>>,
+    pub rent_payer: Option<RentPayer<'info>>,
+}
+
+impl<'info> Deref for PoolRewardExtensions<'info> {
+    type Target = [AccountLoader<'info, PoolRewardExtension>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.reward_extensions
+    }
+}
+
+impl<'info> PoolRewardExtensions<'info> {
+    /// Sets the reward growth outside of a tick just initialized by a liquidity change in all the
+    /// reward extensions
+    pub fn initialize_tick(&self, tick_current: i32, tick_state: &mut TickState) -> Result<()> {
+        for reward_extension in self.iter() {
+            reward_extension
+                .load()?
+                .initialize_tick(tick_current, tick_state);
+        }
+        Ok(())
+    }
+
+    /// Flips the reward growth outside of the tick a swap crosses in all the reward extensions
+    pub fn cross_tick(&self, tick_state: &mut TickState) -> Result<()> {
+        for reward_extension in self.iter() {
+            reward_extension.load()?.cross_tick(tick_state);
+        }
+        Ok(())
+    }
+
+    /// Accrues the rewards of the personal position in all the reward extensions, must be called
+    /// with the liquidity of the position before it changes and `liquidity_after` the change
+    pub fn update_position(
+        &self,
+        personal_position: &PersonalPositionState,
+        pool_state: &PoolState,
+        tick_array_lower: &AccountLoader<'info, TickArrayState>,
+        tick_array_upper: &AccountLoader<'info, TickArrayState>,
+        liquidity_after: u128,
+    ) -> Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let tick_spacing = i32::from(pool_state.tick_spacing);
+        let tick_lower_state = *tick_array_lower
+            .load_mut()?
+            .get_tick_state_mut(personal_position.tick_lower_index, tick_spacing)?;
+        let tick_upper_state = *tick_array_upper
+            .load_mut()?
+            .get_tick_state_mut(personal_position.tick_upper_index, tick_spacing)?;
+        for reward_extension in self.iter() {
+            PoolRewardExtension::update_position(
+                reward_extension,
+                self.rent_payer.as_ref(),
+                personal_position,
+                &tick_lower_state,
+                &tick_upper_state,
+                pool_state.tick_current,
+                liquidity_after,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Take the `reward_extension_num` reward extensions of the pool from the front of
+/// `remaining_accounts`, in the order of their index.
+pub fn get_pool_reward_extensions<'info>(
+    pool_id: &Pubkey,
+    reward_extension_num: u16,
+    remaining_accounts: &mut &[AccountInfo<'info>],
+) -> Result<PoolRewardExtensions<'info>> {
+    let reward_extension_num = usize::from(reward_extension_num);
+    require_gte!(
+        remaining_accounts.len(),
+        reward_extension_num,
+        ErrorCode::MissingPoolRewardExtensionAccount
+    );
+    let mut reward_extensions = Vec::with_capacity(reward_extension_num);
+    for (index, account_info) in remaining_accounts[..reward_extension_num]
+        .iter()
+        .enumerate()
+    {
+        let reward_extension = AccountLoader::<PoolRewardExtension>::try_from(account_info)?;
+        {
+            let extension = reward_extension.load()?;
+            require_keys_eq!(
+                extension.pool_id,
+                *pool_id,
+                ErrorCode::MissingPoolRewardExtensionAccount
+            );
+            require_eq!(
+                usize::from(extension.index),
+                index,
+                ErrorCode::MissingPoolRewardExtensionAccount
+            );
+        }
+        reward_extensions.push(reward_extension);
+    }
+    *remaining_accounts = &remaining_accounts[reward_extension_num..];
+    Ok(PoolRewardExtensions {
+        reward_extensions,
+        rent_payer: None,
+    })
+}
+
+/// Take the reward extensions of the pool like `get_pool_reward_extensions` for the instructions
+/// changing the positions, they may be followed by the system program and a writable signer
+/// paying the rent of the new position entries.
+pub fn get_pool_reward_extensions_with_rent_payer<'info>(
+    pool_id: &Pubkey,
+    reward_extension_num: u16,
+    remaining_accounts: &mut &[AccountInfo<'info>],
+) -> Result<PoolRewardExtensions<'info>> {
+    let mut reward_extensions =
+        get_pool_reward_extensions(pool_id, reward_extension_num, remaining_accounts)?;
+    reward_extensions.rent_payer = get_rent_payer(remaining_accounts)?;
+    Ok(reward_extensions)
+}
+
+/// Take the system program and the rent payer from the front of `remaining_accounts` if the
+/// first account is the system program
+pub fn get_rent_payer<'info>(
+    remaining_accounts: &mut &[AccountInfo<'info>],
+) -> Result<Option<RentPayer<'info>>> {
+    match *remaining_accounts {
+        [system_program_info, payer, ..] if system_program_info.key() == System::id() => {
+            require!(payer.is_signer, anchor_lang::error::ErrorCode::AccountNotSigner);
+            require!(payer.is_writable, anchor_lang::error::ErrorCode::AccountNotMutable);
+            let rent_payer = RentPayer {
+                system_program: Program::try_from(system_program_info)?,
+                payer: payer.clone(),
+            };
+            *remaining_accounts = &remaining_accounts[2..];
+            Ok(Some(rent_payer))
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod pool_reward_extension_test {
+    use super::*;
+    use crate::libraries::fixed_point_64::Q64;
+
+    fn build_extension(reward_growth_global_x64: u128) -> (PoolRewardExtension, Vec<u8>) {
+        let mut extension = PoolRewardExtension::default();
+        extension.initialize(
+            Pubkey::new_unique(),
+            0,
+            RewardInfo {
+                token_mint: Pubkey::new_unique(),
+                reward_growth_global_x64,
+                ..Default::default()
+            },
+        );
+        (extension, vec![0u8; 1024])
+    }
+
+    fn build_tick(tick: i32, reward_growth_outside_x64: u128) -> TickState {
+        let mut tick_state = TickState {
+            tick,
+            ..Default::default()
+        };
+        tick_state.extension_reward_growths_outside_x64[0] = reward_growth_outside_x64;
+        tick_state
+    }
+
+    #[test]
+    fn initialize_and_cross_tick_test() {
+        let (mut extension, _) = build_extension(1000);
+        extension.index = 1;
+        let mut tick_lower_state = build_tick(-10, 7);
+        let mut tick_upper_state = build_tick(10, 7);
+
+        // a tick at or below the current tick takes all the reward growth as outside
+        extension.initialize_tick(0, &mut tick_lower_state);
+        extension.initialize_tick(0, &mut tick_upper_state);
+        assert_eq!(
+            { tick_lower_state.extension_reward_growths_outside_x64 },
+            [7, 1000, 0]
+        );
+        assert_eq!(
+            { tick_upper_state.extension_reward_growths_outside_x64 },
+            [7, 0, 0]
+        );
+
+        extension.reward_info.reward_growth_global_x64 = 1500;
+        extension.cross_tick(&mut tick_lower_state);
+        extension.cross_tick(&mut tick_upper_state);
+        assert_eq!(
+            { tick_lower_state.extension_reward_growths_outside_x64 },
+            [7, 500, 0]
+        );
+        assert_eq!(
+            { tick_upper_state.extension_reward_growths_outside_x64 },
+            [7, 1500, 0]
+        );
+    }
+
+    #[test]
+    fn get_reward_growth_inside_test() {
+        let (extension, _) = build_extension(1000);
+        let tick_lower_state = build_tick(-10, 300);
+        let tick_upper_state = build_tick(10, 100);
+        // in range
+        assert_eq!(
+            extension.get_reward_growth_inside(&tick_lower_state, &tick_upper_state, 0),
+            600
+        );
+        // below the range
+        assert_eq!(
+            extension.get_reward_growth_inside(&tick_lower_state, &tick_upper_state, -20),
+            300u128.wrapping_sub(100)
+        );
+        // above the range
+        assert_eq!(
+            extension.get_reward_growth_inside(&tick_lower_state, &tick_upper_state, 20),
+            100u128.wrapping_sub(300)
+        );
+        // ticks initialized before the extension
+        assert_eq!(
+            extension.get_reward_growth_inside(&build_tick(-20, 0), &build_tick(20, 0), 0),
+            1000
+        );
+    }
+
+    #[test]
+    fn update_position_reward_test() {
+        let (mut extension, mut entries) = build_extension(0);
+        let position_nft_mint = Pubkey::new_unique();
+        let liquidity = 2u128;
+        let (tick_lower_state, tick_upper_state) = (build_tick(-10, 0), build_tick(10, 0));
+
+        // the first update records the reward growth inside
+        extension.reward_info.reward_growth_global_x64 = 10 * Q64;
+        extension
+            .update_position_reward(
+                &mut entries,
+                &position_nft_mint,
+                &tick_lower_state,
+                &tick_upper_state,
+                0,
+                0,
+                liquidity,
+            )
+            .unwrap();
+        let position = extension.get_position_reward_growth(&entries, &position_nft_mint);
+        assert_eq!({ position.growth_inside_last_x64 }, 10 * Q64);
+        assert_eq!({ position.reward_amount_owed }, 0);
+
+        extension.reward_info.reward_growth_global_x64 = 15 * Q64;
+        extension
+            .update_position_reward(
+                &mut entries,
+                &position_nft_mint,
+                &tick_lower_state,
+                &tick_upper_state,
+                0,
+                liquidity,
+                liquidity,
+            )
+            .unwrap();
+        let position = extension.get_position_reward_growth(&entries, &position_nft_mint);
+        assert_eq!({ position.growth_inside_last_x64 }, 15 * Q64);
+        assert_eq!({ position.reward_amount_owed }, 10);
+        assert_eq!({ extension.position_num }, 1);
+
+        // a position without entry and liquidity stays without entry
+        extension.reward_info.reward_growth_global_x64 = 0;
+        let other_nft_mint = Pubkey::new_unique();
+        extension
+            .update_position_reward(
+                &mut entries,
+                &other_nft_mint,
+                &tick_lower_state,
+                &tick_upper_state,
+                0,
+                liquidity,
+                liquidity,
+            )
+            .unwrap();
+        assert_eq!({ extension.position_num }, 1);
+    }
+
+    #[test]
+    fn remove_cleared_position_test() {
+        let (mut extension, mut entries) = build_extension(10 * Q64);
+        let position_nft_mint = Pubkey::new_unique();
+        let (tick_lower_state, tick_upper_state) = (build_tick(-10, 0), build_tick(10, 0));
+        extension
+            .update_position_reward(
+                &mut entries,
+                &position_nft_mint,
+                &tick_lower_state,
+                &tick_upper_state,
+                0,
+                0,
+                2,
+            )
+            .unwrap();
+        assert_eq!({ extension.position_num }, 1);
+
+        // all the liquidity is removed, the entry stays until the owed reward is collected
+        extension.reward_info.reward_growth_global_x64 = 15 * Q64;
+        extension
+            .update_position_reward(
+                &mut entries,
+                &position_nft_mint,
+                &tick_lower_state,
+                &tick_upper_state,
+                0,
+                2,
+                0,
+            )
+            .unwrap();
+        let mut position = extension.get_position_reward_growth(&entries, &position_nft_mint);
+        assert_eq!({ position.reward_amount_owed }, 10);
+        assert_eq!({ extension.position_num }, 1);
+
+        position.reward_amount_owed = 0;
+        extension
+            .set_position_reward_growth(&mut entries, position, 0)
+            .unwrap();
+        assert_eq!({ extension.position_num }, 0);
+        assert_eq!(extension.entries_len(), 0);
+
+        // a position holding liquidity keeps its reward growth
+        position.growth_inside_last_x64 = 15 * Q64;
+        extension
+            .set_position_reward_growth(&mut entries, position, 1)
+            .unwrap();
+        assert_eq!({ extension.position_num }, 1);
+    }
+
+    #[test]
+    fn get_rent_payer_test() {
+        let system_program_id = System::id();
+        let payer_key = Pubkey::new_unique();
+        let tick_array_key = Pubkey::new_unique();
+        let (mut system_lamports, mut payer_lamports, mut tick_array_lamports) = (0, 0, 0);
+        let (mut system_data, mut payer_data, mut tick_array_data) = (vec![], vec![], vec![]);
+        let system_program_info = AccountInfo::new(
+            &system_program_id,
+            false,
+            false,
+            &mut system_lamports,
+            &mut system_data,
+            &system_program_id,
+            true,
+            0,
+        );
+        let payer = AccountInfo::new(
+            &payer_key,
+            true,
+            true,
+            &mut payer_lamports,
+            &mut payer_data,
+            &system_program_id,
+            false,
+            0,
+        );
+        let tick_array = AccountInfo::new(
+            &tick_array_key,
+            false,
+            true,
+            &mut tick_array_lamports,
+            &mut tick_array_data,
+            &system_program_id,
+            false,
+            0,
+        );
+
+        // the tick arrays follow the rent payer
+        let accounts = [system_program_info.clone(), payer.clone(), tick_array.clone()];
+        let mut remaining_accounts = &accounts[..];
+        let rent_payer = get_rent_payer(&mut remaining_accounts).unwrap().unwrap();
+        assert_eq!(rent_payer.payer.key(), payer_key);
+        assert_eq!(remaining_accounts.len(), 1);
+        assert_eq!(remaining_accounts[0].key(), tick_array_key);
+
+        // no rent payer
+        let accounts = [tick_array.clone()];
+        let mut remaining_accounts = &accounts[..];
+        assert!(get_rent_payer(&mut remaining_accounts).unwrap().is_none());
+        assert_eq!(remaining_accounts.len(), 1);
+
+        // the rent payer must sign
+        let mut payer_not_signer = payer.clone();
+        payer_not_signer.is_signer = false;
+        let accounts = [system_program_info, payer_not_signer];
+        let mut remaining_accounts = &accounts[..];
+        assert!(get_rent_payer(&mut remaining_accounts).is_err());
+    }
+}
@@ -43,12 +43,19 @@ pub struct ProtocolPositionState {
 
     /// The reward growth per unit of liquidity as of the last update to liquidity
     pub reward_growth_inside: [u128; REWARD_NUM], // 24
+
+    /// The account paid the rent of the protocol position, refunded when the protocol position is closed
+    pub payer: Pubkey,
+
+    /// The number of personal positions on the range, the protocol position can only be closed
+    /// once it drops to zero
+    pub personal_position_count: u64,
     // Unused bytes for future upgrades.
-    pub padding: [u64; 8],
+    pub padding: [u64; 3],
 }
 
 impl ProtocolPositionState {
-    pub const LEN: usize = 8 + 1 + 32 + 4 + 4 + 16 + 16 + 16 + 8 + 8 + 16 * REWARD_NUM + 64;
+    pub const LEN: usize = 8 + 1 + 32 + 4 + 4 + 16 + 16 + 16 + 8 + 8 + 16 * REWARD_NUM + 32 + 32;
 
     pub fn update(
         &mut self,
@@ -2,6 +2,7 @@ use super::pool::PoolState;
 use crate::error::ErrorCode;
 use crate::libraries::{liquidity_math, tick_math};
 use crate::pool::{RewardInfo, REWARD_NUM};
+use crate::pool_reward_extension::MAX_REWARD_EXTENSION_NUM;
 use crate::util::*;
 use crate::Result;
 use anchor_lang::{error::ErrorCode as anchorErrorCode, prelude::*, system_program};
@@ -24,12 +25,17 @@ pub struct TickArrayState {
     pub start_tick_index: i32,
     pub ticks: [TickState; TICK_ARRAY_SIZE_USIZE],
     pub initialized_tick_count: u8,
+    /// The account paid the rent of the tick array, refunded when the tick array is closed
+    pub payer: Pubkey,
+    /// The number of ticks of the array referenced by a protocol position, the tick array can only
+    /// be closed once it drops to zero
+    pub protocol_position_count: u32,
     // Unused bytes for future upgrades.
-    pub padding: [u8; 115],
+    pub padding: [u8; 79],
 }
 
 impl TickArrayState {
-    pub const LEN: usize = 8 + 32 + 4 + TickState::LEN * TICK_ARRAY_SIZE_USIZE + 1 + 115;
+    pub const LEN: usize = 8 + 32 + 4 + TickState::LEN * TICK_ARRAY_SIZE_USIZE + 1 + 32 + 4 + 79;
 
     fn discriminator() -> [u8; 8] {
         [192, 155, 85, 205, 49, 249, 129, 42]
@@ -85,6 +91,7 @@ impl TickArrayState {
         );
 
         let tick_array_state = if tick_array_account_info.owner == &system_program::ID {
+            let payer_key = payer.key();
             let (expect_pda_address, bump) = Pubkey::find_program_address(
                 &[
                     TICK_ARRAY_SEED.as_bytes(),
@@ -118,6 +125,7 @@ impl TickArrayState {
                     tick_spacing,
                     pool_state_loader.key(),
                 )?;
+                tick_array_account.payer = payer_key;
             }
             // save the 8 byte discriminator
             tick_array_state_loader.exit(&crate::id())?;
@@ -156,6 +164,17 @@ impl TickArrayState {
         Ok(())
     }
 
+    /// Counts a reference of a protocol position to a tick of the array
+    pub fn update_protocol_position_count(&mut self, add: bool) -> Result<()> {
+        self.protocol_position_count = if add {
+            self.protocol_position_count.checked_add(1)
+        } else {
+            self.protocol_position_count.checked_sub(1)
+        }
+        .unwrap();
+        Ok(())
+    }
+
     pub fn get_tick_state_mut(
         &mut self,
         tick_index: i32,
@@ -291,7 +310,9 @@ impl Default for TickArrayState {
             ticks: [TickState::default(); TICK_ARRAY_SIZE_USIZE],
             start_tick_index: 0,
             initialized_tick_count: 0,
-            padding: [0; 115],
+            payer: Pubkey::default(),
+            protocol_position_count: 0,
+            padding: [0; 79],
         }
     }
 }
@@ -313,17 +334,15 @@ pub struct TickState {
 
     // Reward growth per unit of liquidity like fee, array of Q64.64
     pub reward_growths_outside_x64: [u128; REWARD_NUM],
+    // Reward growth per unit of liquidity of the reward extensions by their index, array of Q64.64
+    pub extension_reward_growths_outside_x64: [u128; MAX_REWARD_EXTENSION_NUM],
     // Unused bytes for future upgrades.
-    pub padding: [u32; 13],
-    // pub cross_up_liquidity_delta: u128,
-    // pub cross_down_liquidity_delta: u128,
-    // pub range_order_cross_up_time: u64,
-    // pub range_order_cross_down_time: u64,
-    // pub padding: u32,
+    pub padding: [u32; 1],
 }
 
 impl TickState {
-    pub const LEN: usize = 4 + 16 + 16 + 16 + 16 + 16 * REWARD_NUM + 16 + 16 + 8 + 8 + 4;
+    pub const LEN: usize =
+        4 + 16 + 16 + 16 + 16 + 16 * REWARD_NUM + 16 * MAX_REWARD_EXTENSION_NUM + 4;
 
     pub fn initialize(&mut self, tick: i32, tick_spacing: u16) -> Result<()> {
         check_tick_boundary(tick, tick_spacing)?;
@@ -404,6 +423,8 @@ impl TickState {
         self.fee_growth_outside_0_x64 = 0;
         self.fee_growth_outside_1_x64 = 0;
         self.reward_growths_outside_x64 = [0; REWARD_NUM];
+        // the reward extensions accrue the rewards of the positions after their ticks are cleared,
+        // their reward growths outside are reset once the tick is initialized again
     }
 
     pub fn is_initialized(self) -> bool {
@@ -802,6 +823,25 @@ pub mod tick_array_test {
             next_tick_state = tick_array.next_initialized_tick(-10, 15, false).unwrap();
             assert!(next_tick_state.is_none());
         }
+
+        #[test]
+        fn clear_keeps_extension_reward_growths_test() {
+            assert_eq!(std::mem::size_of::<TickState>(), TickState::LEN);
+
+            let mut tick_state = build_tick_with_fee_reward_growth(10, 1, 2, 3).into_inner();
+            tick_state.liquidity_gross = 1;
+            tick_state.extension_reward_growths_outside_x64 = [4, 5, 6];
+            tick_state.clear();
+            assert!(!tick_state.is_initialized());
+            assert_eq!(
+                identity(tick_state.reward_growths_outside_x64),
+                [0; REWARD_NUM]
+            );
+            assert_eq!(
+                identity(tick_state.extension_reward_growths_outside_x64),
+                [4, 5, 6]
+            );
+        }
     }
 
     mod get_fee_growth_inside_test {
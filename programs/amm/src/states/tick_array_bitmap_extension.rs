@@ -0,0 +1,253 @@
+use crate::error::ErrorCode;
+use crate::libraries::tick_array_bit_map::{
+    get_tick_array_index, next_set_bit, previous_set_bit, TICK_ARRAY_BITMAP_SIZE,
+};
+use crate::states::tick_array::{
+    MAX_TICK_ARRAY_START_INDEX, MIN_TICK_ARRAY_START_INDEX, TICK_ARRAY_SIZE,
+};
+use anchor_lang::prelude::*;
+
+pub const TICK_ARRAY_BITMAP_EXTENSION_SEED: &str = "tick_array_bitmap_extension";
+/// Bitmaps of 512 tick arrays each side of the extension holds, enough for the whole tick range of tick spacing 1
+pub const EXTENSION_TICKARRAY_BITMAP_SIZE: usize = 9;
+
+/// The tick array bitmap of the pool beyond the 1024 tick arrays of `PoolState::tick_array_bitmap`,
+/// only narrow tick spacing pools need it to cover the whole tick range.
+/// PDA of `[TICK_ARRAY_BITMAP_EXTENSION_SEED, pool_state]`
+#[account(zero_copy(unsafe))]
+#[repr(packed)]
+#[derive(Debug)]
+pub struct TickArrayBitmapExtension {
+    /// The pool the extension belongs to
+    pub pool_id: Pubkey,
+    /// Tick arrays above the pool bitmap, bit n is the tick array of index 512 + n
+    pub positive_tick_array_bitmap: [[u64; 8]; EXTENSION_TICKARRAY_BITMAP_SIZE],
+    /// Tick arrays below the pool bitmap, bit n is the tick array of index -513 - n
+    pub negative_tick_array_bitmap: [[u64; 8]; EXTENSION_TICKARRAY_BITMAP_SIZE],
+}
+
+impl Default for TickArrayBitmapExtension {
+    #[inline]
+    fn default() -> TickArrayBitmapExtension {
+        TickArrayBitmapExtension {
+            pool_id: Pubkey::default(),
+            positive_tick_array_bitmap: [[0; 8]; EXTENSION_TICKARRAY_BITMAP_SIZE],
+            negative_tick_array_bitmap: [[0; 8]; EXTENSION_TICKARRAY_BITMAP_SIZE],
+        }
+    }
+}
+
+impl TickArrayBitmapExtension {
+    pub const LEN: usize = 8 + 32 + 64 * EXTENSION_TICKARRAY_BITMAP_SIZE * 2;
+
+    pub fn initialize(&mut self, pool_id: Pubkey) {
+        self.pool_id = pool_id;
+        self.positive_tick_array_bitmap = [[0; 8]; EXTENSION_TICKARRAY_BITMAP_SIZE];
+        self.negative_tick_array_bitmap = [[0; 8]; EXTENSION_TICKARRAY_BITMAP_SIZE];
+    }
+
+    pub fn get_address(pool_id: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                TICK_ARRAY_BITMAP_EXTENSION_SEED.as_bytes(),
+                pool_id.as_ref(),
+            ],
+            &crate::id(),
+        )
+        .0
+    }
+
+    /// Returns whether the positive bitmap holds the tick array and the bit offset of it
+    fn get_bitmap_offset(tick_array_start_index: i32, tick_spacing: u16) -> Result<(bool, usize)> {
+        require!(
+            (MIN_TICK_ARRAY_START_INDEX..=MAX_TICK_ARRAY_START_INDEX)
+                .contains(&tick_array_start_index),
+            ErrorCode::InvaildTickIndex
+        );
+        require_eq!(
+            0,
+            tick_array_start_index % (TICK_ARRAY_SIZE * i32::from(tick_spacing))
+        );
+        let tick_array_index = get_tick_array_index(tick_array_start_index, tick_spacing.into());
+        let (is_positive, offset) = if tick_array_index >= TICK_ARRAY_BITMAP_SIZE {
+            (true, tick_array_index - TICK_ARRAY_BITMAP_SIZE)
+        } else if tick_array_index < -TICK_ARRAY_BITMAP_SIZE {
+            (false, -tick_array_index - TICK_ARRAY_BITMAP_SIZE - 1)
+        } else {
+            // covered by the bitmap of the pool
+            return err!(ErrorCode::InvalidTickArrayBoundary);
+        };
+        Ok((is_positive, offset as usize))
+    }
+
+    /// Flips the initialized bit of the tick array beyond the pool bitmap
+    pub fn flip_tick_array_bit(
+        &mut self,
+        tick_array_start_index: i32,
+        tick_spacing: u16,
+    ) -> Result<()> {
+        let (is_positive, offset) = Self::get_bitmap_offset(tick_array_start_index, tick_spacing)?;
+        let mut bitmap = if is_positive {
+            self.positive_tick_array_bitmap
+        } else {
+            self.negative_tick_array_bitmap
+        };
+        bitmap[offset / 512][offset % 512 / 64] ^= 1 << (offset % 64);
+        if is_positive {
+            self.positive_tick_array_bitmap = bitmap;
+        } else {
+            self.negative_tick_array_bitmap = bitmap;
+        }
+        Ok(())
+    }
+
+    /// Whether the tick array beyond the pool bitmap is initialized
+    pub fn is_tick_array_initialized(
+        &self,
+        tick_array_start_index: i32,
+        tick_spacing: u16,
+    ) -> Result<bool> {
+        let (is_positive, offset) = Self::get_bitmap_offset(tick_array_start_index, tick_spacing)?;
+        let bitmap = if is_positive {
+            self.positive_tick_array_bitmap
+        } else {
+            self.negative_tick_array_bitmap
+        };
+        Ok(bitmap[offset / 512][offset % 512 / 64] & (1 << (offset % 64)) != 0)
+    }
+
+    /// Search the next initialized tick array above the tick array index in the positive bitmap
+    pub fn next_positive_tick_array_index(&self, tick_array_index: i32) -> Option<i32> {
+        let bitmap = self.positive_tick_array_bitmap;
+        let from = (tick_array_index + 1 - TICK_ARRAY_BITMAP_SIZE).max(0) as usize;
+        next_set_bit(&bitmap, from).map(|offset| offset as i32 + TICK_ARRAY_BITMAP_SIZE)
+    }
+
+    /// Search the next initialized tick array below the tick array index in the positive bitmap
+    pub fn previous_positive_tick_array_index(&self, tick_array_index: i32) -> Option<i32> {
+        let to = tick_array_index - 1 - TICK_ARRAY_BITMAP_SIZE;
+        if to < 0 {
+            return None;
+        }
+        let bitmap = self.positive_tick_array_bitmap;
+        previous_set_bit(&bitmap, to as usize).map(|offset| offset as i32 + TICK_ARRAY_BITMAP_SIZE)
+    }
+
+    /// Search the next initialized tick array above the tick array index in the negative bitmap
+    pub fn next_negative_tick_array_index(&self, tick_array_index: i32) -> Option<i32> {
+        let to = -(tick_array_index + 1) - TICK_ARRAY_BITMAP_SIZE - 1;
+        if to < 0 {
+            return None;
+        }
+        let bitmap = self.negative_tick_array_bitmap;
+        previous_set_bit(&bitmap, to as usize)
+            .map(|offset| -(offset as i32) - TICK_ARRAY_BITMAP_SIZE - 1)
+    }
+
+    /// Search the next initialized tick array below the tick array index in the negative bitmap
+    pub fn previous_negative_tick_array_index(&self, tick_array_index: i32) -> Option<i32> {
+        let from = (-(tick_array_index - 1) - TICK_ARRAY_BITMAP_SIZE - 1).max(0) as usize;
+        let bitmap = self.negative_tick_array_bitmap;
+        next_set_bit(&bitmap, from).map(|offset| -(offset as i32) - TICK_ARRAY_BITMAP_SIZE - 1)
+    }
+}
+
+/// Take the tick array bitmap extension of the pool from the front of `remaining_accounts` if it is there
+pub fn get_tick_array_bitmap_extension<'info>(
+    pool_id: &Pubkey,
+    remaining_accounts: &mut &[AccountInfo<'info>],
+) -> Result<Option<AccountLoader<'info, TickArrayBitmapExtension>>> {
+    match remaining_accounts.first() {
+        Some(account_info)
+            if account_info.key() == TickArrayBitmapExtension::get_address(pool_id) =>
+        {
+            let tick_array_bitmap_extension =
+                AccountLoader::<TickArrayBitmapExtension>::try_from(account_info)?;
+            *remaining_accounts = &remaining_accounts[1..];
+            Ok(Some(tick_array_bitmap_extension))
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tick_array_bitmap_extension_test {
+    use super::*;
+
+    #[test]
+    fn flip_tick_array_bit_test() {
+        let tick_spacing = 1;
+        let mut extension = TickArrayBitmapExtension::default();
+        // covered by the pool bitmap
+        assert!(extension.flip_tick_array_bit(30660, tick_spacing).is_err());
+        assert!(extension.flip_tick_array_bit(-30720, tick_spacing).is_err());
+        // not a tick array start index
+        assert!(extension.flip_tick_array_bit(30721, tick_spacing).is_err());
+
+        extension.flip_tick_array_bit(30720, tick_spacing).unwrap();
+        extension.flip_tick_array_bit(-30780, tick_spacing).unwrap();
+        extension
+            .flip_tick_array_bit(MAX_TICK_ARRAY_START_INDEX, tick_spacing)
+            .unwrap();
+        extension
+            .flip_tick_array_bit(MIN_TICK_ARRAY_START_INDEX, tick_spacing)
+            .unwrap();
+        assert_eq!({ extension.positive_tick_array_bitmap }[0][0], 1);
+        assert_eq!({ extension.negative_tick_array_bitmap }[0][0], 1);
+        // 306600 / 60 - 512 = 4598
+        assert_eq!(
+            { extension.positive_tick_array_bitmap }[8][(4598 % 512) / 64],
+            1 << (4598 % 64)
+        );
+        // 307200 / 60 - 513 = 4607, the last bit
+        assert_eq!({ extension.negative_tick_array_bitmap }[8][7], 1 << 63);
+        assert!(extension
+            .is_tick_array_initialized(30720, tick_spacing)
+            .unwrap());
+        assert!(!extension
+            .is_tick_array_initialized(30780, tick_spacing)
+            .unwrap());
+
+        extension.flip_tick_array_bit(30720, tick_spacing).unwrap();
+        assert!(!extension
+            .is_tick_array_initialized(30720, tick_spacing)
+            .unwrap());
+        assert_eq!({ extension.positive_tick_array_bitmap }[0][0], 0);
+    }
+
+    #[test]
+    fn search_tick_array_index_test() {
+        let tick_spacing = 1;
+        let mut extension = TickArrayBitmapExtension::default();
+        for start_index in [30720, 60000, 300000, -30780, -60000, -300000] {
+            extension
+                .flip_tick_array_bit(start_index, tick_spacing)
+                .unwrap();
+        }
+        assert_eq!(extension.next_positive_tick_array_index(511), Some(512));
+        assert_eq!(extension.next_positive_tick_array_index(512), Some(1000));
+        assert_eq!(extension.next_positive_tick_array_index(5000), None);
+        assert_eq!(
+            extension.previous_positive_tick_array_index(5110),
+            Some(5000)
+        );
+        assert_eq!(
+            extension.previous_positive_tick_array_index(1000),
+            Some(512)
+        );
+        assert_eq!(extension.previous_positive_tick_array_index(512), None);
+
+        assert_eq!(
+            extension.previous_negative_tick_array_index(-512),
+            Some(-513)
+        );
+        assert_eq!(
+            extension.previous_negative_tick_array_index(-513),
+            Some(-1000)
+        );
+        assert_eq!(extension.previous_negative_tick_array_index(-5000), None);
+        assert_eq!(extension.next_negative_tick_array_index(-5120), Some(-5000));
+        assert_eq!(extension.next_negative_tick_array_index(-1000), Some(-513));
+        assert_eq!(extension.next_negative_tick_array_index(-513), None);
+    }
+}
@@ -0,0 +1,314 @@
+use super::{FEE_RATE_DENOMINATOR_VALUE, MAX_TRADE_FEE_RATE_OVERRIDE};
+use crate::error::ErrorCode;
+use crate::instructions::DynamicFeeParam;
+use anchor_lang::prelude::*;
+
+pub const TIMELOCK_CONFIG_SEED: &str = "timelock_config";
+pub const TIMELOCK_ACTION_SEED: &str = "timelock_action";
+/// The longest delay of the timelock, 30 days
+pub const MAX_TIMELOCK_DELAY: u64 = 30 * 24 * 60 * 60;
+
+/// The delay of the timelocked admin actions, the fee rate and pool status changes of the admin
+/// must be queued once the delay is set.
+/// PDA of `[TIMELOCK_CONFIG_SEED]`
+#[account]
+#[derive(Default, Debug)]
+pub struct TimelockConfig {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The seconds a queued action waits before it can be executed, 0 disables the timelock
+    pub delay: u64,
+    /// The number of actions ever queued, the id of the next action
+    pub action_num: u64,
+    // padding space for upgrade
+    pub padding: [u64; 8],
+}
+
+impl TimelockConfig {
+    pub const LEN: usize = 8 + 1 + 8 + 8 + 8 * 8;
+
+    /// The admin acts immediately only if the timelock config is not created or its delay is 0
+    pub fn check_disabled(timelock_config: &AccountInfo) -> Result<()> {
+        if timelock_config.data_is_empty() {
+            return Ok(());
+        }
+        let timelock_config = Account::<TimelockConfig>::try_from(timelock_config)?;
+        require_eq!(timelock_config.delay, 0, ErrorCode::TimelockEnabled);
+        Ok(())
+    }
+}
+
+/// The admin actions the timelock can delay
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AdminAction {
+    SetTradeFeeRate {
+        amm_config: Pubkey,
+        trade_fee_rate: u32,
+    },
+    SetProtocolFeeRate {
+        amm_config: Pubkey,
+        protocol_fee_rate: u32,
+    },
+    SetFundFeeRate {
+        amm_config: Pubkey,
+        fund_fee_rate: u32,
+    },
+    UpdatePoolStatus {
+        pool_state: Pubkey,
+        status: u8,
+    },
+    /// 0 removes the trade fee rate override of the pool
+    SetPoolTradeFeeRate {
+        pool_state: Pubkey,
+        trade_fee_rate_override: u32,
+    },
+    SetDynamicFeeParams {
+        pool_state: Pubkey,
+        param: DynamicFeeParam,
+    },
+    /// Lowering the delay is timelocked by the current delay
+    SetTimelockDelay {
+        delay: u64,
+    },
+}
+
+impl AdminAction {
+    /// The largest variant plus the variant index
+    pub const LEN: usize = 1 + 32 + 4 + 4 + 4 + 2 + 2 + 2;
+
+    /// Rejects an action that can never be executed, the fee rates shared with the other fee
+    /// rates of the amm config are checked again once executed
+    pub fn check(&self) -> Result<()> {
+        match *self {
+            AdminAction::SetTradeFeeRate { trade_fee_rate, .. } => require_gt!(
+                FEE_RATE_DENOMINATOR_VALUE,
+                trade_fee_rate,
+                ErrorCode::InvalidTradeFeeRate
+            ),
+            AdminAction::SetProtocolFeeRate {
+                protocol_fee_rate, ..
+            } => require_gte!(
+                FEE_RATE_DENOMINATOR_VALUE,
+                protocol_fee_rate,
+                ErrorCode::InvalidProtocolFeeRate
+            ),
+            AdminAction::SetFundFeeRate { fund_fee_rate, .. } => require_gte!(
+                FEE_RATE_DENOMINATOR_VALUE,
+                fund_fee_rate,
+                ErrorCode::InvalidFundFeeRate
+            ),
+            AdminAction::UpdatePoolStatus { .. } => {}
+            AdminAction::SetPoolTradeFeeRate {
+                trade_fee_rate_override,
+                ..
+            } => require_gte!(
+                MAX_TRADE_FEE_RATE_OVERRIDE,
+                trade_fee_rate_override,
+                ErrorCode::InvalidTradeFeeRateOverride
+            ),
+            AdminAction::SetDynamicFeeParams { param, .. } => {
+                // the cap is checked against the trade fee rate of the pool once executed
+                param.check(0)?
+            }
+            AdminAction::SetTimelockDelay { delay } => {
+                require_gte!(MAX_TIMELOCK_DELAY, delay, ErrorCode::InvalidTimelockDelay)
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An admin action queued behind the timelock, anyone can execute it once the delay passes.
+/// PDA of `[TIMELOCK_ACTION_SEED, id]`
+#[account]
+#[derive(Debug)]
+pub struct TimelockAction {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The index of the action in the queue order
+    pub id: u64,
+    /// The action to execute
+    pub action: AdminAction,
+    /// The time from which the action can be executed
+    pub execute_after: u64,
+    /// Pays the rent of the action and receives it back once executed or cancelled
+    pub payer: Pubkey,
+}
+
+impl TimelockAction {
+    pub const LEN: usize = 8 + 1 + 8 + AdminAction::LEN + 8 + 32;
+}
+
+/// Emitted when an admin action is queued behind the timelock
+#[event]
+pub struct QueueAdminActionEvent {
+    #[index]
+    pub id: u64,
+    pub action: AdminAction,
+    pub execute_after: u64,
+}
+
+/// Emitted when a queued admin action is cancelled before its execution
+#[event]
+pub struct CancelAdminActionEvent {
+    #[index]
+    pub id: u64,
+    pub action: AdminAction,
+}
+
+/// Emitted when a queued admin action is executed
+#[event]
+pub struct ExecuteAdminActionEvent {
+    #[index]
+    pub id: u64,
+    pub action: AdminAction,
+}
+
+/// Emitted when the delay of the timelock changes, directly or by an executed admin action
+#[event]
+pub struct SetTimelockDelayEvent {
+    pub old_delay: u64,
+    pub new_delay: u64,
+}
+
+#[cfg(test)]
+mod timelock_test {
+    use super::*;
+
+    #[test]
+    fn admin_action_len_test() {
+        let actions = [
+            AdminAction::SetTradeFeeRate {
+                amm_config: Pubkey::new_unique(),
+                trade_fee_rate: u32::MAX,
+            },
+            AdminAction::SetProtocolFeeRate {
+                amm_config: Pubkey::new_unique(),
+                protocol_fee_rate: u32::MAX,
+            },
+            AdminAction::SetFundFeeRate {
+                amm_config: Pubkey::new_unique(),
+                fund_fee_rate: u32::MAX,
+            },
+            AdminAction::UpdatePoolStatus {
+                pool_state: Pubkey::new_unique(),
+                status: u8::MAX,
+            },
+            AdminAction::SetPoolTradeFeeRate {
+                pool_state: Pubkey::new_unique(),
+                trade_fee_rate_override: u32::MAX,
+            },
+            AdminAction::SetDynamicFeeParams {
+                pool_state: Pubkey::new_unique(),
+                param: DynamicFeeParam {
+                    max_fee_rate: u32::MAX,
+                    dynamic_fee_control: u32::MAX,
+                    max_volatility_accumulator: u32::MAX,
+                    filter_period: u16::MAX,
+                    decay_period: u16::MAX,
+                    reduction_factor: u16::MAX,
+                },
+            },
+            AdminAction::SetTimelockDelay {
+                delay: MAX_TIMELOCK_DELAY,
+            },
+        ];
+        let max_len = actions
+            .iter()
+            .map(|action| action.try_to_vec().unwrap().len())
+            .max()
+            .unwrap();
+        assert_eq!(max_len, AdminAction::LEN);
+    }
+
+    #[test]
+    fn check_admin_action_test() {
+        let amm_config = Pubkey::new_unique();
+        let pool_state = Pubkey::new_unique();
+        let param = DynamicFeeParam {
+            max_fee_rate: 100_000,
+            dynamic_fee_control: 50_000,
+            max_volatility_accumulator: 350_000,
+            filter_period: 30,
+            decay_period: 600,
+            reduction_factor: 5_000,
+        };
+        for action in [
+            AdminAction::SetTradeFeeRate {
+                amm_config,
+                trade_fee_rate: FEE_RATE_DENOMINATOR_VALUE - 1,
+            },
+            AdminAction::SetProtocolFeeRate {
+                amm_config,
+                protocol_fee_rate: FEE_RATE_DENOMINATOR_VALUE,
+            },
+            AdminAction::SetFundFeeRate {
+                amm_config,
+                fund_fee_rate: FEE_RATE_DENOMINATOR_VALUE,
+            },
+            AdminAction::UpdatePoolStatus {
+                pool_state,
+                status: u8::MAX,
+            },
+            AdminAction::SetPoolTradeFeeRate {
+                pool_state,
+                trade_fee_rate_override: MAX_TRADE_FEE_RATE_OVERRIDE,
+            },
+            AdminAction::SetDynamicFeeParams { pool_state, param },
+            AdminAction::SetTimelockDelay {
+                delay: MAX_TIMELOCK_DELAY,
+            },
+        ] {
+            action.check().unwrap();
+        }
+
+        for (action, error) in [
+            (
+                AdminAction::SetTradeFeeRate {
+                    amm_config,
+                    trade_fee_rate: FEE_RATE_DENOMINATOR_VALUE,
+                },
+                ErrorCode::InvalidTradeFeeRate,
+            ),
+            (
+                AdminAction::SetProtocolFeeRate {
+                    amm_config,
+                    protocol_fee_rate: FEE_RATE_DENOMINATOR_VALUE + 1,
+                },
+                ErrorCode::InvalidProtocolFeeRate,
+            ),
+            (
+                AdminAction::SetFundFeeRate {
+                    amm_config,
+                    fund_fee_rate: FEE_RATE_DENOMINATOR_VALUE + 1,
+                },
+                ErrorCode::InvalidFundFeeRate,
+            ),
+            (
+                AdminAction::SetPoolTradeFeeRate {
+                    pool_state,
+                    trade_fee_rate_override: MAX_TRADE_FEE_RATE_OVERRIDE + 1,
+                },
+                ErrorCode::InvalidTradeFeeRateOverride,
+            ),
+            (
+                AdminAction::SetDynamicFeeParams {
+                    pool_state,
+                    param: DynamicFeeParam {
+                        filter_period: 601,
+                        ..param
+                    },
+                },
+                ErrorCode::InvalidDynamicFeeParam,
+            ),
+            (
+                AdminAction::SetTimelockDelay {
+                    delay: MAX_TIMELOCK_DELAY + 1,
+                },
+                ErrorCode::InvalidTimelockDelay,
+            ),
+        ] {
+            assert_eq!(action.check().unwrap_err(), error.into());
+        }
+    }
+}
@@ -54,3 +54,24 @@ pub fn create_or_allocate_account<'a>(
     }
     Ok(())
 }
+
+pub fn realloc_account<'a>(
+    payer: AccountInfo<'a>,
+    system_program: AccountInfo<'a>,
+    target_account: AccountInfo<'a>,
+    space: usize,
+) -> Result<()> {
+    let required_lamports = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(target_account.lamports());
+    if required_lamports > 0 {
+        let cpi_accounts = system_program::Transfer {
+            from: payer,
+            to: target_account.clone(),
+        };
+        let cpi_context = CpiContext::new(system_program, cpi_accounts);
+        system_program::transfer(cpi_context, required_lamports)?;
+    }
+    target_account.realloc(space, true)?;
+    Ok(())
+}
@@ -1,37 +1,59 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token_2022;
-use anchor_spl::token_interface::{Token2022, CloseAccount, TransferChecked, Burn};
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{
+        transfer_fee::{TransferFeeAmount, TransferFeeConfig, MAX_FEE_BASIS_POINTS},
+        ExtensionType,
+    },
+    instruction::initialize_mint_close_authority,
+    pod::pod_from_bytes,
+    state::{Account as AccountState, AccountState as TokenAccountState, Mint as MintState, Multisig},
+};
+use anchor_lang::solana_program::{self, instruction::Instruction, program_pack::Pack};
+use anchor_lang::system_program::{self, CreateAccount};
+use std::mem::size_of;
+use anchor_spl::token_interface::{Token2022, TokenInterface, CloseAccount, TransferChecked, Burn, InitializeAccount3, InitializeMint2};
 use anchor_spl::token_interface::{TokenAccount, Mint};
+use crate::error::ErrorCode;
 use crate::states::*;
+use crate::util::create_or_allocate_account;
 
 pub fn transfer_from_user_to_pool_vault<'info>(
     signer: &Signer<'info>,
     from: &InterfaceAccount<'info, TokenAccount>,
     to_vault: &InterfaceAccount<'info, TokenAccount>,
-    token_program: &Program<'info, Token2022>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    transfer_hook_accounts: &[AccountInfo<'info>],
     amount: u64,
-    decimals: u8,
 ) -> Result<()> {
     if amount == 0 {
         return Ok(());
     }
 
-    token_2022::transfer_checked(CpiContext::new(
-        token_program.to_account_info(),
-        TransferChecked {
-            from: from.to_account_info(),
-            mint: from.to_account_info(),
-            to: to_vault.to_account_info(),
-            authority: signer.to_account_info(),
-        },
-    ), amount, decimals)
+    transfer_checked_with_hook(
+        CpiContext::new(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: from.to_account_info(),
+                mint: mint.to_account_info(),
+                to: to_vault.to_account_info(),
+                authority: signer.to_account_info(),
+            },
+        )
+        .with_remaining_accounts(transfer_hook_accounts.to_vec()),
+        amount,
+        mint.decimals,
+    )
 }
 
 pub fn transfer_from_pool_vault_to_user<'info>(
     pool_state_loader: &AccountLoader<'info, PoolState>,
     from_vault: &InterfaceAccount<'info, TokenAccount>,
     to: &InterfaceAccount<'info, TokenAccount>,
-    token_program: &Program<'info, Token2022>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    transfer_hook_accounts: &[AccountInfo<'info>],
     amount: u64,
 ) -> Result<()> {
     if amount == 0 {
@@ -46,25 +68,487 @@ pub fn transfer_from_pool_vault_to_user<'info>(
         &[pool_state.bump],
     ];
 
-    let decimals;
-    if pool_state.token_mint_0.eq(&from_vault.mint) {
-        decimals = pool_state.mint_decimals_0
-    } else {
-        decimals = pool_state.mint_decimals_1
-    }
-
-    token_2022::transfer_checked(
+    transfer_checked_with_hook(
         CpiContext::new_with_signer(
             token_program.to_account_info(),
             TransferChecked {
                 from: from_vault.to_account_info(),
-                mint: from_vault.to_account_info(),
+                mint: mint.to_account_info(),
                 to: to.to_account_info(),
                 authority: pool_state_loader.to_account_info(),
             },
             &[&pool_state_seeds[..]],
+        )
+        .with_remaining_accounts(transfer_hook_accounts.to_vec()),
+        amount,
+        mint.decimals,
+    )
+}
+
+/// `token_2022::transfer_checked` which also forwards the remaining accounts of the context,
+/// so that the token program can invoke the transfer hook program of the mint
+fn transfer_checked_with_hook<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    let mut ix = token_2022::spl_token_2022::instruction::transfer_checked(
+        ctx.program.key,
+        ctx.accounts.from.key,
+        ctx.accounts.mint.key,
+        ctx.accounts.to.key,
+        ctx.accounts.authority.key,
+        &[],
+        amount,
+        decimals,
+    )?;
+    let mut account_infos = vec![
+        ctx.accounts.from,
+        ctx.accounts.mint,
+        ctx.accounts.to,
+        ctx.accounts.authority,
+    ];
+    for account_info in ctx.remaining_accounts {
+        ix.accounts.push(if account_info.is_writable {
+            AccountMeta::new(account_info.key(), account_info.is_signer)
+        } else {
+            AccountMeta::new_readonly(account_info.key(), account_info.is_signer)
+        });
+        account_infos.push(account_info);
+    }
+    solana_program::program::invoke_signed(&ix, &account_infos, ctx.signer_seeds)
+        .map_err(Into::into)
+}
+
+/// Get the `TransferFeeConfig` extension of a mint. It is looked up in the raw TLV entries, since
+/// `StateWithExtensions::get_extension` stops at the first extension type this crate doesn't know.
+fn get_transfer_fee_config(mint_data: &[u8]) -> Result<Option<TransferFeeConfig>> {
+    for (extension_type, value) in get_mint_extensions(mint_data)? {
+        if extension_type == u16::from(ExtensionType::TransferFeeConfig) {
+            return Ok(Some(*pod_from_bytes::<TransferFeeConfig>(value)?));
+        }
+    }
+    Ok(None)
+}
+
+/// Calculate the fee withheld by the mint when `pre_fee_amount` is transferred,
+/// return 0 if the mint has no `TransferFeeConfig` extension
+pub fn get_transfer_fee(mint: &InterfaceAccount<Mint>, pre_fee_amount: u64) -> Result<u64> {
+    let mint_info = mint.to_account_info();
+    let mint_data = mint_info.try_borrow_data()?;
+    calculate_transfer_fee(&mint_data, Clock::get()?.epoch, pre_fee_amount)
+}
+
+/// Calculate the fee that must be added to a transfer so that the receiver gets exactly
+/// `post_fee_amount`, return 0 if the mint has no `TransferFeeConfig` extension
+pub fn get_transfer_inverse_fee(
+    mint: &InterfaceAccount<Mint>,
+    post_fee_amount: u64,
+) -> Result<u64> {
+    let mint_info = mint.to_account_info();
+    let mint_data = mint_info.try_borrow_data()?;
+    calculate_transfer_inverse_fee(&mint_data, Clock::get()?.epoch, post_fee_amount)
+}
+
+/// `get_transfer_fee` of the raw mint data at `epoch`
+fn calculate_transfer_fee(mint_data: &[u8], epoch: u64, pre_fee_amount: u64) -> Result<u64> {
+    let fee = if let Some(transfer_fee_config) = get_transfer_fee_config(mint_data)? {
+        transfer_fee_config
+            .calculate_epoch_fee(epoch, pre_fee_amount)
+            .unwrap()
+    } else {
+        0
+    };
+    Ok(fee)
+}
+
+/// `get_transfer_inverse_fee` of the raw mint data at `epoch`
+fn calculate_transfer_inverse_fee(
+    mint_data: &[u8],
+    epoch: u64,
+    post_fee_amount: u64,
+) -> Result<u64> {
+    let fee = if let Some(transfer_fee_config) = get_transfer_fee_config(mint_data)? {
+        let transfer_fee = transfer_fee_config.get_epoch_fee(epoch);
+        // the inverse of a 100% fee is undefined, the whole transfer is withheld up to the maximum fee
+        if u16::from(transfer_fee.transfer_fee_basis_points) == MAX_FEE_BASIS_POINTS {
+            u64::from(transfer_fee.maximum_fee)
+        } else {
+            transfer_fee_config
+                .calculate_inverse_epoch_fee(epoch, post_fee_amount)
+                .unwrap()
+        }
+    } else {
+        0
+    };
+    Ok(fee)
+}
+
+/// The length of a token program 2022 account with the account extensions required by the mint
+fn get_vault_account_len(mint_data: &[u8]) -> Result<usize> {
+    let mut extensions_len = 0;
+    for (extension_type, _) in get_mint_extensions(mint_data)? {
+        let account_extension_len = if extension_type == u16::from(ExtensionType::TransferFeeConfig) {
+            Some(size_of::<TransferFeeAmount>())
+        } else if extension_type == u16::from(ExtensionType::NonTransferable) {
+            Some(0)
+        } else if extension_type == TRANSFER_HOOK_EXTENSION_TYPE {
+            // TransferHookAccount only holds the `transferring` flag
+            Some(1)
+        } else {
+            None
+        };
+        if let Some(account_extension_len) = account_extension_len {
+            extensions_len += 4 + account_extension_len;
+        }
+    }
+    if extensions_len == 0 {
+        return Ok(AccountState::LEN);
+    }
+    let account_len = AccountState::LEN + 1 + extensions_len;
+    // the token program keeps accounts with extensions distinguishable from multisig accounts
+    if account_len == Multisig::LEN {
+        Ok(account_len + 4)
+    } else {
+        Ok(account_len)
+    }
+}
+
+/// Create a token account for the pool at the `token_account` PDA, owned by the token program of the `mint`
+pub fn create_token_vault_account<'info>(
+    payer: &Signer<'info>,
+    pool_state: &AccountInfo<'info>,
+    token_account: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    system_program: &Program<'info, System>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer_seeds: &[&[u8]],
+) -> Result<()> {
+    let mint_info = mint.to_account_info();
+    require_keys_eq!(
+        *mint_info.owner,
+        token_program.key(),
+        ErrorCode::InvalidVaultTokenProgram
+    );
+    let space = if *mint_info.owner == Token2022::id() {
+        let mint_data = mint_info.try_borrow_data()?;
+        get_vault_account_len(&mint_data)?
+    } else {
+        anchor_spl::token::TokenAccount::LEN
+    };
+    create_or_allocate_account(
+        token_program.key,
+        payer.to_account_info(),
+        system_program.to_account_info(),
+        token_account.clone(),
+        signer_seeds,
+        space,
+    )?;
+    token_2022::initialize_account3(CpiContext::new(
+        token_program.to_account_info(),
+        InitializeAccount3 {
+            account: token_account.clone(),
+            mint: mint_info,
+            authority: pool_state.clone(),
+        },
+    ))
+}
+
+/// The TLV type of the `TransferHook` mint extension, unknown to the spl-token-2022 version we build against
+pub const TRANSFER_HOOK_EXTENSION_TYPE: u16 = 14;
+/// Seed of the transfer hook `ExtraAccountMetaList` PDA, derived with the mint under the hook program
+pub const EXTRA_ACCOUNT_METAS_SEED: &str = "extra-account-metas";
+/// Packed length of an `ExtraAccountMeta`: discriminator, address config, is_signer, is_writable
+pub const EXTRA_ACCOUNT_META_LEN: usize = 35;
+
+/// Parse the (extension type, value) TLV entries of a token program 2022 mint. Unlike
+/// `StateWithExtensions::get_extension_types`, extension types this crate doesn't know are kept.
+pub fn get_mint_extensions(mint_data: &[u8]) -> Result<Vec<(u16, &[u8])>> {
+    let mut extensions = Vec::new();
+    if mint_data.len() <= MintState::LEN {
+        return Ok(extensions);
+    }
+    // the base mint is padded to the length of a token account, followed by the account type
+    let mut offset = AccountState::LEN + 1;
+    while offset + 4 <= mint_data.len() {
+        let extension_type = u16::from_le_bytes([mint_data[offset], mint_data[offset + 1]]);
+        if extension_type == u16::from(ExtensionType::Uninitialized) {
+            break;
+        }
+        let length = usize::from(u16::from_le_bytes([
+            mint_data[offset + 2],
+            mint_data[offset + 3],
+        ]));
+        let value_start = offset + 4;
+        let value_end = value_start + length;
+        require_gte!(mint_data.len(), value_end, ErrorCode::InvalidMintExtensionData);
+        extensions.push((extension_type, &mint_data[value_start..value_end]));
+        offset = value_end;
+    }
+    Ok(extensions)
+}
+
+/// Get the transfer hook program of a token program 2022 mint, if any
+pub fn get_transfer_hook_program_id(mint: &InterfaceAccount<Mint>) -> Result<Option<Pubkey>> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner != Token2022::id() {
+        return Ok(None);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    for (extension_type, value) in get_mint_extensions(&mint_data)? {
+        if extension_type == TRANSFER_HOOK_EXTENSION_TYPE {
+            // the extension is the hook authority followed by the hook program id, zero means none
+            require_eq!(value.len(), 64, ErrorCode::InvalidMintExtensionData);
+            let program_id = Pubkey::new_from_array(value[32..64].try_into().unwrap());
+            if program_id != Pubkey::default() {
+                return Ok(Some(program_id));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Take the accounts required by the transfer hook of `mint` from the front of `remaining_accounts`.
+///
+/// If the mint has a transfer hook program, the accounts must be in the order
+/// `[hook_program, extra_account_meta_list, ...extra accounts of the list]`, otherwise nothing is taken.
+pub fn get_transfer_hook_accounts<'info>(
+    mint: &InterfaceAccount<Mint>,
+    remaining_accounts: &mut &[AccountInfo<'info>],
+) -> Result<Vec<AccountInfo<'info>>> {
+    let hook_program_id = match get_transfer_hook_program_id(mint)? {
+        Some(hook_program_id) => hook_program_id,
+        None => return Ok(Vec::new()),
+    };
+    require_gte!(
+        remaining_accounts.len(),
+        2,
+        ErrorCode::InvalidTransferHookAccounts
+    );
+    let hook_program = &remaining_accounts[0];
+    let extra_account_meta_list = &remaining_accounts[1];
+    require_keys_eq!(
+        hook_program.key(),
+        hook_program_id,
+        ErrorCode::InvalidTransferHookAccounts
+    );
+    let (extra_account_meta_list_key, _) = Pubkey::find_program_address(
+        &[EXTRA_ACCOUNT_METAS_SEED.as_bytes(), mint.key().as_ref()],
+        &hook_program_id,
+    );
+    require_keys_eq!(
+        extra_account_meta_list.key(),
+        extra_account_meta_list_key,
+        ErrorCode::InvalidTransferHookAccounts
+    );
+
+    // the list is a TLV entry of the execute instruction: 8 bytes discriminator, 4 bytes length,
+    // then a slice of ExtraAccountMeta with 4 bytes count
+    let mut extra_addresses = Vec::new();
+    {
+        let data = extra_account_meta_list.try_borrow_data()?;
+        if data.len() >= 16 {
+            let count = u32::from_le_bytes(data[12..16].try_into().unwrap()) as usize;
+            for i in 0..count {
+                let offset = 16 + i * EXTRA_ACCOUNT_META_LEN;
+                require_gte!(
+                    data.len(),
+                    offset + EXTRA_ACCOUNT_META_LEN,
+                    ErrorCode::InvalidTransferHookAccounts
+                );
+                // only fixed address metas can be checked here, the token program resolves the others
+                extra_addresses.push(if data[offset] == 0 {
+                    Some(Pubkey::new_from_array(
+                        data[offset + 1..offset + 33].try_into().unwrap(),
+                    ))
+                } else {
+                    None
+                });
+            }
+        }
+    }
+    require_gte!(
+        remaining_accounts.len(),
+        2 + extra_addresses.len(),
+        ErrorCode::InvalidTransferHookAccounts
+    );
+    let (transfer_hook_accounts, rest) = remaining_accounts.split_at(2 + extra_addresses.len());
+    for (account_info, address) in transfer_hook_accounts[2..].iter().zip(extra_addresses) {
+        if let Some(address) = address {
+            require_keys_eq!(
+                account_info.key(),
+                address,
+                ErrorCode::InvalidTransferHookAccounts
+            );
+        }
+    }
+    *remaining_accounts = rest;
+    Ok(transfer_hook_accounts.to_vec())
+}
+
+/// Reject token program 2022 mints whose extensions allow to drain or freeze the pool vaults,
+/// unless the mint is on the whitelist of the operation account
+pub fn check_mint_extensions(
+    mint: &InterfaceAccount<Mint>,
+    operation_state: &OperationState,
+) -> Result<()> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner != Token2022::id() || operation_state.validate_whitelist_mint(mint.key()) {
+        return Ok(());
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    for (extension_type, value) in get_mint_extensions(&mint_data)? {
+        if extension_type == u16::from(ExtensionType::PermanentDelegate) {
+            return err!(ErrorCode::NotSupportMintWithPermanentDelegate);
+        }
+        if extension_type == u16::from(ExtensionType::NonTransferable) {
+            return err!(ErrorCode::NotSupportNonTransferableMint);
+        }
+        if extension_type == u16::from(ExtensionType::DefaultAccountState)
+            && value.first() == Some(&(TokenAccountState::Frozen as u8))
+        {
+            return err!(ErrorCode::NotSupportMintWithDefaultFrozenState);
+        }
+        if extension_type == u16::from(ExtensionType::ConfidentialTransferMint) {
+            return err!(ErrorCode::NotSupportMintWithConfidentialTransfer);
+        }
+        if extension_type == TRANSFER_HOOK_EXTENSION_TYPE {
+            return err!(ErrorCode::NotSupportMintWithTransferHook);
+        }
+    }
+    Ok(())
+}
+
+/// The TLV type of the `MetadataPointer` mint extension, unknown to the spl-token-2022 version we build against
+pub const METADATA_POINTER_EXTENSION_TYPE: u16 = 18;
+/// The TLV type of the `TokenMetadata` mint extension, unknown to the spl-token-2022 version we build against
+pub const TOKEN_METADATA_EXTENSION_TYPE: u16 = 19;
+/// Instruction tag of the token program 2022 `MetadataPointerExtension` instructions
+const METADATA_POINTER_EXTENSION_INSTRUCTION: u8 = 39;
+/// Discriminator of the token metadata interface `Initialize` instruction,
+/// the first 8 bytes of sha256("spl_token_metadata_interface:initialize_account")
+const TOKEN_METADATA_INITIALIZE_DISCRIMINATOR: [u8; 8] = [210, 225, 30, 162, 88, 184, 77, 141];
+
+/// Create a token program 2022 mint with 0 decimals which stores its own metadata: the `MetadataPointer`
+/// extension points at the mint itself and the `TokenMetadata` extension holds `name`, `symbol` and `uri`.
+/// `authority` is the mint and metadata update authority, `close_authority` can close the mint once
+/// the supply is burned, which reclaims all the rent.
+pub fn create_mint_with_metadata<'info>(
+    payer: &Signer<'info>,
+    mint: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    close_authority: &Pubkey,
+    system_program: &Program<'info, System>,
+    token_program: &Program<'info, Token2022>,
+    name: String,
+    symbol: String,
+    uri: String,
+    signers_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let (space, metadata_len) = get_mint_with_metadata_len(&name, &symbol, &uri);
+    system_program::create_account(
+        CpiContext::new(
+            system_program.to_account_info(),
+            CreateAccount {
+                from: payer.to_account_info(),
+                to: mint.clone(),
+            },
         ),
-        amount, decimals)
+        Rent::get()?.minimum_balance(space + metadata_len),
+        u64::try_from(space).unwrap(),
+        token_program.key,
+    )?;
+
+    solana_program::program::invoke(
+        &initialize_mint_close_authority(token_program.key, mint.key, Some(close_authority))?,
+        std::slice::from_ref(mint),
+    )?;
+
+    let mut data = vec![METADATA_POINTER_EXTENSION_INSTRUCTION, 0];
+    data.extend_from_slice(authority.key.as_ref());
+    data.extend_from_slice(mint.key.as_ref());
+    solana_program::program::invoke(
+        &Instruction {
+            program_id: token_program.key(),
+            accounts: vec![AccountMeta::new(mint.key(), false)],
+            data,
+        },
+        std::slice::from_ref(mint),
+    )?;
+
+    token_2022::initialize_mint2(
+        CpiContext::new(
+            token_program.to_account_info(),
+            InitializeMint2 { mint: mint.clone() },
+        ),
+        0,
+        authority.key,
+        None,
+    )?;
+
+    solana_program::program::invoke_signed(
+        &Instruction {
+            program_id: token_program.key(),
+            accounts: vec![
+                AccountMeta::new(mint.key(), false),
+                AccountMeta::new_readonly(authority.key(), false),
+                AccountMeta::new_readonly(mint.key(), false),
+                AccountMeta::new_readonly(authority.key(), true),
+            ],
+            data: get_token_metadata_initialize_data(&name, &symbol, &uri),
+        },
+        &[mint.clone(), authority.clone()],
+        signers_seeds,
+    )
+    .map_err(Into::into)
+}
+
+/// The length the mint of `create_mint_with_metadata` is created with, holding the fixed length
+/// extensions, and the length of the `TokenMetadata` entry the token program appends to it.
+/// The token program reallocates the mint to append the variable length metadata, so the rent
+/// of the final length is paid upfront.
+fn get_mint_with_metadata_len(name: &str, symbol: &str, uri: &str) -> (usize, usize) {
+    // MintCloseAuthority and MetadataPointer
+    let space = AccountState::LEN + 1 + (4 + size_of::<Pubkey>()) + (4 + 2 * size_of::<Pubkey>());
+    // the TLV header, the update authority and the mint, the borsh encoded strings and the empty
+    // additional metadata
+    let metadata_len = 4
+        + 2 * size_of::<Pubkey>()
+        + (4 + name.len())
+        + (4 + symbol.len())
+        + (4 + uri.len())
+        + 4;
+    (space, metadata_len)
+}
+
+/// The data of the token metadata interface `Initialize` instruction
+fn get_token_metadata_initialize_data(name: &str, symbol: &str, uri: &str) -> Vec<u8> {
+    // borsh encoded strings: 4 bytes length then the bytes
+    let mut data = TOKEN_METADATA_INITIALIZE_DISCRIMINATOR.to_vec();
+    for field in [name, symbol, uri] {
+        data.extend_from_slice(&u32::try_from(field.len()).unwrap().to_le_bytes());
+        data.extend_from_slice(field.as_bytes());
+    }
+    data
+}
+
+/// Get the close authority of a token program 2022 mint, if any
+pub fn get_mint_close_authority(mint: &InterfaceAccount<Mint>) -> Result<Option<Pubkey>> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner != Token2022::id() {
+        return Ok(None);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    for (extension_type, value) in get_mint_extensions(&mint_data)? {
+        if extension_type == u16::from(ExtensionType::MintCloseAuthority) {
+            require_eq!(value.len(), 32, ErrorCode::InvalidMintExtensionData);
+            let close_authority = Pubkey::new_from_array(value.try_into().unwrap());
+            if close_authority != Pubkey::default() {
+                return Ok(Some(close_authority));
+            }
+        }
+    }
+    Ok(None)
 }
 
 pub fn close_spl_account<'a, 'b, 'c, 'info>(
@@ -106,3 +590,443 @@ pub fn burn<'a, 'b, 'c, 'info>(
         amount,
     )
 }
+
+#[cfg(test)]
+mod token_test {
+    use super::*;
+    use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFee;
+
+    /// The data of a token program 2022 mint with the given (extension type, value) TLV entries
+    fn mint_data_with_extensions(extensions: &[(u16, &[u8])]) -> Vec<u8> {
+        let mut data = vec![0u8; AccountState::LEN];
+        // account type of a mint
+        data.push(1);
+        for (extension_type, value) in extensions {
+            data.extend_from_slice(&extension_type.to_le_bytes());
+            data.extend_from_slice(&u16::try_from(value.len()).unwrap().to_le_bytes());
+            data.extend_from_slice(value);
+        }
+        data
+    }
+
+    fn transfer_fee(epoch: u64, transfer_fee_basis_points: u16, maximum_fee: u64) -> TransferFee {
+        TransferFee {
+            epoch: epoch.into(),
+            maximum_fee: maximum_fee.into(),
+            transfer_fee_basis_points: transfer_fee_basis_points.into(),
+        }
+    }
+
+    fn mint_data_with_transfer_fee(
+        older_transfer_fee: TransferFee,
+        newer_transfer_fee: TransferFee,
+    ) -> Vec<u8> {
+        let transfer_fee_config = TransferFeeConfig {
+            older_transfer_fee,
+            newer_transfer_fee,
+            ..Default::default()
+        };
+        mint_data_with_extensions(&[(
+            u16::from(ExtensionType::TransferFeeConfig),
+            bytemuck::bytes_of(&transfer_fee_config),
+        )])
+    }
+
+    /// An account whose `AccountInfo` borrows its fields
+    struct TestAccount {
+        key: Pubkey,
+        owner: Pubkey,
+        lamports: u64,
+        data: Vec<u8>,
+    }
+
+    impl TestAccount {
+        fn new(key: Pubkey, owner: Pubkey, data: Vec<u8>) -> Self {
+            Self {
+                key,
+                owner,
+                lamports: 0,
+                data,
+            }
+        }
+
+        /// A token program 2022 mint at `key` with the given extensions
+        fn mint(key: Pubkey, extensions: &[(u16, &[u8])]) -> Self {
+            let mut data = mint_data_with_extensions(extensions);
+            if extensions.is_empty() {
+                data.truncate(MintState::LEN);
+            }
+            // is_initialized
+            data[45] = 1;
+            Self::new(key, Token2022::id(), data)
+        }
+
+        fn account_info(&mut self) -> AccountInfo<'_> {
+            AccountInfo::new(
+                &self.key,
+                false,
+                false,
+                &mut self.lamports,
+                &mut self.data,
+                &self.owner,
+                false,
+                0,
+            )
+        }
+    }
+
+    /// Run `check_mint_extensions` on a token program 2022 mint at `mint_key` with the given extensions
+    fn check_mint_data(
+        mint_key: Pubkey,
+        extensions: &[(u16, &[u8])],
+        operation_state: &OperationState,
+    ) -> Result<()> {
+        let mut mint_account = TestAccount::mint(mint_key, extensions);
+        let mint_info = mint_account.account_info();
+        let mint = InterfaceAccount::<Mint>::try_from(&mint_info)?;
+        check_mint_extensions(&mint, operation_state)
+    }
+
+    #[test]
+    fn check_mint_extensions_test() {
+        let operation_state = OperationState {
+            bump: 0,
+            operation_owners: [Pubkey::default(); OPERATION_SIZE_USIZE],
+            whitelist_mints: [Pubkey::default(); WHITE_MINT_SIZE_USIZE],
+        };
+        let mint_close_authority = [7u8; 32];
+        let default_initialized = [TokenAccountState::Initialized as u8];
+        let default_frozen = [TokenAccountState::Frozen as u8];
+        let confidential_transfer = [0u8; 65];
+        let transfer_hook = [1u8; 64];
+        // the extensions which can't drain or freeze the vaults are accepted
+        for extensions in [
+            vec![],
+            vec![(
+                u16::from(ExtensionType::MintCloseAuthority),
+                &mint_close_authority[..],
+            )],
+            vec![(
+                u16::from(ExtensionType::DefaultAccountState),
+                &default_initialized[..],
+            )],
+        ] {
+            check_mint_data(Pubkey::new_unique(), &extensions, &operation_state).unwrap();
+        }
+
+        let rejected_extensions: [(u16, &[u8], ErrorCode); 5] = [
+            (
+                u16::from(ExtensionType::PermanentDelegate),
+                &mint_close_authority,
+                ErrorCode::NotSupportMintWithPermanentDelegate,
+            ),
+            (
+                u16::from(ExtensionType::NonTransferable),
+                &[],
+                ErrorCode::NotSupportNonTransferableMint,
+            ),
+            (
+                u16::from(ExtensionType::DefaultAccountState),
+                &default_frozen,
+                ErrorCode::NotSupportMintWithDefaultFrozenState,
+            ),
+            (
+                u16::from(ExtensionType::ConfidentialTransferMint),
+                &confidential_transfer,
+                ErrorCode::NotSupportMintWithConfidentialTransfer,
+            ),
+            (
+                TRANSFER_HOOK_EXTENSION_TYPE,
+                &transfer_hook,
+                ErrorCode::NotSupportMintWithTransferHook,
+            ),
+        ];
+        for (extension_type, value, error_code) in rejected_extensions {
+            // behind an accepted extension
+            let extensions = [
+                (
+                    u16::from(ExtensionType::MintCloseAuthority),
+                    &mint_close_authority[..],
+                ),
+                (extension_type, value),
+            ];
+            let result = check_mint_data(Pubkey::new_unique(), &extensions, &operation_state);
+            assert_eq!(result.unwrap_err(), error_code.into());
+
+            // the whitelisted mint is accepted whatever its extensions
+            let mint_key = Pubkey::new_unique();
+            let mut whitelisted_operation_state = operation_state;
+            whitelisted_operation_state.whitelist_mints[0] = mint_key;
+            check_mint_data(mint_key, &extensions, &whitelisted_operation_state).unwrap();
+        }
+    }
+
+    #[test]
+    fn get_mint_extensions_test() {
+        // a mint without extensions
+        assert!(get_mint_extensions(&[0u8; MintState::LEN])
+            .unwrap()
+            .is_empty());
+
+        let mint_data = mint_data_with_extensions(&[
+            (u16::from(ExtensionType::NonTransferable), &[]),
+            (TRANSFER_HOOK_EXTENSION_TYPE, &[1u8; 64]),
+        ]);
+        let extensions = get_mint_extensions(&mint_data).unwrap();
+        assert_eq!(extensions.len(), 2);
+        assert_eq!(
+            extensions[0],
+            (u16::from(ExtensionType::NonTransferable), &[][..])
+        );
+        assert_eq!(extensions[1], (TRANSFER_HOOK_EXTENSION_TYPE, &[1u8; 64][..]));
+
+        // the value overruns the data
+        let mut mint_data =
+            mint_data_with_extensions(&[(TRANSFER_HOOK_EXTENSION_TYPE, &[1u8; 64])]);
+        mint_data.truncate(mint_data.len() - 1);
+        assert_eq!(
+            get_mint_extensions(&mint_data).unwrap_err(),
+            ErrorCode::InvalidMintExtensionData.into()
+        );
+    }
+
+    /// The data of an `ExtraAccountMetaList` of the execute instruction with the given metas of
+    /// (discriminator, address config)
+    fn extra_account_meta_list_data(metas: &[(u8, [u8; 32])]) -> Vec<u8> {
+        // the execute instruction discriminator
+        let mut data = vec![105, 37, 101, 197, 75, 251, 102, 26];
+        data.extend_from_slice(
+            &u32::try_from(4 + metas.len() * EXTRA_ACCOUNT_META_LEN)
+                .unwrap()
+                .to_le_bytes(),
+        );
+        data.extend_from_slice(&u32::try_from(metas.len()).unwrap().to_le_bytes());
+        for (discriminator, address_config) in metas {
+            data.push(*discriminator);
+            data.extend_from_slice(address_config);
+            // is_signer, is_writable
+            data.extend_from_slice(&[0, 1]);
+        }
+        data
+    }
+
+    #[test]
+    fn get_transfer_hook_accounts_test() {
+        let hook_program_id = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let mut transfer_hook = [0u8; 64];
+        transfer_hook[32..].copy_from_slice(hook_program_id.as_ref());
+        let mut mint_account =
+            TestAccount::mint(mint_key, &[(TRANSFER_HOOK_EXTENSION_TYPE, &transfer_hook)]);
+        let mint_info = mint_account.account_info();
+        let mint = InterfaceAccount::<Mint>::try_from(&mint_info).unwrap();
+        assert_eq!(
+            get_transfer_hook_program_id(&mint).unwrap(),
+            Some(hook_program_id)
+        );
+
+        // a fixed address extra account followed by a seed derived one
+        let fixed_account_key = Pubkey::new_unique();
+        let mut seed_config = [0u8; 32];
+        // a literal seed
+        seed_config[..4].copy_from_slice(&[1, 2, b'a', b'b']);
+        let extra_account_meta_list_key = Pubkey::find_program_address(
+            &[EXTRA_ACCOUNT_METAS_SEED.as_bytes(), mint_key.as_ref()],
+            &hook_program_id,
+        )
+        .0;
+        let mut accounts = [
+            TestAccount::new(hook_program_id, Pubkey::default(), vec![]),
+            TestAccount::new(
+                extra_account_meta_list_key,
+                hook_program_id,
+                extra_account_meta_list_data(&[
+                    (0, fixed_account_key.to_bytes()),
+                    (1, seed_config),
+                ]),
+            ),
+            TestAccount::new(fixed_account_key, Pubkey::default(), vec![]),
+            TestAccount::new(Pubkey::new_unique(), Pubkey::default(), vec![]),
+            // the accounts of the next transfer are kept
+            TestAccount::new(Pubkey::new_unique(), Pubkey::default(), vec![]),
+        ];
+        let account_infos = accounts
+            .iter_mut()
+            .map(TestAccount::account_info)
+            .collect::<Vec<_>>();
+
+        let mut remaining_accounts = &account_infos[..];
+        let transfer_hook_accounts =
+            get_transfer_hook_accounts(&mint, &mut remaining_accounts).unwrap();
+        assert_eq!(transfer_hook_accounts.len(), 4);
+        assert_eq!(remaining_accounts.len(), 1);
+        assert_eq!(remaining_accounts[0].key(), account_infos[4].key());
+
+        // the fixed address extra account must match
+        let wrong_accounts = [
+            account_infos[0].clone(),
+            account_infos[1].clone(),
+            account_infos[3].clone(),
+            account_infos[3].clone(),
+        ];
+        let mut remaining_accounts = &wrong_accounts[..];
+        assert_eq!(
+            get_transfer_hook_accounts(&mint, &mut remaining_accounts).unwrap_err(),
+            ErrorCode::InvalidTransferHookAccounts.into()
+        );
+        // the hook program must match
+        let mut remaining_accounts = &account_infos[1..];
+        assert_eq!(
+            get_transfer_hook_accounts(&mint, &mut remaining_accounts).unwrap_err(),
+            ErrorCode::InvalidTransferHookAccounts.into()
+        );
+        // the extra accounts must be passed
+        let mut remaining_accounts = &account_infos[..3];
+        assert_eq!(
+            get_transfer_hook_accounts(&mint, &mut remaining_accounts).unwrap_err(),
+            ErrorCode::InvalidTransferHookAccounts.into()
+        );
+
+        // nothing is taken for a mint without transfer hook
+        let mut mint_account = TestAccount::mint(Pubkey::new_unique(), &[]);
+        let mint_info = mint_account.account_info();
+        let mint = InterfaceAccount::<Mint>::try_from(&mint_info).unwrap();
+        let mut remaining_accounts = &account_infos[..];
+        assert!(get_transfer_hook_accounts(&mint, &mut remaining_accounts)
+            .unwrap()
+            .is_empty());
+        assert_eq!(remaining_accounts.len(), account_infos.len());
+    }
+
+    #[test]
+    fn mint_with_metadata_len_test() {
+        let (name, symbol, uri) = ("Raydium Concentrated Liquidity", "RCL", "https://uri");
+        let (space, metadata_len) = get_mint_with_metadata_len(name, symbol, uri);
+        // the base mint with the MintCloseAuthority and MetadataPointer extensions
+        let mint_data = mint_data_with_extensions(&[
+            (u16::from(ExtensionType::MintCloseAuthority), &[0u8; 32]),
+            (METADATA_POINTER_EXTENSION_TYPE, &[0u8; 64]),
+        ]);
+        assert_eq!(space, mint_data.len());
+        // the TokenMetadata entry: update authority, mint, name, symbol, uri, additional metadata
+        let token_metadata = (
+            Pubkey::default(),
+            Pubkey::default(),
+            name.to_string(),
+            symbol.to_string(),
+            uri.to_string(),
+            Vec::<(String, String)>::new(),
+        );
+        assert_eq!(metadata_len, 4 + token_metadata.try_to_vec().unwrap().len());
+    }
+
+    #[test]
+    fn token_metadata_initialize_data_test() {
+        let data = get_token_metadata_initialize_data("name", "symbol", "uri");
+        assert_eq!(data[..8], TOKEN_METADATA_INITIALIZE_DISCRIMINATOR);
+        let (name, symbol, uri) = <(String, String, String)>::try_from_slice(&data[8..]).unwrap();
+        assert_eq!(
+            (name.as_str(), symbol.as_str(), uri.as_str()),
+            ("name", "symbol", "uri")
+        );
+    }
+
+    #[test]
+    fn get_mint_close_authority_test() {
+        let close_authority = Pubkey::new_unique();
+        let mut mint_account = TestAccount::mint(
+            Pubkey::new_unique(),
+            &[(
+                u16::from(ExtensionType::MintCloseAuthority),
+                close_authority.as_ref(),
+            )],
+        );
+        let mint_info = mint_account.account_info();
+        let mint = InterfaceAccount::<Mint>::try_from(&mint_info).unwrap();
+        assert_eq!(get_mint_close_authority(&mint).unwrap(), Some(close_authority));
+
+        // a cleared close authority
+        let mut mint_account = TestAccount::mint(
+            Pubkey::new_unique(),
+            &[(u16::from(ExtensionType::MintCloseAuthority), &[0u8; 32])],
+        );
+        let mint_info = mint_account.account_info();
+        let mint = InterfaceAccount::<Mint>::try_from(&mint_info).unwrap();
+        assert_eq!(get_mint_close_authority(&mint).unwrap(), None);
+
+        // a mint of the token program has no extension
+        let mut mint_account = TestAccount::mint(Pubkey::new_unique(), &[]);
+        mint_account.owner = anchor_spl::token::ID;
+        let mint_info = mint_account.account_info();
+        let mint = InterfaceAccount::<Mint>::try_from(&mint_info).unwrap();
+        assert_eq!(get_mint_close_authority(&mint).unwrap(), None);
+    }
+
+    #[test]
+    fn transfer_fee_without_extension_test() {
+        let mint_data = vec![0u8; MintState::LEN];
+        assert_eq!(calculate_transfer_fee(&mint_data, 0, 1_000_000).unwrap(), 0);
+        assert_eq!(
+            calculate_transfer_inverse_fee(&mint_data, 0, 1_000_000).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn transfer_fee_rounding_test() {
+        // 1%
+        let fee = transfer_fee(0, 100, u64::MAX);
+        let mint_data = mint_data_with_transfer_fee(fee, fee);
+        assert_eq!(calculate_transfer_fee(&mint_data, 0, 0).unwrap(), 0);
+        // the fee rounds up
+        assert_eq!(calculate_transfer_fee(&mint_data, 0, 1).unwrap(), 1);
+        assert_eq!(calculate_transfer_fee(&mint_data, 0, 100).unwrap(), 1);
+        assert_eq!(calculate_transfer_fee(&mint_data, 0, 101).unwrap(), 2);
+
+        assert_eq!(calculate_transfer_inverse_fee(&mint_data, 0, 0).unwrap(), 0);
+        assert_eq!(calculate_transfer_inverse_fee(&mint_data, 0, 98).unwrap(), 1);
+        assert_eq!(calculate_transfer_inverse_fee(&mint_data, 0, 99).unwrap(), 1);
+        assert_eq!(calculate_transfer_inverse_fee(&mint_data, 0, 100).unwrap(), 2);
+        // the inverse fee always lets the receiver get the requested amount
+        for post_fee_amount in [1, 7, 99, 100, 12345, 1_000_000_007] {
+            let inverse_fee =
+                calculate_transfer_inverse_fee(&mint_data, 0, post_fee_amount).unwrap();
+            let pre_fee_amount = post_fee_amount + inverse_fee;
+            assert_eq!(
+                pre_fee_amount - calculate_transfer_fee(&mint_data, 0, pre_fee_amount).unwrap(),
+                post_fee_amount
+            );
+        }
+    }
+
+    #[test]
+    fn transfer_fee_maximum_fee_test() {
+        // 1% up to 10
+        let fee = transfer_fee(0, 100, 10);
+        let mint_data = mint_data_with_transfer_fee(fee, fee);
+        assert_eq!(calculate_transfer_fee(&mint_data, 0, 900).unwrap(), 9);
+        assert_eq!(calculate_transfer_fee(&mint_data, 0, 1_000_000).unwrap(), 10);
+        assert_eq!(
+            calculate_transfer_inverse_fee(&mint_data, 0, 1_000_000).unwrap(),
+            10
+        );
+
+        // 100% up to 50, the inverse fee is the maximum fee
+        let fee = transfer_fee(0, MAX_FEE_BASIS_POINTS, 50);
+        let mint_data = mint_data_with_transfer_fee(fee, fee);
+        assert_eq!(calculate_transfer_fee(&mint_data, 0, 30).unwrap(), 30);
+        assert_eq!(calculate_transfer_fee(&mint_data, 0, 100).unwrap(), 50);
+        assert_eq!(calculate_transfer_inverse_fee(&mint_data, 0, 1).unwrap(), 50);
+    }
+
+    #[test]
+    fn transfer_fee_epoch_test() {
+        // the newer fee of 2% applies from epoch 10
+        let mint_data = mint_data_with_transfer_fee(
+            transfer_fee(0, 100, u64::MAX),
+            transfer_fee(10, 200, u64::MAX),
+        );
+        assert_eq!(calculate_transfer_fee(&mint_data, 9, 10_000).unwrap(), 100);
+        assert_eq!(calculate_transfer_fee(&mint_data, 10, 10_000).unwrap(), 200);
+    }
+}